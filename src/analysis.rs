@@ -0,0 +1,759 @@
+/*!
+Helpers for analyzing the typed data from the `models` module.  These all
+work on data you've already fetched, so they never hit the network
+themselves.
+
+```ignore,rust
+use rbgg::{analysis, bgg2::Client2, models::{Collection, Plays}};
+
+let cl = Client2::new_from_defaults();
+let plays = Plays::from_value(&cl.plays_b(Some("someuser"), None, None, None)?)?;
+let coll = Collection::from_value(&cl.collection_b("someuser", None)?)?;
+
+for game in analysis::played_not_owned(&plays.plays, &coll, 3) {
+    println!("{} ({} plays)", game.name, game.plays);
+}
+```
+*/
+use crate::models::{Collection, CollectionItem, Play, Plays, ThingItem};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// A game that has been played, but isn't owned
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnownedGame {
+    pub id: usize,
+    pub name: String,
+    /// The total number of plays, taking the quantity of each play into
+    /// account
+    pub plays: u32,
+}
+
+/// Return the games that have been played at least `min_plays` times but
+/// aren't owned according to the collection, most played first.  This is
+/// the basis for "should I buy this?" tooling.
+pub fn played_not_owned(
+    plays: &[Play],
+    collection: &Collection,
+    min_plays: u32,
+) -> Vec<UnownedGame> {
+    let owned: HashSet<usize> = collection
+        .items
+        .iter()
+        .filter(|i| i.status.own)
+        .map(|i| i.id)
+        .collect();
+
+    let mut counts: HashMap<usize, UnownedGame> = HashMap::new();
+    for play in plays {
+        if owned.contains(&play.item.id) {
+            continue;
+        }
+
+        let entry = counts.entry(play.item.id).or_insert_with(|| UnownedGame {
+            id: play.item.id,
+            name: play.item.name.clone(),
+            plays: 0,
+        });
+        entry.plays += play.quantity.max(1);
+    }
+
+    let mut ret: Vec<UnownedGame> = counts
+        .into_values()
+        .filter(|g| g.plays >= min_plays)
+        .collect();
+    ret.sort_by(|a, b| b.plays.cmp(&a.plays).then_with(|| a.name.cmp(&b.name)));
+
+    return ret;
+}
+
+/// Aggregate counts over a set of plays
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlaySummary {
+    /// The total number of plays, taking the quantity of each play into
+    /// account
+    pub total: u32,
+    /// The number of plays at each location.  Plays without a location are
+    /// counted under an empty string.
+    pub locations: BTreeMap<String, u32>,
+    /// The number of plays for each number of players
+    pub player_counts: BTreeMap<usize, u32>,
+    /// The number of plays where the players weren't recorded
+    pub unknown_player_count: u32,
+}
+
+impl PlaySummary {
+    /// The locations, most played at first
+    pub fn top_locations(&self) -> Vec<(&str, u32)> {
+        let mut ret: Vec<(&str, u32)> = self
+            .locations
+            .iter()
+            .map(|(loc, count)| (loc.as_str(), *count))
+            .collect();
+        ret.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+        return ret;
+    }
+}
+
+/// Aggregate the per-location play counts and the distribution of player
+/// counts over a set of plays
+pub fn play_summary(plays: &[Play]) -> PlaySummary {
+    let mut ret = PlaySummary::default();
+
+    for play in plays {
+        let quantity = play.quantity.max(1);
+
+        ret.total += quantity;
+        *ret.locations
+            .entry(play.location.trim().to_string())
+            .or_default() += quantity;
+
+        if play.players.is_empty() {
+            ret.unknown_player_count += quantity;
+        } else {
+            *ret.player_counts.entry(play.players.len()).or_default() += quantity;
+        }
+    }
+
+    return ret;
+}
+
+/// Stats for someone the user has played with
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Companion {
+    /// The companion's BGG username, or their name if they don't have one
+    pub ident: String,
+    /// The number of plays shared with the user
+    pub plays: u32,
+    /// The number of distinct games played together
+    pub games: usize,
+    /// The number of shared plays that counted towards win stats
+    pub win_stat_plays: u32,
+    /// The number of those plays the companion won
+    pub wins: u32,
+}
+
+impl Companion {
+    /// The fraction of the shared plays (that count for win stats) won by
+    /// this companion
+    pub fn win_rate(&self) -> Option<f64> {
+        if self.win_stat_plays == 0 {
+            return None;
+        }
+
+        return Some(self.wins as f64 / self.win_stat_plays as f64);
+    }
+}
+
+/// Aggregate the players across a user's plays into stats per co-player,
+/// most frequent first.  The user themselves (per `plays.username`) is
+/// excluded.
+pub fn companions(plays: &Plays) -> Vec<Companion> {
+    let me = plays.username.clone().unwrap_or_default();
+    let mut found: HashMap<String, (Companion, HashSet<usize>)> = HashMap::new();
+
+    for play in &plays.plays {
+        let quantity = play.quantity.max(1);
+
+        for player in &play.players {
+            let ident = player.ident();
+            if ident.is_empty() || (!me.is_empty() && player.username == me) {
+                continue;
+            }
+
+            let (comp, games) = found.entry(ident.to_string()).or_insert_with(|| {
+                let comp = Companion {
+                    ident: ident.to_string(),
+                    ..Default::default()
+                };
+                (comp, HashSet::new())
+            });
+
+            comp.plays += quantity;
+            games.insert(play.item.id);
+            if !play.no_win_stats {
+                comp.win_stat_plays += quantity;
+                if player.win {
+                    comp.wins += quantity;
+                }
+            }
+        }
+    }
+
+    let mut ret: Vec<Companion> = found
+        .into_values()
+        .map(|(mut comp, games)| {
+            comp.games = games.len();
+            comp
+        })
+        .collect();
+    ret.sort_by(|a, b| b.plays.cmp(&a.plays).then_with(|| a.ident.cmp(&b.ident)));
+
+    return ret;
+}
+
+/// A player's record for a single game
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GameRecord {
+    pub id: usize,
+    pub name: String,
+    /// The number of plays that counted towards win stats
+    pub plays: u32,
+    pub wins: u32,
+    /// The numeric scores for the game as (date, score), in the order the
+    /// plays were given
+    pub scores: Vec<(String, f64)>,
+}
+
+impl GameRecord {
+    /// The fraction of plays that were won
+    pub fn win_rate(&self) -> Option<f64> {
+        if self.plays == 0 {
+            return None;
+        }
+
+        return Some(self.wins as f64 / self.plays as f64);
+    }
+
+    /// The number of plays that were lost
+    pub fn losses(&self) -> u32 {
+        return self.plays - self.wins;
+    }
+}
+
+/// Build the win/loss record and score history, per game, for the named
+/// player (matched by BGG username or name).  Plays that are marked as not
+/// counting towards win stats are skipped.  The games are returned most
+/// played first.
+pub fn win_loss(plays: &[Play], who: &str) -> Vec<GameRecord> {
+    let mut found: HashMap<usize, GameRecord> = HashMap::new();
+
+    for play in plays {
+        if play.no_win_stats {
+            continue;
+        }
+
+        let player = match play.players.iter().find(|p| p.is(who)) {
+            Some(p) => p,
+            None => continue,
+        };
+        let quantity = play.quantity.max(1);
+        let rec = found.entry(play.item.id).or_insert_with(|| GameRecord {
+            id: play.item.id,
+            name: play.item.name.clone(),
+            ..Default::default()
+        });
+
+        rec.plays += quantity;
+        if player.win {
+            rec.wins += quantity;
+        }
+        if let Some(score) = player.score_value() {
+            rec.scores.push((play.date.clone(), score));
+        }
+    }
+
+    let mut ret: Vec<GameRecord> = found.into_values().collect();
+    ret.sort_by(|a, b| b.plays.cmp(&a.plays).then_with(|| a.name.cmp(&b.name)));
+
+    return ret;
+}
+
+/// An overlap found in a collection
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Overlap {
+    /// The same game is in the collection more than once, usually as
+    /// different versions
+    Duplicate {
+        id: usize,
+        name: String,
+        coll_ids: Vec<usize>,
+    },
+    /// Both a game and a reimplementation of it are in the collection
+    Reimplementation {
+        base_id: usize,
+        base_name: String,
+        id: usize,
+        name: String,
+    },
+}
+
+/// Find the overlaps in a collection: games that are in the collection
+/// more than once, and games that are in the collection along with a
+/// reimplementation of them.  The collection doesn't include links, so the
+/// reimplementations are supplied as (game ID, reimplemented game ID) pairs,
+/// which come from the "boardgamereimplementation" links on thing responses.
+pub fn overlaps(collection: &Collection, reimplementations: &[(usize, usize)]) -> Vec<Overlap> {
+    let mut ret = vec![];
+    let mut by_id: BTreeMap<usize, Vec<&CollectionItem>> = BTreeMap::new();
+
+    for item in &collection.items {
+        by_id.entry(item.id).or_default().push(item);
+    }
+
+    for (id, items) in &by_id {
+        if items.len() > 1 {
+            ret.push(Overlap::Duplicate {
+                id: *id,
+                name: items[0].name.clone(),
+                coll_ids: items.iter().filter_map(|i| i.coll_id).collect(),
+            });
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for (id, base_id) in reimplementations {
+        if !seen.insert((*id, *base_id)) {
+            continue;
+        }
+
+        if let (Some(item), Some(base)) = (by_id.get(id), by_id.get(base_id)) {
+            ret.push(Overlap::Reimplementation {
+                base_id: *base_id,
+                base_name: base[0].name.clone(),
+                id: *id,
+                name: item[0].name.clone(),
+            });
+        }
+    }
+
+    return ret;
+}
+
+/// A game one user has for trade that another user wants
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TradeMatch {
+    pub id: usize,
+    pub name: String,
+    /// The link to the game on BGG
+    pub url: String,
+    /// The wishlist priority the wanting user gave it, if it's on their
+    /// wishlist
+    pub wishlist_priority: Option<u8>,
+}
+
+/// The trade matches between two users, in both directions
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TradeMatches {
+    /// Games the first user has for trade that the second user wants
+    pub first_to_second: Vec<TradeMatch>,
+    /// Games the second user has for trade that the first user wants
+    pub second_to_first: Vec<TradeMatch>,
+}
+
+impl TradeMatches {
+    /// Whether there's a match in both directions, meaning a trade could
+    /// actually work
+    pub fn is_mutual(&self) -> bool {
+        return !self.first_to_second.is_empty() && !self.second_to_first.is_empty();
+    }
+}
+
+/// Cross each user's for-trade list with the other user's want list and
+/// wishlist.  Matches are sorted by wishlist priority (most wanted first).
+pub fn trade_matches(first: &Collection, second: &Collection) -> TradeMatches {
+    return TradeMatches {
+        first_to_second: trades_for(first, second),
+        second_to_first: trades_for(second, first),
+    };
+}
+
+/// The link to an item on BGG
+fn item_url(item: &CollectionItem) -> String {
+    let subtype = match item.subtype.as_str() {
+        "" => "boardgame",
+        s => s,
+    };
+
+    return format!("https://boardgamegeek.com/{}/{}", subtype, item.id);
+}
+
+/// The games `giver` has for trade that `wanter` wants
+fn trades_for(giver: &Collection, wanter: &Collection) -> Vec<TradeMatch> {
+    let wants: HashMap<usize, &CollectionItem> = wanter
+        .items
+        .iter()
+        .filter(|i| i.status.want || i.status.want_to_buy || i.status.wishlist)
+        .map(|i| (i.id, i))
+        .collect();
+    let mut seen = HashSet::new();
+
+    let mut ret: Vec<TradeMatch> = giver
+        .items
+        .iter()
+        .filter(|i| i.status.for_trade && seen.insert(i.id))
+        .filter_map(|i| {
+            wants.get(&i.id).map(|want| TradeMatch {
+                id: i.id,
+                name: i.name.clone(),
+                url: item_url(i),
+                wishlist_priority: want
+                    .status
+                    .wishlist_priority
+                    .filter(|_| want.status.wishlist),
+            })
+        })
+        .collect();
+    // Items that aren't on the wishlist sort after those that are
+    ret.sort_by_key(|m| (m.wishlist_priority.unwrap_or(u8::MAX), m.id));
+
+    return ret;
+}
+
+/// A game on a user's wishlist, along with its current standing on BGG
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WishlistEntry {
+    pub id: usize,
+    pub name: String,
+    /// The overall rank for the game's subtype, if it is ranked
+    pub rank: Option<u32>,
+    pub average: Option<f64>,
+    pub bayes_average: Option<f64>,
+}
+
+/// The wishlist entries at a single priority level
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WishlistGroup {
+    /// From 1 (must have) to 5 (don't buy this)
+    pub priority: u8,
+    /// The entries, best ranked first
+    pub entries: Vec<WishlistEntry>,
+}
+
+/// Group the wishlist items in a collection by priority, highest priority
+/// (1) first, enriching them with the rank and ratings from the given
+/// things (fetched with `stats=1`).  Within a priority, games are sorted by
+/// rank with unranked games last.
+pub fn wishlist_report(collection: &Collection, things: &[ThingItem]) -> Vec<WishlistGroup> {
+    let stats: HashMap<usize, &ThingItem> = things.iter().map(|t| (t.id, t)).collect();
+    let mut groups: BTreeMap<u8, Vec<WishlistEntry>> = BTreeMap::new();
+
+    for item in collection.items.iter().filter(|i| i.status.wishlist) {
+        let mut entry = WishlistEntry {
+            id: item.id,
+            name: item.name.clone(),
+            ..Default::default()
+        };
+
+        if let Some(s) = stats.get(&item.id).and_then(|t| t.statistics.as_ref()) {
+            entry.rank = s.rank();
+            entry.average = s.average;
+            entry.bayes_average = s.bayes_average;
+        }
+
+        // BGG's default priority is 3 ("like to have")
+        let priority = item.status.wishlist_priority.unwrap_or(3);
+        groups.entry(priority).or_default().push(entry);
+    }
+
+    return groups
+        .into_iter()
+        .map(|(priority, mut entries)| {
+            entries.sort_by_key(|e| (e.rank.unwrap_or(u32::MAX), e.id));
+            WishlistGroup { priority, entries }
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PlayItem, Player, Rank, Statistics};
+
+    fn play(id: usize, name: &str, quantity: u32) -> Play {
+        return Play {
+            quantity,
+            item: PlayItem {
+                id,
+                name: name.into(),
+                object_type: "thing".into(),
+            },
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn test_played_not_owned() {
+        let mut owned = CollectionItem {
+            id: 1,
+            ..Default::default()
+        };
+        owned.status.own = true;
+        // Previously owned games don't count as owned
+        let mut prev = CollectionItem {
+            id: 3,
+            ..Default::default()
+        };
+        prev.status.prev_owned = true;
+        let coll = Collection {
+            total_items: 2,
+            items: vec![owned, prev],
+        };
+
+        let plays = vec![
+            play(1, "Owned", 5),
+            play(2, "Friend's Game", 1),
+            play(2, "Friend's Game", 2),
+            play(3, "Sold It", 1),
+            play(4, "Once", 1),
+        ];
+
+        let res = played_not_owned(&plays, &coll, 1);
+        assert_eq!(res.len(), 3);
+        assert_eq!(
+            res[0],
+            UnownedGame {
+                id: 2,
+                name: "Friend's Game".into(),
+                plays: 3,
+            }
+        );
+
+        let res = played_not_owned(&plays, &coll, 2);
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].id, 2);
+    }
+
+    #[test]
+    fn test_play_summary() {
+        let mut plays = vec![play(1, "A", 2), play(2, "B", 1), play(1, "A", 1)];
+        plays[0].location = "Club".into();
+        plays[0].players = vec![Player::default(); 4];
+        plays[1].location = "Home".into();
+        plays[1].players = vec![Player::default(); 2];
+        plays[2].location = " Club ".into();
+
+        let res = play_summary(&plays);
+        assert_eq!(res.total, 4);
+        assert_eq!(res.locations["Club"], 3);
+        assert_eq!(res.locations["Home"], 1);
+        assert_eq!(res.player_counts[&4], 2);
+        assert_eq!(res.player_counts[&2], 1);
+        assert_eq!(res.unknown_player_count, 1);
+        assert_eq!(res.top_locations(), vec![("Club", 3), ("Home", 1)]);
+    }
+
+    fn player(username: &str, name: &str, win: bool) -> Player {
+        return Player {
+            username: username.into(),
+            name: name.into(),
+            win,
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn test_companions() {
+        let mut plays = vec![play(1, "A", 1), play(2, "B", 2), play(1, "A", 1)];
+        plays[0].players = vec![player("me", "Me", true), player("pal", "Pal", false)];
+        plays[1].players = vec![
+            player("me", "Me", false),
+            player("pal", "Pal", true),
+            player("", "Guest", false),
+        ];
+        plays[2].no_win_stats = true;
+        plays[2].players = vec![player("me", "Me", false), player("pal", "Pal", true)];
+        let plays = Plays {
+            username: Some("me".into()),
+            plays,
+            ..Default::default()
+        };
+
+        let res = companions(&plays);
+        assert_eq!(res.len(), 2);
+
+        let pal = &res[0];
+        assert_eq!(pal.ident, "pal");
+        assert_eq!(pal.plays, 4);
+        assert_eq!(pal.games, 2);
+        assert_eq!(pal.win_stat_plays, 3);
+        assert_eq!(pal.wins, 2);
+        assert!((pal.win_rate().unwrap() - 2.0 / 3.0).abs() < f64::EPSILON);
+
+        assert_eq!(res[1].ident, "Guest");
+        assert_eq!(res[1].win_rate(), Some(0.0));
+    }
+
+    #[test]
+    fn test_win_loss() {
+        let mut plays = vec![
+            play(1, "A", 1),
+            play(1, "A", 1),
+            play(2, "B", 1),
+            play(2, "B", 1),
+        ];
+        plays[0].date = "2023-01-01".into();
+        plays[0].players = vec![player("me", "Me", true), player("pal", "Pal", false)];
+        plays[0].players[0].score = "52".into();
+        plays[1].date = "2023-01-02".into();
+        plays[1].players = vec![player("me", "Me", false), player("pal", "Pal", true)];
+        plays[1].players[0].score = "40".into();
+        plays[2].players = vec![player("", "Me", true)];
+        plays[3].no_win_stats = true;
+        plays[3].players = vec![player("me", "Me", true)];
+
+        let res = win_loss(&plays, "me");
+        assert_eq!(res.len(), 2);
+        assert_eq!(res[0].id, 1);
+        assert_eq!(res[0].plays, 2);
+        assert_eq!(res[0].wins, 1);
+        assert_eq!(res[0].losses(), 1);
+        assert_eq!(res[0].win_rate(), Some(0.5));
+        assert_eq!(
+            res[0].scores,
+            vec![
+                ("2023-01-01".to_string(), 52.0),
+                ("2023-01-02".to_string(), 40.0)
+            ]
+        );
+
+        // Matched by name, and the no win stats play is skipped
+        assert_eq!(res[1].id, 2);
+        assert_eq!(res[1].plays, 1);
+        assert!(res[1].scores.is_empty());
+
+        assert!(win_loss(&plays, "nobody").is_empty());
+    }
+
+    fn item(id: usize, coll_id: usize, name: &str) -> CollectionItem {
+        return CollectionItem {
+            id,
+            coll_id: Some(coll_id),
+            name: name.into(),
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn test_overlaps() {
+        let coll = Collection {
+            total_items: 4,
+            items: vec![
+                item(1, 10, "Base"),
+                item(1, 11, "Base"),
+                item(2, 12, "Base: Reimplemented"),
+                item(3, 13, "Other"),
+            ],
+        };
+
+        let res = overlaps(&coll, &[(2, 1), (2, 1), (4, 3)]);
+        assert_eq!(
+            res,
+            vec![
+                Overlap::Duplicate {
+                    id: 1,
+                    name: "Base".into(),
+                    coll_ids: vec![10, 11],
+                },
+                Overlap::Reimplementation {
+                    base_id: 1,
+                    base_name: "Base".into(),
+                    id: 2,
+                    name: "Base: Reimplemented".into(),
+                },
+            ]
+        );
+
+        assert!(overlaps(&Collection::default(), &[]).is_empty());
+    }
+
+    #[test]
+    fn test_trade_matches() {
+        let mut mine = vec![item(1, 10, "A"), item(2, 11, "B"), item(3, 12, "C")];
+        mine[0].status.for_trade = true;
+        mine[1].status.for_trade = true;
+        mine[2].status.wishlist = true;
+        mine[2].status.wishlist_priority = Some(2);
+
+        let mut theirs = vec![item(1, 20, "A"), item(2, 21, "B"), item(3, 22, "C")];
+        theirs[0].status.want = true;
+        theirs[1].status.wishlist = true;
+        theirs[1].status.wishlist_priority = Some(1);
+        theirs[2].status.for_trade = true;
+        theirs[2].subtype = "boardgameexpansion".into();
+
+        let first = Collection {
+            total_items: 3,
+            items: mine,
+        };
+        let second = Collection {
+            total_items: 3,
+            items: theirs,
+        };
+
+        let res = trade_matches(&first, &second);
+        assert!(res.is_mutual());
+        assert_eq!(res.first_to_second.len(), 2);
+        // The wishlisted game comes first
+        assert_eq!(res.first_to_second[0].id, 2);
+        assert_eq!(res.first_to_second[0].wishlist_priority, Some(1));
+        assert_eq!(
+            res.first_to_second[0].url,
+            "https://boardgamegeek.com/boardgame/2"
+        );
+        assert_eq!(res.first_to_second[1].id, 1);
+        assert_eq!(res.first_to_second[1].wishlist_priority, None);
+
+        assert_eq!(res.second_to_first.len(), 1);
+        assert_eq!(
+            res.second_to_first[0].url,
+            "https://boardgamegeek.com/boardgameexpansion/3"
+        );
+
+        let res = trade_matches(&first, &Collection::default());
+        assert!(!res.is_mutual());
+    }
+
+    fn thing(id: usize, rank: Option<u32>) -> ThingItem {
+        return ThingItem {
+            id,
+            statistics: Some(Statistics {
+                average: Some(7.0),
+                bayes_average: Some(6.5),
+                ranks: vec![Rank {
+                    rank_type: "subtype".into(),
+                    value: rank,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn test_wishlist_report() {
+        let mut items = vec![
+            item(1, 10, "A"),
+            item(2, 11, "B"),
+            item(3, 12, "C"),
+            item(4, 13, "D"),
+        ];
+        for (i, prio) in [(0, Some(2)), (1, Some(2)), (2, None)] {
+            items[i].status.wishlist = true;
+            items[i].status.wishlist_priority = prio;
+        }
+        let coll = Collection {
+            total_items: 4,
+            items,
+        };
+        let things = vec![thing(1, None), thing(2, Some(100)), thing(3, Some(5))];
+
+        let res = wishlist_report(&coll, &things);
+        assert_eq!(res.len(), 2);
+        assert_eq!(res[0].priority, 2);
+        // Ranked games come first
+        let ids: Vec<usize> = res[0].entries.iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![2, 1]);
+        assert_eq!(res[0].entries[0].rank, Some(100));
+        assert_eq!(res[0].entries[0].average, Some(7.0));
+
+        assert_eq!(res[1].priority, 3);
+        assert_eq!(res[1].entries[0].id, 3);
+
+        // Missing stats still get an entry
+        let res = wishlist_report(&coll, &[]);
+        assert_eq!(res[0].entries.len(), 2);
+        assert_eq!(res[0].entries[0].rank, None);
+    }
+}
@@ -0,0 +1,566 @@
+/*!
+Traits covering the endpoints of each version of the API, so that code
+using a client can take `impl BggApi2` (or `impl BggApi1`) rather than the
+concrete client, and have a mock substituted in its tests.
+
+```ignore,rust
+use rbgg::{api::BggApi2, bgg2::Hotness};
+
+#[cfg(feature = "async")]
+async fn hottest(api: &impl BggApi2) -> rbgg::Result<String> {
+    let hot = api.hot(Hotness::BoardGame, None).await?;
+    return Ok(hot["items"]["item"][0]["name"]["@value"].to_string());
+}
+```
+
+These only cover the endpoints themselves.  The typed and convenience
+methods are built on top of them, and are still available on the clients.
+*/
+use crate::bgg1::Client1;
+use crate::bgg2::{Client2, Family, Hotness, Search, Thing, ThingFamily};
+use crate::errors::Result;
+use crate::utils::{IntoParams, Params};
+use serde_json::Value;
+#[cfg(feature = "async")]
+use std::future::Future;
+
+/// The endpoints of the version 1 API, as implemented by `Client1`
+pub trait BggApi1 {
+    /// Search for games by name (async)
+    #[cfg(feature = "async")]
+    fn search(
+        &self,
+        search: &str,
+        options: Option<Params>,
+    ) -> impl Future<Output = Result<Value>> + Send;
+
+    /// Search for games by name (blocking)
+    fn search_b(&self, search: &str, options: Option<Params>) -> Result<Value>;
+
+    /// Get the details of one or more games by ID (async)
+    #[cfg(feature = "async")]
+    fn boardgame(
+        &self,
+        game_ids: &Vec<usize>,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send;
+
+    /// Get the details of one or more games by ID (blocking)
+    fn boardgame_b(&self, game_ids: &Vec<usize>, options: impl IntoParams + Send) -> Result<Value>;
+
+    /// Get a user's collection (async)
+    #[cfg(feature = "async")]
+    fn collection(
+        &self,
+        username: &str,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send;
+
+    /// Get a user's collection (blocking)
+    fn collection_b(&self, username: &str, options: impl IntoParams + Send) -> Result<Value>;
+
+    /// Get a thread, with its articles (async)
+    #[cfg(feature = "async")]
+    fn thread(
+        &self,
+        thread_id: usize,
+        options: Option<Params>,
+    ) -> impl Future<Output = Result<Value>> + Send;
+
+    /// Get a thread, with its articles (blocking)
+    fn thread_b(&self, thread_id: usize, options: Option<Params>) -> Result<Value>;
+
+    /// Get a geeklist (async)
+    #[cfg(feature = "async")]
+    fn geeklist(
+        &self,
+        list_id: usize,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send;
+
+    /// Get a geeklist (blocking)
+    fn geeklist_b(&self, list_id: usize, options: impl IntoParams + Send) -> Result<Value>;
+
+    /// Call an arbitrary endpoint, relative to the API prefix (async)
+    #[cfg(feature = "async")]
+    fn raw(&self, path: &str, params: Option<Params>)
+        -> impl Future<Output = Result<Value>> + Send;
+
+    /// Call an arbitrary endpoint, relative to the API prefix (blocking)
+    fn raw_b(&self, path: &str, params: Option<Params>) -> Result<Value>;
+}
+
+/// The endpoints of the version 2 API, as implemented by `Client2`
+pub trait BggApi2 {
+    /// Search for items by name (async)
+    #[cfg(feature = "async")]
+    fn search(
+        &self,
+        query: &str,
+        stypes: &Vec<Search>,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send;
+
+    /// Search for items by name (blocking)
+    fn search_b(
+        &self,
+        query: &str,
+        stypes: &Vec<Search>,
+        options: impl IntoParams + Send,
+    ) -> Result<Value>;
+
+    /// Get the details of one or more things by ID (async)
+    #[cfg(feature = "async")]
+    fn thing(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Thing>,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send;
+
+    /// Get the details of one or more things by ID (blocking)
+    fn thing_b(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Thing>,
+        options: impl IntoParams + Send,
+    ) -> Result<Value>;
+
+    /// Get the details of one or more families by ID (async)
+    #[cfg(feature = "async")]
+    fn family(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Family>,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send;
+
+    /// Get the details of one or more families by ID (blocking)
+    fn family_b(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Family>,
+        options: impl IntoParams + Send,
+    ) -> Result<Value>;
+
+    /// Get the list of forums for a thing or family (async)
+    #[cfg(feature = "async")]
+    fn forumlist(
+        &self,
+        game_id: usize,
+        ltype: ThingFamily,
+    ) -> impl Future<Output = Result<Value>> + Send;
+
+    /// Get the list of forums for a thing or family (blocking)
+    fn forumlist_b(&self, game_id: usize, ltype: ThingFamily) -> Result<Value>;
+
+    /// Get a page of the threads in a forum (async)
+    #[cfg(feature = "async")]
+    fn forum(
+        &self,
+        forum_id: usize,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send;
+
+    /// Get a page of the threads in a forum (blocking)
+    fn forum_b(&self, forum_id: usize, options: impl IntoParams + Send) -> Result<Value>;
+
+    /// Get a thread, with its articles (async)
+    #[cfg(feature = "async")]
+    fn thread(
+        &self,
+        thread_id: usize,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send;
+
+    /// Get a thread, with its articles (blocking)
+    fn thread_b(&self, thread_id: usize, options: impl IntoParams + Send) -> Result<Value>;
+
+    /// Get a user's profile (async)
+    #[cfg(feature = "async")]
+    fn user(
+        &self,
+        username: &str,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send;
+
+    /// Get a user's profile (blocking)
+    fn user_b(&self, username: &str, options: impl IntoParams + Send) -> Result<Value>;
+
+    /// Get a guild (async)
+    #[cfg(feature = "async")]
+    fn guild(
+        &self,
+        guild_id: usize,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send;
+
+    /// Get a guild (blocking)
+    fn guild_b(&self, guild_id: usize, options: impl IntoParams + Send) -> Result<Value>;
+
+    /// Get the logged plays for a user and/or item (async)
+    #[cfg(feature = "async")]
+    fn plays(
+        &self,
+        username: Option<&str>,
+        item_id: Option<usize>,
+        ttype: Option<ThingFamily>,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send;
+
+    /// Get the logged plays for a user and/or item (blocking)
+    fn plays_b(
+        &self,
+        username: Option<&str>,
+        item_id: Option<usize>,
+        ttype: Option<ThingFamily>,
+        options: impl IntoParams + Send,
+    ) -> Result<Value>;
+
+    /// Get a user's collection (async)
+    #[cfg(feature = "async")]
+    fn collection(
+        &self,
+        username: &str,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send;
+
+    /// Get a user's collection (blocking)
+    fn collection_b(&self, username: &str, options: impl IntoParams + Send) -> Result<Value>;
+
+    /// Get the latest hotness (async)
+    #[cfg(feature = "async")]
+    fn hot(
+        &self,
+        htype: Hotness,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send;
+
+    /// Get the latest hotness (blocking)
+    fn hot_b(&self, htype: Hotness, options: impl IntoParams + Send) -> Result<Value>;
+
+    /// Call an arbitrary endpoint, relative to the API prefix (async)
+    #[cfg(feature = "async")]
+    fn raw(&self, path: &str, params: Option<Params>)
+        -> impl Future<Output = Result<Value>> + Send;
+
+    /// Call an arbitrary endpoint, relative to the API prefix (blocking)
+    fn raw_b(&self, path: &str, params: Option<Params>) -> Result<Value>;
+}
+
+impl BggApi1 for Client1 {
+    #[cfg(feature = "async")]
+    fn search(
+        &self,
+        search: &str,
+        options: Option<Params>,
+    ) -> impl Future<Output = Result<Value>> + Send {
+        return Client1::search(self, search, options);
+    }
+
+    fn search_b(&self, search: &str, options: Option<Params>) -> Result<Value> {
+        return Client1::search_b(self, search, options);
+    }
+
+    #[cfg(feature = "async")]
+    fn boardgame(
+        &self,
+        game_ids: &Vec<usize>,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send {
+        return Client1::boardgame(self, game_ids, options);
+    }
+
+    fn boardgame_b(&self, game_ids: &Vec<usize>, options: impl IntoParams + Send) -> Result<Value> {
+        return Client1::boardgame_b(self, game_ids, options);
+    }
+
+    #[cfg(feature = "async")]
+    fn collection(
+        &self,
+        username: &str,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send {
+        return Client1::collection(self, username, options);
+    }
+
+    fn collection_b(&self, username: &str, options: impl IntoParams + Send) -> Result<Value> {
+        return Client1::collection_b(self, username, options);
+    }
+
+    #[cfg(feature = "async")]
+    fn thread(
+        &self,
+        thread_id: usize,
+        options: Option<Params>,
+    ) -> impl Future<Output = Result<Value>> + Send {
+        return Client1::thread(self, thread_id, options);
+    }
+
+    fn thread_b(&self, thread_id: usize, options: Option<Params>) -> Result<Value> {
+        return Client1::thread_b(self, thread_id, options);
+    }
+
+    #[cfg(feature = "async")]
+    fn geeklist(
+        &self,
+        list_id: usize,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send {
+        return Client1::geeklist(self, list_id, options);
+    }
+
+    fn geeklist_b(&self, list_id: usize, options: impl IntoParams + Send) -> Result<Value> {
+        return Client1::geeklist_b(self, list_id, options);
+    }
+
+    #[cfg(feature = "async")]
+    fn raw(
+        &self,
+        path: &str,
+        params: Option<Params>,
+    ) -> impl Future<Output = Result<Value>> + Send {
+        return Client1::raw(self, path, params);
+    }
+
+    fn raw_b(&self, path: &str, params: Option<Params>) -> Result<Value> {
+        return Client1::raw_b(self, path, params);
+    }
+}
+
+impl BggApi2 for Client2 {
+    #[cfg(feature = "async")]
+    fn search(
+        &self,
+        query: &str,
+        stypes: &Vec<Search>,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send {
+        return Client2::search(self, query, stypes, options);
+    }
+
+    fn search_b(
+        &self,
+        query: &str,
+        stypes: &Vec<Search>,
+        options: impl IntoParams + Send,
+    ) -> Result<Value> {
+        return Client2::search_b(self, query, stypes, options);
+    }
+
+    #[cfg(feature = "async")]
+    fn thing(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Thing>,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send {
+        return Client2::thing(self, ids, ttypes, options);
+    }
+
+    fn thing_b(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Thing>,
+        options: impl IntoParams + Send,
+    ) -> Result<Value> {
+        return Client2::thing_b(self, ids, ttypes, options);
+    }
+
+    #[cfg(feature = "async")]
+    fn family(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Family>,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send {
+        return Client2::family(self, ids, ttypes, options);
+    }
+
+    fn family_b(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Family>,
+        options: impl IntoParams + Send,
+    ) -> Result<Value> {
+        return Client2::family_b(self, ids, ttypes, options);
+    }
+
+    #[cfg(feature = "async")]
+    fn forumlist(
+        &self,
+        game_id: usize,
+        ltype: ThingFamily,
+    ) -> impl Future<Output = Result<Value>> + Send {
+        return Client2::forumlist(self, game_id, ltype);
+    }
+
+    fn forumlist_b(&self, game_id: usize, ltype: ThingFamily) -> Result<Value> {
+        return Client2::forumlist_b(self, game_id, ltype);
+    }
+
+    #[cfg(feature = "async")]
+    fn forum(
+        &self,
+        forum_id: usize,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send {
+        return Client2::forum(self, forum_id, options);
+    }
+
+    fn forum_b(&self, forum_id: usize, options: impl IntoParams + Send) -> Result<Value> {
+        return Client2::forum_b(self, forum_id, options);
+    }
+
+    #[cfg(feature = "async")]
+    fn thread(
+        &self,
+        thread_id: usize,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send {
+        return Client2::thread(self, thread_id, options);
+    }
+
+    fn thread_b(&self, thread_id: usize, options: impl IntoParams + Send) -> Result<Value> {
+        return Client2::thread_b(self, thread_id, options);
+    }
+
+    #[cfg(feature = "async")]
+    fn user(
+        &self,
+        username: &str,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send {
+        return Client2::user(self, username, options);
+    }
+
+    fn user_b(&self, username: &str, options: impl IntoParams + Send) -> Result<Value> {
+        return Client2::user_b(self, username, options);
+    }
+
+    #[cfg(feature = "async")]
+    fn guild(
+        &self,
+        guild_id: usize,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send {
+        return Client2::guild(self, guild_id, options);
+    }
+
+    fn guild_b(&self, guild_id: usize, options: impl IntoParams + Send) -> Result<Value> {
+        return Client2::guild_b(self, guild_id, options);
+    }
+
+    #[cfg(feature = "async")]
+    fn plays(
+        &self,
+        username: Option<&str>,
+        item_id: Option<usize>,
+        ttype: Option<ThingFamily>,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send {
+        return Client2::plays(self, username, item_id, ttype, options);
+    }
+
+    fn plays_b(
+        &self,
+        username: Option<&str>,
+        item_id: Option<usize>,
+        ttype: Option<ThingFamily>,
+        options: impl IntoParams + Send,
+    ) -> Result<Value> {
+        return Client2::plays_b(self, username, item_id, ttype, options);
+    }
+
+    #[cfg(feature = "async")]
+    fn collection(
+        &self,
+        username: &str,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send {
+        return Client2::collection(self, username, options);
+    }
+
+    fn collection_b(&self, username: &str, options: impl IntoParams + Send) -> Result<Value> {
+        return Client2::collection_b(self, username, options);
+    }
+
+    #[cfg(feature = "async")]
+    fn hot(
+        &self,
+        htype: Hotness,
+        options: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Value>> + Send {
+        return Client2::hot(self, htype, options);
+    }
+
+    fn hot_b(&self, htype: Hotness, options: impl IntoParams + Send) -> Result<Value> {
+        return Client2::hot_b(self, htype, options);
+    }
+
+    #[cfg(feature = "async")]
+    fn raw(
+        &self,
+        path: &str,
+        params: Option<Params>,
+    ) -> impl Future<Output = Result<Value>> + Send {
+        return Client2::raw(self, path, params);
+    }
+
+    fn raw_b(&self, path: &str, params: Option<Params>) -> Result<Value> {
+        return Client2::raw_b(self, path, params);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::HttpConfig;
+    use crate::retry::RetryPolicy;
+
+    fn config() -> HttpConfig {
+        return HttpConfig {
+            throttle: None,
+            retry: RetryPolicy::none(),
+            ..Default::default()
+        };
+    }
+
+    /// Something generic over the API, like downstream code would be
+    #[cfg(feature = "async")]
+    async fn hot(api: &(impl BggApi2 + Sync)) -> Result<Value> {
+        return api.hot(Hotness::BoardGame, None).await;
+    }
+
+    #[cfg(feature = "async")]
+    fn assert_send<T: Send>(_: &T) {}
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_api_traits() {
+        // Nothing is listening here, so these all fail, but they go through
+        // the traits
+        let cl2 =
+            Client2::new_with_config(Some("http://127.0.0.1:1".into()), None, config()).unwrap();
+        let fut = hot(&cl2);
+        assert_send(&fut);
+        assert!(fut.await.is_err());
+        assert!(BggApi2::forum(&cl2, 19, None).await.is_err());
+
+        let cl1 =
+            Client1::new_with_config(Some("http://127.0.0.1:1".into()), None, config()).unwrap();
+        assert!(BggApi1::geeklist(&cl1, 1, None).await.is_err());
+    }
+
+    #[test]
+    fn test_api_traits_b() {
+        let cl2 =
+            Client2::new_with_config(Some("http://127.0.0.1:1".into()), None, config()).unwrap();
+        assert!(BggApi2::hot_b(&cl2, Hotness::BoardGame, None).is_err());
+        assert!(BggApi2::forum_b(&cl2, 19, None).is_err());
+
+        let cl1 =
+            Client1::new_with_config(Some("http://127.0.0.1:1".into()), None, config()).unwrap();
+        assert!(BggApi1::raw_b(&cl1, "boardgame/1", None).is_err());
+    }
+}
@@ -19,22 +19,353 @@ let cl = bgg1::Client1::new(None, None);
 let opts = Params::from([("exact".to_string(), "1".to_string())]);
 let resp = cl.search_b("bruges", Some(opts)).unwrap();
 ```
+
+The boardgame, collection and geeklist calls also have a typed options
+builder, which can be passed anywhere the `Params` can.
+
+```ignore,rust
+use rbgg::bgg1::{BoardgameOptions, Client1};
+
+let cl = Client1::new_from_defaults();
+let opts = BoardgameOptions::new().stats(true).comments(true);
+let resp = cl.boardgame_b(&vec![136888], opts).unwrap();
+```
 */
 
-use crate::utils::{self, Params};
-use anyhow::Result;
+use crate::breaker::BreakerState;
+use crate::cancel::CancellationToken;
+use crate::errors::{RbggError, Result};
+use crate::http::{CallConfig, HttpClient, HttpConfig, Stats};
+use crate::models::GeekList;
+use crate::throttle::{Priority, ThrottleState};
+use crate::utils::{self, IntoParams, Params};
+use chrono::NaiveDate;
 use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::{Bound, RangeBounds};
+use std::time::Instant;
+
+/// Add the page number to the options for a geeklist request
+fn geeklist_page_opts(options: Option<Params>, page: usize) -> Params {
+    let mut opts = utils::get_opts(options);
+    opts.insert("page".into(), page.to_string());
+
+    return opts;
+}
+
+/// Typed options for `geeklist()`
+///
+/// ```ignore,rust
+/// let res = cl.geeklist_b(12345, GeekListOptions::new().comments(true))?;
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GeekListOptions {
+    comments: Option<bool>,
+}
+
+impl GeekListOptions {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    /// Include the comments on the list and its items
+    pub fn comments(mut self, val: bool) -> Self {
+        self.comments = Some(val);
+
+        return self;
+    }
+}
+
+impl IntoParams for GeekListOptions {
+    fn into_params(self) -> Result<Option<Params>> {
+        let mut ret = Params::new();
+        if let Some(comments) = self.comments {
+            ret.insert("comments".into(), utils::flag(comments));
+        }
+
+        return Ok(Some(ret));
+    }
+}
+
+/// Typed options for `collection()`, following the v1 API docs.  The status
+/// filters (`own()`, `wishlist()`, etc.) include only matching items when set
+/// to true, and exclude them when set to false.
+///
+/// ```ignore,rust
+/// let opts = CollectionOptions1::new().own(true).rating(7.0..);
+/// let res = cl.collection_b("someuser", opts)?;
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CollectionOptions1 {
+    flags: BTreeMap<&'static str, bool>,
+    wishlist_priority: Option<u8>,
+    rating: Option<(Bound<f64>, Bound<f64>)>,
+    bgg_rating: Option<(Bound<f64>, Bound<f64>)>,
+    plays: Option<(Bound<u32>, Bound<u32>)>,
+}
+
+impl CollectionOptions1 {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    pub fn own(self, val: bool) -> Self {
+        return self.flag("own", val);
+    }
+
+    pub fn rated(self, val: bool) -> Self {
+        return self.flag("rated", val);
+    }
+
+    pub fn played(self, val: bool) -> Self {
+        return self.flag("played", val);
+    }
+
+    /// Items the user has commented on
+    pub fn comment(self, val: bool) -> Self {
+        return self.flag("comment", val);
+    }
+
+    /// Items marked for trade
+    pub fn trade(self, val: bool) -> Self {
+        return self.flag("trade", val);
+    }
+
+    /// Items the user wants in trade
+    pub fn want(self, val: bool) -> Self {
+        return self.flag("want", val);
+    }
+
+    pub fn wishlist(self, val: bool) -> Self {
+        return self.flag("wishlist", val);
+    }
+
+    pub fn preordered(self, val: bool) -> Self {
+        return self.flag("preordered", val);
+    }
+
+    pub fn want_to_play(self, val: bool) -> Self {
+        return self.flag("wanttoplay", val);
+    }
+
+    pub fn want_to_buy(self, val: bool) -> Self {
+        return self.flag("wanttobuy", val);
+    }
+
+    pub fn prev_owned(self, val: bool) -> Self {
+        return self.flag("prevowned", val);
+    }
+
+    pub fn has_parts(self, val: bool) -> Self {
+        return self.flag("hasparts", val);
+    }
+
+    pub fn want_parts(self, val: bool) -> Self {
+        return self.flag("wantparts", val);
+    }
+
+    /// Include private info (requires being logged in as the user)
+    pub fn show_private(self, val: bool) -> Self {
+        return self.flag("showprivate", val);
+    }
+
+    /// Only wishlist items of this priority, from 1 (must have) to 5
+    /// (don't buy this)
+    pub fn wishlist_priority(mut self, priority: u8) -> Self {
+        self.wishlist_priority = Some(priority);
+
+        return self;
+    }
+
+    /// Only items the user rated within this range (e.g. `7.0..=9.0` or
+    /// `8.0..`)
+    pub fn rating<R: RangeBounds<f64>>(mut self, range: R) -> Self {
+        self.rating = Some((range.start_bound().cloned(), range.end_bound().cloned()));
+
+        return self;
+    }
+
+    /// Only items with a BGG rating within this range
+    pub fn bgg_rating<R: RangeBounds<f64>>(mut self, range: R) -> Self {
+        self.bgg_rating = Some((range.start_bound().cloned(), range.end_bound().cloned()));
+
+        return self;
+    }
+
+    /// Only items the user has played this many times (e.g. `1..10`)
+    pub fn plays<R: RangeBounds<u32>>(mut self, range: R) -> Self {
+        self.plays = Some((range.start_bound().cloned(), range.end_bound().cloned()));
+
+        return self;
+    }
+
+    fn flag(mut self, key: &'static str, val: bool) -> Self {
+        self.flags.insert(key, val);
+
+        return self;
+    }
+}
+
+impl IntoParams for CollectionOptions1 {
+    fn into_params(self) -> Result<Option<Params>> {
+        let mut ret = Params::new();
+
+        for (key, val) in &self.flags {
+            ret.insert(key.to_string(), utils::flag(*val));
+        }
+        if let Some(priority) = self.wishlist_priority {
+            if !(1..=5).contains(&priority) {
+                return Err(RbggError::InvalidInput(format!(
+                    "The wishlist priority must be from 1 to 5, not {}",
+                    priority
+                )));
+            }
+            ret.insert("wishlistpriority".into(), priority.to_string());
+        }
+        if let Some(range) = self.rating {
+            utils::range_params(&mut ret, &range, "minrating", "rating", None)?;
+        }
+        if let Some(range) = self.bgg_rating {
+            utils::range_params(&mut ret, &range, "minbggrating", "bggrating", None)?;
+        }
+        if let Some(range) = self.plays {
+            utils::range_params(&mut ret, &range, "minplays", "maxplays", Some(1))?;
+        }
+
+        return Ok(Some(ret));
+    }
+}
+
+/// Typed options for `boardgame()`
+///
+/// ```ignore,rust
+/// let opts = BoardgameOptions::new()
+///     .stats(true)
+///     .historical(true)
+///     .from(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap())
+///     .to(NaiveDate::from_ymd_opt(2024, 6, 30).unwrap());
+/// let res = cl.boardgame_b(&vec![136888], opts)?;
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BoardgameOptions {
+    flags: BTreeMap<&'static str, bool>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+}
+
+impl BoardgameOptions {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    /// Include the comments on the game
+    pub fn comments(self, val: bool) -> Self {
+        return self.flag("comments", val);
+    }
+
+    /// Include the ratings and other stats for the game
+    pub fn stats(self, val: bool) -> Self {
+        return self.flag("stats", val);
+    }
+
+    /// Include the historical rankings for the game, over the range given
+    /// by `from()` and `to()`
+    pub fn historical(self, val: bool) -> Self {
+        return self.flag("historical", val);
+    }
+
+    /// Include the marketplace price history for the game
+    pub fn price_history(self, val: bool) -> Self {
+        return self.flag("pricehistory", val);
+    }
+
+    /// Include the current marketplace listings for the game
+    pub fn marketplace(self, val: bool) -> Self {
+        return self.flag("marketplace", val);
+    }
+
+    /// The start of the historical range
+    pub fn from(mut self, date: NaiveDate) -> Self {
+        self.from = Some(date);
+
+        return self;
+    }
+
+    /// The end of the historical range
+    pub fn to(mut self, date: NaiveDate) -> Self {
+        self.to = Some(date);
+
+        return self;
+    }
+
+    fn flag(mut self, key: &'static str, val: bool) -> Self {
+        self.flags.insert(key, val);
+
+        return self;
+    }
+}
+
+impl IntoParams for BoardgameOptions {
+    fn into_params(self) -> Result<Option<Params>> {
+        let mut ret = Params::new();
+
+        for (key, val) in &self.flags {
+            ret.insert(key.to_string(), utils::flag(*val));
+        }
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from > to {
+                return Err(RbggError::InvalidInput(format!(
+                    "The from date ({}) is after the to date ({})",
+                    from, to
+                )));
+            }
+        }
+        if let Some(date) = self.from {
+            ret.insert("from".into(), date.format("%Y-%m-%d").to_string());
+        }
+        if let Some(date) = self.to {
+            ret.insert("to".into(), date.format("%Y-%m-%d").to_string());
+        }
+
+        return Ok(Some(ret));
+    }
+}
 
 /// A representation of a client to hold the url info for accessing the API
+#[derive(Clone)]
 pub struct Client1 {
     pub url_base: String,
     pub api_prefix: String,
+    http: HttpClient,
+    endpoint_defaults: HashMap<String, Params>,
 }
 
 impl Client1 {
     /// If the url_base or api_prefix are not supplied, the defaults will be
     /// used instead ("https://boardgamegeek.com" and "xmlapi", respectively)
     pub fn new(url_base: Option<String>, api_prefix: Option<String>) -> Self {
+        return Self::new_with_http(url_base, api_prefix, HttpClient::default());
+    }
+
+    /// Like `new()`, but with a custom configuration for the underlying
+    /// HTTP client
+    pub fn new_with_config(
+        url_base: Option<String>,
+        api_prefix: Option<String>,
+        config: HttpConfig,
+    ) -> Result<Self> {
+        let http = HttpClient::new(config)?;
+
+        return Ok(Self::new_with_http(url_base, api_prefix, http));
+    }
+
+    /// Like `new()`, but using an existing `HttpClient`.  This allows
+    /// multiple API clients to share the same connection pools and settings.
+    pub fn new_with_http(
+        url_base: Option<String>,
+        api_prefix: Option<String>,
+        http: HttpClient,
+    ) -> Self {
         let ub;
         let prefix;
 
@@ -56,6 +387,8 @@ impl Client1 {
         return Self {
             url_base: ub,
             api_prefix: prefix,
+            http,
+            endpoint_defaults: HashMap::new(),
         };
     }
 
@@ -65,6 +398,7 @@ impl Client1 {
     }
 
     /// Search for a game on BGG and return the JSON response
+    #[cfg(feature = "async")]
     pub async fn search(&self, search: &str, options: Option<Params>) -> Result<Value> {
         let url = self.get_full_url(
             "search".into(),
@@ -72,7 +406,7 @@ impl Client1 {
             Some(Params::from([("search".into(), search.into())])),
             None,
         );
-        let data = utils::get_json_resp(&url).await?;
+        let data = self.http.get_json(&url).await?;
 
         return Ok(data);
     }
@@ -85,60 +419,79 @@ impl Client1 {
             Some(Params::from([("search".into(), search.into())])),
             None,
         );
-        let data = utils::get_json_resp_b(&url)?;
+        let data = self.http.get_json_b(&url)?;
 
         return Ok(data);
     }
 
     /// Async retrieve information about a particular game given its game ID(s).
     /// Note that you pass in a vec of game IDs here as you can get info on
-    /// more than 1 game in a single call
-    pub async fn boardgame(&self, game_ids: &Vec<usize>, options: Option<Params>) -> Result<Value> {
+    /// more than 1 game in a single call.  If none of them are found, this
+    /// returns `RbggError::NotFound`.
+    #[cfg(feature = "async")]
+    pub async fn boardgame(
+        &self,
+        game_ids: &Vec<usize>,
+        options: impl IntoParams,
+    ) -> Result<Value> {
         // Convert the int vec to Vec<&str>
         let ids: Vec<String> = game_ids.iter().map(|i| i.to_string()).collect();
-        let url = self.get_full_url("boardgame".into(), options, None, Some(&ids));
-        let data = utils::get_json_resp(&url).await?;
+        let url = self.get_full_url("boardgame".into(), options.into_params()?, None, Some(&ids));
+        let res = self.http.get_json(&url).await;
 
-        return Ok(data);
+        return utils::check_found(res, "boardgame", &ids.join(","));
     }
 
     /// Retrieve information about a particular game given its game ID(s).
     /// Note that you pass in a vec of game IDs here as you can get info on
-    /// more than 1 game in a single call
-    pub fn boardgame_b(&self, game_ids: &Vec<usize>, options: Option<Params>) -> Result<Value> {
+    /// more than 1 game in a single call.  If none of them are found, this
+    /// returns `RbggError::NotFound`.
+    pub fn boardgame_b(&self, game_ids: &Vec<usize>, options: impl IntoParams) -> Result<Value> {
         // Convert the int vec to Vec<&str>
         let ids: Vec<String> = game_ids.iter().map(|i| i.to_string()).collect();
-        let url = self.get_full_url("boardgame".into(), options, None, Some(&ids));
-        let data = utils::get_json_resp_b(&url)?;
+        let url = self.get_full_url("boardgame".into(), options.into_params()?, None, Some(&ids));
+        let res = self.http.get_json_b(&url);
 
-        return Ok(data);
+        return utils::check_found(res, "boardgame", &ids.join(","));
     }
 
     /// Async retrieve a user's collection.  Note that there are a variety of
-    /// different parameters that can be used here.
-    pub async fn collection(&self, username: &str, options: Option<Params>) -> Result<Value> {
+    /// different parameters that can be used here, see `CollectionOptions1`.
+    #[cfg(feature = "async")]
+    pub async fn collection(&self, username: &str, options: impl IntoParams) -> Result<Value> {
         let addons = vec![username.to_string()];
-        let url = self.get_full_url("collection".into(), options, None, Some(&addons));
-        let data = utils::get_json_resp(&url).await?;
+        let url = self.get_full_url(
+            "collection".into(),
+            options.into_params()?,
+            None,
+            Some(&addons),
+        );
+        let data = self.http.get_json(&url).await?;
 
         return Ok(data);
     }
 
     /// Retrieve a user's collection.  Note that there are a variety of
-    /// different parameters that can be used here.
-    pub fn collection_b(&self, username: &str, options: Option<Params>) -> Result<Value> {
+    /// different parameters that can be used here, see `CollectionOptions1`.
+    pub fn collection_b(&self, username: &str, options: impl IntoParams) -> Result<Value> {
         let addons = vec![username.to_string()];
-        let url = self.get_full_url("collection".into(), options, None, Some(&addons));
-        let data = utils::get_json_resp_b(&url)?;
+        let url = self.get_full_url(
+            "collection".into(),
+            options.into_params()?,
+            None,
+            Some(&addons),
+        );
+        let data = self.http.get_json_b(&url)?;
 
         return Ok(data);
     }
 
     /// Async get a forum/game thread.  Note that the thread ID is an int
+    #[cfg(feature = "async")]
     pub async fn thread(&self, thread_id: usize, options: Option<Params>) -> Result<Value> {
         let addons = vec![thread_id.to_string()];
         let url = self.get_full_url("thread".into(), options, None, Some(&addons));
-        let data = utils::get_json_resp(&url).await?;
+        let data = self.http.get_json(&url).await?;
 
         return Ok(data);
     }
@@ -147,29 +500,201 @@ impl Client1 {
     pub fn thread_b(&self, thread_id: usize, options: Option<Params>) -> Result<Value> {
         let addons = vec![thread_id.to_string()];
         let url = self.get_full_url("thread".into(), options, None, Some(&addons));
-        let data = utils::get_json_resp_b(&url)?;
+        let data = self.http.get_json_b(&url)?;
 
         return Ok(data);
     }
 
     /// Async get a geeklist.  Note that the list ID is an int
-    pub async fn geeklist(&self, list_id: usize, options: Option<Params>) -> Result<Value> {
+    #[cfg(feature = "async")]
+    pub async fn geeklist(&self, list_id: usize, options: impl IntoParams) -> Result<Value> {
         let addons = vec![list_id.to_string()];
-        let url = self.get_full_url("thread".into(), options, None, Some(&addons));
-        let data = utils::get_json_resp(&url).await?;
+        let url = self.get_full_url(
+            "geeklist".into(),
+            options.into_params()?,
+            None,
+            Some(&addons),
+        );
+        let data = self.http.get_json(&url).await?;
 
         return Ok(data);
     }
 
     /// Get a geeklist.  Note that the list ID is an int
-    pub fn geeklist_b(&self, list_id: usize, options: Option<Params>) -> Result<Value> {
+    pub fn geeklist_b(&self, list_id: usize, options: impl IntoParams) -> Result<Value> {
         let addons = vec![list_id.to_string()];
-        let url = self.get_full_url("thread".into(), options, None, Some(&addons));
-        let data = utils::get_json_resp_b(&url)?;
+        let url = self.get_full_url(
+            "geeklist".into(),
+            options.into_params()?,
+            None,
+            Some(&addons),
+        );
+        let data = self.http.get_json_b(&url)?;
 
         return Ok(data);
     }
 
+    /// Return a clone of this client whose requests are queued at the given
+    /// priority when a throttle is configured.  The clone shares the
+    /// throttle, and everything else, with this client.
+    pub fn with_priority(&self, priority: Priority) -> Self {
+        let mut ret = self.clone();
+        ret.http = self.http.with_priority(priority);
+
+        return ret;
+    }
+
+    /// Return a clone of this client that applies the given per-call
+    /// settings (e.g. `CallConfig::no_cache()`) to every call made through
+    /// it.  The clone shares the HTTP client, throttle, etc. with the
+    /// original.
+    pub fn with_call_config(&self, config: &CallConfig) -> Self {
+        let mut ret = self.clone();
+        ret.http = self.http.with_call_config(config);
+
+        return ret;
+    }
+
+    /// Return a clone of this client whose calls are stopped, with
+    /// `RbggError::Cancelled`, when the token is cancelled.  See `cancel`.
+    pub fn with_cancel(&self, token: &CancellationToken) -> Self {
+        let mut ret = self.clone();
+        ret.http = self.http.with_cancel(token);
+
+        return ret;
+    }
+
+    /// Return a clone of this client whose calls are stopped, with
+    /// `RbggError::DeadlineExceeded`, if they're still going at the deadline
+    pub fn with_deadline(&self, deadline: Instant) -> Self {
+        let mut ret = self.clone();
+        ret.http = self.http.with_deadline(deadline);
+
+        return ret;
+    }
+
+    /// Return a snapshot of the request counters (retries, 202 waits, etc.)
+    /// for this client
+    pub fn stats(&self) -> Stats {
+        return self.http.stats();
+    }
+
+    /// Return a snapshot of the circuit breaker, or `None` if this client
+    /// doesn't have one
+    pub fn breaker_state(&self) -> Option<BreakerState> {
+        return self.http.breaker_state();
+    }
+
+    /// Return a snapshot of the throttle (its current rate, etc.), or `None`
+    /// if this client isn't throttled
+    pub fn throttle_state(&self) -> Option<ThrottleState> {
+        return self.http.throttle_state();
+    }
+
+    /// Async get a geeklist, parsed into the typed model.  This is a single
+    /// request, so long lists may not have all of their items; see
+    /// `geeklist_all()` for those.
+    #[cfg(feature = "async")]
+    pub async fn geeklist_typed(
+        &self,
+        list_id: usize,
+        options: impl IntoParams,
+    ) -> Result<GeekList> {
+        let data = self.geeklist(list_id, options).await?;
+
+        return GeekList::from_value(&data);
+    }
+
+    /// Get a geeklist, parsed into the typed model.  This is a single
+    /// request, so long lists may not have all of their items; see
+    /// `geeklist_all_b()` for those.
+    pub fn geeklist_typed_b(&self, list_id: usize, options: impl IntoParams) -> Result<GeekList> {
+        let data = self.geeklist_b(list_id, options)?;
+
+        return GeekList::from_value(&data);
+    }
+
+    /// Get (async) a geeklist, along with all of its items.  Long lists
+    /// may take multiple requests, which are made until all of the items
+    /// have been fetched.  Use `GeekListOptions::new().comments(true)` to
+    /// include the comments on the list and each of its items.
+    #[cfg(feature = "async")]
+    pub async fn geeklist_all(&self, list_id: usize, options: impl IntoParams) -> Result<GeekList> {
+        let options = options.into_params()?;
+        let mut page = 1;
+        let mut ret = GeekList::from_value(&self.geeklist(list_id, options.clone()).await?)?;
+
+        while !ret.is_complete() {
+            page += 1;
+            let opts = geeklist_page_opts(options.clone(), page);
+            let next = GeekList::from_value(&self.geeklist(list_id, Some(opts)).await?)?;
+
+            // If BGG didn't give us anything new, we're done
+            if ret.merge(next) == 0 {
+                break;
+            }
+        }
+
+        return Ok(ret);
+    }
+
+    /// Get a geeklist, along with all of its items.  Long lists may take
+    /// multiple requests, which are made until all of the items have been
+    /// fetched.  Use `GeekListOptions::new().comments(true)` to include the
+    /// comments on the list and each of its items.
+    pub fn geeklist_all_b(&self, list_id: usize, options: impl IntoParams) -> Result<GeekList> {
+        let options = options.into_params()?;
+        let mut page = 1;
+        let mut ret = GeekList::from_value(&self.geeklist_b(list_id, options.clone())?)?;
+
+        while !ret.is_complete() {
+            page += 1;
+            let opts = geeklist_page_opts(options.clone(), page);
+            let next = GeekList::from_value(&self.geeklist_b(list_id, Some(opts))?)?;
+
+            // If BGG didn't give us anything new, we're done
+            if ret.merge(next) == 0 {
+                break;
+            }
+        }
+
+        return Ok(ret);
+    }
+
+    /// Call (async) an arbitrary endpoint, for anything this crate doesn't
+    /// wrap (yet).  The `path` is relative to the API prefix (e.g. "hot"),
+    /// and the request goes through the same throttling and conversion as
+    /// every other call.
+    #[cfg(feature = "async")]
+    pub async fn raw(&self, path: &str, params: Option<Params>) -> Result<Value> {
+        let url = self.get_full_url(path.trim_matches('/').into(), params, None, None);
+
+        return self.http.get_json(&url).await;
+    }
+
+    /// Call (sync) an arbitrary endpoint, for anything this crate doesn't
+    /// wrap (yet).  The `path` is relative to the API prefix (e.g. "hot"),
+    /// and the request goes through the same throttling and conversion as
+    /// every other call.
+    pub fn raw_b(&self, path: &str, params: Option<Params>) -> Result<Value> {
+        let url = self.get_full_url(path.trim_matches('/').into(), params, None, None);
+
+        return self.http.get_json_b(&url);
+    }
+
+    /// Register default options for the given endpoint (e.g. "thing" or
+    /// "guild"), replacing any previously set for it.  These are used for
+    /// every call to that endpoint, beneath any options passed to the call
+    /// itself, so a call can always override them.
+    pub fn set_endpoint_defaults(&mut self, endpoint: &str, params: Params) {
+        self.endpoint_defaults.insert(endpoint.to_string(), params);
+    }
+
+    /// Return the default options registered for the given endpoint, if any
+    pub fn endpoint_defaults(&self, endpoint: &str) -> Option<&Params> {
+        return self.endpoint_defaults.get(endpoint);
+    }
+
     /* Begin private functions */
 
     /// A private function for building a URL given the action that is being
@@ -206,7 +731,13 @@ impl Client1 {
         default_params: Option<Params>,
         uri_addons: Option<&Vec<String>>,
     ) -> String {
-        let mut opts = utils::get_opts(params);
+        let mut opts = self
+            .endpoint_defaults
+            .get(&path)
+            .cloned()
+            .unwrap_or_default();
+        // Options for the call override the endpoint defaults
+        opts.extend(utils::get_opts(params));
         // Add the default options
         if let Some(def_params) = default_params {
             for (k, v) in &def_params {
@@ -292,4 +823,92 @@ mod tests {
             "https://boardgamegeek.com/xmlapi/boardgame/a,b,c?comments=1".to_string()
         );
     }
+
+    #[test]
+    fn test_endpoint_defaults() {
+        let mut cl = Client1::new_from_defaults();
+        cl.set_endpoint_defaults("boardgame", Params::from([("stats".into(), "1".into())]));
+
+        let ids = vec!["1".to_string()];
+        let url = cl.get_full_url("boardgame".into(), None, None, Some(&ids));
+        assert_eq!(url, "https://boardgamegeek.com/xmlapi/boardgame/1?stats=1");
+
+        let url = cl.get_full_url(
+            "boardgame".into(),
+            Some(Params::from([("stats".into(), "0".into())])),
+            None,
+            Some(&ids),
+        );
+        assert_eq!(url, "https://boardgamegeek.com/xmlapi/boardgame/1?stats=0");
+    }
+
+    #[test]
+    fn test_geeklist_options() {
+        let cl = Client1::new_from_defaults();
+        let opts = GeekListOptions::new().comments(true).into_params().unwrap();
+        let url = cl.get_full_url("geeklist".into(), opts, None, Some(&vec!["123".into()]));
+
+        assert_eq!(
+            url,
+            "https://boardgamegeek.com/xmlapi/geeklist/123?comments=1"
+        );
+        assert_eq!(
+            GeekListOptions::new().into_params().unwrap(),
+            Some(Params::new())
+        );
+    }
+
+    #[test]
+    fn test_collection_options() {
+        let opts = CollectionOptions1::new()
+            .own(true)
+            .want_to_play(false)
+            .wishlist_priority(2)
+            .rating(7.0..=9.5)
+            .plays(1..10)
+            .into_params()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(opts["own"], "1");
+        assert_eq!(opts["wanttoplay"], "0");
+        assert_eq!(opts["wishlistpriority"], "2");
+        assert_eq!(opts["minrating"], "7");
+        assert_eq!(opts["rating"], "9.5");
+        assert_eq!(opts["minplays"], "1");
+        assert_eq!(opts["maxplays"], "9");
+
+        assert!(CollectionOptions1::new()
+            .wishlist_priority(6)
+            .into_params()
+            .is_err());
+    }
+
+    #[test]
+    fn test_boardgame_options() {
+        let jan = NaiveDate::from_ymd_opt(2024, 1, 5).unwrap();
+        let jun = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+        let opts = BoardgameOptions::new()
+            .stats(true)
+            .historical(true)
+            .price_history(false)
+            .from(jan)
+            .to(jun)
+            .into_params()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(opts["stats"], "1");
+        assert_eq!(opts["historical"], "1");
+        assert_eq!(opts["pricehistory"], "0");
+        assert_eq!(opts["from"], "2024-01-05");
+        assert_eq!(opts["to"], "2024-06-30");
+        assert!(!opts.contains_key("comments"));
+
+        assert!(BoardgameOptions::new()
+            .from(jun)
+            .to(jan)
+            .into_params()
+            .is_err());
+    }
 }
@@ -18,14 +18,55 @@ let cl = bgg2::Client2::new(None, None);
 let opts = Params::from([("exact".to_string(), "1".to_string())]);
 let resp = cl.search_b("bruges", Some(opts)).unwrap();
 ```
+
+Many calls also have a typed options builder, which avoids having to know
+the raw key names and value formats.  These can be passed anywhere the
+`Params` can.
+
+```ignore,rust
+use rbgg::bgg2::{Client2, Search, SearchOptions};
+
+let cl = Client2::new_from_defaults();
+let opts = SearchOptions::new().exact(true);
+let resp = cl.search_b("bruges", &vec![Search::BoardGame], opts).unwrap();
+```
 */
 
-use crate::utils::{self, Params};
-use anyhow::{anyhow, Result};
+use crate::analysis::{self, WishlistGroup};
+use crate::breaker::{BreakerConfig, BreakerState};
+use crate::cache::{MemoryCache, ResponseCache};
+use crate::cancel::CancellationToken;
+use crate::errors::{RbggError, Result};
+use crate::http::{ApiResponse, CallConfig, HttpClient, HttpConfig, Stats};
+use crate::metrics::MetricsSink;
+use crate::models::{
+    Article, Collection, Comment, Forum, Guild, Play, Plays, SearchResults, ThingItem, Things,
+    Thread, ThreadSummary,
+};
+use crate::retry::{QueuePolicy, RetryPolicy};
+use crate::throttle::{Priority, ThrottleConfig, ThrottleState};
+use crate::transport::HttpTransport;
+use crate::utils::{self, IntoParams, Paginator, Params};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+#[cfg(feature = "async")]
+use futures_util::stream::{self, Stream, StreamExt};
 use serde_json::Value;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::fmt;
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::io::Write;
+use std::ops::{Bound, RangeBounds};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+#[cfg(feature = "async")]
+use tokio::io::AsyncWrite;
 
 /// This is used mainly for raw thing() calls
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Thing {
     BoardGame,
     BoardGameExpansion,
@@ -59,6 +100,7 @@ impl fmt::Display for Thing {
 }
 
 /// This is used for search() calls
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Search {
     BoardGame,
     BoardGameExpansion,
@@ -90,6 +132,7 @@ impl fmt::Display for Search {
 }
 
 /// This is for use with the raw family() call
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Family {
     Rpg,
     RpgPeriodical,
@@ -117,6 +160,7 @@ impl fmt::Display for Family {
 }
 
 /// This is for use with some calls
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ThingFamily {
     Thing,
     Family,
@@ -141,6 +185,7 @@ impl fmt::Display for ThingFamily {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Hotness {
     BoardGame,
     Rpg,
@@ -177,562 +222,3038 @@ impl fmt::Display for Hotness {
     }
 }
 
-/// A representation of a client to hold the url info for accessing the API
-pub struct Client2 {
-    pub url_base: String,
-    pub api_prefix: String,
+/// The site a user's hot and top lists are for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Domain {
+    BoardGame,
+    Rpg,
+    VideoGame,
 }
 
-impl Client2 {
-    /// If the url_base or api_prefix are not supplied, the defaults will be
-    /// used instead ("https://boardgamegeek.com" and "xmlapi2", respectively)
-    pub fn new(url_base: Option<String>, api_prefix: Option<String>) -> Self {
-        let ub;
-        let prefix;
-
-        if let Some(u) = url_base {
-            ub = match u.strip_suffix('/') {
-                Some(stripped) => stripped.to_string(),
-                None => u,
-            };
-        } else {
-            ub = "https://boardgamegeek.com".to_string();
-        }
-
-        if let Some(p) = api_prefix {
-            prefix = p.as_str().trim_matches('/').to_string();
-        } else {
-            prefix = "xmlapi2".to_string();
-        }
-
-        return Self {
-            url_base: ub,
-            api_prefix: prefix,
+impl Domain {
+    pub fn as_str(&self) -> &'static str {
+        return match self {
+            Domain::BoardGame => "boardgame",
+            Domain::Rpg => "rpg",
+            Domain::VideoGame => "videogame",
         };
     }
+}
 
-    pub fn new_from_defaults() -> Self {
-        return Self::new(None, None);
+impl fmt::Display for Domain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "{}", self.as_str());
     }
+}
 
-    /// Search (async) the site for the given query and search types
-    pub async fn search(
-        &self,
-        query: &str,
-        stypes: &Vec<Search>,
-        options: Option<Params>,
-    ) -> Result<Value> {
-        let params = Params::from([
-            ("query".into(), query.into()),
-            (
-                "type".into(),
-                stypes
-                    .iter()
-                    .map(|t| t.as_str())
-                    .collect::<Vec<&'static str>>()
-                    .join(","),
-            ),
-        ]);
-
-        let url = self.get_full_url("search".into(), options, Some(params));
-
-        let data = utils::get_json_resp(&url).await?;
+/// Typed options for `search()`
+///
+/// ```ignore,rust
+/// let opts = SearchOptions::new().exact(true);
+/// let res = cl.search_b("bruges", &vec![Search::BoardGame], opts)?;
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    exact: Option<bool>,
+}
 
-        return Ok(data);
+impl SearchOptions {
+    pub fn new() -> Self {
+        return Self::default();
     }
 
-    /// Search (async) the site for the given query and search types
-    pub fn search_b(
-        &self,
-        query: &str,
-        stypes: &Vec<Search>,
-        options: Option<Params>,
-    ) -> Result<Value> {
-        let params = Params::from([
-            ("query".into(), query.into()),
-            (
-                "type".into(),
-                stypes
-                    .iter()
-                    .map(|t| t.as_str())
-                    .collect::<Vec<&'static str>>()
-                    .join(","),
-            ),
-        ]);
-
-        let url = self.get_full_url("search".into(), options, Some(params));
+    /// Only return items whose name matches the query exactly
+    pub fn exact(mut self, exact: bool) -> Self {
+        self.exact = Some(exact);
 
-        let data = utils::get_json_resp_b(&url)?;
-
-        return Ok(data);
+        return self;
     }
+}
 
-    /* Begin "thing"s */
+impl IntoParams for SearchOptions {
+    fn into_params(self) -> Result<Option<Params>> {
+        let mut ret = Params::new();
+        if let Some(exact) = self.exact {
+            ret.insert("exact".into(), utils::flag(exact));
+        }
 
-    /// This is the core (async) function for getting various "things" as
-    /// described by the BGG API.  It's also possible to use the convenience
-    /// functions like `boardgame()` instead, which will set the thing type
-    /// for you.
-    pub async fn thing(
-        &self,
-        ids: &Vec<usize>,
-        ttypes: &Vec<Thing>,
-        options: Option<Params>,
-    ) -> Result<Value> {
-        // Convert the numeric ids to strings
-        let sids: Vec<String> = ids.iter().map(|i| i.to_string()).collect();
-        let params = Params::from([
-            ("id".into(), sids.join(",")),
-            (
-                "type".into(),
-                ttypes
-                    .iter()
-                    .map(|t| t.as_str())
-                    .collect::<Vec<&'static str>>()
-                    .join(","),
-            ),
-        ]);
-        let url = self.get_full_url("thing".into(), options, Some(params));
+        return Ok(Some(ret));
+    }
+}
 
-        let data = utils::get_json_resp(&url).await?;
+/// Typed options for `collection()`.  The status filters (`own()`,
+/// `wishlist()`, etc.) include only matching items when set to true, and
+/// exclude them when set to false.
+///
+/// ```ignore,rust
+/// let opts = CollectionOptions::new()
+///     .own(true)
+///     .stats(true)
+///     .rating(7.0..)
+///     .modified_since(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+/// let res = cl.collection_b("someuser", opts)?;
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CollectionOptions {
+    flags: BTreeMap<&'static str, bool>,
+    subtype: Option<Thing>,
+    exclude_subtype: Option<Thing>,
+    ids: Vec<usize>,
+    coll_id: Option<usize>,
+    wishlist_priority: Option<u8>,
+    rating: Option<(Bound<f64>, Bound<f64>)>,
+    bgg_rating: Option<(Bound<f64>, Bound<f64>)>,
+    plays: Option<(Bound<u32>, Bound<u32>)>,
+    modified_since: Option<NaiveDateTime>,
+}
 
-        return Ok(data);
+impl CollectionOptions {
+    pub fn new() -> Self {
+        return Self::default();
     }
 
-    /// This is the core (sync) function for getting various "things" as
-    /// described by the BGG API.  It's also possible to use the convenience
-    /// functions like `boardgame()` instead, which will set the thing type
-    /// for you.
-    pub fn thing_b(
-        &self,
-        ids: &Vec<usize>,
-        ttypes: &Vec<Thing>,
-        options: Option<Params>,
-    ) -> Result<Value> {
-        // Convert the numeric ids to strings
-        let sids: Vec<String> = ids.iter().map(|i| i.to_string()).collect();
-        let params = Params::from([
-            ("id".into(), sids.join(",")),
-            (
-                "type".into(),
-                ttypes
-                    .iter()
-                    .map(|t| t.as_str())
-                    .collect::<Vec<&'static str>>()
-                    .join(","),
-            ),
-        ]);
-        let url = self.get_full_url("thing".into(), options, Some(params));
-
-        let data = utils::get_json_resp_b(&url)?;
+    /// Include version info for each item
+    pub fn version(self, val: bool) -> Self {
+        return self.flag("version", val);
+    }
 
-        return Ok(data);
+    /// Only return the name and status of each item
+    pub fn brief(self, val: bool) -> Self {
+        return self.flag("brief", val);
     }
 
-    /// A (async) convenience function for getting the info for a board game
-    pub async fn boardgame(&self, ids: &Vec<usize>, options: Option<Params>) -> Result<Value> {
-        return self.thing(ids, &vec![Thing::BoardGame], options).await;
+    /// Include the ratings and other stats for each item
+    pub fn stats(self, val: bool) -> Self {
+        return self.flag("stats", val);
     }
 
-    /// A (sync) convenience function for getting the info for a board game
-    pub fn boardgame_b(&self, ids: &Vec<usize>, options: Option<Params>) -> Result<Value> {
-        return self.thing_b(ids, &vec![Thing::BoardGame], options);
+    pub fn own(self, val: bool) -> Self {
+        return self.flag("own", val);
     }
 
-    /// A (async) convenience function for getting the info for a board game
-    /// expansion
-    pub async fn boardgameexpansion(
-        &self,
-        ids: &Vec<usize>,
-        options: Option<Params>,
-    ) -> Result<Value> {
-        return self
-            .thing(ids, &vec![Thing::BoardGameExpansion], options)
-            .await;
+    pub fn rated(self, val: bool) -> Self {
+        return self.flag("rated", val);
     }
 
-    /// A (sync) convenience function for getting the info for a board game
-    /// expansion
-    pub fn boardgameexpansion_b(&self, ids: &Vec<usize>, options: Option<Params>) -> Result<Value> {
-        return self.thing_b(ids, &vec![Thing::BoardGameExpansion], options);
+    pub fn played(self, val: bool) -> Self {
+        return self.flag("played", val);
     }
 
-    /// A (async) convenience function for getting the info for a board game
-    /// accessory
-    pub async fn boardgameaccessory(
-        &self,
-        ids: &Vec<usize>,
-        options: Option<Params>,
-    ) -> Result<Value> {
-        return self
-            .thing(ids, &vec![Thing::BoardGameAccessory], options)
-            .await;
+    /// Items the user has commented on
+    pub fn comment(self, val: bool) -> Self {
+        return self.flag("comment", val);
     }
 
-    /// A (sync) convenience function for getting the info for a board game
-    /// accessory
-    pub fn boardgameaccessory_b(&self, ids: &Vec<usize>, options: Option<Params>) -> Result<Value> {
-        return self.thing_b(ids, &vec![Thing::BoardGameAccessory], options);
+    /// Items marked for trade
+    pub fn trade(self, val: bool) -> Self {
+        return self.flag("trade", val);
     }
 
-    /// A (async) convenience function for getting the info for a video game
-    pub async fn videogame(&self, ids: &Vec<usize>, options: Option<Params>) -> Result<Value> {
-        return self.thing(ids, &vec![Thing::VideoGame], options).await;
+    /// Items the user wants in trade
+    pub fn want(self, val: bool) -> Self {
+        return self.flag("want", val);
     }
 
-    /// A (sync) convenience function for getting the info for a video game
-    pub fn videogame_b(&self, ids: &Vec<usize>, options: Option<Params>) -> Result<Value> {
-        return self.thing_b(ids, &vec![Thing::VideoGame], options);
+    pub fn wishlist(self, val: bool) -> Self {
+        return self.flag("wishlist", val);
     }
 
-    /// A (async) convenience function for getting the info for a rpg item
-    pub async fn rpgitem(&self, ids: &Vec<usize>, options: Option<Params>) -> Result<Value> {
-        return self.thing(ids, &vec![Thing::RpgItem], options).await;
+    pub fn preordered(self, val: bool) -> Self {
+        return self.flag("preordered", val);
     }
 
-    /// A (sync) convenience function for getting the info for a rpg item
-    pub fn rpgitem_b(&self, ids: &Vec<usize>, options: Option<Params>) -> Result<Value> {
-        return self.thing_b(ids, &vec![Thing::RpgItem], options);
+    pub fn want_to_play(self, val: bool) -> Self {
+        return self.flag("wanttoplay", val);
     }
 
-    /// A (async) convenience function for getting the info for a rpg issue
-    pub async fn rpgissue(&self, ids: &Vec<usize>, options: Option<Params>) -> Result<Value> {
-        return self.thing(ids, &vec![Thing::RpgIssue], options).await;
+    pub fn want_to_buy(self, val: bool) -> Self {
+        return self.flag("wanttobuy", val);
     }
 
-    /// A (sync) convenience function for getting the info for a rpg issue
-    pub fn rpgissue_b(&self, ids: &Vec<usize>, options: Option<Params>) -> Result<Value> {
-        return self.thing_b(ids, &vec![Thing::RpgIssue], options);
+    pub fn prev_owned(self, val: bool) -> Self {
+        return self.flag("prevowned", val);
     }
 
-    /* End "thing"s */
+    pub fn has_parts(self, val: bool) -> Self {
+        return self.flag("hasparts", val);
+    }
 
-    /* Begin "family" items */
+    pub fn want_parts(self, val: bool) -> Self {
+        return self.flag("wantparts", val);
+    }
 
-    /// This is the core (async) function for getting various "family" items as
-    /// described by the BGG API.  It's also possible to use the convenience
-    /// functions like `rpg()` instead, which will set the thing type
-    /// for you.
-    pub async fn family(&self, ids: &Vec<usize>, ttypes: &Vec<Family>) -> Result<Value> {
-        // Convert the numeric ids to strings
-        let sids: Vec<String> = ids.iter().map(|i| i.to_string()).collect();
-        let params = Params::from([
-            ("id".into(), sids.join(",")),
-            (
-                "type".into(),
-                ttypes
-                    .iter()
-                    .map(|t| t.as_str())
-                    .collect::<Vec<&'static str>>()
-                    .join(","),
-            ),
-        ]);
-        let url = self.get_full_url("family".into(), None, Some(params));
+    /// Include private info (requires being logged in as the user)
+    pub fn show_private(self, val: bool) -> Self {
+        return self.flag("showprivate", val);
+    }
 
-        let data = utils::get_json_resp(&url).await?;
+    /// Only items of this type.  BGG defaults to board games, which
+    /// includes the expansions.
+    pub fn subtype(mut self, subtype: Thing) -> Self {
+        self.subtype = Some(subtype);
 
-        return Ok(data);
+        return self;
     }
 
-    /// This is the core (sync) function for getting various "family" items as
-    /// described by the BGG API.  It's also possible to use the convenience
-    /// functions like `rpg()` instead, which will set the thing type
-    /// for you.
-    pub fn family_b(&self, ids: &Vec<usize>, ttypes: &Vec<Family>) -> Result<Value> {
-        // Convert the numeric ids to strings
-        let sids: Vec<String> = ids.iter().map(|i| i.to_string()).collect();
-        let params = Params::from([
-            ("id".into(), sids.join(",")),
-            (
-                "type".into(),
-                ttypes
-                    .iter()
-                    .map(|t| t.as_str())
-                    .collect::<Vec<&'static str>>()
-                    .join(","),
-            ),
-        ]);
-        let url = self.get_full_url("family".into(), None, Some(params));
+    /// Leave out items of this type (e.g. `Thing::BoardGameExpansion`)
+    pub fn exclude_subtype(mut self, subtype: Thing) -> Self {
+        self.exclude_subtype = Some(subtype);
 
-        let data = utils::get_json_resp_b(&url)?;
-
-        return Ok(data);
+        return self;
     }
 
-    /// A (async) convenience function for getting the info for a rpg
-    pub async fn rpg(&self, ids: &Vec<usize>) -> Result<Value> {
-        return self.family(ids, &vec![Family::Rpg]).await;
-    }
+    /// Only these items
+    pub fn ids(mut self, ids: &[usize]) -> Self {
+        self.ids = ids.to_vec();
 
-    /// A (sync) convenience function for getting the info for a rpg
-    pub fn rpg_b(&self, ids: &Vec<usize>) -> Result<Value> {
-        return self.family_b(ids, &vec![Family::Rpg]);
+        return self;
     }
 
-    /// A (async) convenience function for getting the info for a rpg
-    /// periodical
-    pub async fn rpgperiodical(&self, ids: &Vec<usize>) -> Result<Value> {
-        return self.family(ids, &vec![Family::RpgPeriodical]).await;
-    }
+    /// Only the collection entry with this collection ID
+    pub fn coll_id(mut self, coll_id: usize) -> Self {
+        self.coll_id = Some(coll_id);
 
-    /// A (sync) convenience function for getting the info for a rpg
-    /// periodical
-    pub fn rpgperiodical_b(&self, ids: &Vec<usize>) -> Result<Value> {
-        return self.family_b(ids, &vec![Family::RpgPeriodical]);
+        return self;
     }
 
-    /// A (async) convenience function for getting the info for a board game
-    /// family
-    pub async fn boardgamefamily(&self, ids: &Vec<usize>) -> Result<Value> {
-        return self.family(ids, &vec![Family::BoardGameFamily]).await;
-    }
+    /// Only wishlist items of this priority, from 1 (must have) to 5
+    /// (don't buy this)
+    pub fn wishlist_priority(mut self, priority: u8) -> Self {
+        self.wishlist_priority = Some(priority);
 
-    /// A (sync) convenience function for getting the info for a board game
-    /// family
-    pub fn boardgamefamily_b(&self, ids: &Vec<usize>) -> Result<Value> {
-        return self.family_b(ids, &vec![Family::BoardGameFamily]);
+        return self;
     }
 
-    /* End "family" items */
+    /// Only items the user rated within this range (e.g. `7.0..=9.0` or
+    /// `8.0..`)
+    pub fn rating<R: RangeBounds<f64>>(mut self, range: R) -> Self {
+        self.rating = Some((range.start_bound().cloned(), range.end_bound().cloned()));
 
-    /// Get a (async) list of forums for a given game (by ID)
-    pub async fn forumlist(&self, game_id: usize, ltype: ThingFamily) -> Result<Value> {
-        let params = Params::from([
-            ("id".into(), game_id.to_string()),
-            ("type".into(), ltype.to_string()),
-        ]);
-        let url = self.get_full_url("forumlist".into(), None, Some(params));
+        return self;
+    }
 
-        let data = utils::get_json_resp(&url).await?;
+    /// Only items with a BGG rating within this range
+    pub fn bgg_rating<R: RangeBounds<f64>>(mut self, range: R) -> Self {
+        self.bgg_rating = Some((range.start_bound().cloned(), range.end_bound().cloned()));
 
-        return Ok(data);
+        return self;
     }
 
-    /// Get a (sync) list of forums for a given game (by ID)
-    pub fn forumlist_b(&self, game_id: usize, ltype: ThingFamily) -> Result<Value> {
-        let params = Params::from([
-            ("id".into(), game_id.to_string()),
-            ("type".into(), ltype.to_string()),
-        ]);
-        let url = self.get_full_url("forumlist".into(), None, Some(params));
-
-        let data = utils::get_json_resp_b(&url)?;
+    /// Only items the user has played this many times (e.g. `1..10`)
+    pub fn plays<R: RangeBounds<u32>>(mut self, range: R) -> Self {
+        self.plays = Some((range.start_bound().cloned(), range.end_bound().cloned()));
 
-        return Ok(data);
+        return self;
     }
 
-    /// Get a (async) list of threads in a particular forum by forum ID
-    pub async fn forum(&self, forum_id: usize, options: Option<Params>) -> Result<Value> {
-        let params = Params::from([("id".into(), forum_id.to_string())]);
-        let url = self.get_full_url("forumlist".into(), options, Some(params));
-
-        let data = utils::get_json_resp(&url).await?;
+    /// Only items modified on or after this date
+    pub fn modified_since(mut self, date: NaiveDate) -> Self {
+        self.modified_since = Some(date.and_time(NaiveTime::MIN));
 
-        return Ok(data);
+        return self;
     }
 
-    /// Get a (sync) list of threads in a particular forum by forum ID
-    pub async fn forum_b(&self, forum_id: usize, options: Option<Params>) -> Result<Value> {
-        let params = Params::from([("id".into(), forum_id.to_string())]);
-        let url = self.get_full_url("forumlist".into(), options, Some(params));
-
-        let data = utils::get_json_resp_b(&url)?;
+    /// Only items modified at or after this time
+    pub fn modified_since_time(mut self, time: NaiveDateTime) -> Self {
+        self.modified_since = Some(time);
 
-        return Ok(data);
+        return self;
     }
 
-    /// Get a (async) thread by ID
-    pub async fn thread(&self, thread_id: usize, options: Option<Params>) -> Result<Value> {
-        let params = Params::from([("id".into(), thread_id.to_string())]);
-        let url = self.get_full_url("thread".into(), options, Some(params));
-
-        let data = utils::get_json_resp(&url).await?;
+    fn flag(mut self, key: &'static str, val: bool) -> Self {
+        self.flags.insert(key, val);
 
-        return Ok(data);
+        return self;
     }
+}
 
-    /// Get a (sync) list of threads in a particular forum by forum ID
-    pub fn thread_b(&self, thread_id: usize, options: Option<Params>) -> Result<Value> {
+impl IntoParams for CollectionOptions {
+    fn into_params(self) -> Result<Option<Params>> {
+        let mut ret = Params::new();
+
+        for (key, val) in &self.flags {
+            ret.insert(key.to_string(), utils::flag(*val));
+        }
+        if let Some(subtype) = self.subtype {
+            ret.insert("subtype".into(), subtype.to_string());
+        }
+        if let Some(subtype) = self.exclude_subtype {
+            ret.insert("excludesubtype".into(), subtype.to_string());
+        }
+        if !self.ids.is_empty() {
+            let ids: Vec<String> = self.ids.iter().map(|i| i.to_string()).collect();
+            ret.insert("id".into(), ids.join(","));
+        }
+        if let Some(coll_id) = self.coll_id {
+            ret.insert("collid".into(), coll_id.to_string());
+        }
+        if let Some(priority) = self.wishlist_priority {
+            if !(1..=5).contains(&priority) {
+                return Err(RbggError::InvalidInput(format!(
+                    "The wishlist priority must be from 1 to 5, not {}",
+                    priority
+                )));
+            }
+            ret.insert("wishlistpriority".into(), priority.to_string());
+        }
+        if let Some(range) = self.rating {
+            utils::range_params(&mut ret, &range, "minrating", "rating", None)?;
+        }
+        if let Some(range) = self.bgg_rating {
+            utils::range_params(&mut ret, &range, "minbggrating", "bggrating", None)?;
+        }
+        if let Some(range) = self.plays {
+            utils::range_params(&mut ret, &range, "minplays", "maxplays", Some(1))?;
+        }
+        if let Some(time) = self.modified_since {
+            ret.insert(
+                "modifiedsince".into(),
+                time.format("%Y-%m-%d %H:%M:%S").to_string(),
+            );
+        }
+
+        return Ok(Some(ret));
+    }
+}
+
+/// Typed options for `plays()`
+///
+/// ```ignore,rust
+/// let opts = PlaysOptions::new()
+///     .min_date(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap())
+///     .max_date(NaiveDate::from_ymd_opt(2024, 12, 31).unwrap());
+/// let res = cl.plays_b(Some("someuser"), None, None, opts)?;
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlaysOptions {
+    min_date: Option<NaiveDate>,
+    max_date: Option<NaiveDate>,
+    subtype: Option<Thing>,
+    page: Option<usize>,
+}
+
+impl PlaysOptions {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    /// Only plays on or after this date
+    pub fn min_date(mut self, date: NaiveDate) -> Self {
+        self.min_date = Some(date);
+
+        return self;
+    }
+
+    /// Only plays on or before this date
+    pub fn max_date(mut self, date: NaiveDate) -> Self {
+        self.max_date = Some(date);
+
+        return self;
+    }
+
+    /// Only plays of this type of item
+    pub fn subtype(mut self, subtype: Thing) -> Self {
+        self.subtype = Some(subtype);
+
+        return self;
+    }
+
+    /// The page of results to get, starting at 1
+    pub fn page(mut self, page: usize) -> Self {
+        self.page = Some(page);
+
+        return self;
+    }
+}
+
+impl IntoParams for PlaysOptions {
+    fn into_params(self) -> Result<Option<Params>> {
+        let mut ret = Params::new();
+
+        if let (Some(min), Some(max)) = (self.min_date, self.max_date) {
+            if min > max {
+                return Err(RbggError::InvalidInput(format!(
+                    "The min date ({}) is after the max date ({})",
+                    min, max
+                )));
+            }
+        }
+        if let Some(date) = self.min_date {
+            ret.insert("mindate".into(), date.format("%Y-%m-%d").to_string());
+        }
+        if let Some(date) = self.max_date {
+            ret.insert("maxdate".into(), date.format("%Y-%m-%d").to_string());
+        }
+        if let Some(subtype) = self.subtype {
+            ret.insert("subtype".into(), subtype.to_string());
+        }
+        if let Some(page) = self.page {
+            ret.insert("page".into(), page.to_string());
+        }
+
+        return Ok(Some(ret));
+    }
+}
+
+/// Typed options for `thread()`
+///
+/// ```ignore,rust
+/// let opts = ThreadOptions::new().min_article_id(123456).count(20);
+/// let res = cl.thread_b(1234, opts)?;
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ThreadOptions {
+    min_article_id: Option<usize>,
+    min_article_date: Option<NaiveDateTime>,
+    count: Option<usize>,
+}
+
+impl ThreadOptions {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    /// Only articles with this ID or later
+    pub fn min_article_id(mut self, id: usize) -> Self {
+        self.min_article_id = Some(id);
+
+        return self;
+    }
+
+    /// Only articles posted at or after this time
+    pub fn min_article_date(mut self, time: NaiveDateTime) -> Self {
+        self.min_article_date = Some(time);
+
+        return self;
+    }
+
+    /// The maximum number of articles to get
+    pub fn count(mut self, count: usize) -> Self {
+        self.count = Some(count);
+
+        return self;
+    }
+}
+
+impl IntoParams for ThreadOptions {
+    fn into_params(self) -> Result<Option<Params>> {
+        let mut ret = Params::new();
+
+        if let Some(id) = self.min_article_id {
+            ret.insert("minarticleid".into(), id.to_string());
+        }
+        if let Some(time) = self.min_article_date {
+            ret.insert(
+                "minarticledate".into(),
+                time.format("%Y-%m-%d %H:%M:%S").to_string(),
+            );
+        }
+        if let Some(count) = self.count {
+            ret.insert("count".into(), count.to_string());
+        }
+
+        return Ok(Some(ret));
+    }
+}
+
+/// Typed options for `user()`
+///
+/// ```ignore,rust
+/// let opts = UserOptions::new().buddies(true).top(true).domain(Domain::Rpg);
+/// let res = cl.user_b("someuser", opts)?;
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UserOptions {
+    buddies: Option<bool>,
+    guilds: Option<bool>,
+    hot: Option<bool>,
+    top: Option<bool>,
+    domain: Option<Domain>,
+    page: Option<usize>,
+}
+
+impl UserOptions {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    /// Include the user's buddies
+    pub fn buddies(mut self, val: bool) -> Self {
+        self.buddies = Some(val);
+
+        return self;
+    }
+
+    /// Include the guilds the user is in
+    pub fn guilds(mut self, val: bool) -> Self {
+        self.guilds = Some(val);
+
+        return self;
+    }
+
+    /// Include the user's hot 10 list
+    pub fn hot(mut self, val: bool) -> Self {
+        self.hot = Some(val);
+
+        return self;
+    }
+
+    /// Include the user's top 10 list
+    pub fn top(mut self, val: bool) -> Self {
+        self.top = Some(val);
+
+        return self;
+    }
+
+    /// The site the hot and top lists are for (board games by default)
+    pub fn domain(mut self, domain: Domain) -> Self {
+        self.domain = Some(domain);
+
+        return self;
+    }
+
+    /// The page of buddies and guilds to get, starting at 1
+    pub fn page(mut self, page: usize) -> Self {
+        self.page = Some(page);
+
+        return self;
+    }
+}
+
+impl IntoParams for UserOptions {
+    fn into_params(self) -> Result<Option<Params>> {
+        let mut ret = Params::new();
+
+        for (key, val) in [
+            ("buddies", self.buddies),
+            ("guilds", self.guilds),
+            ("hot", self.hot),
+            ("top", self.top),
+        ] {
+            if let Some(val) = val {
+                ret.insert(key.into(), utils::flag(val));
+            }
+        }
+        if let Some(domain) = self.domain {
+            ret.insert("domain".into(), domain.to_string());
+        }
+        if let Some(page) = self.page {
+            ret.insert("page".into(), page.to_string());
+        }
+
+        return Ok(Some(ret));
+    }
+}
+
+/// Typed options for `thing()` and its convenience functions
+///
+/// ```ignore,rust
+/// let opts = ThingOptions::new().stats(true).comments(true).page_size(50);
+/// let res = cl.boardgame_b(&vec![136888], opts)?;
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ThingOptions {
+    flags: BTreeMap<&'static str, bool>,
+    page: Option<usize>,
+    page_size: Option<usize>,
+}
+
+impl ThingOptions {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    /// Include the published versions
+    pub fn versions(self, val: bool) -> Self {
+        return self.flag("versions", val);
+    }
+
+    /// Include the videos
+    pub fn videos(self, val: bool) -> Self {
+        return self.flag("videos", val);
+    }
+
+    /// Include the ratings and ranking stats
+    pub fn stats(self, val: bool) -> Self {
+        return self.flag("stats", val);
+    }
+
+    /// Include the marketplace listings
+    pub fn marketplace(self, val: bool) -> Self {
+        return self.flag("marketplace", val);
+    }
+
+    /// Include a page of comments (along with any ratings)
+    pub fn comments(self, val: bool) -> Self {
+        return self.flag("comments", val);
+    }
+
+    /// Include a page of ratings (along with any comments).  This
+    /// overrides `comments()`.
+    pub fn rating_comments(self, val: bool) -> Self {
+        return self.flag("ratingcomments", val);
+    }
+
+    /// The page of comments to get, starting at 1
+    pub fn page(mut self, page: usize) -> Self {
+        self.page = Some(page);
+
+        return self;
+    }
+
+    /// The number of comments per page, from 10 to 100
+    pub fn page_size(mut self, size: usize) -> Self {
+        self.page_size = Some(size);
+
+        return self;
+    }
+
+    fn flag(mut self, key: &'static str, val: bool) -> Self {
+        self.flags.insert(key, val);
+
+        return self;
+    }
+}
+
+impl IntoParams for ThingOptions {
+    fn into_params(self) -> Result<Option<Params>> {
+        let mut ret = Params::new();
+
+        for (key, val) in &self.flags {
+            ret.insert(key.to_string(), utils::flag(*val));
+        }
+        if let Some(page) = self.page {
+            if page == 0 {
+                return Err(RbggError::InvalidInput(
+                    "The comments page must be 1 or more".into(),
+                ));
+            }
+            ret.insert("page".into(), page.to_string());
+        }
+        if let Some(size) = self.page_size {
+            if !(10..=COMMENTS_PAGE_SIZE).contains(&size) {
+                return Err(RbggError::InvalidInput(format!(
+                    "The page size must be from 10 to 100, not {}",
+                    size
+                )));
+            }
+            ret.insert("pagesize".into(), size.to_string());
+        }
+
+        return Ok(Some(ret));
+    }
+}
+
+/// The most IDs BGG will accept in a single thing() call
+const THING_CHUNK_SIZE: usize = 20;
+
+/// The largest page size BGG allows when paging through comments
+const COMMENTS_PAGE_SIZE: usize = 100;
+
+/// The number of threads BGG returns in each page of a forum
+const FORUM_PAGE_SIZE: usize = 50;
+
+/// The number of articles to fetch at a time when streaming a thread
+const THREAD_PAGE_SIZE: usize = 100;
+
+/// The number of plays BGG returns in each page
+const PLAYS_PAGE_SIZE: usize = 100;
+
+/// Build the base params for a thing() call
+fn thing_params(ids: &Vec<usize>, ttypes: &Vec<Thing>) -> Params {
+    // Convert the numeric ids to strings
+    let sids: Vec<String> = ids.iter().map(|i| i.to_string()).collect();
+
+    return Params::from([
+        ("id".into(), sids.join(",")),
+        (
+            "type".into(),
+            ttypes
+                .iter()
+                .map(|t| t.as_str())
+                .collect::<Vec<&'static str>>()
+                .join(","),
+        ),
+    ]);
+}
+
+/// Merge a flag into the options for a thing() call, so it can be combined
+/// with any others (e.g. a `ThingOptions`)
+fn with_flag(options: impl IntoParams, flag: &str) -> Result<Option<Params>> {
+    let mut ret = options.into_params()?.unwrap_or_default();
+    ret.insert(flag.into(), utils::flag(true));
+
+    return Ok(Some(ret));
+}
+
+/// Run one of the `utils::check_*()` functions over the value of a
+/// `*_with_meta()` response, so it fails the same way as the plain call
+fn check_meta(
+    res: Result<ApiResponse>,
+    check: impl FnOnce(Result<Value>) -> Result<Value>,
+) -> Result<ApiResponse> {
+    return match res {
+        Ok(mut resp) => {
+            resp.value = check(Ok(resp.value))?;
+            Ok(resp)
+        }
+        // The checks only ever turn one error into another
+        Err(e) => check(Err(e)).map(|_| unreachable!()),
+    };
+}
+
+/// Run one of the `utils::check_*()` functions over the JSON of a
+/// `*_with_xml()` response, so it fails the same way as the plain call
+fn check_xml(
+    res: Result<(String, Value)>,
+    check: impl FnOnce(Result<Value>) -> Result<Value>,
+) -> Result<(String, Value)> {
+    return match res {
+        Ok((xml, value)) => Ok((xml, check(Ok(value))?)),
+        Err(e) => check(Err(e)).map(|_| unreachable!()),
+    };
+}
+
+/// Run one of the `utils::check_*()` functions over the error from a
+/// `*_to_writer()` call.  A response that's been written out is never an
+/// error response, so there's only an error to check.
+fn check_written(
+    res: Result<u64>,
+    check: impl FnOnce(Result<Value>) -> Result<Value>,
+) -> Result<u64> {
+    return match res {
+        Ok(written) => Ok(written),
+        Err(e) => check(Err(e)).map(|_| unreachable!()),
+    };
+}
+
+/// The options for thing_ratingcomments(), validated when they're
+/// converted to params
+fn ratingcomments_options(page: usize, page_size: usize) -> ThingOptions {
+    return ThingOptions::new()
+        .rating_comments(true)
+        .page(page)
+        .page_size(page_size);
+}
+
+/// The params for thing_full(), which turn on everything BGG can include
+fn thing_full_params(ids: &[usize]) -> Params {
+    let sids: Vec<String> = ids.iter().map(|i| i.to_string()).collect();
+
+    return Params::from([
+        ("id".into(), sids.join(",")),
+        ("stats".into(), "1".into()),
+        ("versions".into(), "1".into()),
+        ("videos".into(), "1".into()),
+        ("marketplace".into(), "1".into()),
+        ("comments".into(), "1".into()),
+        ("page".into(), "1".into()),
+    ]);
+}
+
+/// Build the params for fetching a slice of a thread
+fn thread_slice_params(thread_id: usize, from_article: usize, count: usize) -> Params {
+    return Params::from([
+        ("id".into(), thread_id.to_string()),
+        ("minarticleid".into(), from_article.to_string()),
+        ("count".into(), count.to_string()),
+    ]);
+}
+
+/// The collection params needed for user_ratings()
+fn user_ratings_params() -> Params {
+    return Params::from([("rated".into(), "1".into()), ("stats".into(), "1".into())]);
+}
+
+/// The collection params needed for wishlist_report()
+fn wishlist_params() -> Params {
+    return Params::from([("wishlist".into(), "1".into())]);
+}
+
+/// The params to include the stats in a thing() call
+fn stats_params() -> Params {
+    return Params::from([("stats".into(), "1".into())]);
+}
+
+/// The thing types to look up the wishlist items as, since expansions are
+/// wishlisted as well as games
+fn wishlist_types() -> Vec<Thing> {
+    return vec![Thing::BoardGame, Thing::BoardGameExpansion];
+}
+
+/// The (unique) IDs of the wishlist items in a collection
+fn wishlist_ids(coll: &Collection) -> Vec<usize> {
+    let mut ret: Vec<usize> = coll
+        .items
+        .iter()
+        .filter(|i| i.status.wishlist)
+        .map(|i| i.id)
+        .collect();
+    ret.sort();
+    ret.dedup();
+
+    return ret;
+}
+
+/// Pull the ratings out of a collection response for user_ratings()
+fn user_ratings_from(data: &Value) -> Result<Vec<(usize, f64, Option<f64>)>> {
+    let coll = Collection::from_value(data)?;
+
+    return Ok(coll
+        .items
+        .iter()
+        .filter_map(|i| i.user_rating().map(|r| (i.id, r, i.average_rating())))
+        .collect());
+}
+
+/// Build the params for fetching a single page of comments for a thing
+fn comments_params(id: usize, page: usize) -> Params {
+    return Params::from([
+        ("id".into(), id.to_string()),
+        ("comments".into(), "1".into()),
+        ("page".into(), page.to_string()),
+        ("pagesize".into(), COMMENTS_PAGE_SIZE.to_string()),
+    ]);
+}
+
+/// Pull the comments out of a thing response, taking ownership of them so
+/// the rest of the response can be dropped.  Returns the comments along
+/// with the total number of comments BGG reports for the thing.
+fn take_comments_page(mut data: Value) -> (Vec<Value>, usize) {
+    let comments = data["items"]["item"]["comments"].take();
+    let total = comments["@totalitems"]
+        .as_str()
+        .and_then(|t| t.parse().ok())
+        .unwrap_or(0);
+
+    let ret = match comments {
+        Value::Object(mut map) => match map.remove("comment") {
+            Some(Value::Array(list)) => list,
+            // A single comment is not wrapped in an array
+            Some(Value::Object(c)) => vec![Value::Object(c)],
+            _ => vec![],
+        },
+        _ => vec![],
+    };
+
+    return (ret, total);
+}
+
+/// The params for a single page of a forum
+fn page_params(page: usize) -> Params {
+    return Params::from([("page".into(), page.to_string())]);
+}
+
+/// Turn a page of results into the individual results, so that a failed
+/// page becomes a single error
+fn flatten_page<T>(res: Result<Vec<T>>) -> Vec<Result<T>> {
+    return match res {
+        Ok(items) => items.into_iter().map(Ok).collect(),
+        Err(e) => vec![Err(e)],
+    };
+}
+
+/// Stream the items from a paged endpoint.  `fetch` gets a page given its
+/// position (a page number, article ID, etc.) and returns its items along
+/// with the position of the next page, if there is one.
+#[cfg(feature = "async")]
+fn stream_pages<'a, T, S, F, Fut>(start: S, fetch: F) -> impl Stream<Item = Result<T>> + 'a
+where
+    T: 'a,
+    S: 'a,
+    F: FnMut(S) -> Fut + 'a,
+    Fut: Future<Output = Result<(Vec<T>, Option<S>)>> + 'a,
+{
+    let pages = stream::unfold((Some(start), fetch), |(next, mut fetch)| async move {
+        let cur = next?;
+
+        return match fetch(cur).await {
+            Ok((items, next)) => Some((Ok(items), (next, fetch))),
+            Err(e) => Some((Err(e), (None, fetch))),
+        };
+    });
+
+    return pages.flat_map(|res| stream::iter(flatten_page(res)));
+}
+
+/// The blocking version of `stream_pages()`
+fn iter_pages<'a, T, S, F>(start: S, mut fetch: F) -> impl Iterator<Item = Result<T>> + 'a
+where
+    T: 'a,
+    S: 'a,
+    F: FnMut(S) -> Result<(Vec<T>, Option<S>)> + 'a,
+{
+    let mut next = Some(start);
+    let pages = std::iter::from_fn(move || {
+        let cur = next.take()?;
+
+        return match fetch(cur) {
+            Ok((items, n)) => {
+                next = n;
+                Some(Ok(items))
+            }
+            Err(e) => Some(Err(e)),
+        };
+    });
+
+    return pages.flat_map(flatten_page);
+}
+
+/// Parse a page of a forum, along with the next page number
+fn forum_page(data: &Value, page: usize) -> Result<(Vec<ThreadSummary>, Option<usize>)> {
+    let forum = Forum::from_value(data)?;
+    let done = forum.threads.is_empty() || page * FORUM_PAGE_SIZE >= forum.num_threads;
+    let next = if done { None } else { Some(page + 1) };
+
+    return Ok((forum.threads, next));
+}
+
+/// Parse a slice of a thread, along with the ID to start the next one from
+fn thread_page(data: &Value) -> Result<(Vec<Article>, Option<usize>)> {
+    let thread = Thread::from_value(data)?;
+    let next = match thread.articles.last() {
+        Some(last) if thread.articles.len() >= THREAD_PAGE_SIZE => Some(last.id + 1),
+        _ => None,
+    };
+
+    return Ok((thread.articles, next));
+}
+
+/// Parse a page of comments out of a thing response, along with the next
+/// page number.  A thing without any comments gives an empty page.
+fn comments_page(data: &Value, page: usize) -> Result<(Vec<Comment>, Option<usize>)> {
+    let comments = Things::from_value(data)?
+        .items
+        .into_iter()
+        .next()
+        .and_then(|i| i.comments)
+        .unwrap_or_default();
+    let next = match comments.has_next(COMMENTS_PAGE_SIZE) {
+        true => Some(page + 1),
+        false => None,
+    };
+
+    return Ok((comments.comments, next));
+}
+
+/// The options for a page of plays, on top of the caller's options
+fn plays_page_options(options: &Result<Option<Params>>, page: usize) -> Result<Params> {
+    let mut ret = match options {
+        Ok(opts) => utils::get_opts(opts.clone()),
+        Err(e) => return Err(e.clone()),
+    };
+    ret.insert("page".into(), page.to_string());
+
+    return Ok(ret);
+}
+
+/// Parse a page of plays, along with the next page number
+fn plays_page(data: &Value, page: usize) -> Result<(Vec<Play>, Option<usize>)> {
+    let plays = Plays::from_value(data)?;
+    let done = plays.plays.is_empty() || page * PLAYS_PAGE_SIZE >= plays.total;
+    let next = if done { None } else { Some(page + 1) };
+
+    return Ok((plays.plays, next));
+}
+
+/// A request prepared ahead of time, to be run as part of a
+/// `Client2::batch()`
+///
+/// ```ignore,rust
+/// use rbgg::bgg2::{BatchRequest, Client2, Thing};
+///
+/// let cl = Client2::new_from_defaults();
+/// let reqs = vec![
+///     BatchRequest::thing(&vec![136888], &vec![Thing::BoardGame], None)?,
+///     BatchRequest::user("someuser", None)?,
+///     BatchRequest::collection("someuser", None)?,
+/// ];
+/// // At most 2 requests are in flight at once
+/// for res in cl.batch(reqs, 2).await {
+///     println!("{}", res?);
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchRequest {
+    /// The endpoint, relative to the API prefix (e.g. "thing")
+    pub path: String,
+    /// The options for the call, which override any endpoint defaults
+    pub options: Option<Params>,
+    /// The params that make up the request itself (e.g. the IDs)
+    pub params: Params,
+}
+
+impl BatchRequest {
+    /// A request for the given "things", like `Client2::thing()`
+    pub fn thing(ids: &Vec<usize>, ttypes: &Vec<Thing>, options: impl IntoParams) -> Result<Self> {
+        return Self::new("thing", options, thing_params(ids, ttypes));
+    }
+
+    /// A request for a user, like `Client2::user()`
+    pub fn user(username: &str, options: impl IntoParams) -> Result<Self> {
+        let params = Params::from([("name".into(), username.into())]);
+
+        return Self::new("user", options, params);
+    }
+
+    /// A request for a user's collection, like `Client2::collection()`
+    pub fn collection(username: &str, options: impl IntoParams) -> Result<Self> {
+        let params = Params::from([("username".into(), username.into())]);
+
+        return Self::new("collection", options, params);
+    }
+
+    /// A request for an arbitrary endpoint, like `Client2::raw()`
+    pub fn raw(path: &str, params: Option<Params>) -> Self {
+        return Self {
+            path: path.trim_matches('/').to_string(),
+            options: params,
+            params: Params::new(),
+        };
+    }
+
+    fn new(path: &str, options: impl IntoParams, params: Params) -> Result<Self> {
+        return Ok(Self {
+            path: path.to_string(),
+            options: options.into_params()?,
+            params,
+        });
+    }
+}
+
+/// The result of a `Client2::ping()`
+#[derive(Clone, Debug)]
+pub struct PingReport {
+    /// Whether BGG responded with a valid API response
+    pub reachable: bool,
+    /// How long the request took, including any time spent waiting on the
+    /// throttle
+    pub latency: Duration,
+    /// What went wrong, if BGG wasn't reachable
+    pub error: Option<String>,
+}
+
+impl PingReport {
+    fn new(res: Result<Value>, latency: Duration) -> Self {
+        let error = match res {
+            // A valid hot list response will have the items element
+            Ok(data) if data.get("items").is_some() => None,
+            Ok(_) => Some("Unexpected response from BGG".to_string()),
+            Err(e) => Some(e.to_string()),
+        };
+
+        return Self {
+            reachable: error.is_none(),
+            latency,
+            error,
+        };
+    }
+}
+
+/// A builder for a `Client2`, for when the defaults need changing.  Anything
+/// that isn't set is left at its default.
+///
+/// ```ignore,rust
+/// use rbgg::bgg2::Client2;
+/// use std::time::Duration;
+///
+/// let cl = Client2::builder()
+///     .timeout(Duration::from_secs(30))
+///     .user_agent("mytool/1.0 (me@example.com)")
+///     .build()?;
+/// ```
+#[derive(Clone, Default)]
+pub struct Client2Builder {
+    url_base: Option<String>,
+    api_prefix: Option<String>,
+    config: HttpConfig,
+    http: Option<HttpClient>,
+}
+
+impl Client2Builder {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    /// The base url of the site, defaults to "https://boardgamegeek.com"
+    pub fn url_base(mut self, url_base: impl Into<String>) -> Self {
+        self.url_base = Some(url_base.into());
+        return self;
+    }
+
+    /// The path prefix of the API, defaults to "xmlapi2"
+    pub fn api_prefix(mut self, api_prefix: impl Into<String>) -> Self {
+        self.api_prefix = Some(api_prefix.into());
+        return self;
+    }
+
+    /// Replace the whole HTTP configuration.  This overwrites anything set
+    /// by the HTTP setters below, so call it first.
+    pub fn config(mut self, config: HttpConfig) -> Self {
+        self.config = config;
+        return self;
+    }
+
+    /// Use an existing `HttpClient`, so that multiple API clients share the
+    /// same connection pools and settings.  When this is set, the HTTP
+    /// configuration on the builder is ignored.
+    pub fn http(mut self, http: HttpClient) -> Self {
+        self.http = Some(http);
+        return self;
+    }
+
+    /// The longest a single request may take, or `None` for no limit
+    pub fn timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.config.timeout = timeout.into();
+        return self;
+    }
+
+    /// The longest a request may take to connect, or `None` for no limit
+    pub fn connect_timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.config.connect_timeout = timeout.into();
+        return self;
+    }
+
+    /// The User-Agent header to send.  BGG asks that automated tools
+    /// identify themselves.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.config.user_agent = Some(user_agent.into());
+        return self;
+    }
+
+    /// Add a header to send with every request.  This replaces any header
+    /// with the same name, including ones set by the other options.
+    pub fn default_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.config.headers.push((name.into(), value.into()));
+        return self;
+    }
+
+    /// Whether to ask for compressed responses
+    pub fn compression(mut self, compression: bool) -> Self {
+        self.config.compression = compression;
+        return self;
+    }
+
+    /// The throttle settings, or `None` to disable throttling
+    pub fn throttle(mut self, throttle: Option<ThrottleConfig>) -> Self {
+        self.config.throttle = throttle;
+        return self;
+    }
+
+    /// How to retry requests that fail for a transient reason
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.config.retry = retry;
+        return self;
+    }
+
+    /// How long to wait on BGG when it queues a request
+    pub fn queue(mut self, queue: QueuePolicy) -> Self {
+        self.config.queue = queue;
+        return self;
+    }
+
+    /// Where to record metrics about each request
+    pub fn metrics(mut self, metrics: Arc<dyn MetricsSink>) -> Self {
+        self.config.metrics = Some(metrics);
+        return self;
+    }
+
+    /// Fail fast while BGG is down.  See `breaker`.
+    pub fn breaker(mut self, breaker: BreakerConfig) -> Self {
+        self.config.breaker = Some(breaker);
+        return self;
+    }
+
+    /// Share a single request between concurrent calls for the same url
+    pub fn coalesce(mut self, coalesce: bool) -> Self {
+        self.config.coalesce = coalesce;
+        return self;
+    }
+
+    /// Make conditional requests for urls that have been fetched before
+    pub fn conditional(mut self, conditional: bool) -> Self {
+        self.config.conditional = conditional;
+        return self;
+    }
+
+    /// Where to cache responses
+    pub fn cache(mut self, cache: Arc<dyn ResponseCache>) -> Self {
+        self.config.cache = Some(cache);
+        return self;
+    }
+
+    /// Cache up to `capacity` responses in memory (see `MemoryCache`)
+    pub fn memory_cache(mut self, capacity: usize) -> Self {
+        self.config.cache = Some(Arc::new(MemoryCache::new(capacity)));
+        return self;
+    }
+
+    /// Whether to return BGG's error responses as `RbggError::Api` (the
+    /// default), rather than as JSON like any other response
+    pub fn api_errors(mut self, api_errors: bool) -> Self {
+        self.config.api_errors = api_errors;
+        return self;
+    }
+
+    /// What sends the requests (see `transport`)
+    pub fn transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        self.config.transport = Some(transport);
+        return self;
+    }
+
+    /// How long responses are cached for
+    pub fn cache_ttl(mut self, ttl: Duration) -> Self {
+        self.config.cache_ttl = ttl;
+        return self;
+    }
+
+    /// The Accept-Language header to send
+    pub fn accept_language(mut self, lang: impl Into<String>) -> Self {
+        self.config.accept_language = Some(lang.into());
+        return self;
+    }
+
+    /// Build the client.  This can only fail when creating the HTTP client
+    /// fails, e.g. with an invalid header value.
+    pub fn build(mut self) -> Result<Client2> {
+        let http = match self.http.take() {
+            Some(http) => http,
+            None => HttpClient::new(self.config.clone())?,
+        };
+
+        return Ok(self.finish(http));
+    }
+
+    fn finish(self, http: HttpClient) -> Client2 {
+        let ub = match self.url_base {
+            Some(u) => match u.strip_suffix('/') {
+                Some(stripped) => stripped.to_string(),
+                None => u,
+            },
+            None => "https://boardgamegeek.com".to_string(),
+        };
+
+        let prefix = match self.api_prefix {
+            Some(p) => p.trim_matches('/').to_string(),
+            None => "xmlapi2".to_string(),
+        };
+
+        return Client2 {
+            url_base: ub,
+            api_prefix: prefix,
+            http,
+            endpoint_defaults: HashMap::new(),
+        };
+    }
+}
+
+/// A representation of a client to hold the url info for accessing the API
+#[derive(Clone)]
+pub struct Client2 {
+    pub url_base: String,
+    pub api_prefix: String,
+    http: HttpClient,
+    endpoint_defaults: HashMap<String, Params>,
+}
+
+impl Client2 {
+    /// Start building a client with non-default settings
+    pub fn builder() -> Client2Builder {
+        return Client2Builder::new();
+    }
+
+    /// If the url_base or api_prefix are not supplied, the defaults will be
+    /// used instead ("https://boardgamegeek.com" and "xmlapi2", respectively)
+    pub fn new(url_base: Option<String>, api_prefix: Option<String>) -> Self {
+        return Self::new_with_http(url_base, api_prefix, HttpClient::default());
+    }
+
+    /// Like `new()`, but with a custom configuration for the underlying
+    /// HTTP client
+    pub fn new_with_config(
+        url_base: Option<String>,
+        api_prefix: Option<String>,
+        config: HttpConfig,
+    ) -> Result<Self> {
+        let builder = Client2Builder {
+            url_base,
+            api_prefix,
+            config,
+            http: None,
+        };
+
+        return builder.build();
+    }
+
+    /// Like `new()`, but using an existing `HttpClient`.  This allows
+    /// multiple API clients to share the same connection pools and settings.
+    pub fn new_with_http(
+        url_base: Option<String>,
+        api_prefix: Option<String>,
+        http: HttpClient,
+    ) -> Self {
+        let builder = Client2Builder {
+            url_base,
+            api_prefix,
+            ..Default::default()
+        };
+
+        return builder.finish(http);
+    }
+
+    pub fn new_from_defaults() -> Self {
+        return Self::new(None, None);
+    }
+
+    /// Search (async) the site for the given query and search types
+    #[cfg(feature = "async")]
+    pub async fn search(
+        &self,
+        query: &str,
+        stypes: &Vec<Search>,
+        options: impl IntoParams,
+    ) -> Result<Value> {
+        let params = Params::from([
+            ("query".into(), query.into()),
+            (
+                "type".into(),
+                stypes
+                    .iter()
+                    .map(|t| t.as_str())
+                    .collect::<Vec<&'static str>>()
+                    .join(","),
+            ),
+        ]);
+
+        let url = self.get_full_url("search".into(), options.into_params()?, Some(params));
+
+        let data = self.http.get_json(&url).await?;
+
+        return Ok(data);
+    }
+
+    /// Search (async) the site for the given query and search types
+    pub fn search_b(
+        &self,
+        query: &str,
+        stypes: &Vec<Search>,
+        options: impl IntoParams,
+    ) -> Result<Value> {
+        let params = Params::from([
+            ("query".into(), query.into()),
+            (
+                "type".into(),
+                stypes
+                    .iter()
+                    .map(|t| t.as_str())
+                    .collect::<Vec<&'static str>>()
+                    .join(","),
+            ),
+        ]);
+
+        let url = self.get_full_url("search".into(), options.into_params()?, Some(params));
+
+        let data = self.http.get_json_b(&url)?;
+
+        return Ok(data);
+    }
+
+    /// Search (async) the site for the given query and search types, parsed
+    /// into the typed model
+    #[cfg(feature = "async")]
+    pub async fn search_typed(
+        &self,
+        query: &str,
+        stypes: &Vec<Search>,
+        options: impl IntoParams,
+    ) -> Result<SearchResults> {
+        let data = self.search(query, stypes, options).await?;
+
+        return SearchResults::from_value(&data);
+    }
+
+    /// Search the site for the given query and search types, parsed into the
+    /// typed model
+    pub fn search_typed_b(
+        &self,
+        query: &str,
+        stypes: &Vec<Search>,
+        options: impl IntoParams,
+    ) -> Result<SearchResults> {
+        let data = self.search_b(query, stypes, options)?;
+
+        return SearchResults::from_value(&data);
+    }
+
+    /// A (async) convenience function for searching only board games
+    #[cfg(feature = "async")]
+    pub async fn search_boardgames(&self, query: &str, options: impl IntoParams) -> Result<Value> {
+        return self.search(query, &vec![Search::BoardGame], options).await;
+    }
+
+    /// A (sync) convenience function for searching only board games
+    pub fn search_boardgames_b(&self, query: &str, options: impl IntoParams) -> Result<Value> {
+        return self.search_b(query, &vec![Search::BoardGame], options);
+    }
+
+    /// A (async) convenience function for searching only board game
+    /// expansions
+    #[cfg(feature = "async")]
+    pub async fn search_expansions(&self, query: &str, options: impl IntoParams) -> Result<Value> {
+        return self
+            .search(query, &vec![Search::BoardGameExpansion], options)
+            .await;
+    }
+
+    /// A (sync) convenience function for searching only board game
+    /// expansions
+    pub fn search_expansions_b(&self, query: &str, options: impl IntoParams) -> Result<Value> {
+        return self.search_b(query, &vec![Search::BoardGameExpansion], options);
+    }
+
+    /// A (async) convenience function for searching only board game
+    /// accessories
+    #[cfg(feature = "async")]
+    pub async fn search_accessories(&self, query: &str, options: impl IntoParams) -> Result<Value> {
+        return self
+            .search(query, &vec![Search::BoardGameAccessory], options)
+            .await;
+    }
+
+    /// A (sync) convenience function for searching only board game
+    /// accessories
+    pub fn search_accessories_b(&self, query: &str, options: impl IntoParams) -> Result<Value> {
+        return self.search_b(query, &vec![Search::BoardGameAccessory], options);
+    }
+
+    /// A (async) convenience function for searching only video games
+    #[cfg(feature = "async")]
+    pub async fn search_videogames(&self, query: &str, options: impl IntoParams) -> Result<Value> {
+        return self.search(query, &vec![Search::VideoGame], options).await;
+    }
+
+    /// A (sync) convenience function for searching only video games
+    pub fn search_videogames_b(&self, query: &str, options: impl IntoParams) -> Result<Value> {
+        return self.search_b(query, &vec![Search::VideoGame], options);
+    }
+
+    /// A (async) convenience function for searching only rpg items
+    #[cfg(feature = "async")]
+    pub async fn search_rpgitems(&self, query: &str, options: impl IntoParams) -> Result<Value> {
+        return self.search(query, &vec![Search::RpgItem], options).await;
+    }
+
+    /// A (sync) convenience function for searching only rpg items
+    pub fn search_rpgitems_b(&self, query: &str, options: impl IntoParams) -> Result<Value> {
+        return self.search_b(query, &vec![Search::RpgItem], options);
+    }
+
+    /* Begin "thing"s */
+
+    /// This is the core (async) function for getting various "things" as
+    /// described by the BGG API.  It's also possible to use the convenience
+    /// functions like `boardgame()` instead, which will set the thing type
+    /// for you.  If none of the IDs are found, this returns
+    /// `RbggError::NotFound`.
+    #[cfg(feature = "async")]
+    pub async fn thing(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Thing>,
+        options: impl IntoParams,
+    ) -> Result<Value> {
+        let url = self.get_full_url(
+            "thing".into(),
+            options.into_params()?,
+            Some(thing_params(ids, ttypes)),
+        );
+        let res = self.http.get_json(&url).await;
+
+        return utils::check_found(res, "thing", &utils::join_ids(ids));
+    }
+
+    /// This is the core (sync) function for getting various "things" as
+    /// described by the BGG API.  It's also possible to use the convenience
+    /// functions like `boardgame()` instead, which will set the thing type
+    /// for you.  If none of the IDs are found, this returns
+    /// `RbggError::NotFound`.
+    pub fn thing_b(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Thing>,
+        options: impl IntoParams,
+    ) -> Result<Value> {
+        let url = self.get_full_url(
+            "thing".into(),
+            options.into_params()?,
+            Some(thing_params(ids, ttypes)),
+        );
+        let res = self.http.get_json_b(&url);
+
+        return utils::check_found(res, "thing", &utils::join_ids(ids));
+    }
+
+    /// Like `thing()`, but also returns the status, headers, timing, etc.
+    /// of the response
+    #[cfg(feature = "async")]
+    pub async fn thing_with_meta(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Thing>,
+        options: impl IntoParams,
+    ) -> Result<ApiResponse> {
+        let url = self.get_full_url(
+            "thing".into(),
+            options.into_params()?,
+            Some(thing_params(ids, ttypes)),
+        );
+
+        let res = self.http.get_json_with_meta(&url).await;
+
+        return check_meta(res, |r| {
+            utils::check_found(r, "thing", &utils::join_ids(ids))
+        });
+    }
+
+    /// Like `thing_b()`, but also returns the status, headers, timing, etc.
+    /// of the response
+    pub fn thing_with_meta_b(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Thing>,
+        options: impl IntoParams,
+    ) -> Result<ApiResponse> {
+        let url = self.get_full_url(
+            "thing".into(),
+            options.into_params()?,
+            Some(thing_params(ids, ttypes)),
+        );
+
+        let res = self.http.get_json_with_meta_b(&url);
+
+        return check_meta(res, |r| {
+            utils::check_found(r, "thing", &utils::join_ids(ids))
+        });
+    }
+
+    /// Like `thing()`, but returns the raw XML response along with its JSON
+    /// conversion, from the one request
+    #[cfg(feature = "async")]
+    pub async fn thing_with_xml(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Thing>,
+        options: impl IntoParams,
+    ) -> Result<(String, Value)> {
+        let url = self.get_full_url(
+            "thing".into(),
+            options.into_params()?,
+            Some(thing_params(ids, ttypes)),
+        );
+
+        let res = self.http.get_xml_and_json(&url).await;
+
+        return check_xml(res, |r| {
+            utils::check_found(r, "thing", &utils::join_ids(ids))
+        });
+    }
+
+    /// Like `thing_b()`, but returns the raw XML response along with its
+    /// JSON conversion, from the one request
+    pub fn thing_with_xml_b(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Thing>,
+        options: impl IntoParams,
+    ) -> Result<(String, Value)> {
+        let url = self.get_full_url(
+            "thing".into(),
+            options.into_params()?,
+            Some(thing_params(ids, ttypes)),
+        );
+
+        let res = self.http.get_xml_and_json_b(&url);
+
+        return check_xml(res, |r| {
+            utils::check_found(r, "thing", &utils::join_ids(ids))
+        });
+    }
+
+    /// Get (async) the given "things", parsed into the typed model.  Set
+    /// `stats`, `versions`, etc. in the options to fill in the optional
+    /// parts of each item.
+    #[cfg(feature = "async")]
+    pub async fn thing_typed(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Thing>,
+        options: impl IntoParams,
+    ) -> Result<Vec<ThingItem>> {
+        let data = self.thing(ids, ttypes, options).await?;
+
+        return Ok(Things::from_value(&data)?.items);
+    }
+
+    /// Get (sync) the given "things", parsed into the typed model.  Set
+    /// `stats`, `versions`, etc. in the options to fill in the optional
+    /// parts of each item.
+    pub fn thing_typed_b(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Thing>,
+        options: impl IntoParams,
+    ) -> Result<Vec<ThingItem>> {
+        let data = self.thing_b(ids, ttypes, options)?;
+
+        return Ok(Things::from_value(&data)?.items);
+    }
+
+    /// A (async) convenience function for getting the info for a board game
+    #[cfg(feature = "async")]
+    pub async fn boardgame(&self, ids: &Vec<usize>, options: impl IntoParams) -> Result<Value> {
+        return self.thing(ids, &vec![Thing::BoardGame], options).await;
+    }
+
+    /// A (sync) convenience function for getting the info for a board game
+    pub fn boardgame_b(&self, ids: &Vec<usize>, options: impl IntoParams) -> Result<Value> {
+        return self.thing_b(ids, &vec![Thing::BoardGame], options);
+    }
+
+    /// A (async) convenience function for getting the info for a board game
+    /// expansion
+    #[cfg(feature = "async")]
+    pub async fn boardgameexpansion(
+        &self,
+        ids: &Vec<usize>,
+        options: impl IntoParams,
+    ) -> Result<Value> {
+        return self
+            .thing(ids, &vec![Thing::BoardGameExpansion], options)
+            .await;
+    }
+
+    /// A (sync) convenience function for getting the info for a board game
+    /// expansion
+    pub fn boardgameexpansion_b(
+        &self,
+        ids: &Vec<usize>,
+        options: impl IntoParams,
+    ) -> Result<Value> {
+        return self.thing_b(ids, &vec![Thing::BoardGameExpansion], options);
+    }
+
+    /// A (async) convenience function for getting the info for a board game
+    /// accessory
+    #[cfg(feature = "async")]
+    pub async fn boardgameaccessory(
+        &self,
+        ids: &Vec<usize>,
+        options: impl IntoParams,
+    ) -> Result<Value> {
+        return self
+            .thing(ids, &vec![Thing::BoardGameAccessory], options)
+            .await;
+    }
+
+    /// A (sync) convenience function for getting the info for a board game
+    /// accessory
+    pub fn boardgameaccessory_b(
+        &self,
+        ids: &Vec<usize>,
+        options: impl IntoParams,
+    ) -> Result<Value> {
+        return self.thing_b(ids, &vec![Thing::BoardGameAccessory], options);
+    }
+
+    /// A (async) convenience function for getting the info for a video game
+    #[cfg(feature = "async")]
+    pub async fn videogame(&self, ids: &Vec<usize>, options: impl IntoParams) -> Result<Value> {
+        return self.thing(ids, &vec![Thing::VideoGame], options).await;
+    }
+
+    /// A (sync) convenience function for getting the info for a video game
+    pub fn videogame_b(&self, ids: &Vec<usize>, options: impl IntoParams) -> Result<Value> {
+        return self.thing_b(ids, &vec![Thing::VideoGame], options);
+    }
+
+    /// A (async) convenience function for getting the info for a rpg item
+    #[cfg(feature = "async")]
+    pub async fn rpgitem(&self, ids: &Vec<usize>, options: impl IntoParams) -> Result<Value> {
+        return self.thing(ids, &vec![Thing::RpgItem], options).await;
+    }
+
+    /// A (sync) convenience function for getting the info for a rpg item
+    pub fn rpgitem_b(&self, ids: &Vec<usize>, options: impl IntoParams) -> Result<Value> {
+        return self.thing_b(ids, &vec![Thing::RpgItem], options);
+    }
+
+    /// A (async) convenience function for getting the info for a rpg issue
+    #[cfg(feature = "async")]
+    pub async fn rpgissue(&self, ids: &Vec<usize>, options: impl IntoParams) -> Result<Value> {
+        return self.thing(ids, &vec![Thing::RpgIssue], options).await;
+    }
+
+    /// A (sync) convenience function for getting the info for a rpg issue
+    pub fn rpgissue_b(&self, ids: &Vec<usize>, options: impl IntoParams) -> Result<Value> {
+        return self.thing_b(ids, &vec![Thing::RpgIssue], options);
+    }
+
+    /// A (async) convenience function for getting the info for board games
+    /// along with their published versions.  Any other options given are
+    /// passed along.
+    #[cfg(feature = "async")]
+    pub async fn boardgame_versions(
+        &self,
+        ids: &Vec<usize>,
+        options: impl IntoParams,
+    ) -> Result<Value> {
+        return self.boardgame(ids, with_flag(options, "versions")?).await;
+    }
+
+    /// A (sync) convenience function for getting the info for board games
+    /// along with their published versions.  Any other options given are
+    /// passed along.
+    pub fn boardgame_versions_b(
+        &self,
+        ids: &Vec<usize>,
+        options: impl IntoParams,
+    ) -> Result<Value> {
+        return self.boardgame_b(ids, with_flag(options, "versions")?);
+    }
+
+    /// A (async) convenience function for getting the info for things of
+    /// the given types along with their videos.  Any other options given
+    /// are passed along.
+    #[cfg(feature = "async")]
+    pub async fn thing_videos(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Thing>,
+        options: impl IntoParams,
+    ) -> Result<Value> {
+        return self.thing(ids, ttypes, with_flag(options, "videos")?).await;
+    }
+
+    /// A (sync) convenience function for getting the info for things of the
+    /// given types along with their videos.  Any other options given are
+    /// passed along.
+    pub fn thing_videos_b(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Thing>,
+        options: impl IntoParams,
+    ) -> Result<Value> {
+        return self.thing_b(ids, ttypes, with_flag(options, "videos")?);
+    }
+
+    /// A (async) convenience function for getting the info for things of
+    /// the given types along with their marketplace listings.  Any other
+    /// options given are passed along.
+    #[cfg(feature = "async")]
+    pub async fn thing_marketplace(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Thing>,
+        options: impl IntoParams,
+    ) -> Result<Value> {
+        return self
+            .thing(ids, ttypes, with_flag(options, "marketplace")?)
+            .await;
+    }
+
+    /// A (sync) convenience function for getting the info for things of the
+    /// given types along with their marketplace listings.  Any other
+    /// options given are passed along.
+    pub fn thing_marketplace_b(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Thing>,
+        options: impl IntoParams,
+    ) -> Result<Value> {
+        return self.thing_b(ids, ttypes, with_flag(options, "marketplace")?);
+    }
+
+    /// Get (async) a page of ratings (along with any comments) for a thing
+    /// of any type.  `page` starts at 1 and `page_size` must be from 10 to
+    /// 100, otherwise this returns `RbggError::InvalidInput`.
+    #[cfg(feature = "async")]
+    pub async fn thing_ratingcomments(
+        &self,
+        id: usize,
+        page: usize,
+        page_size: usize,
+    ) -> Result<Value> {
+        let url = self.get_full_url(
+            "thing".into(),
+            ratingcomments_options(page, page_size).into_params()?,
+            Some(Params::from([("id".into(), id.to_string())])),
+        );
+        let res = self.http.get_json(&url).await;
+
+        return utils::check_found(res, "thing", &id.to_string());
+    }
+
+    /// Get (sync) a page of ratings (along with any comments) for a thing
+    /// of any type.  `page` starts at 1 and `page_size` must be from 10 to
+    /// 100, otherwise this returns `RbggError::InvalidInput`.
+    pub fn thing_ratingcomments_b(
+        &self,
+        id: usize,
+        page: usize,
+        page_size: usize,
+    ) -> Result<Value> {
+        let url = self.get_full_url(
+            "thing".into(),
+            ratingcomments_options(page, page_size).into_params()?,
+            Some(Params::from([("id".into(), id.to_string())])),
+        );
+        let res = self.http.get_json_b(&url);
+
+        return utils::check_found(res, "thing", &id.to_string());
+    }
+
+    /// Get (async) everything BGG has on the given things (of any type):
+    /// stats, versions, videos, marketplace listings and the first page of
+    /// comments, parsed into the typed model.  More than 20 IDs are split
+    /// across multiple requests.
+    #[cfg(feature = "async")]
+    pub async fn thing_full(&self, ids: &[usize]) -> Result<Things> {
+        let mut ret = Things::default();
+
+        for chunk in ids.chunks(THING_CHUNK_SIZE) {
+            let url = self.get_full_url("thing".into(), None, Some(thing_full_params(chunk)));
+            let data = self.http.get_json(&url).await?;
+            ret.items.extend(Things::from_value(&data)?.items);
+        }
+
+        return Ok(ret);
+    }
+
+    /// Get (sync) everything BGG has on the given things (of any type):
+    /// stats, versions, videos, marketplace listings and the first page of
+    /// comments, parsed into the typed model.  More than 20 IDs are split
+    /// across multiple requests.
+    pub fn thing_full_b(&self, ids: &[usize]) -> Result<Things> {
+        let mut ret = Things::default();
+
+        for chunk in ids.chunks(THING_CHUNK_SIZE) {
+            let url = self.get_full_url("thing".into(), None, Some(thing_full_params(chunk)));
+            let data = self.http.get_json_b(&url)?;
+            ret.items.extend(Things::from_value(&data)?.items);
+        }
+
+        return Ok(ret);
+    }
+
+    /// Harvest (async) every comment for a given thing, one page at a time.
+    /// Each page of comments is handed to `handler` and then dropped, so
+    /// memory stays flat even for games with tens of thousands of comments.
+    /// Returns the total number of comments processed.
+    #[cfg(feature = "async")]
+    pub async fn harvest_comments<F>(&self, id: usize, mut handler: F) -> Result<usize>
+    where
+        F: FnMut(Vec<Value>) -> Result<()>,
+    {
+        let mut pager = self.comments_paginator(id);
+        let mut count = 0;
+
+        while let Some(data) = pager.next_page().await? {
+            let (comments, _) = take_comments_page(data);
+            count += comments.len();
+            handler(comments)?;
+        }
+
+        return Ok(count);
+    }
+
+    /// Harvest (sync) every comment for a given thing, one page at a time.
+    /// Each page of comments is handed to `handler` and then dropped, so
+    /// memory stays flat even for games with tens of thousands of comments.
+    /// Returns the total number of comments processed.
+    pub fn harvest_comments_b<F>(&self, id: usize, mut handler: F) -> Result<usize>
+    where
+        F: FnMut(Vec<Value>) -> Result<()>,
+    {
+        let mut pager = self.comments_paginator(id);
+        let mut count = 0;
+
+        while let Some(data) = pager.next_page_b()? {
+            let (comments, _) = take_comments_page(data);
+            count += comments.len();
+            handler(comments)?;
+        }
+
+        return Ok(count);
+    }
+
+    /// Stream (async) every comment for a given thing, fetching the pages
+    /// (of the largest size BGG allows) as they're needed.  An error ends
+    /// the stream.
+    #[cfg(feature = "async")]
+    pub fn thing_comments_stream(&self, id: usize) -> impl Stream<Item = Result<Comment>> + '_ {
+        return stream_pages(1, move |page| async move {
+            let url = self.get_full_url("thing".into(), None, Some(comments_params(id, page)));
+            let data = self.http.get_json(&url).await?;
+
+            return comments_page(&data, page);
+        });
+    }
+
+    /// Iterate (sync) over every comment for a given thing, fetching the
+    /// pages (of the largest size BGG allows) as they're needed.  An error
+    /// ends the iteration.
+    pub fn thing_comments_iter_b(&self, id: usize) -> impl Iterator<Item = Result<Comment>> + '_ {
+        return iter_pages(1, move |page| {
+            let url = self.get_full_url("thing".into(), None, Some(comments_params(id, page)));
+            let data = self.http.get_json_b(&url)?;
+
+            return comments_page(&data, page);
+        });
+    }
+
+    /// Write (async) the raw (XML) response for the given "things" straight
+    /// to `writer` without holding the whole response in memory.  Returns
+    /// the number of bytes written.
+    #[cfg(feature = "async")]
+    pub async fn thing_to_writer<W>(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Thing>,
+        options: impl IntoParams,
+        writer: &mut W,
+    ) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let url = self.get_full_url(
+            "thing".into(),
+            options.into_params()?,
+            Some(thing_params(ids, ttypes)),
+        );
+
+        let res = self.http.get_to_writer(&url, writer).await;
+
+        return check_written(res, |r| {
+            utils::check_found(r, "thing", &utils::join_ids(ids))
+        });
+    }
+
+    /// Write (sync) the raw (XML) response for the given "things" straight
+    /// to `writer` without holding the whole response in memory.  Returns
+    /// the number of bytes written.
+    pub fn thing_to_writer_b<W>(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Thing>,
+        options: impl IntoParams,
+        writer: &mut W,
+    ) -> Result<u64>
+    where
+        W: Write,
+    {
+        let url = self.get_full_url(
+            "thing".into(),
+            options.into_params()?,
+            Some(thing_params(ids, ttypes)),
+        );
+
+        let res = self.http.get_to_writer_b(&url, writer);
+
+        return check_written(res, |r| {
+            utils::check_found(r, "thing", &utils::join_ids(ids))
+        });
+    }
+
+    /* End "thing"s */
+
+    /* Begin "family" items */
+
+    /// This is the core (async) function for getting various "family" items as
+    /// described by the BGG API.  It's also possible to use the convenience
+    /// functions like `rpg()` instead, which will set the thing type
+    /// for you.  BGG doesn't currently document any options for families,
+    /// but any given are passed along.  If none of the IDs are found, this
+    /// returns `RbggError::NotFound`.
+    #[cfg(feature = "async")]
+    pub async fn family(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Family>,
+        options: impl IntoParams,
+    ) -> Result<Value> {
+        // Convert the numeric ids to strings
+        let sids: Vec<String> = ids.iter().map(|i| i.to_string()).collect();
+        let params = Params::from([
+            ("id".into(), sids.join(",")),
+            (
+                "type".into(),
+                ttypes
+                    .iter()
+                    .map(|t| t.as_str())
+                    .collect::<Vec<&'static str>>()
+                    .join(","),
+            ),
+        ]);
+        let url = self.get_full_url("family".into(), options.into_params()?, Some(params));
+        let res = self.http.get_json(&url).await;
+
+        return utils::check_found(res, "family", &utils::join_ids(ids));
+    }
+
+    /// This is the core (sync) function for getting various "family" items as
+    /// described by the BGG API.  It's also possible to use the convenience
+    /// functions like `rpg()` instead, which will set the thing type
+    /// for you.  BGG doesn't currently document any options for families,
+    /// but any given are passed along.  If none of the IDs are found, this
+    /// returns `RbggError::NotFound`.
+    pub fn family_b(
+        &self,
+        ids: &Vec<usize>,
+        ttypes: &Vec<Family>,
+        options: impl IntoParams,
+    ) -> Result<Value> {
+        // Convert the numeric ids to strings
+        let sids: Vec<String> = ids.iter().map(|i| i.to_string()).collect();
+        let params = Params::from([
+            ("id".into(), sids.join(",")),
+            (
+                "type".into(),
+                ttypes
+                    .iter()
+                    .map(|t| t.as_str())
+                    .collect::<Vec<&'static str>>()
+                    .join(","),
+            ),
+        ]);
+        let url = self.get_full_url("family".into(), options.into_params()?, Some(params));
+        let res = self.http.get_json_b(&url);
+
+        return utils::check_found(res, "family", &utils::join_ids(ids));
+    }
+
+    /// A (async) convenience function for getting the info for a rpg
+    #[cfg(feature = "async")]
+    pub async fn rpg(&self, ids: &Vec<usize>, options: impl IntoParams) -> Result<Value> {
+        return self.family(ids, &vec![Family::Rpg], options).await;
+    }
+
+    /// A (sync) convenience function for getting the info for a rpg
+    pub fn rpg_b(&self, ids: &Vec<usize>, options: impl IntoParams) -> Result<Value> {
+        return self.family_b(ids, &vec![Family::Rpg], options);
+    }
+
+    /// A (async) convenience function for getting the info for a rpg
+    /// periodical
+    #[cfg(feature = "async")]
+    pub async fn rpgperiodical(&self, ids: &Vec<usize>, options: impl IntoParams) -> Result<Value> {
+        return self
+            .family(ids, &vec![Family::RpgPeriodical], options)
+            .await;
+    }
+
+    /// A (sync) convenience function for getting the info for a rpg
+    /// periodical
+    pub fn rpgperiodical_b(&self, ids: &Vec<usize>, options: impl IntoParams) -> Result<Value> {
+        return self.family_b(ids, &vec![Family::RpgPeriodical], options);
+    }
+
+    /// A (async) convenience function for getting the info for a board game
+    /// family
+    #[cfg(feature = "async")]
+    pub async fn boardgamefamily(
+        &self,
+        ids: &Vec<usize>,
+        options: impl IntoParams,
+    ) -> Result<Value> {
+        return self
+            .family(ids, &vec![Family::BoardGameFamily], options)
+            .await;
+    }
+
+    /// A (sync) convenience function for getting the info for a board game
+    /// family
+    pub fn boardgamefamily_b(&self, ids: &Vec<usize>, options: impl IntoParams) -> Result<Value> {
+        return self.family_b(ids, &vec![Family::BoardGameFamily], options);
+    }
+
+    /* End "family" items */
+
+    /// Get a (async) list of forums for a given game (by ID)
+    #[cfg(feature = "async")]
+    pub async fn forumlist(&self, game_id: usize, ltype: ThingFamily) -> Result<Value> {
+        let params = Params::from([
+            ("id".into(), game_id.to_string()),
+            ("type".into(), ltype.to_string()),
+        ]);
+        let url = self.get_full_url("forumlist".into(), None, Some(params));
+
+        let data = self.http.get_json(&url).await?;
+
+        return Ok(data);
+    }
+
+    /// Get a (sync) list of forums for a given game (by ID)
+    pub fn forumlist_b(&self, game_id: usize, ltype: ThingFamily) -> Result<Value> {
+        let params = Params::from([
+            ("id".into(), game_id.to_string()),
+            ("type".into(), ltype.to_string()),
+        ]);
+        let url = self.get_full_url("forumlist".into(), None, Some(params));
+
+        let data = self.http.get_json_b(&url)?;
+
+        return Ok(data);
+    }
+
+    /// A (async) convenience function for getting the list of forums for a
+    /// family (by ID).  This is the same as calling `forumlist()` with
+    /// `ThingFamily::Family`, which is easy to forget.
+    #[cfg(feature = "async")]
+    pub async fn forumlist_family(&self, family_id: usize) -> Result<Value> {
+        return self.forumlist(family_id, ThingFamily::Family).await;
+    }
+
+    /// A (sync) convenience function for getting the list of forums for a
+    /// family (by ID).  This is the same as calling `forumlist_b()` with
+    /// `ThingFamily::Family`, which is easy to forget.
+    pub fn forumlist_family_b(&self, family_id: usize) -> Result<Value> {
+        return self.forumlist_b(family_id, ThingFamily::Family);
+    }
+
+    /// Get a (async) list of threads in a particular forum by forum ID
+    #[cfg(feature = "async")]
+    pub async fn forum(&self, forum_id: usize, options: impl IntoParams) -> Result<Value> {
+        let params = Params::from([("id".into(), forum_id.to_string())]);
+        let url = self.get_full_url("forum".into(), options.into_params()?, Some(params));
+
+        let data = self.http.get_json(&url).await?;
+
+        return Ok(data);
+    }
+
+    /// Get a (sync) list of threads in a particular forum by forum ID
+    pub fn forum_b(&self, forum_id: usize, options: impl IntoParams) -> Result<Value> {
+        let params = Params::from([("id".into(), forum_id.to_string())]);
+        let url = self.get_full_url("forum".into(), options.into_params()?, Some(params));
+
+        let data = self.http.get_json_b(&url)?;
+
+        return Ok(data);
+    }
+
+    /// Stream (async) every thread in a forum, fetching the pages as they're
+    /// needed.  An error ends the stream.
+    ///
+    /// ```ignore,rust
+    /// let mut threads = Box::pin(cl.forum_stream(19));
+    /// while let Some(thread) = threads.next().await {
+    ///     println!("{}", thread?.subject);
+    /// }
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn forum_stream(&self, forum_id: usize) -> impl Stream<Item = Result<ThreadSummary>> + '_ {
+        return stream_pages(1, move |page| async move {
+            let data = self.forum(forum_id, Some(page_params(page))).await?;
+
+            return forum_page(&data, page);
+        });
+    }
+
+    /// Iterate (sync) over every thread in a forum, fetching the pages as
+    /// they're needed.  An error ends the iteration.
+    pub fn forum_iter_b(
+        &self,
+        forum_id: usize,
+    ) -> impl Iterator<Item = Result<ThreadSummary>> + '_ {
+        return iter_pages(1, move |page| {
+            let data = self.forum_b(forum_id, Some(page_params(page)))?;
+
+            return forum_page(&data, page);
+        });
+    }
+
+    /// Stream (async) every article in a thread, fetching them in batches as
+    /// they're needed.  An error ends the stream.
+    #[cfg(feature = "async")]
+    pub fn thread_stream(&self, thread_id: usize) -> impl Stream<Item = Result<Article>> + '_ {
+        return stream_pages(0, move |from| async move {
+            let data = self.thread_slice(thread_id, from, THREAD_PAGE_SIZE).await?;
+
+            return thread_page(&data);
+        });
+    }
+
+    /// Iterate (sync) over every article in a thread, fetching them in
+    /// batches as they're needed.  An error ends the iteration.
+    pub fn thread_iter_b(&self, thread_id: usize) -> impl Iterator<Item = Result<Article>> + '_ {
+        return iter_pages(0, move |from| {
+            let data = self.thread_slice_b(thread_id, from, THREAD_PAGE_SIZE)?;
+
+            return thread_page(&data);
+        });
+    }
+
+    /// Get a (async) thread by ID
+    #[cfg(feature = "async")]
+    pub async fn thread(&self, thread_id: usize, options: impl IntoParams) -> Result<Value> {
+        let params = Params::from([("id".into(), thread_id.to_string())]);
+        let url = self.get_full_url("thread".into(), options.into_params()?, Some(params));
+
+        let data = self.http.get_json(&url).await?;
+
+        return Ok(data);
+    }
+
+    /// Get a (sync) list of threads in a particular forum by forum ID
+    pub fn thread_b(&self, thread_id: usize, options: impl IntoParams) -> Result<Value> {
         let params = Params::from([("id".into(), thread_id.to_string())]);
-        let url = self.get_full_url("thread".into(), options, Some(params));
+        let url = self.get_full_url("thread".into(), options.into_params()?, Some(params));
 
-        let data = utils::get_json_resp_b(&url)?;
+        let data = self.http.get_json_b(&url)?;
 
         return Ok(data);
     }
 
-    /// Get a (async) user by their username
-    pub async fn user(&self, username: &str, options: Option<Params>) -> Result<Value> {
+    /// Get (async) a slice of a thread: at most `count` articles, starting
+    /// from the article with the ID `from_article`.  This is handy for
+    /// rendering long threads incrementally.
+    #[cfg(feature = "async")]
+    pub async fn thread_slice(
+        &self,
+        thread_id: usize,
+        from_article: usize,
+        count: usize,
+    ) -> Result<Value> {
+        let params = thread_slice_params(thread_id, from_article, count);
+        let url = self.get_full_url("thread".into(), None, Some(params));
+
+        let data = self.http.get_json(&url).await?;
+
+        return Ok(data);
+    }
+
+    /// Get (sync) a slice of a thread: at most `count` articles, starting
+    /// from the article with the ID `from_article`.  This is handy for
+    /// rendering long threads incrementally.
+    pub fn thread_slice_b(
+        &self,
+        thread_id: usize,
+        from_article: usize,
+        count: usize,
+    ) -> Result<Value> {
+        let params = thread_slice_params(thread_id, from_article, count);
+        let url = self.get_full_url("thread".into(), None, Some(params));
+
+        let data = self.http.get_json_b(&url)?;
+
+        return Ok(data);
+    }
+
+    /// Get a (async) user by their username, or `RbggError::UnknownUser` if
+    /// there's no such user
+    #[cfg(feature = "async")]
+    pub async fn user(&self, username: &str, options: impl IntoParams) -> Result<Value> {
+        let params = Params::from([("name".into(), username.into())]);
+        let url = self.get_full_url("user".into(), options.into_params()?, Some(params));
+        let res = self.http.get_json(&url).await;
+
+        return utils::check_user(res, username);
+    }
+
+    /// Get a (sync) user by their username, or `RbggError::UnknownUser` if
+    /// there's no such user
+    pub fn user_b(&self, username: &str, options: impl IntoParams) -> Result<Value> {
         let params = Params::from([("name".into(), username.into())]);
-        let url = self.get_full_url("user".into(), options, Some(params));
+        let url = self.get_full_url("user".into(), options.into_params()?, Some(params));
+        let res = self.http.get_json_b(&url);
+
+        return utils::check_user(res, username);
+    }
+
+    /// Get a (async) guild by ID
+    #[cfg(feature = "async")]
+    pub async fn guild(&self, guild_id: usize, options: impl IntoParams) -> Result<Value> {
+        let params = Params::from([("id".into(), guild_id.to_string())]);
+        let url = self.get_full_url("guild".into(), options.into_params()?, Some(params));
+
+        let data = self.http.get_json(&url).await?;
+
+        return Ok(data);
+    }
+
+    /// Get a (sync) guild by ID
+    pub fn guild_b(&self, guild_id: usize, options: impl IntoParams) -> Result<Value> {
+        let params = Params::from([("id".into(), guild_id.to_string())]);
+        let url = self.get_full_url("guild".into(), options.into_params()?, Some(params));
+
+        let data = self.http.get_json_b(&url)?;
+
+        return Ok(data);
+    }
+
+    /// Get a (async) guild by ID, parsed into the typed model.  Set
+    /// `members=1` (and `page`) in the options to include the members.
+    #[cfg(feature = "async")]
+    pub async fn guild_typed(&self, guild_id: usize, options: impl IntoParams) -> Result<Guild> {
+        let data = self.guild(guild_id, options).await?;
+
+        return Guild::from_value(&data);
+    }
+
+    /// Get a (sync) guild by ID, parsed into the typed model.  Set
+    /// `members=1` (and `page`) in the options to include the members.
+    pub fn guild_typed_b(&self, guild_id: usize, options: impl IntoParams) -> Result<Guild> {
+        let data = self.guild_b(guild_id, options)?;
+
+        return Guild::from_value(&data);
+    }
+
+    /// Get (async) plays for a user or for a particular item. Either a
+    /// username or item ID + ttype MUST be supplied.  An unknown username
+    /// is returned as `RbggError::UnknownUser`.
+    #[cfg(feature = "async")]
+    pub async fn plays(
+        &self,
+        username: Option<&str>,
+        item_id: Option<usize>,
+        ttype: Option<ThingFamily>,
+        options: impl IntoParams,
+    ) -> Result<Value> {
+        if username.is_none() && (item_id.is_none() || ttype.is_none()) {
+            return Err(RbggError::InvalidInput(
+                "You must supply either a username or item_id + ttype".into(),
+            ));
+        }
+
+        if username.is_some() && item_id.is_some() {
+            return Err(RbggError::InvalidInput(
+                "You must supply either a username or item_id + ttype, not both".into(),
+            ));
+        }
+
+        let params;
+        if let Some(u) = username {
+            params = Params::from([("username".into(), u.into())]);
+        } else if let Some(id) = item_id {
+            params = Params::from([
+                ("id".into(), id.to_string()),
+                ("type".into(), ttype.unwrap().to_string()),
+            ]);
+        } else {
+            // We should never get here
+            return Err(RbggError::InvalidInput(
+                "We have a logic bug here as this should never happen".into(),
+            ));
+        }
+
+        let url = self.get_full_url("plays".into(), options.into_params()?, Some(params));
+        let res = self.http.get_json(&url).await;
+
+        return match username {
+            Some(username) => utils::check_user(res, username),
+            None => res,
+        };
+    }
+
+    /// Get (sync) plays for a user or for a particular item. Either a
+    /// username or item ID + ttype MUST be supplied.  An unknown username
+    /// is returned as `RbggError::UnknownUser`.
+    pub fn plays_b(
+        &self,
+        username: Option<&str>,
+        item_id: Option<usize>,
+        ttype: Option<ThingFamily>,
+        options: impl IntoParams,
+    ) -> Result<Value> {
+        if username.is_none() && (item_id.is_none() || ttype.is_none()) {
+            return Err(RbggError::InvalidInput(
+                "You must supply either a username or item_id + ttype".into(),
+            ));
+        }
+
+        if username.is_some() && item_id.is_some() {
+            return Err(RbggError::InvalidInput(
+                "You must supply either a username or item_id + ttype, not both".into(),
+            ));
+        }
+
+        let params;
+        if let Some(u) = username {
+            params = Params::from([("username".into(), u.into())]);
+        } else if let Some(id) = item_id {
+            params = Params::from([
+                ("id".into(), id.to_string()),
+                ("type".into(), ttype.unwrap().to_string()),
+            ]);
+        } else {
+            // We should never get here
+            return Err(RbggError::InvalidInput(
+                "We have a logic bug here as this should never happen".into(),
+            ));
+        }
+
+        let url = self.get_full_url("plays".into(), options.into_params()?, Some(params));
+        let res = self.http.get_json_b(&url);
+
+        return match username {
+            Some(username) => utils::check_user(res, username),
+            None => res,
+        };
+    }
+
+    /// Stream (async) every play for a user or for a particular item,
+    /// fetching the pages as they're needed.  The arguments are the same as
+    /// for `plays()`, and an error ends the stream.
+    #[cfg(feature = "async")]
+    pub fn plays_stream<'a>(
+        &'a self,
+        username: Option<&'a str>,
+        item_id: Option<usize>,
+        ttype: Option<ThingFamily>,
+        options: impl IntoParams,
+    ) -> impl Stream<Item = Result<Play>> + 'a {
+        let options = options.into_params();
+
+        return stream_pages(1, move |page| {
+            let options = plays_page_options(&options, page);
+            async move {
+                let data = self.plays(username, item_id, ttype, options?).await?;
+
+                return plays_page(&data, page);
+            }
+        });
+    }
+
+    /// Iterate (sync) over every play for a user or for a particular item,
+    /// fetching the pages as they're needed.  The arguments are the same as
+    /// for `plays_b()`, and an error ends the iteration.
+    pub fn plays_iter_b<'a>(
+        &'a self,
+        username: Option<&'a str>,
+        item_id: Option<usize>,
+        ttype: Option<ThingFamily>,
+        options: impl IntoParams,
+    ) -> impl Iterator<Item = Result<Play>> + 'a {
+        let options = options.into_params();
+
+        return iter_pages(1, move |page| {
+            let options = plays_page_options(&options, page)?;
+            let data = self.plays_b(username, item_id, ttype, options)?;
+
+            return plays_page(&data, page);
+        });
+    }
+
+    /// Get a (async) user's collection by username, or
+    /// `RbggError::UnknownUser` if there's no such user
+    #[cfg(feature = "async")]
+    pub async fn collection(&self, username: &str, options: impl IntoParams) -> Result<Value> {
+        let params = Params::from([("username".into(), username.into())]);
+        let url = self.get_full_url("collection".into(), options.into_params()?, Some(params));
+        let res = self.http.get_json(&url).await;
+
+        return utils::check_user(res, username);
+    }
+
+    /// Get a (sync) user's collection by username, or
+    /// `RbggError::UnknownUser` if there's no such user
+    pub fn collection_b(&self, username: &str, options: impl IntoParams) -> Result<Value> {
+        let params = Params::from([("username".into(), username.into())]);
+        let url = self.get_full_url("collection".into(), options.into_params()?, Some(params));
+        let res = self.http.get_json_b(&url);
+
+        return utils::check_user(res, username);
+    }
+
+    /// Like `collection()`, but also returns the status, headers, timing,
+    /// etc. of the response
+    #[cfg(feature = "async")]
+    pub async fn collection_with_meta(
+        &self,
+        username: &str,
+        options: impl IntoParams,
+    ) -> Result<ApiResponse> {
+        let params = Params::from([("username".into(), username.into())]);
+        let url = self.get_full_url("collection".into(), options.into_params()?, Some(params));
+
+        let res = self.http.get_json_with_meta(&url).await;
+
+        return check_meta(res, |r| utils::check_user(r, username));
+    }
+
+    /// Like `collection_b()`, but also returns the status, headers, timing,
+    /// etc. of the response
+    pub fn collection_with_meta_b(
+        &self,
+        username: &str,
+        options: impl IntoParams,
+    ) -> Result<ApiResponse> {
+        let params = Params::from([("username".into(), username.into())]);
+        let url = self.get_full_url("collection".into(), options.into_params()?, Some(params));
+
+        let res = self.http.get_json_with_meta_b(&url);
+
+        return check_meta(res, |r| utils::check_user(r, username));
+    }
+
+    /// Like `collection()`, but returns the raw XML response along with its
+    /// JSON conversion, from the one request
+    #[cfg(feature = "async")]
+    pub async fn collection_with_xml(
+        &self,
+        username: &str,
+        options: impl IntoParams,
+    ) -> Result<(String, Value)> {
+        let params = Params::from([("username".into(), username.into())]);
+        let url = self.get_full_url("collection".into(), options.into_params()?, Some(params));
+
+        let res = self.http.get_xml_and_json(&url).await;
+
+        return check_xml(res, |r| utils::check_user(r, username));
+    }
+
+    /// Like `collection_b()`, but returns the raw XML response along with
+    /// its JSON conversion, from the one request
+    pub fn collection_with_xml_b(
+        &self,
+        username: &str,
+        options: impl IntoParams,
+    ) -> Result<(String, Value)> {
+        let params = Params::from([("username".into(), username.into())]);
+        let url = self.get_full_url("collection".into(), options.into_params()?, Some(params));
+
+        let res = self.http.get_xml_and_json_b(&url);
+
+        return check_xml(res, |r| utils::check_user(r, username));
+    }
+
+    /// Get a (async) user's collection by username, parsed into the typed
+    /// model
+    #[cfg(feature = "async")]
+    pub async fn collection_typed(
+        &self,
+        username: &str,
+        options: impl IntoParams,
+    ) -> Result<Collection> {
+        let data = self.collection(username, options).await?;
+
+        return Collection::from_value(&data);
+    }
+
+    /// Get a (sync) user's collection by username, parsed into the typed
+    /// model
+    pub fn collection_typed_b(
+        &self,
+        username: &str,
+        options: impl IntoParams,
+    ) -> Result<Collection> {
+        let data = self.collection_b(username, options)?;
+
+        return Collection::from_value(&data);
+    }
+
+    /// Get (async) every game a user has rated as (game ID, the user's
+    /// rating, BGG's average rating) tuples.  This is the dataset most
+    /// recommendation engines want.
+    #[cfg(feature = "async")]
+    pub async fn user_ratings(&self, username: &str) -> Result<Vec<(usize, f64, Option<f64>)>> {
+        let data = self
+            .collection(username, Some(user_ratings_params()))
+            .await?;
+
+        return user_ratings_from(&data);
+    }
+
+    /// Get (sync) every game a user has rated as (game ID, the user's
+    /// rating, BGG's average rating) tuples.  This is the dataset most
+    /// recommendation engines want.
+    pub fn user_ratings_b(&self, username: &str) -> Result<Vec<(usize, f64, Option<f64>)>> {
+        let data = self.collection_b(username, Some(user_ratings_params()))?;
+
+        return user_ratings_from(&data);
+    }
+
+    /// Get (async) a report of a user's wishlist, grouped by priority and
+    /// enriched with each game's current rank and ratings.  This makes
+    /// (chunked) thing requests for the stats on top of the collection
+    /// request.
+    #[cfg(feature = "async")]
+    pub async fn wishlist_report(&self, username: &str) -> Result<Vec<WishlistGroup>> {
+        let data = self.collection(username, Some(wishlist_params())).await?;
+        let coll = Collection::from_value(&data)?;
+        let mut things = vec![];
+
+        for chunk in wishlist_ids(&coll).chunks(THING_CHUNK_SIZE) {
+            let data = self
+                .thing(&chunk.to_vec(), &wishlist_types(), Some(stats_params()))
+                .await?;
+            things.extend(Things::from_value(&data)?.items);
+        }
+
+        return Ok(analysis::wishlist_report(&coll, &things));
+    }
+
+    /// Get (sync) a report of a user's wishlist, grouped by priority and
+    /// enriched with each game's current rank and ratings.  This makes
+    /// (chunked) thing requests for the stats on top of the collection
+    /// request.
+    pub fn wishlist_report_b(&self, username: &str) -> Result<Vec<WishlistGroup>> {
+        let data = self.collection_b(username, Some(wishlist_params()))?;
+        let coll = Collection::from_value(&data)?;
+        let mut things = vec![];
+
+        for chunk in wishlist_ids(&coll).chunks(THING_CHUNK_SIZE) {
+            let data = self.thing_b(&chunk.to_vec(), &wishlist_types(), Some(stats_params()))?;
+            things.extend(Things::from_value(&data)?.items);
+        }
+
+        return Ok(analysis::wishlist_report(&coll, &things));
+    }
+
+    /// Write (async) a user's raw (XML) collection straight to `writer`
+    /// without holding the whole response in memory.  This is what you want
+    /// for very large collections that are being exported to disk.  Returns
+    /// the number of bytes written.
+    #[cfg(feature = "async")]
+    pub async fn collection_to_writer<W>(
+        &self,
+        username: &str,
+        options: impl IntoParams,
+        writer: &mut W,
+    ) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let params = Params::from([("username".into(), username.into())]);
+        let url = self.get_full_url("collection".into(), options.into_params()?, Some(params));
+
+        let res = self.http.get_to_writer(&url, writer).await;
+
+        return check_written(res, |r| utils::check_user(r, username));
+    }
+
+    /// Write (sync) a user's raw (XML) collection straight to `writer`
+    /// without holding the whole response in memory.  This is what you want
+    /// for very large collections that are being exported to disk.  Returns
+    /// the number of bytes written.
+    pub fn collection_to_writer_b<W>(
+        &self,
+        username: &str,
+        options: impl IntoParams,
+        writer: &mut W,
+    ) -> Result<u64>
+    where
+        W: Write,
+    {
+        let params = Params::from([("username".into(), username.into())]);
+        let url = self.get_full_url("collection".into(), options.into_params()?, Some(params));
+
+        let res = self.http.get_to_writer_b(&url, writer);
+
+        return check_written(res, |r| utils::check_user(r, username));
+    }
+
+    /// Get (async) the latest hotness on BGG.  BGG doesn't currently
+    /// document any options for this, but any given are passed along.
+    #[cfg(feature = "async")]
+    pub async fn hot(&self, htype: Hotness, options: impl IntoParams) -> Result<Value> {
+        let params = Params::from([("type".into(), htype.to_string())]);
+        let url = self.get_full_url("hot".into(), options.into_params()?, Some(params));
+
+        let data = self.http.get_json(&url).await?;
+
+        return Ok(data);
+    }
+
+    /// Get (sync) the latest hotness on BGG.  BGG doesn't currently document
+    /// any options for this, but any given are passed along.
+    pub fn hot_b(&self, htype: Hotness, options: impl IntoParams) -> Result<Value> {
+        let params = Params::from([("type".into(), htype.to_string())]);
+        let url = self.get_full_url("hot".into(), options.into_params()?, Some(params));
+
+        let data = self.http.get_json_b(&url)?;
+
+        return Ok(data);
+    }
+
+    /// A (async) convenience function for getting the hottest board games
+    #[cfg(feature = "async")]
+    pub async fn hot_boardgames(&self, options: impl IntoParams) -> Result<Value> {
+        return self.hot(Hotness::BoardGame, options).await;
+    }
+
+    /// A (sync) convenience function for getting the hottest board games
+    pub fn hot_boardgames_b(&self, options: impl IntoParams) -> Result<Value> {
+        return self.hot_b(Hotness::BoardGame, options);
+    }
+
+    /// A (async) convenience function for getting the hottest rpgs
+    #[cfg(feature = "async")]
+    pub async fn hot_rpgs(&self, options: impl IntoParams) -> Result<Value> {
+        return self.hot(Hotness::Rpg, options).await;
+    }
+
+    /// A (sync) convenience function for getting the hottest rpgs
+    pub fn hot_rpgs_b(&self, options: impl IntoParams) -> Result<Value> {
+        return self.hot_b(Hotness::Rpg, options);
+    }
+
+    /// A (async) convenience function for getting the hottest video games
+    #[cfg(feature = "async")]
+    pub async fn hot_videogames(&self, options: impl IntoParams) -> Result<Value> {
+        return self.hot(Hotness::VideoGame, options).await;
+    }
+
+    /// A (sync) convenience function for getting the hottest video games
+    pub fn hot_videogames_b(&self, options: impl IntoParams) -> Result<Value> {
+        return self.hot_b(Hotness::VideoGame, options);
+    }
+
+    /// A (async) convenience function for getting the hottest board game people
+    #[cfg(feature = "async")]
+    pub async fn hot_boardgame_persons(&self, options: impl IntoParams) -> Result<Value> {
+        return self.hot(Hotness::BoardGamePerson, options).await;
+    }
+
+    /// A (sync) convenience function for getting the hottest board game people
+    pub fn hot_boardgame_persons_b(&self, options: impl IntoParams) -> Result<Value> {
+        return self.hot_b(Hotness::BoardGamePerson, options);
+    }
+
+    /// A (async) convenience function for getting the hottest rpg people
+    #[cfg(feature = "async")]
+    pub async fn hot_rpg_persons(&self, options: impl IntoParams) -> Result<Value> {
+        return self.hot(Hotness::RpgPerson, options).await;
+    }
+
+    /// A (sync) convenience function for getting the hottest rpg people
+    pub fn hot_rpg_persons_b(&self, options: impl IntoParams) -> Result<Value> {
+        return self.hot_b(Hotness::RpgPerson, options);
+    }
+
+    /// A (async) convenience function for getting the hottest board game companies
+    #[cfg(feature = "async")]
+    pub async fn hot_boardgame_companies(&self, options: impl IntoParams) -> Result<Value> {
+        return self.hot(Hotness::BoardGameCompany, options).await;
+    }
+
+    /// A (sync) convenience function for getting the hottest board game companies
+    pub fn hot_boardgame_companies_b(&self, options: impl IntoParams) -> Result<Value> {
+        return self.hot_b(Hotness::BoardGameCompany, options);
+    }
+
+    /// A (async) convenience function for getting the hottest rpg companies
+    #[cfg(feature = "async")]
+    pub async fn hot_rpg_companies(&self, options: impl IntoParams) -> Result<Value> {
+        return self.hot(Hotness::RpgCompany, options).await;
+    }
+
+    /// A (sync) convenience function for getting the hottest rpg companies
+    pub fn hot_rpg_companies_b(&self, options: impl IntoParams) -> Result<Value> {
+        return self.hot_b(Hotness::RpgCompany, options);
+    }
+
+    /// A (async) convenience function for getting the hottest video game companies
+    #[cfg(feature = "async")]
+    pub async fn hot_videogame_companies(&self, options: impl IntoParams) -> Result<Value> {
+        return self.hot(Hotness::VideoGameCompany, options).await;
+    }
+
+    /// A (sync) convenience function for getting the hottest video game companies
+    pub fn hot_videogame_companies_b(&self, options: impl IntoParams) -> Result<Value> {
+        return self.hot_b(Hotness::VideoGameCompany, options);
+    }
+
+    /// Check (async) that BGG is reachable by making a cheap request.  This
+    /// never fails outright; any problem is reported in the returned
+    /// `PingReport`, which makes it handy for readiness probes.
+    #[cfg(feature = "async")]
+    pub async fn ping(&self) -> PingReport {
+        let url = self.ping_url();
+        let start = Instant::now();
+        let res = self.http.get_json(&url).await;
+
+        return PingReport::new(res, start.elapsed());
+    }
+
+    /// Check (sync) that BGG is reachable by making a cheap request.  This
+    /// never fails outright; any problem is reported in the returned
+    /// `PingReport`, which makes it handy for readiness probes.
+    pub fn ping_b(&self) -> PingReport {
+        let url = self.ping_url();
+        let start = Instant::now();
+        let res = self.http.get_json_b(&url);
+
+        return PingReport::new(res, start.elapsed());
+    }
+
+    /// Run (async) the prepared requests, with at most `max_concurrent` of
+    /// them in flight at once.  The results are in the same order as the
+    /// requests, and a failed request doesn't stop the rest.  The client's
+    /// throttle still applies, so this mostly helps to overlap the time
+    /// spent waiting on BGG.
+    #[cfg(feature = "async")]
+    pub async fn batch(
+        &self,
+        requests: Vec<BatchRequest>,
+        max_concurrent: usize,
+    ) -> Vec<Result<Value>> {
+        let urls: Vec<String> = requests.into_iter().map(|r| self.batch_url(r)).collect();
+
+        return stream::iter(urls)
+            .map(|url| async move { self.http.get_json(&url).await })
+            .buffered(max_concurrent.max(1))
+            .collect()
+            .await;
+    }
+
+    /// Run (sync) the prepared requests on up to `max_concurrent` threads.
+    /// The results are in the same order as the requests, and a failed
+    /// request doesn't stop the rest.
+    pub fn batch_b(
+        &self,
+        requests: Vec<BatchRequest>,
+        max_concurrent: usize,
+    ) -> Vec<Result<Value>> {
+        let urls: Vec<String> = requests.into_iter().map(|r| self.batch_url(r)).collect();
+        let results: Vec<Mutex<Option<Result<Value>>>> =
+            urls.iter().map(|_| Mutex::new(None)).collect();
+        let next = AtomicUsize::new(0);
+        let workers = max_concurrent.max(1).min(urls.len());
+
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    if i >= urls.len() {
+                        break;
+                    }
+                    let res = self.http.get_json_b(&urls[i]);
+                    *results[i].lock().unwrap_or_else(|e| e.into_inner()) = Some(res);
+                });
+            }
+        });
+
+        return results
+            .into_iter()
+            .map(|r| {
+                r.into_inner()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .unwrap_or(Err(RbggError::Cancelled))
+            })
+            .collect();
+    }
 
-        let data = utils::get_json_resp(&url).await?;
+    /// Return a clone of this client whose requests are queued at the given
+    /// priority when a throttle is configured.  The clone shares the
+    /// throttle, and everything else, with this client.
+    pub fn with_priority(&self, priority: Priority) -> Self {
+        let mut ret = self.clone();
+        ret.http = self.http.with_priority(priority);
 
-        return Ok(data);
+        return ret;
     }
 
-    /// Get a (sync) user by their username
-    pub fn user_b(&self, username: &str, options: Option<Params>) -> Result<Value> {
-        let params = Params::from([("name".into(), username.into())]);
-        let url = self.get_full_url("user".into(), options, Some(params));
-
-        let data = utils::get_json_resp_b(&url)?;
+    /// Return a clone of this client that applies the given per-call
+    /// settings (e.g. `CallConfig::no_cache()`) to every call made through
+    /// it.  The clone shares the HTTP client, throttle, etc. with the
+    /// original.
+    pub fn with_call_config(&self, config: &CallConfig) -> Self {
+        let mut ret = self.clone();
+        ret.http = self.http.with_call_config(config);
 
-        return Ok(data);
+        return ret;
     }
 
-    /// Get a (async) guild by ID
-    pub async fn guild(&self, guild_id: usize, options: Option<Params>) -> Result<Value> {
-        let params = Params::from([("id".into(), guild_id.to_string())]);
-        let url = self.get_full_url("guild".into(), options, Some(params));
-
-        let data = utils::get_json_resp(&url).await?;
+    /// Return a clone of this client whose calls are stopped, with
+    /// `RbggError::Cancelled`, when the token is cancelled.  See `cancel`.
+    pub fn with_cancel(&self, token: &CancellationToken) -> Self {
+        let mut ret = self.clone();
+        ret.http = self.http.with_cancel(token);
 
-        return Ok(data);
+        return ret;
     }
 
-    /// Get a (async) guild by ID
-    pub fn guild_b(&self, guild_id: usize, options: Option<Params>) -> Result<Value> {
-        let params = Params::from([("id".into(), guild_id.to_string())]);
-        let url = self.get_full_url("guild".into(), options, Some(params));
+    /// Return a clone of this client whose calls are stopped, with
+    /// `RbggError::DeadlineExceeded`, if they're still going at the deadline
+    pub fn with_deadline(&self, deadline: Instant) -> Self {
+        let mut ret = self.clone();
+        ret.http = self.http.with_deadline(deadline);
 
-        let data = utils::get_json_resp_b(&url)?;
+        return ret;
+    }
 
-        return Ok(data);
+    /// Return a snapshot of the request counters (retries, 202 waits, etc.)
+    /// for this client
+    pub fn stats(&self) -> Stats {
+        return self.http.stats();
     }
 
-    /// Get (async) plays for a user or for a particular item. Either a
-    /// username or item ID + ttype MUST be supplied
-    pub async fn plays(
-        &self,
-        username: Option<&str>,
-        item_id: Option<usize>,
-        ttype: Option<ThingFamily>,
-        options: Option<Params>,
-    ) -> Result<Value> {
-        if username.is_none() && (item_id.is_none() || ttype.is_none()) {
-            // TODO: Replace with custom error type
-            return Err(anyhow!(
-                "You must supply either a username or item_id + ttype"
-            ));
-        }
+    /// Return a snapshot of the circuit breaker, or `None` if this client
+    /// doesn't have one
+    pub fn breaker_state(&self) -> Option<BreakerState> {
+        return self.http.breaker_state();
+    }
 
-        if username.is_some() && item_id.is_some() {
-            // TODO: Replace with custom error type
-            return Err(anyhow!(
-                "You must supply either a username or item_id + ttype, not both"
-            ));
-        }
+    /// Return a snapshot of the throttle (its current rate, etc.), or `None`
+    /// if this client isn't throttled
+    pub fn throttle_state(&self) -> Option<ThrottleState> {
+        return self.http.throttle_state();
+    }
 
-        let params;
-        if let Some(u) = username {
-            params = Params::from([("username".into(), u.into())]);
-        } else if let Some(id) = item_id {
-            params = Params::from([
-                ("id".into(), id.to_string()),
-                ("type".into(), ttype.unwrap().to_string()),
-            ]);
-        } else {
-            // We should never get here
-            return Err(anyhow!(
-                "We have a logic bug here as this should never happen"
-            ));
-        }
+    /// Call (async) an arbitrary endpoint, for anything this crate doesn't
+    /// wrap (yet).  The `path` is relative to the API prefix (e.g. "hot"),
+    /// and the request goes through the same throttling and conversion as
+    /// every other call.
+    #[cfg(feature = "async")]
+    pub async fn raw(&self, path: &str, params: Option<Params>) -> Result<Value> {
+        let url = self.get_full_url(path.trim_matches('/').into(), params, None);
 
-        let url = self.get_full_url("plays".into(), options, Some(params));
+        return self.http.get_json(&url).await;
+    }
 
-        let data = utils::get_json_resp(&url).await?;
+    /// Call (sync) an arbitrary endpoint, for anything this crate doesn't
+    /// wrap (yet).  The `path` is relative to the API prefix (e.g. "hot"),
+    /// and the request goes through the same throttling and conversion as
+    /// every other call.
+    pub fn raw_b(&self, path: &str, params: Option<Params>) -> Result<Value> {
+        let url = self.get_full_url(path.trim_matches('/').into(), params, None);
 
-        return Ok(data);
+        return self.http.get_json_b(&url);
     }
 
-    /// Get (sync) plays for a user or for a particular item. Either a
-    /// username or item ID + ttype MUST be supplied
-    pub fn plays_b(
-        &self,
-        username: Option<&str>,
-        item_id: Option<usize>,
-        ttype: Option<ThingFamily>,
-        options: Option<Params>,
-    ) -> Result<Value> {
-        if username.is_none() && (item_id.is_none() || ttype.is_none()) {
-            // TODO: Replace with custom error type
-            return Err(anyhow!(
-                "You must supply either a username or item_id + ttype"
-            ));
-        }
+    /// Like `raw()`, but also returns the status, headers, timing, etc. of
+    /// the response
+    #[cfg(feature = "async")]
+    pub async fn raw_with_meta(&self, path: &str, params: Option<Params>) -> Result<ApiResponse> {
+        let url = self.get_full_url(path.trim_matches('/').into(), params, None);
 
-        if username.is_some() && item_id.is_some() {
-            // TODO: Replace with custom error type
-            return Err(anyhow!(
-                "You must supply either a username or item_id + ttype, not both"
-            ));
-        }
+        return self.http.get_json_with_meta(&url).await;
+    }
 
-        let params;
-        if let Some(u) = username {
-            params = Params::from([("username".into(), u.into())]);
-        } else if let Some(id) = item_id {
-            params = Params::from([
-                ("id".into(), id.to_string()),
-                ("type".into(), ttype.unwrap().to_string()),
-            ]);
-        } else {
-            // We should never get here
-            return Err(anyhow!(
-                "We have a logic bug here as this should never happen"
-            ));
-        }
+    /// Like `raw_b()`, but also returns the status, headers, timing, etc.
+    /// of the response
+    pub fn raw_with_meta_b(&self, path: &str, params: Option<Params>) -> Result<ApiResponse> {
+        let url = self.get_full_url(path.trim_matches('/').into(), params, None);
 
-        let url = self.get_full_url("plays".into(), options, Some(params));
+        return self.http.get_json_with_meta_b(&url);
+    }
 
-        let data = utils::get_json_resp_b(&url)?;
+    /// Like `raw()`, but returns the raw XML response along with its JSON
+    /// conversion, from the one request
+    #[cfg(feature = "async")]
+    pub async fn raw_with_xml(
+        &self,
+        path: &str,
+        params: Option<Params>,
+    ) -> Result<(String, Value)> {
+        let url = self.get_full_url(path.trim_matches('/').into(), params, None);
 
-        return Ok(data);
+        return self.http.get_xml_and_json(&url).await;
     }
 
-    /// Get a (async) user's collection by username
-    pub async fn collection(&self, username: &str, options: Option<Params>) -> Result<Value> {
-        let params = Params::from([("username".into(), username.into())]);
-        let url = self.get_full_url("collection".into(), options, Some(params));
-
-        let data = utils::get_json_resp(&url).await?;
+    /// Like `raw_b()`, but returns the raw XML response along with its JSON
+    /// conversion, from the one request
+    pub fn raw_with_xml_b(&self, path: &str, params: Option<Params>) -> Result<(String, Value)> {
+        let url = self.get_full_url(path.trim_matches('/').into(), params, None);
 
-        return Ok(data);
+        return self.http.get_xml_and_json_b(&url);
     }
 
-    /// Get a (sync) user's collection by username
-    pub fn collection_b(&self, username: &str, options: Option<Params>) -> Result<Value> {
-        let params = Params::from([("username".into(), username.into())]);
-        let url = self.get_full_url("collection".into(), options, Some(params));
+    /// Create a `Paginator` for any paginated endpoint, like "plays".  The
+    /// `root` is a JSON pointer to the element holding the items (e.g.
+    /// "/plays") and `item` is the name of each item (e.g. "play").
+    pub fn paginator(
+        &self,
+        path: &str,
+        options: impl IntoParams,
+        root: &str,
+        item: &str,
+    ) -> Result<Paginator> {
+        let mut params = self
+            .endpoint_defaults
+            .get(path)
+            .cloned()
+            .unwrap_or_default();
+        params.extend(utils::get_opts(options.into_params()?));
+        let url = format!("{}/{}/{}", self.url_base, self.api_prefix, path);
+
+        return Ok(Paginator::new(self.http.clone(), &url, params, root, item));
+    }
 
-        let data = utils::get_json_resp_b(&url)?;
+    /// Fetch (async) every page of a paginated endpoint, and merge them into
+    /// a single response with all of the items.  The arguments are the same
+    /// as for `paginator()`.
+    #[cfg(feature = "async")]
+    pub async fn collect_all_pages(
+        &self,
+        path: &str,
+        options: impl IntoParams,
+        root: &str,
+        item: &str,
+    ) -> Result<Value> {
+        return self
+            .paginator(path, options, root, item)?
+            .collect_all()
+            .await;
+    }
 
-        return Ok(data);
+    /// Fetch (sync) every page of a paginated endpoint, and merge them into
+    /// a single response with all of the items.  The arguments are the same
+    /// as for `paginator()`.
+    pub fn collect_all_pages_b(
+        &self,
+        path: &str,
+        options: impl IntoParams,
+        root: &str,
+        item: &str,
+    ) -> Result<Value> {
+        return self.paginator(path, options, root, item)?.collect_all_b();
     }
 
-    /// Get (async) the latest hotness on BGG
-    pub async fn hot(&self, htype: Hotness) -> Result<Value> {
-        let params = Params::from([("type".into(), htype.to_string())]);
-        let url = self.get_full_url("hot".into(), None, Some(params));
+    /// The paginator for all of the comments on a thing
+    fn comments_paginator(&self, id: usize) -> Paginator {
+        let params = Params::from([
+            ("id".into(), id.to_string()),
+            ("comments".into(), "1".into()),
+            ("pagesize".into(), COMMENTS_PAGE_SIZE.to_string()),
+        ]);
+        let url = format!("{}/{}/thing", self.url_base, self.api_prefix);
+
+        return Paginator::new(
+            self.http.clone(),
+            &url,
+            params,
+            "/items/item/comments",
+            "comment",
+        )
+        .total_key("@totalitems")
+        .page_size(COMMENTS_PAGE_SIZE);
+    }
 
-        let data = utils::get_json_resp(&url).await?;
+    /// Register default options for the given endpoint (e.g. "thing" or
+    /// "guild"), replacing any previously set for it.  These are used for
+    /// every call to that endpoint, beneath any options passed to the call
+    /// itself, so a call can always override them.
+    pub fn set_endpoint_defaults(&mut self, endpoint: &str, params: Params) {
+        self.endpoint_defaults.insert(endpoint.to_string(), params);
+    }
 
-        return Ok(data);
+    /// Return the default options registered for the given endpoint, if any
+    pub fn endpoint_defaults(&self, endpoint: &str) -> Option<&Params> {
+        return self.endpoint_defaults.get(endpoint);
     }
 
-    /// Get (sync) the latest hotness on BGG
-    pub async fn hot_b(&self, htype: Hotness) -> Result<Value> {
-        let params = Params::from([("type".into(), htype.to_string())]);
-        let url = self.get_full_url("hot".into(), None, Some(params));
+    /* Begin private functions */
 
-        let data = utils::get_json_resp_b(&url)?;
+    /// The url used for ping().  The rpg company hot list is about the
+    /// smallest response BGG will give us.
+    fn ping_url(&self) -> String {
+        let params = Params::from([("type".into(), Hotness::RpgCompany.to_string())]);
 
-        return Ok(data);
+        return self.get_full_url("hot".into(), None, Some(params));
     }
 
-    /* Begin private functions */
-
     /// A private function for building a URL given the action that is being
     /// called (like "search"). `uri_addons` are items to be appended to the
     /// url *before* the query string.
@@ -750,13 +3271,23 @@ impl Client2 {
 
     /// Another simple private function to get the full url for the purposes
     /// of deduping code between the sync and async functionality
+    fn batch_url(&self, req: BatchRequest) -> String {
+        return self.get_full_url(req.path, req.options, Some(req.params));
+    }
+
     fn get_full_url(
         &self,
         path: String,
         params: Option<Params>,
         default_params: Option<Params>,
     ) -> String {
-        let mut opts = utils::get_opts(params);
+        let mut opts = self
+            .endpoint_defaults
+            .get(&path)
+            .cloned()
+            .unwrap_or_default();
+        // Options for the call override the endpoint defaults
+        opts.extend(utils::get_opts(params));
         // Add the default options
         if let Some(def_params) = default_params {
             for (k, v) in &def_params {
@@ -773,6 +3304,146 @@ impl Client2 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::http::DEFAULT_TIMEOUT;
+    use crate::transport::{
+        AsyncBody, BlockingBody, TransportRequest, TransportResponse, TransportResult,
+    };
+    use futures_util::future::BoxFuture;
+    use http::StatusCode;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    /// Answer a single request with a 404, returning the base url to send it
+    /// to and a handle giving the request line that was received
+    fn serve_once() -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut line = String::new();
+            BufReader::new(&stream).read_line(&mut line).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+            return line;
+        });
+
+        return (base, handle);
+    }
+
+    /// Records the url of each request, answering those for an endpoint in
+    /// `bodies` with its body, and the rest with a 404
+    #[derive(Debug, Default)]
+    struct Recorder {
+        urls: Mutex<Vec<String>>,
+        bodies: Vec<(&'static str, &'static str)>,
+    }
+
+    impl Recorder {
+        fn respond(&self, url: &str) -> (StatusCode, &'static str) {
+            self.urls.lock().unwrap().push(url.to_string());
+            let (path, _) = url.split_once('?').unwrap_or((url, ""));
+
+            return match self.bodies.iter().find(|(ep, _)| path.ends_with(ep)) {
+                Some((_, body)) => (StatusCode::OK, body),
+                None => (StatusCode::NOT_FOUND, "<a/>"),
+            };
+        }
+    }
+
+    impl HttpTransport for Recorder {
+        fn send(&self, req: TransportRequest) -> BoxFuture<'_, TransportResult<AsyncBody>> {
+            let (status, body) = self.respond(&req.url);
+            return Box::pin(async move {
+                return Ok(TransportResponse::<AsyncBody>::new(status, &req.url, body));
+            });
+        }
+
+        fn send_b(&self, req: TransportRequest) -> TransportResult<BlockingBody> {
+            let (status, body) = self.respond(&req.url);
+            return Ok(TransportResponse::<BlockingBody>::new(
+                status, &req.url, body,
+            ));
+        }
+    }
+
+    const UNKNOWN_USER: &str =
+        "<errors><error><message>Invalid username specified</message></error></errors>";
+
+    #[cfg(feature = "async")]
+    fn recording_client() -> (Client2, Arc<Recorder>) {
+        return recording_client_with(&[]);
+    }
+
+    /// A client whose requests are recorded, answering those for the given
+    /// endpoints with their bodies
+    fn recording_client_with(bodies: &[(&'static str, &'static str)]) -> (Client2, Arc<Recorder>) {
+        let recorder = Arc::new(Recorder {
+            bodies: bodies.to_vec(),
+            ..Default::default()
+        });
+        let cl = Client2::builder()
+            .url_base("https://bgg")
+            .transport(recorder.clone())
+            .retry(RetryPolicy::none())
+            .throttle(None)
+            .build()
+            .unwrap();
+
+        return (cl, recorder);
+    }
+
+    #[test]
+    fn test_wishlist_report_types() {
+        let collection = r#"<items totalitems="1">
+            <item objecttype="thing" objectid="2" subtype="boardgameexpansion">
+                <status wishlist="1" wishlistpriority="2"/>
+            </item>
+        </items>"#;
+        let (cl, recorder) = recording_client_with(&[("collection", collection)]);
+        // The thing request gets a 404, but it's the url we're after
+        assert!(cl.wishlist_report_b("someone").is_err());
+
+        let urls = recorder.urls.lock().unwrap();
+        assert_eq!(
+            urls[1],
+            "https://bgg/xmlapi2/thing?id=2&stats=1&type=boardgame%2Cboardgameexpansion"
+        );
+    }
+
+    #[test]
+    fn test_with_meta_checks() {
+        let (cl, _) = recording_client_with(&[("thing", "<items/>"), ("collection", UNKNOWN_USER)]);
+
+        let err = cl.thing_with_meta_b(&vec![1], &vec![Thing::BoardGame], None);
+        assert!(matches!(err.unwrap_err(), RbggError::NotFound { .. }));
+        let err = cl.collection_with_meta_b("nobody", None);
+        assert!(matches!(err.unwrap_err(), RbggError::UnknownUser(u) if u == "nobody"));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_with_xml_checks() {
+        let (cl, _) = recording_client_with(&[("thing", "<items/>"), ("collection", UNKNOWN_USER)]);
+
+        let err = cl
+            .thing_with_xml(&vec![1], &vec![Thing::BoardGame], None)
+            .await;
+        assert!(matches!(err.unwrap_err(), RbggError::NotFound { .. }));
+        let err = cl.collection_with_xml("nobody", None).await;
+        assert!(matches!(err.unwrap_err(), RbggError::UnknownUser(u) if u == "nobody"));
+    }
+
+    #[test]
+    fn test_to_writer_checks() {
+        let (cl, _) = recording_client_with(&[("collection", UNKNOWN_USER)]);
+
+        let mut out = vec![];
+        let err = cl.collection_to_writer_b("nobody", None, &mut out);
+        assert!(matches!(err.unwrap_err(), RbggError::UnknownUser(u) if u == "nobody"));
+        assert!(out.is_empty());
+    }
 
     #[test]
     fn test_client() {
@@ -789,6 +3460,179 @@ mod tests {
         assert_eq!(cl.api_prefix, "blah");
     }
 
+    #[test]
+    fn test_batch_request() {
+        let cl = Client2::new_from_defaults();
+        let req = BatchRequest::user("someone", None).unwrap();
+        assert_eq!(req.path, "user");
+        assert_eq!(
+            cl.batch_url(req),
+            "https://boardgamegeek.com/xmlapi2/user?name=someone"
+        );
+
+        let req = BatchRequest::thing(&vec![1, 2], &vec![Thing::BoardGame], None).unwrap();
+        let url = cl.batch_url(req);
+        assert!(url.starts_with("https://boardgamegeek.com/xmlapi2/thing?"));
+        assert!(url.contains("id=1%2C2"));
+
+        let req = BatchRequest::raw("/hot/", None);
+        assert_eq!(req.path, "hot");
+    }
+
+    /// A client pointed at a port nothing is listening on, so every request
+    /// fails straight away
+    fn unreachable_client() -> Client2 {
+        return Client2::builder()
+            .url_base("http://127.0.0.1:1")
+            .throttle(None)
+            .retry(RetryPolicy::none())
+            .build()
+            .unwrap();
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_batch() {
+        let cl = unreachable_client();
+        assert!(cl.batch(vec![], 4).await.is_empty());
+
+        let reqs = vec![
+            BatchRequest::user("a", None).unwrap(),
+            BatchRequest::user("b", None).unwrap(),
+            BatchRequest::user("c", None).unwrap(),
+        ];
+        let res = cl.batch(reqs, 0).await;
+        assert_eq!(res.len(), 3);
+        assert!(res.iter().all(|r| r.is_err()));
+    }
+
+    #[test]
+    fn test_batch_b() {
+        let cl = unreachable_client();
+        assert!(cl.batch_b(vec![], 4).is_empty());
+
+        let reqs = vec![
+            BatchRequest::user("a", None).unwrap(),
+            BatchRequest::user("b", None).unwrap(),
+            BatchRequest::user("c", None).unwrap(),
+        ];
+        let res = cl.batch_b(reqs, 2);
+        assert_eq!(res.len(), 3);
+        assert!(res.iter().all(|r| r.is_err()));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_hot_urls() {
+        let (cl, recorder) = recording_client();
+        let opts = Params::from([("x".into(), "1".into())]);
+        let _ = cl.hot(Hotness::BoardGame, None).await;
+        let _ = cl.hot_rpgs_b(Some(opts));
+        let _ = cl.hot_videogame_companies(None).await;
+
+        let urls = recorder.urls.lock().unwrap().clone();
+        assert_eq!(
+            urls,
+            vec![
+                "https://bgg/xmlapi2/hot?type=boardgame",
+                "https://bgg/xmlapi2/hot?type=rpg&x=1",
+                "https://bgg/xmlapi2/hot?type=videogamecompany",
+            ]
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_thing_flag_urls() {
+        let (cl, recorder) = recording_client();
+        let _ = cl.boardgame_versions(&vec![1, 2], None).await;
+        let _ = cl.thing_videos_b(&vec![3], &vec![Thing::VideoGame], None);
+        let opts = ThingOptions::new().stats(true);
+        let _ = cl
+            .thing_marketplace(&vec![4], &vec![Thing::BoardGame], opts)
+            .await;
+
+        let urls = recorder.urls.lock().unwrap().clone();
+        assert_eq!(
+            urls,
+            vec![
+                "https://bgg/xmlapi2/thing?id=1%2C2&type=boardgame&versions=1",
+                "https://bgg/xmlapi2/thing?id=3&type=videogame&videos=1",
+                "https://bgg/xmlapi2/thing?id=4&marketplace=1&stats=1&type=boardgame",
+            ]
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_thing_ratingcomments() {
+        let (cl, recorder) = recording_client();
+        let _ = cl.thing_ratingcomments(5, 2, 50).await;
+        let _ = cl.thing_ratingcomments_b(6, 1, 100);
+
+        let err = cl.thing_ratingcomments_b(6, 0, 100).unwrap_err();
+        assert!(matches!(err, RbggError::InvalidInput(_)));
+        let err = cl.thing_ratingcomments(6, 1, 5).await.unwrap_err();
+        assert!(matches!(err, RbggError::InvalidInput(_)));
+
+        // Nothing is sent for invalid paging
+        assert_eq!(
+            *recorder.urls.lock().unwrap(),
+            vec![
+                "https://bgg/xmlapi2/thing?id=5&page=2&pagesize=50&ratingcomments=1",
+                "https://bgg/xmlapi2/thing?id=6&page=1&pagesize=100&ratingcomments=1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_builder() {
+        let cl = Client2::builder().build().unwrap();
+        assert_eq!(cl.url_base, "https://boardgamegeek.com");
+        assert_eq!(cl.api_prefix, "xmlapi2");
+        assert_eq!(cl.http.config().timeout, Some(DEFAULT_TIMEOUT));
+
+        let cl = Client2::builder()
+            .url_base("https://example.com/")
+            .api_prefix("/blah/")
+            .timeout(Duration::from_secs(5))
+            .connect_timeout(None)
+            .user_agent("mytool/1.0")
+            .default_header("X-Tool", "mine")
+            .retry(RetryPolicy::none())
+            .throttle(None)
+            .memory_cache(10)
+            .build()
+            .unwrap();
+        assert_eq!(cl.url_base, "https://example.com");
+        assert_eq!(cl.api_prefix, "blah");
+        assert_eq!(cl.http.config().timeout, Some(Duration::from_secs(5)));
+        assert_eq!(cl.http.config().connect_timeout, None);
+        assert_eq!(cl.http.config().user_agent.as_deref(), Some("mytool/1.0"));
+        assert_eq!(
+            cl.http.config().headers,
+            vec![("X-Tool".to_string(), "mine".to_string())]
+        );
+        assert_eq!(cl.http.config().throttle, None);
+        assert_eq!(cl.http.config().retry.max_attempts, 1);
+        assert!(cl.http.config().cache.is_some());
+
+        // An existing client's configuration wins over the builder's
+        let http = HttpClient::default();
+        let cl = Client2::builder()
+            .timeout(Duration::from_secs(5))
+            .http(http)
+            .build()
+            .unwrap();
+        assert_eq!(cl.http.config().timeout, Some(DEFAULT_TIMEOUT));
+
+        assert!(Client2::builder().user_agent("bad\nagent").build().is_err());
+        assert!(Client2::builder()
+            .default_header("bad header", "x")
+            .build()
+            .is_err());
+    }
+
     #[test]
     fn test_gen_url() {
         let cl = Client2::new_from_defaults();
@@ -815,6 +3659,262 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_search_options() {
+        assert_eq!(
+            SearchOptions::new().into_params().unwrap(),
+            Some(Params::new())
+        );
+
+        let params = SearchOptions::new()
+            .exact(true)
+            .into_params()
+            .unwrap()
+            .unwrap();
+        assert_eq!(params["exact"], "1");
+    }
+
+    #[test]
+    fn test_collection_options() {
+        let params = CollectionOptions::new()
+            .own(true)
+            .wishlist(false)
+            .subtype(Thing::BoardGame)
+            .exclude_subtype(Thing::BoardGameExpansion)
+            .ids(&[1, 2])
+            .rating(7.0..=9.5)
+            .plays(1..10)
+            .modified_since(NaiveDate::from_ymd_opt(2024, 2, 3).unwrap())
+            .into_params()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(params["own"], "1");
+        assert_eq!(params["wishlist"], "0");
+        assert_eq!(params["subtype"], "boardgame");
+        assert_eq!(params["excludesubtype"], "boardgameexpansion");
+        assert_eq!(params["id"], "1,2");
+        assert_eq!(params["minrating"], "7");
+        assert_eq!(params["rating"], "9.5");
+        assert_eq!(params["minplays"], "1");
+        assert_eq!(params["maxplays"], "9");
+        assert_eq!(params["modifiedsince"], "2024-02-03 00:00:00");
+
+        assert!(CollectionOptions::new()
+            .wishlist_priority(6)
+            .into_params()
+            .is_err());
+        assert!(CollectionOptions::new()
+            .rating(1.0..5.0)
+            .into_params()
+            .is_err());
+        assert!(CollectionOptions::new().plays(..0).into_params().is_err());
+        assert!(CollectionOptions::new()
+            .plays((Bound::Excluded(u32::MAX), Bound::Unbounded))
+            .into_params()
+            .is_err());
+    }
+
+    #[test]
+    fn test_plays_options() {
+        let jan = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let dec = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+        let params = PlaysOptions::new()
+            .min_date(jan)
+            .max_date(dec)
+            .subtype(Thing::BoardGameExpansion)
+            .page(2)
+            .into_params()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(params["mindate"], "2024-01-01");
+        assert_eq!(params["maxdate"], "2024-12-31");
+        assert_eq!(params["subtype"], "boardgameexpansion");
+        assert_eq!(params["page"], "2");
+
+        let bad = PlaysOptions::new().min_date(dec).max_date(jan);
+        assert!(bad.into_params().is_err());
+    }
+
+    #[test]
+    fn test_thread_options() {
+        let time = NaiveDate::from_ymd_opt(2024, 3, 4)
+            .unwrap()
+            .and_hms_opt(5, 6, 7)
+            .unwrap();
+        let params = ThreadOptions::new()
+            .min_article_id(10)
+            .min_article_date(time)
+            .count(5)
+            .into_params()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(params["minarticleid"], "10");
+        assert_eq!(params["minarticledate"], "2024-03-04 05:06:07");
+        assert_eq!(params["count"], "5");
+    }
+
+    #[test]
+    fn test_user_options() {
+        let params = UserOptions::new()
+            .buddies(true)
+            .guilds(false)
+            .top(true)
+            .domain(Domain::Rpg)
+            .page(3)
+            .into_params()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(params["buddies"], "1");
+        assert_eq!(params["guilds"], "0");
+        assert_eq!(params["top"], "1");
+        assert!(!params.contains_key("hot"));
+        assert_eq!(params["domain"], "rpg");
+        assert_eq!(params["page"], "3");
+    }
+
+    #[test]
+    fn test_thing_options() {
+        let params = ThingOptions::new()
+            .versions(true)
+            .videos(true)
+            .stats(true)
+            .marketplace(false)
+            .rating_comments(true)
+            .page(2)
+            .page_size(25)
+            .into_params()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(params["versions"], "1");
+        assert_eq!(params["videos"], "1");
+        assert_eq!(params["stats"], "1");
+        assert_eq!(params["marketplace"], "0");
+        assert_eq!(params["ratingcomments"], "1");
+        assert!(!params.contains_key("comments"));
+        assert_eq!(params["page"], "2");
+        assert_eq!(params["pagesize"], "25");
+
+        assert!(ThingOptions::new().page_size(9).into_params().is_err());
+        assert!(ThingOptions::new().page_size(101).into_params().is_err());
+        assert!(ThingOptions::new().page(0).into_params().is_err());
+    }
+
+    #[test]
+    fn test_thing_full_params() {
+        let params = thing_full_params(&[1, 2]);
+
+        assert_eq!(params["id"], "1,2");
+        assert!(!params.contains_key("type"));
+        for key in ["stats", "versions", "videos", "marketplace", "comments"] {
+            assert_eq!(params[key], "1");
+        }
+    }
+
+    #[test]
+    fn test_thread_slice_params() {
+        let params = thread_slice_params(123, 456, 10);
+
+        assert_eq!(params["id"], "123");
+        assert_eq!(params["minarticleid"], "456");
+        assert_eq!(params["count"], "10");
+    }
+
+    #[test]
+    fn test_user_ratings_from() {
+        let data = serde_json::json!({
+            "items": {
+                "@totalitems": "2",
+                "item": [
+                    {
+                        "@objectid": "136888",
+                        "stats": {"rating": {"@value": "8", "average": {"@value": "7.3"}}},
+                    },
+                    {
+                        "@objectid": "133473",
+                        "stats": {"rating": {"@value": "N/A", "average": {"@value": "7.0"}}},
+                    },
+                ],
+            },
+        });
+
+        let res = user_ratings_from(&data).unwrap();
+        assert_eq!(res, vec![(136888, 8.0, Some(7.3))]);
+    }
+
+    #[test]
+    fn test_take_comments_page() {
+        let data = serde_json::json!({
+            "items": {
+                "item": {
+                    "@id": "136888",
+                    "comments": {
+                        "@page": "1",
+                        "@totalitems": "2",
+                        "comment": [
+                            {"@username": "a", "@rating": "8", "@value": "Great"},
+                            {"@username": "b", "@rating": "N/A", "@value": "Meh"},
+                        ],
+                    },
+                },
+            },
+        });
+
+        let (comments, total) = take_comments_page(data);
+        assert_eq!(total, 2);
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[1]["@username"], "b");
+
+        // A single comment comes back as an object rather than an array
+        let data = serde_json::json!({
+            "items": {"item": {"comments": {
+                "@totalitems": "1",
+                "comment": {"@username": "a", "@value": "Great"},
+            }}},
+        });
+
+        let (comments, total) = take_comments_page(data);
+        assert_eq!(total, 1);
+        assert_eq!(comments.len(), 1);
+
+        let (comments, total) = take_comments_page(serde_json::json!({}));
+        assert_eq!(total, 0);
+        assert!(comments.is_empty());
+    }
+
+    #[test]
+    fn test_ping_report() {
+        let data = serde_json::json!({"items": {"@termsofuse": "", "item": []}});
+        let rep = PingReport::new(Ok(data), Duration::from_millis(10));
+
+        assert!(rep.reachable);
+        assert!(rep.error.is_none());
+        assert_eq!(rep.latency, Duration::from_millis(10));
+
+        let rep = PingReport::new(Ok(serde_json::json!({})), Duration::from_millis(10));
+        assert!(!rep.reachable);
+
+        let rep = PingReport::new(
+            Err(RbggError::Status { status: 503 }),
+            Duration::from_millis(10),
+        );
+        assert!(!rep.reachable);
+        assert_eq!(
+            rep.error,
+            Some("Request failed with status 503".to_string())
+        );
+
+        let cl = Client2::new_from_defaults();
+        assert_eq!(
+            cl.ping_url(),
+            "https://boardgamegeek.com/xmlapi2/hot?type=rpgcompany"
+        );
+    }
+
     #[test]
     fn test_get_full_url() {
         let cl = Client2::new_from_defaults();
@@ -840,4 +3940,146 @@ mod tests {
             "https://boardgamegeek.com/xmlapi2/boardgame?comments=1".to_string()
         );
     }
+
+    #[test]
+    fn test_endpoint_defaults() {
+        let mut cl = Client2::new_from_defaults();
+        cl.set_endpoint_defaults("thing", Params::from([("stats".into(), "1".into())]));
+
+        assert!(cl.endpoint_defaults("thing").is_some());
+        assert!(cl.endpoint_defaults("guild").is_none());
+
+        let url = cl.get_full_url("thing".into(), None, None);
+        assert_eq!(url, "https://boardgamegeek.com/xmlapi2/thing?stats=1");
+
+        // Call options win over the endpoint defaults
+        let url = cl.get_full_url(
+            "thing".into(),
+            Some(Params::from([("stats".into(), "0".into())])),
+            None,
+        );
+        assert_eq!(url, "https://boardgamegeek.com/xmlapi2/thing?stats=0");
+
+        // Other endpoints are unaffected
+        let url = cl.get_full_url("guild".into(), None, None);
+        assert_eq!(url, "https://boardgamegeek.com/xmlapi2/guild?");
+    }
+
+    #[test]
+    fn test_flatten_page() {
+        let res = flatten_page(Ok(vec![1, 2]));
+        assert_eq!(res.len(), 2);
+        assert_eq!(*res[1].as_ref().unwrap(), 2);
+
+        let res = flatten_page::<usize>(Err(RbggError::Status { status: 500 }));
+        assert_eq!(res.len(), 1);
+        assert!(res[0].is_err());
+    }
+
+    #[test]
+    fn test_forum_path() {
+        let (base, handle) = serve_once();
+        let cl = Client2::new(Some(base), None);
+        assert!(cl.forum_b(19, None).is_err());
+
+        let line = handle.join().unwrap();
+        assert!(line.starts_with("GET /xmlapi2/forum?id=19 "), "{}", line);
+
+        let (base, handle) = serve_once();
+        let cl = Client2::new(Some(base), None);
+        assert!(cl.forum_iter_b(20).next().unwrap().is_err());
+
+        let line = handle.join().unwrap();
+        assert!(
+            line.starts_with("GET /xmlapi2/forum?id=20&page=1 "),
+            "{}",
+            line
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_forum_stream_path() {
+        let (base, handle) = serve_once();
+        let cl = Client2::new(Some(base), None);
+        let mut threads = Box::pin(cl.forum_stream(19));
+        assert!(threads.next().await.unwrap().is_err());
+        assert!(threads.next().await.is_none());
+
+        let line = handle.join().unwrap();
+        assert!(
+            line.starts_with("GET /xmlapi2/forum?id=19&page=1 "),
+            "{}",
+            line
+        );
+    }
+
+    #[test]
+    fn test_comments_page() {
+        let data = serde_json::json!({"items": {"item": {
+            "@id": "1",
+            "comments": {
+                "@page": "2",
+                "@totalitems": "150",
+                "comment": {"@username": "a", "@rating": "7", "@value": "Good"},
+            },
+        }}});
+        let (comments, next) = comments_page(&data, 2).unwrap();
+        assert_eq!(comments.len(), 1);
+        assert_eq!(next, None);
+
+        let data = serde_json::json!({"items": {"item": {"@id": "1"}}});
+        assert!(comments_page(&data, 1).unwrap().0.is_empty());
+    }
+
+    #[test]
+    fn test_pages() {
+        let data = serde_json::json!({"forum": {
+            "@numthreads": "120",
+            "threads": {"thread": [{"@id": "1"}, {"@id": "2"}]},
+        }});
+        assert_eq!(forum_page(&data, 2).unwrap().1, Some(3));
+        assert_eq!(forum_page(&data, 3).unwrap().1, None);
+
+        let data = serde_json::json!({"plays": {"@total": "150", "play": {"@id": "1"}}});
+        assert_eq!(plays_page(&data, 1).unwrap().1, Some(2));
+        assert_eq!(plays_page(&data, 2).unwrap().1, None);
+
+        let opts = plays_page_options(&Ok(None), 3).unwrap();
+        assert_eq!(opts["page"], "3");
+    }
+
+    #[test]
+    fn test_iter_pages() {
+        // Three pages of two items, with the position being the page number
+        let items: Vec<usize> = iter_pages(1, |page: usize| {
+            let next = if page < 3 { Some(page + 1) } else { None };
+            Ok((vec![page * 10, page * 10 + 1], next))
+        })
+        .map(|r| r.unwrap())
+        .collect();
+        assert_eq!(items, vec![10, 11, 20, 21, 30, 31]);
+
+        // An error ends the iteration
+        let res: Vec<Result<usize>> = iter_pages(1, |page: usize| match page {
+            1 => Ok((vec![1], Some(2))),
+            _ => Err(RbggError::Status { status: 500 }),
+        })
+        .collect();
+        assert_eq!(res.len(), 2);
+        assert!(res[1].is_err());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_stream_pages() {
+        let items: Vec<usize> = stream_pages(1, |page: usize| async move {
+            let next = if page < 2 { Some(page + 1) } else { None };
+            Ok((vec![page], next))
+        })
+        .map(|r| r.unwrap())
+        .collect()
+        .await;
+        assert_eq!(items, vec![1, 2]);
+    }
 }
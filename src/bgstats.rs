@@ -0,0 +1,469 @@
+/*!
+Conversion between rbgg's typed plays and the JSON format used by the
+[BG Stats](https://www.bgstatsapp.com/) app for its imports/exports.  This
+makes it easy to move the plays you've logged on BGG into BG Stats.
+
+```ignore,rust
+use rbgg::{bgg2::Client2, bgstats::BgStatsExport, models::Plays};
+
+let cl = Client2::new_from_defaults();
+let plays = Plays::from_value(&cl.plays_b(Some("someuser"), None, None, None)?)?;
+let export = BgStatsExport::from_plays(&plays.plays, Some("someuser"));
+
+export.save("bgstats-import.json")?;
+```
+
+Going the other way, a BG Stats export can be loaded and turned back into
+typed plays, which can then be logged to BGG with a
+[`BggSession`](crate::session::BggSession).
+
+```ignore,rust
+use rbgg::{bgstats::BgStatsExport, session::BggSession};
+
+let export = BgStatsExport::load("BGStatsExport.json")?;
+// Plays that originally came from BGG keep their ID, so skip those
+let plays: Vec<_> = export.to_plays().into_iter().filter(|p| p.id == 0).collect();
+
+let session = BggSession::login_b("someuser", "secret")?;
+session.log_plays_b(&plays)?;
+```
+*/
+use crate::errors::Result;
+use crate::models::{Play, PlayItem, Player};
+use crate::utils;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// A game in a BG Stats export
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BgStatsGame {
+    pub id: usize,
+    pub name: String,
+    pub bgg_id: usize,
+    pub bgg_name: String,
+}
+
+/// A player in a BG Stats export
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BgStatsPlayer {
+    pub id: usize,
+    pub name: String,
+    pub is_anonymous: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bgg_username: Option<String>,
+}
+
+/// A location in a BG Stats export
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BgStatsLocation {
+    pub id: usize,
+    pub name: String,
+}
+
+/// A player's result in a BG Stats play
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BgStatsScore {
+    pub player_ref_id: usize,
+    pub score: String,
+    pub winner: bool,
+    pub new_player: bool,
+    pub start_player: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+}
+
+/// A play in a BG Stats export
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BgStatsPlay {
+    pub game_ref_id: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location_ref_id: Option<usize>,
+    /// The date of the play as "YYYY-MM-DD HH:MM:SS"
+    pub play_date: String,
+    pub duration_min: u32,
+    /// Whether the play is excluded from stats
+    pub ignored: bool,
+    pub manual_winner: bool,
+    /// The ID of the play on BGG
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bgg_id: Option<usize>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub comments: String,
+    pub player_scores: Vec<BgStatsScore>,
+}
+
+/// Information about the user the export belongs to
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BgStatsUserInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub me_ref_id: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bgg_username: Option<String>,
+}
+
+/// The top level of a BG Stats import/export file
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BgStatsExport {
+    pub games: Vec<BgStatsGame>,
+    pub players: Vec<BgStatsPlayer>,
+    pub locations: Vec<BgStatsLocation>,
+    pub plays: Vec<BgStatsPlay>,
+    pub user_info: BgStatsUserInfo,
+}
+
+impl BgStatsExport {
+    /// Convert BGG plays into a BG Stats export.  `username` is the BGG
+    /// user the plays belong to, which BG Stats uses to work out which
+    /// player is "me".  BG Stats has no notion of quantity, so a play with
+    /// a quantity of more than 1 is exported that many times.
+    pub fn from_plays(plays: &[Play], username: Option<&str>) -> Self {
+        let mut ret = Self::default();
+        let mut games: HashMap<usize, usize> = HashMap::new();
+        let mut players: HashMap<String, usize> = HashMap::new();
+        let mut locations: HashMap<String, usize> = HashMap::new();
+
+        for play in plays {
+            let game_ref_id = *games.entry(play.item.id).or_insert_with(|| {
+                let id = ret.games.len() + 1;
+                ret.games.push(BgStatsGame {
+                    id,
+                    name: play.item.name.clone(),
+                    bgg_id: play.item.id,
+                    bgg_name: play.item.name.clone(),
+                });
+                id
+            });
+
+            let location = play.location.trim();
+            let location_ref_id = match location.is_empty() {
+                true => None,
+                false => Some(*locations.entry(location.to_string()).or_insert_with(|| {
+                    let id = ret.locations.len() + 1;
+                    ret.locations.push(BgStatsLocation {
+                        id,
+                        name: location.to_string(),
+                    });
+                    id
+                })),
+            };
+
+            let mut scores = vec![];
+            for player in &play.players {
+                let player_ref_id =
+                    *players
+                        .entry(player.ident().to_string())
+                        .or_insert_with(|| {
+                            let id = ret.players.len() + 1;
+                            let bgg_username = match player.username.is_empty() {
+                                true => None,
+                                false => Some(player.username.clone()),
+                            };
+                            ret.players.push(BgStatsPlayer {
+                                id,
+                                name: match player.name.is_empty() {
+                                    true => player.username.clone(),
+                                    false => player.name.clone(),
+                                },
+                                is_anonymous: false,
+                                bgg_username,
+                            });
+                            id
+                        });
+
+                scores.push(BgStatsScore {
+                    player_ref_id,
+                    score: player.score.clone(),
+                    winner: player.win,
+                    new_player: player.new,
+                    start_player: player.start_position.trim() == "1",
+                    role: match player.color.is_empty() {
+                        true => None,
+                        false => Some(player.color.clone()),
+                    },
+                });
+            }
+
+            let exported = BgStatsPlay {
+                game_ref_id,
+                location_ref_id,
+                play_date: format!("{} 00:00:00", play.date),
+                duration_min: play.length,
+                ignored: play.no_win_stats,
+                manual_winner: false,
+                bgg_id: Some(play.id),
+                comments: play.comments.clone().unwrap_or_default(),
+                player_scores: scores,
+            };
+            for _ in 1..play.quantity.max(1) {
+                ret.plays.push(exported.clone());
+            }
+            ret.plays.push(exported);
+        }
+
+        if let Some(user) = username {
+            ret.user_info = BgStatsUserInfo {
+                me_ref_id: ret
+                    .players
+                    .iter()
+                    .find(|p| p.bgg_username.as_deref() == Some(user))
+                    .map(|p| p.id),
+                bgg_username: Some(user.to_string()),
+            };
+        }
+
+        return ret;
+    }
+
+    /// Convert the export back into BGG plays.  The ID of each play is the
+    /// BGG play ID, if BG Stats knows it, and 0 otherwise.  References to
+    /// games, players or locations that aren't in the export are left
+    /// blank.
+    pub fn to_plays(&self) -> Vec<Play> {
+        let games: HashMap<usize, &BgStatsGame> = self.games.iter().map(|g| (g.id, g)).collect();
+        let players: HashMap<usize, &BgStatsPlayer> =
+            self.players.iter().map(|p| (p.id, p)).collect();
+        let locations: HashMap<usize, &BgStatsLocation> =
+            self.locations.iter().map(|l| (l.id, l)).collect();
+
+        let mut ret = vec![];
+        for play in &self.plays {
+            let item = match games.get(&play.game_ref_id) {
+                Some(game) => PlayItem {
+                    id: game.bgg_id,
+                    name: match game.bgg_name.is_empty() {
+                        true => game.name.clone(),
+                        false => game.bgg_name.clone(),
+                    },
+                    object_type: "thing".to_string(),
+                },
+                None => PlayItem::default(),
+            };
+
+            let mut play_players = vec![];
+            for score in &play.player_scores {
+                let player = players.get(&score.player_ref_id);
+                play_players.push(Player {
+                    username: player
+                        .and_then(|p| p.bgg_username.clone())
+                        .unwrap_or_default(),
+                    name: player.map(|p| p.name.clone()).unwrap_or_default(),
+                    start_position: match score.start_player {
+                        true => "1".to_string(),
+                        false => String::new(),
+                    },
+                    color: score.role.clone().unwrap_or_default(),
+                    score: score.score.clone(),
+                    new: score.new_player,
+                    win: score.winner,
+                    ..Default::default()
+                });
+            }
+
+            ret.push(Play {
+                id: play.bgg_id.unwrap_or(0),
+                // Strip the time off of "YYYY-MM-DD HH:MM:SS"
+                date: play.play_date.split(' ').next().unwrap_or("").to_string(),
+                quantity: 1,
+                length: play.duration_min,
+                no_win_stats: play.ignored,
+                location: play
+                    .location_ref_id
+                    .and_then(|id| locations.get(&id))
+                    .map(|l| l.name.clone())
+                    .unwrap_or_default(),
+                item,
+                comments: match play.comments.is_empty() {
+                    true => None,
+                    false => Some(play.comments.clone()),
+                },
+                players: play_players,
+                ..Default::default()
+            });
+        }
+
+        return ret;
+    }
+
+    /// Parse an export from a JSON string
+    pub fn from_json(data: &str) -> Result<Self> {
+        return Ok(serde_json::from_value(utils::parse_json(data)?)?);
+    }
+
+    /// Load an export (as written by the BG Stats app or `save()`) from the
+    /// given path
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        return Self::from_json(&fs::read_to_string(path)?);
+    }
+
+    /// Serialize the export to a JSON string
+    pub fn to_json(&self) -> Result<String> {
+        return Ok(serde_json::to_string(self)?);
+    }
+
+    /// Save the export (as JSON) to the given path
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, self)?;
+        writer.flush()?;
+
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plays() -> Vec<Play> {
+        let me = Player {
+            username: "me".into(),
+            name: "Me".into(),
+            score: "52".into(),
+            start_position: "1".into(),
+            win: true,
+            ..Default::default()
+        };
+        let guest = Player {
+            name: "Guest".into(),
+            score: "48".into(),
+            color: "Red".into(),
+            new: true,
+            ..Default::default()
+        };
+
+        return vec![
+            Play {
+                id: 1001,
+                date: "2023-01-05".into(),
+                quantity: 1,
+                length: 60,
+                location: "Home".into(),
+                item: PlayItem {
+                    id: 136888,
+                    name: "Bruges".into(),
+                    object_type: "thing".into(),
+                },
+                comments: Some("Close".into()),
+                players: vec![me.clone(), guest],
+                ..Default::default()
+            },
+            Play {
+                id: 1002,
+                date: "2023-01-06".into(),
+                quantity: 2,
+                item: PlayItem {
+                    id: 136888,
+                    name: "Bruges".into(),
+                    object_type: "thing".into(),
+                },
+                players: vec![me],
+                ..Default::default()
+            },
+        ];
+    }
+
+    #[test]
+    fn test_from_plays() {
+        let export = BgStatsExport::from_plays(&plays(), Some("me"));
+
+        assert_eq!(export.games.len(), 1);
+        assert_eq!(export.games[0].bgg_id, 136888);
+        assert_eq!(export.locations.len(), 1);
+        assert_eq!(export.players.len(), 2);
+        assert_eq!(export.players[0].bgg_username, Some("me".into()));
+        assert_eq!(export.players[1].bgg_username, None);
+        assert_eq!(export.user_info.me_ref_id, Some(1));
+
+        // The quantity of 2 on the second play is expanded
+        assert_eq!(export.plays.len(), 3);
+        let play = &export.plays[0];
+        assert_eq!(play.game_ref_id, 1);
+        assert_eq!(play.location_ref_id, Some(1));
+        assert_eq!(play.play_date, "2023-01-05 00:00:00");
+        assert_eq!(play.duration_min, 60);
+        assert_eq!(play.bgg_id, Some(1001));
+        assert_eq!(play.player_scores.len(), 2);
+        assert!(play.player_scores[0].winner);
+        assert!(play.player_scores[0].start_player);
+        assert!(play.player_scores[1].new_player);
+        assert_eq!(play.player_scores[1].role, Some("Red".into()));
+        assert_eq!(export.plays[2].location_ref_id, None);
+    }
+
+    #[test]
+    fn test_to_json() {
+        let export = BgStatsExport::from_plays(&plays(), Some("me"));
+        let data: serde_json::Value = serde_json::from_str(&export.to_json().unwrap()).unwrap();
+
+        assert_eq!(data["games"][0]["bggId"], 136888);
+        assert_eq!(data["plays"][0]["gameRefId"], 1);
+        assert_eq!(data["plays"][0]["playerScores"][0]["playerRefId"], 1);
+        assert_eq!(data["userInfo"]["bggUsername"], "me");
+        assert!(data["plays"][2].get("locationRefId").is_none());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let orig = plays();
+        let export = BgStatsExport::from_json(
+            &BgStatsExport::from_plays(&orig, Some("me"))
+                .to_json()
+                .unwrap(),
+        )
+        .unwrap();
+        let plays = export.to_plays();
+
+        assert_eq!(plays.len(), 3);
+        let play = &plays[0];
+        assert_eq!(play.id, 1001);
+        assert_eq!(play.date, "2023-01-05");
+        assert_eq!(play.length, 60);
+        assert_eq!(play.location, "Home");
+        assert_eq!(play.item.id, 136888);
+        assert_eq!(play.item.name, "Bruges");
+        assert_eq!(play.comments, Some("Close".into()));
+        assert_eq!(play.players.len(), 2);
+        assert_eq!(play.players[0].username, "me");
+        assert_eq!(play.players[0].start_position, "1");
+        assert!(play.players[0].win);
+        assert_eq!(play.players[1].name, "Guest");
+        assert_eq!(play.players[1].color, "Red");
+        assert!(play.players[1].new);
+        assert_eq!(plays[2].location, "");
+    }
+
+    #[test]
+    fn test_from_json_extra_fields() {
+        // Real exports have a lot more in them than we care about
+        let data = r#"{
+            "games": [{"id": 3, "name": "Bruges", "bggId": 136888, "uuid": "x", "cooperative": false}],
+            "players": [{"id": 7, "name": "Me", "isAnonymous": false, "bggUsername": "me"}],
+            "plays": [{
+                "gameRefId": 3,
+                "playDate": "2023-01-05 19:30:00",
+                "durationMin": 45,
+                "rounds": 0,
+                "playerScores": [{"playerRefId": 7, "score": "10", "winner": true, "seatOrder": 0}]
+            }],
+            "challenges": []
+        }"#;
+        let plays = BgStatsExport::from_json(data).unwrap().to_plays();
+
+        assert_eq!(plays.len(), 1);
+        assert_eq!(plays[0].id, 0);
+        assert_eq!(plays[0].date, "2023-01-05");
+        assert_eq!(plays[0].item.name, "Bruges");
+        assert_eq!(plays[0].players[0].username, "me");
+    }
+}
@@ -0,0 +1,250 @@
+/*!
+An optional circuit breaker, which stops a client from hammering BGG while
+it is down.  After a number of consecutive failures (5xx responses,
+timeouts or connection errors) the breaker opens, and every call fails
+straight away with `RbggError::CircuitOpen` until the cooldown is up.  Then
+a single call is let through to test the waters: if it works, the breaker
+closes again, and if not, it stays open for another cooldown.
+
+```ignore,rust
+use rbgg::{bgg2::Client2, breaker::BreakerConfig, http::HttpConfig};
+
+let config = HttpConfig {
+    breaker: Some(BreakerConfig::default()),
+    ..Default::default()
+};
+let cl = Client2::new_with_config(None, None, config)?;
+// ...
+if let Some(state) = cl.breaker_state() {
+    println!("The breaker is {:?}", state.status);
+}
+```
+*/
+use crate::errors::{RbggError, Result};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// The settings for the circuit breaker
+#[derive(Clone, Debug, PartialEq)]
+pub struct BreakerConfig {
+    /// The number of failures in a row that opens the breaker
+    pub failure_threshold: u32,
+    /// How long the breaker stays open before letting a call through
+    pub cooldown: Duration,
+}
+
+impl Default for BreakerConfig {
+    fn default() -> Self {
+        return Self {
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(30),
+        };
+    }
+}
+
+/// Where the breaker is at
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakerStatus {
+    /// Calls are going through as normal
+    Closed,
+    /// Calls are failing fast until the cooldown is up
+    Open,
+    /// The cooldown is up and a call is being let through to see if BGG
+    /// is back
+    HalfOpen,
+}
+
+/// A snapshot of a circuit breaker
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BreakerState {
+    pub status: BreakerStatus,
+    /// The number of failures in a row so far
+    pub consecutive_failures: u32,
+    /// How long until an open breaker lets a call through
+    pub retry_in: Option<Duration>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    failures: u32,
+    open_until: Option<Instant>,
+    // The ID of the call testing the waters, if there is one
+    probing: Option<u64>,
+    probes: u64,
+}
+
+#[derive(Debug)]
+pub(crate) struct CircuitBreaker {
+    config: BreakerConfig,
+    state: Mutex<State>,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: &BreakerConfig) -> Self {
+        return Self {
+            config: config.clone(),
+            state: Mutex::new(State::default()),
+        };
+    }
+
+    /// Check whether a call may go ahead, returning `RbggError::CircuitOpen`
+    /// if not.  If the call is the one let through to test the waters, it
+    /// gets a `Probe`, which must be held until the call has been reported
+    /// with `success()` or `failure()`.  Dropping it before then (say, if
+    /// the call is cancelled) lets another call through instead.
+    pub fn check(&self, now: Instant) -> Result<Option<Probe<'_>>> {
+        let mut state = self.lock();
+
+        if let Some(until) = state.open_until {
+            if now < until {
+                return Err(RbggError::CircuitOpen {
+                    retry_in: until - now,
+                });
+            }
+            if state.probing.is_some() {
+                // Someone else is already testing the waters
+                return Err(RbggError::CircuitOpen {
+                    retry_in: Duration::ZERO,
+                });
+            }
+            state.probes += 1;
+            state.probing = Some(state.probes);
+
+            return Ok(Some(Probe {
+                breaker: self,
+                id: state.probes,
+            }));
+        }
+
+        return Ok(None);
+    }
+
+    /// A call worked, so close the breaker
+    pub fn success(&self) {
+        let mut state = self.lock();
+        *state = State::default();
+    }
+
+    /// A call failed.  Returns whether this tripped the breaker open.
+    pub fn failure(&self, now: Instant) -> bool {
+        let mut state = self.lock();
+        state.failures += 1;
+
+        if state.probing.is_some() || state.failures >= self.config.failure_threshold.max(1) {
+            let tripped = state.open_until.is_none() || state.probing.is_some();
+            state.open_until = Some(now + self.config.cooldown);
+            state.probing = None;
+            return tripped;
+        }
+
+        return false;
+    }
+
+    /// Return a snapshot of the breaker
+    pub fn state(&self, now: Instant) -> BreakerState {
+        let state = self.lock();
+        let (status, retry_in) = match state.open_until {
+            None => (BreakerStatus::Closed, None),
+            Some(until) if now < until => (BreakerStatus::Open, Some(until - now)),
+            Some(_) => (BreakerStatus::HalfOpen, None),
+        };
+
+        return BreakerState {
+            status,
+            consecutive_failures: state.failures,
+            retry_in,
+        };
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        return self.state.lock().unwrap_or_else(|e| e.into_inner());
+    }
+}
+
+/// The call let through a half open breaker to test the waters.  If it's
+/// dropped without the call being reported, the breaker lets another call
+/// through.
+#[derive(Debug)]
+pub(crate) struct Probe<'a> {
+    breaker: &'a CircuitBreaker,
+    id: u64,
+}
+
+impl Drop for Probe<'_> {
+    fn drop(&mut self) {
+        let mut state = self.breaker.lock();
+        if state.probing == Some(self.id) {
+            state.probing = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breaker() {
+        let breaker = CircuitBreaker::new(&BreakerConfig {
+            failure_threshold: 2,
+            cooldown: Duration::from_secs(10),
+        });
+        let now = Instant::now();
+
+        assert!(breaker.check(now).is_ok());
+        assert!(!breaker.failure(now));
+        assert_eq!(breaker.state(now).status, BreakerStatus::Closed);
+        assert!(breaker.failure(now));
+
+        let state = breaker.state(now);
+        assert_eq!(state.status, BreakerStatus::Open);
+        assert_eq!(state.consecutive_failures, 2);
+        assert_eq!(state.retry_in, Some(Duration::from_secs(10)));
+        let err = breaker.check(now).unwrap_err();
+        assert!(matches!(err, RbggError::CircuitOpen { .. }));
+
+        // After the cooldown, only one call gets through
+        let later = now + Duration::from_secs(11);
+        assert_eq!(breaker.state(later).status, BreakerStatus::HalfOpen);
+        let probe = breaker.check(later).unwrap();
+        assert!(probe.is_some());
+        assert!(breaker.check(later).is_err());
+
+        // And if it fails, the breaker opens again
+        assert!(breaker.failure(later));
+        drop(probe);
+        assert_eq!(breaker.state(later).status, BreakerStatus::Open);
+
+        let later = later + Duration::from_secs(11);
+        let probe = breaker.check(later).unwrap();
+        breaker.success();
+        drop(probe);
+        assert_eq!(breaker.state(later).status, BreakerStatus::Closed);
+        assert_eq!(breaker.state(later).consecutive_failures, 0);
+        assert!(breaker.check(later).is_ok());
+    }
+
+    #[test]
+    fn test_dropped_probe() {
+        let breaker = CircuitBreaker::new(&BreakerConfig {
+            failure_threshold: 1,
+            cooldown: Duration::from_secs(10),
+        });
+        let now = Instant::now();
+        assert!(breaker.failure(now));
+
+        // The probe is dropped partway through (e.g. cancelled), so the
+        // next call gets to test the waters instead
+        let later = now + Duration::from_secs(11);
+        let probe = breaker.check(later).unwrap();
+        assert!(breaker.check(later).is_err());
+        drop(probe);
+        let probe = breaker.check(later).unwrap();
+        assert!(probe.is_some());
+
+        // Once a probe has reported back, dropping it changes nothing
+        assert!(breaker.failure(later));
+        drop(probe);
+        assert_eq!(breaker.state(later).status, BreakerStatus::Open);
+    }
+}
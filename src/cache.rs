@@ -0,0 +1,744 @@
+/*!
+Caching of responses, so that repeated lookups don't go to BGG at all.  Set
+a `ResponseCache` in the `http::HttpConfig` (or via `cache()` on the client
+builder) and it is checked, by url, before every request, with each
+successful response stored in it for `HttpConfig::cache_ttl`.
+
+`MemoryCache` is a bounded, in-memory cache that works well for most uses,
+and `DiskCache` keeps responses across restarts.  With the `redis` feature,
+`RedisCache` lets several processes share a cache.  Or you can plug in your
+own storage by implementing `ResponseCache`.
+
+```ignore,rust
+use rbgg::{bgg2::{Client2, Hotness}, cache::ResponseCache};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug)]
+struct MyCache { /* ... */ }
+
+impl ResponseCache for MyCache {
+    fn get(&self, key: &str) -> Option<String> {
+        // ...
+    }
+
+    fn put(&self, key: &str, body: &str, ttl: Duration) {
+        // ...
+    }
+}
+
+let cl = Client2::builder()
+    .cache(Arc::new(MyCache { /* ... */ }))
+    .cache_ttl(Duration::from_secs(600))
+    .build()?;
+// Only the first of these goes to BGG
+cl.hot_b(Hotness::BoardGame, None)?;
+cl.hot_b(Hotness::BoardGame, None)?;
+```
+
+A handle with `CallConfig::no_cache()` skips the cache for its calls, but
+still stores the fresh responses in it.
+*/
+use crate::errors::Result;
+#[cfg(feature = "async")]
+use futures_util::future::{self, BoxFuture};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "redis")]
+use std::sync::Arc;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// How long responses are cached for when a TTL isn't configured
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Storage for the raw (XML) responses from BGG, keyed by the url they came
+/// from.  Blocking calls use `get()` and `put()` on the thread making the
+/// request, and async calls use `get_async()` and `put_async()`, which just
+/// call those by default.  A cache that does any I/O should override the
+/// async ones so that it doesn't block the runtime (`DiskCache` and
+/// `RedisCache` run them with `tokio::task::spawn_blocking()`).  A cache
+/// that can't be read or written should just act like it's empty, as the
+/// request is then made as normal.
+pub trait ResponseCache: fmt::Debug + Send + Sync {
+    /// Return the body stored for the key, if there is one and it hasn't
+    /// expired
+    fn get(&self, key: &str) -> Option<String>;
+
+    /// Store the body for the key, to expire after `ttl`
+    fn put(&self, key: &str, body: &str, ttl: Duration);
+
+    /// `get()` for async calls
+    #[cfg(feature = "async")]
+    fn get_async<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Option<String>> {
+        return Box::pin(future::ready(self.get(key)));
+    }
+
+    /// `put()` for async calls
+    #[cfg(feature = "async")]
+    fn put_async<'a>(&'a self, key: &'a str, body: &'a str, ttl: Duration) -> BoxFuture<'a, ()> {
+        self.put(key, body, ttl);
+
+        return Box::pin(future::ready(()));
+    }
+}
+
+/// An in-memory `ResponseCache` that keeps up to `capacity` responses,
+/// dropping the least recently used once it's full.  Different kinds of
+/// lookup can be kept for different lengths of time by setting a TTL for
+/// their endpoint class, which is the last part of the url's path, like
+/// "hot", "thing" or "collection".
+///
+/// ```ignore,rust
+/// use rbgg::{bgg2::Client2, cache::MemoryCache};
+/// use std::{sync::Arc, time::Duration};
+///
+/// // The hotness changes often, but games rarely do
+/// let cache = MemoryCache::new(1000)
+///     .with_ttl("hot", Duration::from_secs(300))
+///     .with_ttl("thing", Duration::from_secs(24 * 60 * 60));
+/// let cl = Client2::builder().cache(Arc::new(cache)).build()?;
+/// ```
+#[derive(Debug)]
+pub struct MemoryCache {
+    capacity: usize,
+    ttls: HashMap<String, Duration>,
+    state: Mutex<Lru>,
+}
+
+#[derive(Debug, Default)]
+struct Lru {
+    entries: HashMap<String, Entry>,
+    // The keys by when they were last used, oldest first
+    order: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    body: String,
+    // `None` if the TTL is too far out to represent, so it never expires
+    expires: Option<Instant>,
+    used: u64,
+}
+
+impl MemoryCache {
+    /// Create a cache that holds up to `capacity` responses
+    pub fn new(capacity: usize) -> Self {
+        return Self {
+            capacity,
+            ttls: HashMap::new(),
+            state: Mutex::new(Lru::default()),
+        };
+    }
+
+    /// Keep responses for the given endpoint class (e.g. "hot") for `ttl`,
+    /// rather than for the client's `cache_ttl`
+    pub fn with_ttl(mut self, class: &str, ttl: Duration) -> Self {
+        self.ttls.insert(class.to_string(), ttl);
+
+        return self;
+    }
+
+    /// The number of responses held, including any that have expired but
+    /// haven't been dropped yet
+    pub fn len(&self) -> usize {
+        return self.lock().entries.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.len() == 0;
+    }
+
+    /// Drop everything from the cache
+    pub fn clear(&self) {
+        *self.lock() = Lru::default();
+    }
+
+    fn get_at(&self, key: &str, now: Instant) -> Option<String> {
+        let mut lru = self.lock();
+        let lru = &mut *lru;
+        let entry = lru.entries.get_mut(key)?;
+
+        if entry.expires.is_some_and(|expires| expires <= now) {
+            lru.order.remove(&entry.used);
+            lru.entries.remove(key);
+            return None;
+        }
+        lru.tick += 1;
+        lru.order.remove(&entry.used);
+        lru.order.insert(lru.tick, key.to_string());
+        entry.used = lru.tick;
+
+        return Some(entry.body.clone());
+    }
+
+    fn put_at(&self, key: &str, body: &str, ttl: Duration, now: Instant) {
+        if self.capacity == 0 {
+            return;
+        }
+        let ttl = self.ttls.get(&endpoint_class(key)).copied().unwrap_or(ttl);
+        let mut lru = self.lock();
+        lru.tick += 1;
+        let entry = Entry {
+            body: body.to_string(),
+            expires: now.checked_add(ttl),
+            used: lru.tick,
+        };
+
+        if let Some(old) = lru.entries.insert(key.to_string(), entry) {
+            lru.order.remove(&old.used);
+        }
+        let tick = lru.tick;
+        lru.order.insert(tick, key.to_string());
+
+        while lru.entries.len() > self.capacity {
+            match lru.order.pop_first() {
+                Some((_, oldest)) => lru.entries.remove(&oldest),
+                None => break,
+            };
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Lru> {
+        return self.state.lock().unwrap_or_else(|e| e.into_inner());
+    }
+}
+
+impl ResponseCache for MemoryCache {
+    fn get(&self, key: &str) -> Option<String> {
+        return self.get_at(key, Instant::now());
+    }
+
+    fn put(&self, key: &str, body: &str, ttl: Duration) {
+        self.put_at(key, body, ttl, Instant::now());
+    }
+}
+
+/// The endpoint class of a url, which is the last part of its path that
+/// isn't an ID (e.g. "thing" for "/xmlapi2/thing?id=1", or "boardgame" for
+/// "/xmlapi/boardgame/1,2")
+pub fn endpoint_class(url: &str) -> String {
+    let path = match url::Url::parse(url) {
+        Ok(url) => url.path().to_string(),
+        Err(_) => url.split('?').next().unwrap_or_default().to_string(),
+    };
+
+    return path
+        .rsplit('/')
+        .find(|seg| !seg.is_empty() && !seg.starts_with(|c: char| c.is_ascii_digit()))
+        .unwrap_or_default()
+        .to_string();
+}
+
+/// A `ResponseCache` that keeps responses as files in a directory, so that
+/// they survive restarts.  This gives CLI tools and notebooks a warm start.
+/// Each response is stored in its own file, along with the url it came from
+/// and when it expires.  Expired files are removed when they're next looked
+/// up, or all at once with `purge_expired()`.
+///
+/// ```ignore,rust
+/// use rbgg::{bgg2::Client2, cache::DiskCache};
+/// use std::sync::Arc;
+///
+/// let cache = DiskCache::new("/home/me/.cache/rbgg")?;
+/// let cl = Client2::builder().cache(Arc::new(cache)).build()?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct DiskCache {
+    dir: PathBuf,
+    ttls: HashMap<String, Duration>,
+}
+
+/// What's stored in each file
+#[derive(Debug, Serialize, Deserialize)]
+struct DiskEntry {
+    key: String,
+    /// When the entry expires, in seconds since the epoch
+    expires: u64,
+    body: String,
+}
+
+impl DiskCache {
+    /// Create a cache in the given directory, creating it if needed
+    pub fn new(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        return Ok(Self {
+            dir,
+            ttls: HashMap::new(),
+        });
+    }
+
+    /// Keep responses for the given endpoint class (e.g. "hot") for `ttl`,
+    /// rather than for the client's `cache_ttl`
+    pub fn with_ttl(mut self, class: &str, ttl: Duration) -> Self {
+        self.ttls.insert(class.to_string(), ttl);
+
+        return self;
+    }
+
+    /// The directory the responses are stored in
+    pub fn dir(&self) -> &Path {
+        return &self.dir;
+    }
+
+    /// Remove every response from the cache
+    pub fn clear(&self) -> Result<()> {
+        for path in self.files()? {
+            fs::remove_file(path)?;
+        }
+
+        return Ok(());
+    }
+
+    /// Remove the responses that have expired, returning how many there
+    /// were
+    pub fn purge_expired(&self) -> Result<usize> {
+        let now = unix_now();
+        let mut ret = 0;
+
+        for path in self.files()? {
+            let expired = match Self::read(&path) {
+                Some(entry) => entry.expires <= now,
+                None => true,
+            };
+            if expired {
+                fs::remove_file(path)?;
+                ret += 1;
+            }
+        }
+
+        return Ok(ret);
+    }
+
+    /// The cache files in the directory
+    fn files(&self) -> Result<Vec<PathBuf>> {
+        let mut ret = vec![];
+
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                ret.push(path);
+            }
+        }
+
+        return Ok(ret);
+    }
+
+    /// The file the response for the key is stored in
+    fn path(&self, key: &str) -> PathBuf {
+        return self.dir.join(format!("{:016x}.json", fnv1a(key)));
+    }
+
+    fn read(path: &Path) -> Option<DiskEntry> {
+        let data = fs::read_to_string(path).ok()?;
+
+        return serde_json::from_str(&data).ok();
+    }
+
+    fn write(&self, key: &str, entry: &DiskEntry) -> Result<()> {
+        let path = self.path(key);
+        // Write to a temporary file first so that readers never see half a
+        // response.  Each write gets its own, as other threads (and
+        // processes) may be writing the same key at the same time.
+        static WRITES: AtomicU64 = AtomicU64::new(0);
+        let tmp = path.with_extension(format!(
+            "tmp{}-{}",
+            std::process::id(),
+            WRITES.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&tmp, serde_json::to_string(entry)?)?;
+        fs::rename(&tmp, &path)?;
+
+        return Ok(());
+    }
+}
+
+impl ResponseCache for DiskCache {
+    fn get(&self, key: &str) -> Option<String> {
+        let path = self.path(key);
+        let entry = Self::read(&path)?;
+
+        if entry.key != key {
+            // A hash collision, so this is someone else's response
+            return None;
+        }
+        if entry.expires <= unix_now() {
+            let _ = fs::remove_file(path);
+            return None;
+        }
+
+        return Some(entry.body);
+    }
+
+    fn put(&self, key: &str, body: &str, ttl: Duration) {
+        let ttl = self.ttls.get(&endpoint_class(key)).copied().unwrap_or(ttl);
+        let entry = DiskEntry {
+            key: key.to_string(),
+            expires: unix_now().saturating_add(ttl.as_secs()),
+            body: body.to_string(),
+        };
+
+        // A cache that can't be written to just doesn't cache
+        let _ = self.write(key, &entry);
+    }
+
+    /// Read the file on tokio's blocking pool rather than on the runtime
+    #[cfg(feature = "async")]
+    fn get_async<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Option<String>> {
+        let this = self.clone();
+        let key = key.to_string();
+
+        return Box::pin(async move {
+            return tokio::task::spawn_blocking(move || this.get(&key))
+                .await
+                .ok()
+                .flatten();
+        });
+    }
+
+    /// Write the file on tokio's blocking pool rather than on the runtime
+    #[cfg(feature = "async")]
+    fn put_async<'a>(&'a self, key: &'a str, body: &'a str, ttl: Duration) -> BoxFuture<'a, ()> {
+        let this = self.clone();
+        let (key, body) = (key.to_string(), body.to_string());
+
+        return Box::pin(async move {
+            let _ = tokio::task::spawn_blocking(move || this.put(&key, &body, ttl)).await;
+        });
+    }
+}
+
+/// A `ResponseCache` stored in Redis (with the `redis` feature), so that
+/// several processes can share one cache and, between them, stay under
+/// BGG's rate limits.  Redis takes care of expiring the responses.
+///
+/// ```ignore,rust
+/// use rbgg::{bgg2::Client2, cache::RedisCache};
+/// use std::sync::Arc;
+///
+/// let cache = RedisCache::new("redis://127.0.0.1/")?.with_prefix("myapp:bgg:");
+/// let cl = Client2::builder().cache(Arc::new(cache)).build()?;
+/// ```
+#[cfg(feature = "redis")]
+#[derive(Clone)]
+pub struct RedisCache {
+    client: redis::Client,
+    prefix: String,
+    ttls: HashMap<String, Duration>,
+    // Idle connections, shared with any clones.  Each query takes one (or
+    // makes a new one), so concurrent requests don't wait on each other.
+    conns: Arc<Mutex<Vec<redis::Connection>>>,
+}
+
+#[cfg(feature = "redis")]
+impl fmt::Debug for RedisCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f
+            .debug_struct("RedisCache")
+            .field("server", &self.client.get_connection_info().addr)
+            .field("prefix", &self.prefix)
+            .field("ttls", &self.ttls)
+            .finish();
+    }
+}
+
+#[cfg(feature = "redis")]
+impl RedisCache {
+    /// The longest we'll wait on Redis, so that a dead server doesn't hold
+    /// up requests for long
+    const TIMEOUT: Duration = Duration::from_secs(2);
+
+    /// The most idle connections we keep around
+    const MAX_IDLE: usize = 8;
+
+    /// Create a cache using the Redis server at the url (e.g.
+    /// "redis://127.0.0.1/").  This doesn't connect until it's first used.
+    pub fn new(url: &str) -> Result<Self> {
+        return Ok(Self {
+            client: redis::Client::open(url)?,
+            prefix: "rbgg:".to_string(),
+            ttls: HashMap::new(),
+            conns: Arc::new(Mutex::new(vec![])),
+        });
+    }
+
+    /// Set what's prepended to the keys, which is "rbgg:" by default
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_string();
+
+        return self;
+    }
+
+    /// Keep responses for the given endpoint class (e.g. "hot") for `ttl`,
+    /// rather than for the client's `cache_ttl`
+    pub fn with_ttl(mut self, class: &str, ttl: Duration) -> Self {
+        self.ttls.insert(class.to_string(), ttl);
+
+        return self;
+    }
+
+    /// Run the command on an idle connection, or a new one if there aren't
+    /// any.  A connection is only kept for reuse if the command succeeded.
+    fn query<T: redis::FromRedisValue>(&self, cmd: &redis::Cmd) -> Result<T> {
+        let idle = self.lock().pop();
+        let mut conn = match idle {
+            Some(conn) => conn,
+            None => {
+                let new = self.client.get_connection_with_timeout(Self::TIMEOUT)?;
+                new.set_read_timeout(Some(Self::TIMEOUT))?;
+                new.set_write_timeout(Some(Self::TIMEOUT))?;
+                new
+            }
+        };
+
+        let ret = cmd.query(&mut conn)?;
+        let mut idle = self.lock();
+        if idle.len() < Self::MAX_IDLE {
+            idle.push(conn);
+        }
+
+        return Ok(ret);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<redis::Connection>> {
+        return self.conns.lock().unwrap_or_else(|e| e.into_inner());
+    }
+}
+
+#[cfg(feature = "redis")]
+impl ResponseCache for RedisCache {
+    fn get(&self, key: &str) -> Option<String> {
+        let mut cmd = redis::cmd("GET");
+        cmd.arg(format!("{}{}", self.prefix, key));
+
+        return self.query::<Option<String>>(&cmd).ok().flatten();
+    }
+
+    fn put(&self, key: &str, body: &str, ttl: Duration) {
+        let ttl = self.ttls.get(&endpoint_class(key)).copied().unwrap_or(ttl);
+        if ttl.is_zero() {
+            return;
+        }
+
+        let mut cmd = redis::cmd("SET");
+        cmd.arg(format!("{}{}", self.prefix, key))
+            .arg(body)
+            .arg("PX")
+            .arg(ttl.as_millis() as u64);
+        // A cache that can't be written to just doesn't cache
+        let _ = self.query::<()>(&cmd);
+    }
+
+    /// Talk to Redis on tokio's blocking pool rather than on the runtime
+    #[cfg(feature = "async")]
+    fn get_async<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Option<String>> {
+        let this = self.clone();
+        let key = key.to_string();
+
+        return Box::pin(async move {
+            return tokio::task::spawn_blocking(move || this.get(&key))
+                .await
+                .ok()
+                .flatten();
+        });
+    }
+
+    /// Talk to Redis on tokio's blocking pool rather than on the runtime
+    #[cfg(feature = "async")]
+    fn put_async<'a>(&'a self, key: &'a str, body: &'a str, ttl: Duration) -> BoxFuture<'a, ()> {
+        let this = self.clone();
+        let (key, body) = (key.to_string(), body.to_string());
+
+        return Box::pin(async move {
+            let _ = tokio::task::spawn_blocking(move || this.put(&key, &body, ttl)).await;
+        });
+    }
+}
+
+/// The current time in seconds since the epoch
+fn unix_now() -> u64 {
+    return SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+}
+
+/// A 64 bit FNV-1a hash, which (unlike the std hashers) is the same across
+/// builds, so the files can be found again by later runs
+fn fnv1a(data: &str) -> u64 {
+    let mut ret: u64 = 0xcbf29ce484222325;
+    for b in data.bytes() {
+        ret ^= b as u64;
+        ret = ret.wrapping_mul(0x100000001b3);
+    }
+
+    return ret;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disk_cache() {
+        let dir = std::env::temp_dir().join(format!("rbgg-cache-{}", std::process::id()));
+        let cache = DiskCache::new(&dir)
+            .unwrap()
+            .with_ttl("hot", Duration::ZERO);
+        let thing = "https://bgg/xmlapi2/thing?id=1";
+        let hot = "https://bgg/xmlapi2/hot";
+
+        assert!(cache.get(thing).is_none());
+        cache.put(thing, "<items/>", Duration::from_secs(60));
+        cache.put(hot, "<items/>", Duration::from_secs(60));
+        assert_eq!(cache.get(thing).as_deref(), Some("<items/>"));
+
+        // A fresh cache on the same directory sees the same responses
+        let again = DiskCache::new(&dir).unwrap();
+        assert_eq!(again.get(thing).as_deref(), Some("<items/>"));
+
+        // The hot response expired straight away
+        assert_eq!(cache.purge_expired().unwrap(), 1);
+        assert!(cache.get(hot).is_none());
+
+        cache.clear().unwrap();
+        assert!(cache.get(thing).is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_disk_cache_concurrent_writes() {
+        let dir = std::env::temp_dir().join(format!("rbgg-cache-cw-{}", std::process::id()));
+        let cache = DiskCache::new(&dir).unwrap();
+        let key = "https://bgg/xmlapi2/thing?id=1";
+
+        // Every thread writes the same key at once, and each write must
+        // land whole
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let cache = &cache;
+                scope.spawn(move || {
+                    for _ in 0..20 {
+                        let body = format!("<items id=\"{}\"/>", i);
+                        cache.put(key, &body, Duration::from_secs(60));
+                    }
+                });
+            }
+        });
+
+        let body = cache.get(key).unwrap();
+        assert!(body.starts_with("<items id="));
+        // No temporary files are left behind
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_disk_cache_async() {
+        let dir = std::env::temp_dir().join(format!("rbgg-cache-async-{}", std::process::id()));
+        let cache = DiskCache::new(&dir).unwrap();
+        let thing = "https://bgg/xmlapi2/thing?id=1";
+
+        assert!(cache.get_async(thing).await.is_none());
+        cache
+            .put_async(thing, "<items/>", Duration::from_secs(60))
+            .await;
+        assert_eq!(cache.get_async(thing).await.as_deref(), Some("<items/>"));
+        assert_eq!(cache.get(thing).as_deref(), Some("<items/>"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "redis")]
+    #[test]
+    fn test_redis_cache() {
+        assert!(RedisCache::new("notredis://").is_err());
+
+        // Nothing is listening here, so this acts like an empty cache
+        let cache = RedisCache::new("redis://127.0.0.1:1/")
+            .unwrap()
+            .with_prefix("test:");
+        assert_eq!(cache.prefix, "test:");
+        cache.put(
+            "https://bgg/xmlapi2/hot",
+            "<items/>",
+            Duration::from_secs(60),
+        );
+        assert!(cache.get("https://bgg/xmlapi2/hot").is_none());
+    }
+
+    #[cfg(all(feature = "redis", feature = "async"))]
+    #[tokio::test]
+    async fn test_redis_cache_async() {
+        let cache = RedisCache::new("redis://127.0.0.1:1/").unwrap();
+        let hot = "https://bgg/xmlapi2/hot";
+
+        cache
+            .put_async(hot, "<items/>", Duration::from_secs(60))
+            .await;
+        assert!(cache.get_async(hot).await.is_none());
+        assert!(cache.lock().is_empty());
+    }
+
+    #[test]
+    fn test_endpoint_class() {
+        assert_eq!(
+            endpoint_class("https://boardgamegeek.com/xmlapi2/thing?id=1"),
+            "thing"
+        );
+        assert_eq!(
+            endpoint_class("https://boardgamegeek.com/xmlapi/boardgame/1,2"),
+            "boardgame"
+        );
+        assert_eq!(endpoint_class("https://boardgamegeek.com"), "");
+    }
+
+    #[test]
+    fn test_memory_cache() {
+        let cache = MemoryCache::new(2).with_ttl("hot", Duration::from_secs(1));
+        let now = Instant::now();
+        let ttl = Duration::from_secs(60);
+        let (a, b, c) = (
+            "https://bgg/xmlapi2/thing?id=1",
+            "https://bgg/xmlapi2/thing?id=2",
+            "https://bgg/xmlapi2/thing?id=3",
+        );
+
+        cache.put_at(a, "a", ttl, now);
+        cache.put_at(b, "b", ttl, now);
+        // Using a makes b the least recently used
+        assert_eq!(cache.get_at(a, now).as_deref(), Some("a"));
+        cache.put_at(c, "c", ttl, now);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get_at(b, now).is_none());
+        assert_eq!(cache.get_at(c, now).as_deref(), Some("c"));
+
+        // Entries expire after their TTL
+        let later = now + Duration::from_secs(61);
+        assert!(cache.get_at(a, later).is_none());
+        assert_eq!(cache.len(), 1);
+
+        // And the hot endpoint has its own
+        let hot = "https://bgg/xmlapi2/hot?type=boardgame";
+        cache.put_at(hot, "hot", ttl, now);
+        assert!(cache.get_at(hot, now).is_some());
+        assert!(cache.get_at(hot, now + Duration::from_secs(2)).is_none());
+
+        cache.put_at(a, "a2", ttl, now);
+        assert_eq!(cache.get_at(a, now).as_deref(), Some("a2"));
+
+        // A TTL too long to represent never expires
+        cache.put_at(b, "b2", Duration::MAX, now);
+        let much_later = now + Duration::from_secs(60 * 60 * 24 * 365);
+        assert_eq!(cache.get_at(b, much_later).as_deref(), Some("b2"));
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,148 @@
+/*!
+Cancelling requests that are in progress.  Get a handle to a client with a
+`CancellationToken` (via `with_cancel()`) or a deadline (via
+`with_deadline()`), and every call made through it, including any retries,
+202 waits and the pages of a paginated call, will stop with
+`RbggError::Cancelled` or `RbggError::DeadlineExceeded` as soon as the token
+is cancelled or the deadline passes.
+
+```ignore,rust
+use rbgg::{bgg2::Client2, cancel::CancellationToken, errors::RbggError};
+use std::time::{Duration, Instant};
+
+let cl = Client2::new_from_defaults();
+let token = CancellationToken::new();
+
+// Give up on a (potentially long) collection fetch after a minute, or when
+// the user hits cancel, whichever comes first
+let handle = cl
+    .with_cancel(&token)
+    .with_deadline(Instant::now() + Duration::from_secs(60));
+let res = handle.collection("someuser", None).await;
+
+// Elsewhere
+token.cancel();
+```
+
+Async calls are cancelled straight away.  Blocking calls notice between
+attempts and during any waits, and a deadline also limits how long each
+request may take.
+*/
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+#[cfg(feature = "async")]
+use tokio::sync::Notify;
+
+/// A token that can be used to cancel calls.  This is cheap to clone, and
+/// cancelling any clone cancels them all.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    cancelled: Mutex<bool>,
+    cond: Condvar,
+    #[cfg(feature = "async")]
+    notify: Notify,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    /// Cancel every call using this token.  Calls made with it from now on
+    /// will fail straight away.
+    pub fn cancel(&self) {
+        *self.lock() = true;
+        self.inner.cond.notify_all();
+        #[cfg(feature = "async")]
+        self.inner.notify.notify_waiters();
+    }
+
+    /// Whether the token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        return *self.lock();
+    }
+
+    /// Wait until the token is cancelled
+    #[cfg(feature = "async")]
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.inner.notify.notified();
+            tokio::pin!(notified);
+            // Make sure we don't miss a cancel between checking and waiting
+            notified.as_mut().enable();
+
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Wait (blocking) for up to `timeout` for the token to be cancelled,
+    /// returning whether it was
+    pub fn wait_timeout_b(&self, timeout: Duration) -> bool {
+        let guard = self.lock();
+        let (guard, _) = self
+            .inner
+            .cond
+            .wait_timeout_while(guard, timeout, |cancelled| !*cancelled)
+            .unwrap_or_else(|e| e.into_inner());
+
+        return *guard;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, bool> {
+        return self
+            .inner
+            .cancelled
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Instant;
+
+    #[test]
+    fn test_token() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!token.is_cancelled());
+        assert!(!token.wait_timeout_b(Duration::from_millis(1)));
+
+        let start = Instant::now();
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            clone.cancel();
+        });
+        assert!(token.wait_timeout_b(Duration::from_secs(10)));
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert!(token.is_cancelled());
+        handle.join().unwrap();
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_cancelled() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            clone.cancel();
+        });
+        token.cancelled().await;
+        assert!(token.is_cancelled());
+
+        // Already cancelled returns straight away
+        token.cancelled().await;
+    }
+}
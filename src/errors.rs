@@ -0,0 +1,288 @@
+/*!
+The errors returned by this crate.  Everything returns an `RbggError` (via
+the `Result` alias here), so callers can match on the kind of failure
+rather than on the message:
+
+```ignore,rust
+use rbgg::{bgg2::{Client2, Hotness}, errors::RbggError};
+
+let cl = Client2::new_from_defaults();
+match cl.hot_b(Hotness::BoardGame, None) {
+    Ok(data) => println!("{}", data),
+    Err(RbggError::Maintenance { retry_after, .. }) => {
+        // Back off and try again later
+    }
+    Err(RbggError::RateLimited { retry_after }) => {
+        // Slow down
+    }
+    Err(e) => return Err(e),
+}
+```
+
+For retry loops and the like, `is_retryable()` says whether trying again
+later may work, and `status()` gives the HTTP status behind the error.
+
+Errors are cheap to clone, so they can be shared (e.g. between the callers
+waiting on a coalesced request).
+*/
+use crate::transport::TransportError;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+/// The result of everything in this crate
+pub type Result<T, E = RbggError> = std::result::Result<T, E>;
+
+/// The old name for `RbggError`
+#[deprecated(note = "Renamed to RbggError")]
+pub type BggError = RbggError;
+
+/// Everything that can go wrong talking to BGG
+#[derive(Clone, Debug, Error)]
+pub enum RbggError {
+    /// The request couldn't be sent, or its response couldn't be read
+    #[error("The request failed: {0}")]
+    Http(#[from] TransportError),
+
+    /// BGG answered with an error status
+    #[error("Request failed with status {status}")]
+    Status { status: u16 },
+
+    /// BGG answered, but with an error message rather than a response
+    #[error("BGG returned an error: {message}")]
+    Api { message: String },
+
+    /// A lookup found nothing.  `kind` is what was looked up (e.g. "thing",
+    /// "family" or "user") and `id` is the ID(s), or name, asked for.
+    #[error("No {kind} found for {id}")]
+    NotFound { kind: String, id: String },
+
+    /// There's no BGG user with this name
+    #[error("No such user: {0}")]
+    UnknownUser(String),
+
+    /// BGG said we're making too many requests.  `retry_after` is set if
+    /// BGG said how long to wait.
+    #[error("Rate limited by BGG (retry after {retry_after:?})")]
+    RateLimited { retry_after: Option<Duration> },
+
+    /// BGG returned its "down for maintenance" page instead of a response.
+    /// `retry_after` is set if BGG said how long to wait.
+    #[error("BGG is down for maintenance (status {status})")]
+    Maintenance {
+        status: u16,
+        retry_after: Option<Duration>,
+    },
+
+    /// BGG returned some other HTML page (an error page, say) instead of a
+    /// response.  `snippet` is the start of the page.
+    #[error("BGG returned an HTML page instead of a response (status {status}): {snippet}")]
+    ServiceUnavailable { status: u16, snippet: String },
+
+    /// BGG kept answering with a 202 ("your request has been queued") and
+    /// we gave up waiting after `attempts` requests.  See
+    /// `retry::QueuePolicy`.
+    #[error("Gave up waiting on BGG's queue after {attempts} attempts ({waited:?})")]
+    QueueTimeout { attempts: u32, waited: Duration },
+
+    /// The call was cancelled with a `cancel::CancellationToken`
+    #[error("The call was cancelled")]
+    Cancelled,
+
+    /// The call was still going when its deadline passed
+    #[error("The call's deadline passed before it finished")]
+    DeadlineExceeded,
+
+    /// The circuit breaker is open after repeated failures, so the call
+    /// wasn't made.  `retry_in` is how long until it lets a call through.
+    #[error("The circuit breaker is open after repeated failures (retry in {retry_in:?})")]
+    CircuitOpen { retry_in: Duration },
+
+    /// The arguments to a call were invalid, so it wasn't made
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+
+    /// The (XML) response couldn't be converted to JSON.  `status` and
+    /// `url` are those of the response, when it came from BGG, and
+    /// `snippet` is the start of the body.
+    #[error(
+        "Failed to convert the response{} to JSON: {message} (body: {snippet})",
+        response_context(.status, .url)
+    )]
+    XmlParse {
+        message: String,
+        status: Option<u16>,
+        url: Option<String>,
+        snippet: String,
+    },
+
+    /// A JSON response, or a stored one, couldn't be parsed
+    #[error("Invalid JSON: {0}")]
+    Json(Arc<serde_json::Error>),
+
+    /// A response couldn't be parsed into a typed model.  `path` is where
+    /// in the response it failed (e.g. "item[3].@id") and `snippet` is the
+    /// (possibly truncated) JSON found there.
+    #[error("Failed to parse the response at {path}: {source} (found: {snippet})")]
+    Parse {
+        path: String,
+        snippet: String,
+        #[source]
+        source: Arc<serde_json::Error>,
+    },
+
+    /// Reading or writing a file (or other local I/O) failed
+    #[error("I/O error: {0}")]
+    Io(Arc<std::io::Error>),
+
+    /// The response cache failed
+    #[error("Cache error: {0}")]
+    Cache(String),
+}
+
+impl RbggError {
+    /// The HTTP status of the response behind this error, if there was one
+    pub fn status(&self) -> Option<u16> {
+        return match self {
+            Self::Status { status }
+            | Self::Maintenance { status, .. }
+            | Self::ServiceUnavailable { status, .. } => Some(*status),
+            Self::RateLimited { .. } => Some(429),
+            Self::XmlParse { status, .. } => *status,
+            _ => None,
+        };
+    }
+
+    /// Whether the call may work if it's made again later: a dropped
+    /// connection or timeout, a 5xx, or BGG being busy or down.  Errors in
+    /// what was asked for, or in the response, are never retryable.
+    pub fn is_retryable(&self) -> bool {
+        return match self {
+            Self::Http(err) => err.is_transient(),
+            Self::Status { status } => *status == 408 || *status >= 500,
+            Self::RateLimited { .. }
+            | Self::Maintenance { .. }
+            | Self::ServiceUnavailable { .. }
+            | Self::QueueTimeout { .. }
+            | Self::CircuitOpen { .. } => true,
+            _ => false,
+        };
+    }
+}
+
+/// Describe where a response came from, for an error message
+fn response_context(status: &Option<u16>, url: &Option<String>) -> String {
+    return match (status, url) {
+        (Some(status), Some(url)) => format!(" from {} (status {})", url, status),
+        (Some(status), None) => format!(" (status {})", status),
+        (None, Some(url)) => format!(" from {}", url),
+        (None, None) => String::new(),
+    };
+}
+
+#[cfg(feature = "async")]
+impl From<reqwest::Error> for RbggError {
+    fn from(err: reqwest::Error) -> Self {
+        return Self::Http(err.into());
+    }
+}
+
+impl From<serde_json::Error> for RbggError {
+    fn from(err: serde_json::Error) -> Self {
+        return Self::Json(Arc::new(err));
+    }
+}
+
+impl From<http::header::InvalidHeaderValue> for RbggError {
+    fn from(err: http::header::InvalidHeaderValue) -> Self {
+        return Self::InvalidInput(format!("Invalid header value: {}", err));
+    }
+}
+
+impl From<http::header::InvalidHeaderName> for RbggError {
+    fn from(err: http::header::InvalidHeaderName) -> Self {
+        return Self::InvalidInput(format!("Invalid header name: {}", err));
+    }
+}
+
+#[cfg(feature = "redis")]
+impl From<redis::RedisError> for RbggError {
+    fn from(err: redis::RedisError) -> Self {
+        return Self::Cache(err.to_string());
+    }
+}
+
+impl From<std::io::Error> for RbggError {
+    fn from(err: std::io::Error) -> Self {
+        return Self::Io(Arc::new(err));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        let err: RbggError = serde_json::from_str::<u32>("x").unwrap_err().into();
+        assert!(matches!(err, RbggError::Json(_)));
+        assert!(err.to_string().starts_with("Invalid JSON"));
+
+        let err: RbggError = std::io::Error::other("disk").into();
+        assert!(matches!(err.clone(), RbggError::Io(_)));
+        assert_eq!(err.to_string(), "I/O error: disk");
+
+        let err = RbggError::XmlParse {
+            message: "bad".into(),
+            status: Some(200),
+            url: Some("https://bgg/xmlapi2/hot".into()),
+            snippet: "<items".into(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Failed to convert the response from https://bgg/xmlapi2/hot (status 200) to JSON: \
+            bad (body: <items)"
+        );
+    }
+
+    #[test]
+    fn test_status_retryable() {
+        let err = RbggError::Status { status: 503 };
+        assert_eq!(err.status(), Some(503));
+        assert!(err.is_retryable());
+        let err = RbggError::Status { status: 404 };
+        assert_eq!(err.status(), Some(404));
+        assert!(!err.is_retryable());
+
+        let err = RbggError::RateLimited { retry_after: None };
+        assert_eq!(err.status(), Some(429));
+        assert!(err.is_retryable());
+
+        let err = RbggError::Http(TransportError::new(
+            crate::transport::TransportErrorKind::Timeout,
+            "slow",
+        ));
+        assert_eq!(err.status(), None);
+        assert!(err.is_retryable());
+        let err = RbggError::Http(TransportError::new(
+            crate::transport::TransportErrorKind::Other,
+            "bad",
+        ));
+        assert!(!err.is_retryable());
+
+        let err = RbggError::NotFound {
+            kind: "thing".into(),
+            id: "1".into(),
+        };
+        assert_eq!(err.status(), None);
+        assert!(!err.is_retryable());
+        assert!(!RbggError::Cancelled.is_retryable());
+    }
+
+    #[test]
+    fn test_deprecated_alias() {
+        #[allow(deprecated)]
+        let err: BggError = RbggError::Status { status: 404 };
+        assert_eq!(err.to_string(), "Request failed with status 404");
+    }
+}
@@ -0,0 +1,2485 @@
+/*!
+This is the HTTP layer shared by the API clients.  Each client owns an
+`HttpClient`, which holds the underlying connection pools along with the
+configuration (`HttpConfig`) that applies to every request it makes.
+
+Unless you need to tweak how requests are made, you shouldn't need to use
+anything in here directly as `Client1::new()` and `Client2::new()` will
+use the defaults.
+
+```ignore,rust
+use rbgg::{bgg2::Client2, http::HttpConfig};
+
+let config = HttpConfig {
+    compression: false,
+    ..Default::default()
+};
+let cl = Client2::new_with_config(None, None, config)?;
+```
+*/
+use crate::breaker::{BreakerConfig, BreakerState, CircuitBreaker, Probe};
+use crate::cache::{ResponseCache, DEFAULT_CACHE_TTL};
+use crate::cancel::CancellationToken;
+use crate::errors::{RbggError, Result};
+use crate::metrics::{endpoint_name, MetricsSink, RequestEvent};
+use crate::retry::{QueuePolicy, RetryPolicy};
+use crate::throttle::{Priority, Throttle, ThrottleConfig, ThrottleState};
+#[cfg(not(feature = "async"))]
+use crate::transport::UreqTransport;
+use crate::transport::{
+    to_text, BlockingBody, HttpTransport, TransportError, TransportRequest, TransportResponse,
+};
+#[cfg(feature = "async")]
+use crate::transport::{AsyncBody, ReqwestTransport};
+#[cfg(feature = "async")]
+use crate::utils::SingleFlight;
+use crate::utils::{api_error, normalize_url, parse_json, snippet, xml_to_json};
+use chrono::DateTime;
+#[cfg(feature = "async")]
+use futures_util::StreamExt;
+use http::header::{
+    HeaderMap, HeaderName, HeaderValue, ACCEPT_LANGUAGE, CACHE_CONTROL, CONTENT_LENGTH,
+    CONTENT_TYPE, COOKIE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RETRY_AFTER,
+    USER_AGENT,
+};
+use http::{Method, StatusCode};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+#[cfg(feature = "async")]
+use std::future::{self, Future};
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+#[cfg(feature = "async")]
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+#[cfg(feature = "async")]
+use tokio::time;
+
+/// The default time allowed for connecting to BGG
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// The default time allowed for a whole request
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The User-Agent sent when one isn't configured, so that BGG can tell
+/// where the requests are coming from
+pub const DEFAULT_USER_AGENT: &str = concat!("rbgg/", env!("CARGO_PKG_VERSION"));
+
+/// The most urls whose responses are remembered for conditional requests.
+/// Past this, the oldest are forgotten.
+pub const MAX_CONDITIONAL_URLS: usize = 512;
+
+/// The configuration applied to every request made by an `HttpClient`
+#[derive(Clone, Debug)]
+pub struct HttpConfig {
+    /// Ask for gzip/deflate compressed responses (via Accept-Encoding) and
+    /// transparently decompress them.  BGG's XML compresses extremely well,
+    /// so this is on by default.  Like the timeouts, this only applies to
+    /// the default transport.
+    pub compression: bool,
+    /// Limit the rate at which requests are sent.  While requests are
+    /// waiting on the throttle, they are serviced in priority order (see
+    /// `HttpClient::with_priority()`).  This is on by default so that you
+    /// don't get your IP throttled by BGG; set it to `None` to disable it.
+    pub throttle: Option<ThrottleConfig>,
+    /// The Accept-Language header to send with every request
+    /// (e.g. "de-DE,de;q=0.9"), for the parts of BGG that are localized
+    pub accept_language: Option<String>,
+    /// The longest a request may take to connect to BGG.  Defaults to
+    /// `DEFAULT_CONNECT_TIMEOUT`; `None` waits as long as the OS allows.
+    pub connect_timeout: Option<Duration>,
+    /// The longest a request may take, from connecting until the response
+    /// has been read, so that a stalled response can't hang the caller.
+    /// Defaults to `DEFAULT_TIMEOUT`; `None` removes the limit.  This
+    /// applies to each attempt, not to the wait while BGG queues a request.
+    pub timeout: Option<Duration>,
+    /// The User-Agent header to send with every request.  BGG asks API
+    /// consumers to identify themselves, so set this to something that
+    /// names your application (and ideally how to contact you).  Defaults
+    /// to `DEFAULT_USER_AGENT`; `None` sends no User-Agent at all.
+    pub user_agent: Option<String>,
+    /// Any extra headers to send with every request, as (name, value)
+    /// pairs.  These are applied last, so they replace any header set by
+    /// the other options.  Note that setting Accept-Encoding yourself turns
+    /// off the transparent decompression from `compression`.
+    pub headers: Vec<(String, String)>,
+    /// How to retry requests that fail for a transient reason, like a 429,
+    /// a 5xx or a dropped connection
+    pub retry: RetryPolicy,
+    /// How long to wait on BGG when it queues a request (with a 202)
+    pub queue: QueuePolicy,
+    /// Where to record metrics about each request, if anywhere
+    pub metrics: Option<Arc<dyn MetricsSink>>,
+    /// Fail fast while BGG is down, or `None` (the default) to always make
+    /// the request
+    pub breaker: Option<BreakerConfig>,
+    /// Share a single request between concurrent (async) calls for the
+    /// same url, rather than sending it once for each of them.  This is off
+    /// by default.
+    pub coalesce: bool,
+    /// Remember the ETag and Last-Modified of each response (along with its
+    /// body), and send them back as If-None-Match and If-Modified-Since when
+    /// the same url is fetched again.  If BGG answers with a 304, the
+    /// remembered body is used, which saves a lot of bandwidth when polling
+    /// things like the hotness or a collection on a schedule.  This is off
+    /// by default; up to `MAX_CONDITIONAL_URLS` responses are kept.
+    pub conditional: bool,
+    /// Where to cache responses, if anywhere.  This is checked before
+    /// making a request, and successful responses are stored in it.
+    pub cache: Option<Arc<dyn ResponseCache>>,
+    /// How long responses are kept in the cache
+    pub cache_ttl: Duration,
+    /// What sends the requests, or `None` (the default) to use a
+    /// `ReqwestTransport` built from this configuration.  In a build without
+    /// the `async` feature, there's no reqwest, so the default is a
+    /// `UreqTransport` instead.  Otherwise, ureq is only used if it's set
+    /// here, so another crate turning on the `ureq` feature doesn't change
+    /// what your requests go through.
+    pub transport: Option<Arc<dyn HttpTransport>>,
+    /// Return BGG's error responses (e.g. `<error><message>...`) as
+    /// `RbggError::Api`, rather than as JSON like any other response.  This
+    /// is on by default; turn it off to handle them yourself.
+    pub api_errors: bool,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        return Self {
+            compression: true,
+            throttle: Some(ThrottleConfig::default()),
+            accept_language: None,
+            connect_timeout: Some(DEFAULT_CONNECT_TIMEOUT),
+            timeout: Some(DEFAULT_TIMEOUT),
+            user_agent: Some(DEFAULT_USER_AGENT.to_string()),
+            headers: vec![],
+            retry: RetryPolicy::default(),
+            queue: QueuePolicy::default(),
+            metrics: None,
+            breaker: None,
+            coalesce: false,
+            conditional: false,
+            cache: None,
+            cache_ttl: DEFAULT_CACHE_TTL,
+            transport: None,
+            api_errors: true,
+        };
+    }
+}
+
+/// Settings that apply to individual calls, rather than to the whole
+/// client.  Apply them with `with_call_config()` on a client, which returns
+/// a handle that uses them for every call made through it.
+///
+/// ```ignore,rust
+/// use rbgg::{bgg2::{Client2, Hotness}, http::CallConfig};
+///
+/// let cl = Client2::new_from_defaults();
+/// // "Pull to refresh": skip any cached copy, but still cache the result
+/// let data = cl.with_call_config(&CallConfig::no_cache()).hot_b(Hotness::BoardGame, None)?;
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CallConfig {
+    /// The priority to queue the calls at, if not the handle's current one
+    pub priority: Option<Priority>,
+    /// Fetch a fresh copy rather than using a cached one.  The fresh
+    /// response is still stored in the cache for later calls.
+    pub no_cache: bool,
+}
+
+impl CallConfig {
+    /// A config that bypasses the cache
+    pub fn no_cache() -> Self {
+        return Self {
+            no_cache: true,
+            ..Default::default()
+        };
+    }
+
+    /// Set the priority the calls are queued at
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = Some(priority);
+
+        return self;
+    }
+}
+
+/// A converted response along with the details of the HTTP response it came
+/// from, for when the JSON alone isn't enough
+#[derive(Clone, Debug)]
+pub struct ApiResponse {
+    /// The response converted to JSON
+    pub value: Value,
+    /// The status of the final response (after any 202 waits and retries).
+    /// This is a 304 when a conditional request reused an earlier body.
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    /// The total time taken, including any 202 waits and retries
+    pub elapsed: Duration,
+    /// The url of the final response, after any redirects
+    pub final_url: String,
+    /// Whether this came from the `ResponseCache` rather than from BGG.  A
+    /// cached response has a 200 status and no headers.
+    pub cached: bool,
+}
+
+impl ApiResponse {
+    /// The Retry-After header, if BGG set one (in seconds or as a date)
+    pub fn retry_after(&self) -> Option<Duration> {
+        return retry_after(&self.headers);
+    }
+
+    /// The Content-Length header, if set.  This is the size on the wire,
+    /// so it will be the compressed size for a compressed response.
+    pub fn content_length(&self) -> Option<u64> {
+        return self
+            .headers
+            .get(CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok());
+    }
+}
+
+/// A point-in-time snapshot of the counters kept by an `HttpClient`.  These
+/// are cumulative over the life of the client, so operators can diff
+/// successive snapshots to alert on degradation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Total HTTP requests sent, including any repeated requests
+    pub requests: u64,
+    /// Requests repeated after a transient failure
+    pub retries: u64,
+    /// Times BGG responded with a 202 and we had to wait for it to queue up
+    /// the response
+    pub queue_waits: u64,
+    /// Times we were stalled by BGG's rate limiting (HTTP 429)
+    pub rate_limit_stalls: u64,
+    /// Times the circuit breaker has tripped open
+    pub breaker_trips: u64,
+    /// Times a conditional request got a 304, so an earlier body was reused
+    pub not_modified: u64,
+    /// Calls answered from the `ResponseCache`, without a request
+    pub cache_hits: u64,
+}
+
+/// The live counters backing `Stats`
+#[derive(Default)]
+struct Counters {
+    requests: AtomicU64,
+    retries: AtomicU64,
+    queue_waits: AtomicU64,
+    rate_limit_stalls: AtomicU64,
+    breaker_trips: AtomicU64,
+    not_modified: AtomicU64,
+    cache_hits: AtomicU64,
+}
+
+impl Counters {
+    fn snapshot(&self) -> Stats {
+        return Stats {
+            requests: self.requests.load(Ordering::Relaxed),
+            retries: self.retries.load(Ordering::Relaxed),
+            queue_waits: self.queue_waits.load(Ordering::Relaxed),
+            rate_limit_stalls: self.rate_limit_stalls.load(Ordering::Relaxed),
+            breaker_trips: self.breaker_trips.load(Ordering::Relaxed),
+            not_modified: self.not_modified.load(Ordering::Relaxed),
+            cache_hits: self.cache_hits.load(Ordering::Relaxed),
+        };
+    }
+
+    /// Update the counters given the status of a response
+    fn record(&self, status: StatusCode) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+
+        if status == StatusCode::ACCEPTED {
+            self.queue_waits.fetch_add(1, Ordering::Relaxed);
+        } else if status == StatusCode::TOO_MANY_REQUESTS {
+            self.rate_limit_stalls.fetch_add(1, Ordering::Relaxed);
+        } else if status == StatusCode::NOT_MODIFIED {
+            self.not_modified.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// The validators of an earlier response, along with its body, for making
+/// a conditional request
+#[derive(Clone, Debug, PartialEq)]
+struct Validated {
+    etag: Option<HeaderValue>,
+    last_modified: Option<HeaderValue>,
+    body: String,
+}
+
+impl Validated {
+    /// Pull the validators out of a response, if it has any
+    fn from_response(headers: &HeaderMap, body: &str) -> Option<Self> {
+        let etag = headers.get(ETAG).cloned();
+        let last_modified = headers.get(LAST_MODIFIED).cloned();
+        if etag.is_none() && last_modified.is_none() {
+            return None;
+        }
+
+        return Some(Self {
+            etag,
+            last_modified,
+            body: body.to_string(),
+        });
+    }
+
+    /// The headers that make a request conditional on this response
+    fn headers(&self) -> HeaderMap {
+        let mut ret = HeaderMap::new();
+        if let Some(etag) = &self.etag {
+            ret.insert(IF_NONE_MATCH, etag.clone());
+        }
+        if let Some(modified) = &self.last_modified {
+            ret.insert(IF_MODIFIED_SINCE, modified.clone());
+        }
+
+        return ret;
+    }
+}
+
+/// The validated responses by url, forgetting the oldest once there are
+/// more than `capacity`
+#[derive(Debug, Default)]
+struct Validators {
+    capacity: usize,
+    entries: HashMap<String, Validated>,
+    order: VecDeque<String>,
+}
+
+impl Validators {
+    fn new(capacity: usize) -> Self {
+        return Self {
+            capacity,
+            ..Default::default()
+        };
+    }
+
+    fn get(&self, url: &str) -> Option<Validated> {
+        return self.entries.get(url).cloned();
+    }
+
+    fn insert(&mut self, url: &str, validated: Validated) {
+        if self.entries.insert(url.to_string(), validated).is_some() {
+            return;
+        }
+        self.order.push_back(url.to_string());
+
+        while self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// The HTTP client used by the API clients.  This is cheap to clone and all
+/// clones share the same connection pools and throttle.
+#[derive(Clone)]
+pub struct HttpClient {
+    inner: Arc<Inner>,
+    priority: Priority,
+    no_cache: bool,
+    cancel: Option<CancellationToken>,
+    deadline: Option<Instant>,
+}
+
+struct Inner {
+    config: HttpConfig,
+    // What the async calls are sent with
+    #[cfg(feature = "async")]
+    transport: Arc<dyn HttpTransport>,
+    // What the blocking calls are sent with, which is only different from
+    // `transport` if the `ureq` feature picked it
+    transport_b: Arc<dyn HttpTransport>,
+    // The headers sent with every request
+    headers: HeaderMap,
+    counters: Counters,
+    throttle: Option<Throttle>,
+    breaker: Option<CircuitBreaker>,
+    #[cfg(feature = "async")]
+    inflight: SingleFlight<Value>,
+    validators: Mutex<Validators>,
+}
+
+impl HttpClient {
+    /// Create a new client with the given configuration.  An unusable
+    /// throttle or queue configuration gives `RbggError::InvalidInput`.
+    pub fn new(config: HttpConfig) -> Result<Self> {
+        if let Some(throttle) = &config.throttle {
+            throttle.validate()?;
+        }
+        config.queue.validate()?;
+        let headers = default_headers(&config)?;
+        #[cfg(feature = "async")]
+        let transport: Arc<dyn HttpTransport> = match &config.transport {
+            Some(transport) => transport.clone(),
+            None => Arc::new(ReqwestTransport::new(&config)?),
+        };
+        let transport_b: Arc<dyn HttpTransport> = match &config.transport {
+            Some(transport) => transport.clone(),
+            #[cfg(feature = "async")]
+            None => transport.clone(),
+            #[cfg(not(feature = "async"))]
+            None => Arc::new(UreqTransport::new(&config)),
+        };
+
+        return Ok(Self {
+            inner: Arc::new(Inner {
+                throttle: config.throttle.as_ref().map(Throttle::new),
+                breaker: config.breaker.as_ref().map(CircuitBreaker::new),
+                #[cfg(feature = "async")]
+                inflight: SingleFlight::new(),
+                validators: Mutex::new(Validators::new(MAX_CONDITIONAL_URLS)),
+                config,
+                #[cfg(feature = "async")]
+                transport,
+                transport_b,
+                headers,
+                counters: Counters::default(),
+            }),
+            priority: Priority::default(),
+            no_cache: false,
+            cancel: None,
+            deadline: None,
+        });
+    }
+
+    /// Return the configuration for this client
+    pub fn config(&self) -> &HttpConfig {
+        return &self.inner.config;
+    }
+
+    /// Return a clone of this client whose requests are queued at the given
+    /// priority when waiting on the throttle.  The clone shares everything,
+    /// including the throttle, with the original.
+    pub fn with_priority(&self, priority: Priority) -> Self {
+        let mut ret = self.clone();
+        ret.priority = priority;
+
+        return ret;
+    }
+
+    /// Return a clone of this client that applies the given per-call
+    /// settings to its requests.  The clone shares everything else with the
+    /// original.
+    pub fn with_call_config(&self, config: &CallConfig) -> Self {
+        let mut ret = self.clone();
+        if let Some(priority) = config.priority {
+            ret.priority = priority;
+        }
+        ret.no_cache = config.no_cache;
+
+        return ret;
+    }
+
+    /// Return a snapshot of the circuit breaker, or `None` if the client
+    /// doesn't have one
+    pub fn breaker_state(&self) -> Option<BreakerState> {
+        return self.inner.breaker.as_ref().map(|b| b.state(Instant::now()));
+    }
+
+    /// Return a clone of this client whose calls are stopped, with
+    /// `RbggError::Cancelled`, when the token is cancelled
+    pub fn with_cancel(&self, token: &CancellationToken) -> Self {
+        let mut ret = self.clone();
+        ret.cancel = Some(token.clone());
+
+        return ret;
+    }
+
+    /// Return a clone of this client whose calls are stopped, with
+    /// `RbggError::DeadlineExceeded`, if they're still going at the deadline
+    pub fn with_deadline(&self, deadline: Instant) -> Self {
+        let mut ret = self.clone();
+        ret.deadline = Some(deadline);
+
+        return ret;
+    }
+
+    /// Return the priority requests from this client are queued at
+    pub fn priority(&self) -> Priority {
+        return self.priority;
+    }
+
+    /// Return a snapshot of the request counters for this client
+    pub fn stats(&self) -> Stats {
+        return self.inner.counters.snapshot();
+    }
+
+    /// Return a snapshot of the throttle's state, or `None` if the client
+    /// isn't throttled
+    pub fn throttle_state(&self) -> Option<ThrottleState> {
+        return self.inner.throttle.as_ref().map(|t| t.state());
+    }
+
+    /// Fetch the url and convert the XML response to JSON
+    #[cfg(feature = "async")]
+    pub async fn get_json(&self, url: &str) -> Result<Value> {
+        if !self.inner.config.coalesce {
+            return Ok(self.get_json_with_meta(url).await?.value);
+        }
+
+        // The shared request mustn't be stopped by any one caller's token
+        // or deadline, so each caller only stops waiting on it
+        let mut http = self.clone();
+        http.cancel = None;
+        http.deadline = None;
+        let owned = url.to_string();
+        let key = self.flight_key(url);
+        let shared = self.inner.inflight.run(&key, move || async move {
+            return Ok(http.fetch_json(&owned).await?.value);
+        });
+
+        return self.guarded(shared).await;
+    }
+
+    /// The key that calls for the url are coalesced on.  Calls that skip
+    /// the cache are only ever coalesced with each other, so they never get
+    /// a cached response.
+    #[cfg(feature = "async")]
+    fn flight_key(&self, url: &str) -> String {
+        let mode = if self.no_cache { "no-cache" } else { "cache" };
+
+        return format!("{} {}", mode, normalize_url(url));
+    }
+
+    /// Fetch (blocking) the url and convert the XML response to JSON
+    pub fn get_json_b(&self, url: &str) -> Result<Value> {
+        return Ok(self.get_json_with_meta_b(url)?.value);
+    }
+
+    /// Fetch the url and convert the XML response to JSON, along with the
+    /// status, headers, etc. of the response.  These calls are never
+    /// coalesced.
+    #[cfg(feature = "async")]
+    pub async fn get_json_with_meta(&self, url: &str) -> Result<ApiResponse> {
+        return self.guarded(self.fetch_json(url)).await;
+    }
+
+    /// Fetch (blocking) the url and convert the XML response to JSON, along
+    /// with the status, headers, etc. of the response
+    pub fn get_json_with_meta_b(&self, url: &str) -> Result<ApiResponse> {
+        return Ok(self.fetch_b(url)?.1);
+    }
+
+    /// Fetch the url, returning both the raw XML response and its JSON
+    /// conversion, so the XML can be kept for reprocessing later.  These
+    /// calls are never coalesced.
+    #[cfg(feature = "async")]
+    pub async fn get_xml_and_json(&self, url: &str) -> Result<(String, Value)> {
+        let (xml, resp) = self.guarded(self.fetch(url)).await?;
+
+        return Ok((xml, resp.value));
+    }
+
+    /// Fetch (blocking) the url, returning both the raw XML response and
+    /// its JSON conversion
+    pub fn get_xml_and_json_b(&self, url: &str) -> Result<(String, Value)> {
+        let (xml, resp) = self.fetch_b(url)?;
+
+        return Ok((xml, resp.value));
+    }
+
+    /// Fetch the url, whose response is already JSON (rather than XML)
+    #[cfg(feature = "async")]
+    pub async fn get_plain_json(&self, url: &str) -> Result<Value> {
+        return self
+            .guarded(async {
+                let resp = self.get_resp(url).await?;
+                let (status, headers) = (resp.status, resp.headers.clone());
+                let data = resp.text().await?;
+                check_html(status, &headers, &data)?;
+                check_status(status, &headers)?;
+
+                return parse_json(&data);
+            })
+            .await;
+    }
+
+    /// Fetch (blocking) the url, whose response is already JSON (rather than
+    /// XML)
+    pub fn get_plain_json_b(&self, url: &str) -> Result<Value> {
+        let resp = self.get_resp_b(url)?;
+        let (status, headers) = (resp.status, resp.headers.clone());
+        let data = resp.text()?;
+        check_html(status, &headers, &data)?;
+        check_status(status, &headers)?;
+
+        return parse_json(&data);
+    }
+
+    /// Fetch the given url and write the raw (XML) response body to `writer`
+    /// as it arrives, without ever holding the full body in memory.  Returns
+    /// the number of bytes written.
+    #[cfg(feature = "async")]
+    pub async fn get_to_writer<W>(&self, url: &str, writer: &mut W) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        return self
+            .guarded(async {
+                let mut resp = self.get_resp(url).await?;
+                let mut head = vec![];
+                while head.len() < PEEK_LEN {
+                    match resp.body.next().await {
+                        Some(chunk) => head.extend(chunk?),
+                        None => break,
+                    }
+                }
+                if !streamable(resp.status, &resp.headers, &head) {
+                    let (status, headers) = (resp.status, resp.headers.clone());
+                    let final_url = resp.url.clone();
+                    head.extend(resp.bytes().await?);
+                    self.check_body(&final_url, status, &headers, &to_text(head.clone()))?;
+                    writer.write_all(&head).await?;
+                    writer.flush().await?;
+
+                    return Ok(head.len() as u64);
+                }
+
+                writer.write_all(&head).await?;
+                let mut written = head.len() as u64;
+                while let Some(chunk) = resp.body.next().await {
+                    let chunk = chunk?;
+                    writer.write_all(&chunk).await?;
+                    written += chunk.len() as u64;
+                }
+                writer.flush().await?;
+
+                return Ok(written);
+            })
+            .await;
+    }
+
+    /// Fetch (blocking) the given url and write the raw (XML) response body
+    /// to `writer` as it arrives, without ever holding the full body in
+    /// memory.  Returns the number of bytes written.
+    pub fn get_to_writer_b<W>(&self, url: &str, writer: &mut W) -> Result<u64>
+    where
+        W: Write,
+    {
+        let mut resp = self.get_resp_b(url)?;
+        let mut head = vec![];
+        (&mut resp.body)
+            .take(PEEK_LEN as u64)
+            .read_to_end(&mut head)?;
+        if !streamable(resp.status, &resp.headers, &head) {
+            let (status, headers) = (resp.status, resp.headers.clone());
+            let final_url = resp.url.clone();
+            head.extend(resp.bytes()?);
+            self.check_body(&final_url, status, &headers, &to_text(head.clone()))?;
+            writer.write_all(&head)?;
+            writer.flush()?;
+
+            return Ok(head.len() as u64);
+        }
+
+        writer.write_all(&head)?;
+        let written = head.len() as u64 + std::io::copy(&mut resp.body, writer)?;
+        writer.flush()?;
+
+        return Ok(written);
+    }
+
+    /// Run the checks the buffered calls make on a response, for one that's
+    /// going to be written out as is: an HTML page, an error response or an
+    /// error status are all errors
+    fn check_body(
+        &self,
+        url: &str,
+        status: StatusCode,
+        headers: &HeaderMap,
+        data: &str,
+    ) -> Result<()> {
+        check_html(status, headers, data)?;
+        self.convert(url, status, headers, data)?;
+        check_status(status, headers)?;
+
+        return Ok(());
+    }
+
+    /// POST `body` as JSON to the url, passing along the given cookies, and
+    /// return the response headers and body.  This is only used for the
+    /// site (rather than API) endpoints that need a logged in session.
+    #[cfg(feature = "async")]
+    pub(crate) async fn post_json(
+        &self,
+        url: &str,
+        body: &Value,
+        cookies: Option<&str>,
+    ) -> Result<(HeaderMap, String)> {
+        return self
+            .guarded(async {
+                if let Some(throttle) = &self.inner.throttle {
+                    throttle.acquire(self.priority).await;
+                }
+                let req = self.post_request(url, body, cookies)?;
+                let trace = self.trace("POST", url);
+                let resp = match self.inner.transport.send(req).await {
+                    Ok(resp) => resp,
+                    Err(e) => {
+                        trace.failed(1, 0, &e);
+                        return Err(e.into());
+                    }
+                };
+                self.inner.counters.record(resp.status);
+                trace.finish(resp.status, 1, 0);
+                check_status(resp.status, &resp.headers)?;
+                let headers = resp.headers.clone();
+
+                return Ok((headers, resp.text().await?));
+            })
+            .await;
+    }
+
+    /// POST (blocking) `body` as JSON to the url, passing along the given
+    /// cookies, and return the response headers and body
+    pub(crate) fn post_json_b(
+        &self,
+        url: &str,
+        body: &Value,
+        cookies: Option<&str>,
+    ) -> Result<(HeaderMap, String)> {
+        if let Some(throttle) = &self.inner.throttle {
+            throttle.acquire_b(self.priority);
+        }
+        let req = self.post_request(url, body, cookies)?;
+        let trace = self.trace("POST", url);
+        let resp = match self.inner.transport_b.send_b(req) {
+            Ok(resp) => resp,
+            Err(e) => {
+                trace.failed(1, 0, &e);
+                return Err(e.into());
+            }
+        };
+        self.inner.counters.record(resp.status);
+        trace.finish(resp.status, 1, 0);
+        check_status(resp.status, &resp.headers)?;
+        let headers = resp.headers.clone();
+
+        return Ok((headers, resp.text()?));
+    }
+
+    /* Begin private functions */
+
+    /// Build a GET request for the url, with the default headers and the
+    /// given extra ones
+    fn get_request(&self, url: &str, extra: &HeaderMap) -> TransportRequest {
+        let mut req = TransportRequest::get(url);
+        req.headers = self.inner.headers.clone();
+        req.headers.extend(extra.clone());
+        // Ask any caches along the way for a fresh response, if the handle
+        // has the cache turned off
+        if self.no_cache {
+            req.headers
+                .insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        }
+
+        return req;
+    }
+
+    /// Build a request POSTing `body` as JSON to the url
+    fn post_request(
+        &self,
+        url: &str,
+        body: &Value,
+        cookies: Option<&str>,
+    ) -> Result<TransportRequest> {
+        let mut req = TransportRequest::get(url);
+        req.method = Method::POST;
+        req.headers = self.inner.headers.clone();
+        req.headers
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        if let Some(cookies) = cookies {
+            req.headers.insert(COOKIE, HeaderValue::from_str(cookies)?);
+        }
+        req.body = Some(body.to_string());
+
+        return Ok(req);
+    }
+
+    /// Perform the actual request, returning the response once it is ready
+    #[cfg(feature = "async")]
+    async fn get_resp(&self, url: &str) -> Result<TransportResponse<AsyncBody>> {
+        return self.get_resp_with(url, HeaderMap::new()).await;
+    }
+
+    /// Perform the actual request, with the given extra headers, returning
+    /// the response once it is ready
+    #[cfg(feature = "async")]
+    async fn get_resp_with(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+    ) -> Result<TransportResponse<AsyncBody>> {
+        let mut resp;
+        let mut attempt = 1;
+        let mut queued = 0;
+        let mut waited = Duration::ZERO;
+        let trace = self.trace("GET", url);
+
+        // Sometimes, when a large request, often for a user's collection,
+        // is made, we'll get a 202 response and we have to request this again
+        // after the server has cached it on their side
+        loop {
+            // Held until the response has been recorded with the breaker
+            let _probe = match self.check_breaker() {
+                Ok(probe) => probe,
+                Err(e) => {
+                    trace.failed(attempt, queued, &e);
+                    return Err(e);
+                }
+            };
+            if let Some(throttle) = &self.inner.throttle {
+                throttle.acquire(self.priority).await;
+            }
+            let res = self
+                .inner
+                .transport
+                .send(self.get_request(url, &headers))
+                .await;
+            resp = match res {
+                Ok(resp) => resp,
+                Err(e) => match self.failed_send_delay(&e, attempt) {
+                    Some(delay) => {
+                        trace.retry(attempt, delay, &e);
+                        time::sleep(delay).await;
+                        attempt += 1;
+                        continue;
+                    }
+                    None => {
+                        trace.failed(attempt, queued, &e);
+                        return Err(e.into());
+                    }
+                },
+            };
+            self.inner.counters.record(resp.status);
+            self.adapt_throttle(resp.status, queued);
+            self.record_breaker(resp.status.is_server_error());
+            if resp.status == 202 {
+                // We're going to sleep here and try again
+                queued += 1;
+                let delay = match self.queue_delay(queued, &mut waited) {
+                    Ok(delay) => delay,
+                    Err(e) => {
+                        trace.failed(attempt, queued, &e);
+                        return Err(e);
+                    }
+                };
+                trace.queued(queued, delay);
+                time::sleep(delay).await;
+            } else if let Some(delay) = self.status_retry_delay(resp.status, &resp.headers, attempt)
+            {
+                trace.retry(attempt, delay, &resp.status);
+                time::sleep(delay).await;
+                attempt += 1;
+            } else {
+                // We should be good to process the response now
+                break;
+            }
+        }
+        trace.finish(resp.status, attempt, queued);
+
+        return Ok(resp);
+    }
+
+    /// Perform the actual (blocking) request, returning the response once it
+    /// is ready
+    fn get_resp_b(&self, url: &str) -> Result<TransportResponse<BlockingBody>> {
+        return self.get_resp_with_b(url, HeaderMap::new());
+    }
+
+    /// Perform the actual (blocking) request, with the given extra headers,
+    /// returning the response once it is ready
+    fn get_resp_with_b(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+    ) -> Result<TransportResponse<BlockingBody>> {
+        let mut resp;
+        let mut attempt = 1;
+        let mut queued = 0;
+        let mut waited = Duration::ZERO;
+        let trace = self.trace("GET", url);
+
+        // Sometimes, when a large request, often for a user's collection,
+        // is made, we'll get a 202 response and we have to request this again
+        // after the server has cached it on their side
+        loop {
+            self.check_cancel()?;
+            // Held until the response has been recorded with the breaker
+            let _probe = match self.check_breaker() {
+                Ok(probe) => probe,
+                Err(e) => {
+                    trace.failed(attempt, queued, &e);
+                    return Err(e);
+                }
+            };
+            if let Some(throttle) = &self.inner.throttle {
+                throttle.acquire_b(self.priority);
+            }
+            let mut req = self.get_request(url, &headers);
+            req.timeout = self.remaining()?;
+            resp = match self.inner.transport_b.send_b(req) {
+                Ok(resp) => resp,
+                Err(e) => match self.failed_send_delay(&e, attempt) {
+                    Some(delay) => {
+                        trace.retry(attempt, delay, &e);
+                        self.sleep_b(delay)?;
+                        attempt += 1;
+                        continue;
+                    }
+                    None => {
+                        trace.failed(attempt, queued, &e);
+                        // A timeout because of the deadline is reported as such
+                        self.check_cancel()?;
+                        return Err(e.into());
+                    }
+                },
+            };
+            self.inner.counters.record(resp.status);
+            self.adapt_throttle(resp.status, queued);
+            self.record_breaker(resp.status.is_server_error());
+            if resp.status == 202 {
+                // We're going to sleep here and try again
+                queued += 1;
+                let delay = match self.queue_delay(queued, &mut waited) {
+                    Ok(delay) => delay,
+                    Err(e) => {
+                        trace.failed(attempt, queued, &e);
+                        return Err(e);
+                    }
+                };
+                trace.queued(queued, delay);
+                self.sleep_b(delay)?;
+            } else if let Some(delay) = self.status_retry_delay(resp.status, &resp.headers, attempt)
+            {
+                trace.retry(attempt, delay, &resp.status);
+                self.sleep_b(delay)?;
+                attempt += 1;
+            } else {
+                // We should be good to process the response now
+                break;
+            }
+        }
+        trace.finish(resp.status, attempt, queued);
+
+        return Ok(resp);
+    }
+
+    /// Start tracing a request
+    fn trace(&self, method: &'static str, url: &str) -> RequestTrace {
+        return RequestTrace::new(method, url, self.inner.config.metrics.clone());
+    }
+
+    /// Fetch the url and convert the XML response to JSON
+    #[cfg(feature = "async")]
+    async fn fetch_json(&self, url: &str) -> Result<ApiResponse> {
+        return Ok(self.fetch(url).await?.1);
+    }
+
+    /// Fetch the url, returning the raw XML along with the converted
+    /// response
+    #[cfg(feature = "async")]
+    async fn fetch(&self, url: &str) -> Result<(String, ApiResponse)> {
+        let start = Instant::now();
+        if let Some(hit) = self.cache_hit_async(url, start).await {
+            return Ok(hit);
+        }
+        let validated = self.validated(url);
+        let extra = validated
+            .as_ref()
+            .map(Validated::headers)
+            .unwrap_or_default();
+        let resp = self.get_resp_with(url, extra).await?;
+        let (status, headers) = (resp.status, resp.headers.clone());
+        let final_url = resp.url.clone();
+        let data = match validated {
+            Some(v) if status == StatusCode::NOT_MODIFIED => v.body,
+            _ => resp.text().await?,
+        };
+        check_html(status, &headers, &data)?;
+        let value = self.convert(&final_url, status, &headers, &data)?;
+        self.store_validated(url, status, &headers, &data);
+
+        let ret = ApiResponse {
+            value,
+            status,
+            headers,
+            elapsed: start.elapsed(),
+            final_url,
+            cached: false,
+        };
+        self.store_cache_async(url, status, &data).await;
+
+        return Ok((data, ret));
+    }
+
+    /// Fetch (blocking) the url, returning the raw XML along with the
+    /// converted response
+    fn fetch_b(&self, url: &str) -> Result<(String, ApiResponse)> {
+        let start = Instant::now();
+        if let Some(hit) = self.cache_hit(url, start) {
+            return Ok(hit);
+        }
+        let validated = self.validated(url);
+        let extra = validated
+            .as_ref()
+            .map(Validated::headers)
+            .unwrap_or_default();
+        let resp = self.get_resp_with_b(url, extra)?;
+        let (status, headers) = (resp.status, resp.headers.clone());
+        let final_url = resp.url.clone();
+        let data = match validated {
+            Some(v) if status == StatusCode::NOT_MODIFIED => v.body,
+            _ => resp.text()?,
+        };
+        check_html(status, &headers, &data)?;
+        let value = self.convert(&final_url, status, &headers, &data)?;
+        self.store_validated(url, status, &headers, &data);
+
+        let ret = ApiResponse {
+            value,
+            status,
+            headers,
+            elapsed: start.elapsed(),
+            final_url,
+            cached: false,
+        };
+        self.store_cache(url, status, &data);
+
+        return Ok((data, ret));
+    }
+
+    /// Convert the XML response (from `url`) to JSON, checking for an error
+    /// response if the client is set to.  A 429 (once any retries have run
+    /// out) is always an error.
+    fn convert(
+        &self,
+        url: &str,
+        status: StatusCode,
+        headers: &HeaderMap,
+        data: &str,
+    ) -> Result<Value> {
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            return Err(rate_limited(headers));
+        }
+        let ret = match xml_to_json(data) {
+            Ok(ret) => ret,
+            Err(RbggError::XmlParse {
+                message, snippet, ..
+            }) => {
+                return Err(RbggError::XmlParse {
+                    message,
+                    status: Some(status.as_u16()),
+                    url: Some(url.to_string()),
+                    snippet,
+                })
+            }
+            Err(e) => return Err(e),
+        };
+        if self.inner.config.api_errors {
+            check_error_response(&ret, headers)?;
+        }
+
+        return Ok(ret);
+    }
+
+    /// Return the cached response for the url, if there is one and the
+    /// handle isn't skipping the cache.  A cached body that no longer
+    /// converts is treated as a miss.
+    fn cache_hit(&self, url: &str, start: Instant) -> Option<(String, ApiResponse)> {
+        let data = self.read_cache()?.get(&normalize_url(url))?;
+
+        return self.cached_response(url, data, start);
+    }
+
+    /// Return the cached response for the url, without blocking the runtime
+    #[cfg(feature = "async")]
+    async fn cache_hit_async(&self, url: &str, start: Instant) -> Option<(String, ApiResponse)> {
+        let data = self.read_cache()?.get_async(&normalize_url(url)).await?;
+
+        return self.cached_response(url, data, start);
+    }
+
+    /// The cache to look responses up in, unless the handle is skipping it
+    fn read_cache(&self) -> Option<&Arc<dyn ResponseCache>> {
+        if self.no_cache {
+            return None;
+        }
+
+        return self.inner.config.cache.as_ref();
+    }
+
+    /// Convert a cached body for the url into a response
+    fn cached_response(
+        &self,
+        url: &str,
+        data: String,
+        start: Instant,
+    ) -> Option<(String, ApiResponse)> {
+        let value = self
+            .convert(url, StatusCode::OK, &HeaderMap::new(), &data)
+            .ok()?;
+        self.inner
+            .counters
+            .cache_hits
+            .fetch_add(1, Ordering::Relaxed);
+
+        let ret = ApiResponse {
+            value,
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            elapsed: start.elapsed(),
+            final_url: url.to_string(),
+            cached: true,
+        };
+
+        return Some((data, ret));
+    }
+
+    /// Store a successful response in the cache, if there is one.  Entries
+    /// are keyed on the url with its params sorted.
+    fn store_cache(&self, url: &str, status: StatusCode, body: &str) {
+        if let Some(cache) = self.write_cache(status) {
+            cache.put(&normalize_url(url), body, self.inner.config.cache_ttl);
+        }
+    }
+
+    /// Store a successful response in the cache, without blocking the
+    /// runtime
+    #[cfg(feature = "async")]
+    async fn store_cache_async(&self, url: &str, status: StatusCode, body: &str) {
+        if let Some(cache) = self.write_cache(status) {
+            cache
+                .put_async(&normalize_url(url), body, self.inner.config.cache_ttl)
+                .await;
+        }
+    }
+
+    /// The cache to store a response with the given status in, if any
+    fn write_cache(&self, status: StatusCode) -> Option<&Arc<dyn ResponseCache>> {
+        if status == StatusCode::OK || status == StatusCode::NOT_MODIFIED {
+            return self.inner.config.cache.as_ref();
+        }
+
+        return None;
+    }
+
+    /// The earlier response to make a conditional request for the url
+    /// against, if conditional requests are on.  A handle with the cache
+    /// turned off always gets a full response.
+    fn validated(&self, url: &str) -> Option<Validated> {
+        if !self.inner.config.conditional || self.no_cache {
+            return None;
+        }
+
+        return self
+            .inner
+            .validators
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&normalize_url(url));
+    }
+
+    /// Remember the validators of a successful response, if conditional
+    /// requests are on.  Like the cache, they're keyed on the url with its
+    /// params sorted.
+    fn store_validated(&self, url: &str, status: StatusCode, headers: &HeaderMap, body: &str) {
+        if !self.inner.config.conditional || status != StatusCode::OK {
+            return;
+        }
+
+        if let Some(validated) = Validated::from_response(headers, body) {
+            self.inner
+                .validators
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(&normalize_url(url), validated);
+        }
+    }
+
+    /// Run the future, unless the token is cancelled or the deadline passes
+    /// first
+    #[cfg(feature = "async")]
+    async fn guarded<T>(&self, fut: impl Future<Output = Result<T>>) -> Result<T> {
+        if self.cancel.is_none() && self.deadline.is_none() {
+            return fut.await;
+        }
+
+        let cancelled = async {
+            match &self.cancel {
+                Some(token) => token.cancelled().await,
+                None => future::pending().await,
+            }
+        };
+        let deadline = async {
+            match self.deadline {
+                Some(deadline) => time::sleep_until(time::Instant::from_std(deadline)).await,
+                None => future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            res = fut => return res,
+            _ = cancelled => return Err(RbggError::Cancelled),
+            _ = deadline => return Err(RbggError::DeadlineExceeded),
+        }
+    }
+
+    /// Return an error if the token has been cancelled or the deadline has
+    /// passed
+    fn check_cancel(&self) -> Result<()> {
+        if self.cancel.as_ref().is_some_and(|t| t.is_cancelled()) {
+            return Err(RbggError::Cancelled);
+        }
+        self.remaining()?;
+
+        return Ok(());
+    }
+
+    /// The time left until the deadline, if there is one.  This is an error
+    /// once the deadline has passed.
+    fn remaining(&self) -> Result<Option<Duration>> {
+        let deadline = match self.deadline {
+            Some(deadline) => deadline,
+            None => return Ok(None),
+        };
+
+        return match deadline.checked_duration_since(Instant::now()) {
+            Some(left) if !left.is_zero() => Ok(Some(left)),
+            _ => Err(RbggError::DeadlineExceeded),
+        };
+    }
+
+    /// Sleep (blocking), but wake up with an error if the token is
+    /// cancelled or the deadline passes first
+    fn sleep_b(&self, dur: Duration) -> Result<()> {
+        let (dur, past_deadline) = match self.remaining()? {
+            Some(left) if left < dur => (left, true),
+            _ => (dur, false),
+        };
+
+        match &self.cancel {
+            Some(token) => {
+                if token.wait_timeout_b(dur) {
+                    return Err(RbggError::Cancelled);
+                }
+            }
+            None => thread::sleep(dur),
+        }
+        if past_deadline {
+            return Err(RbggError::DeadlineExceeded);
+        }
+
+        return Ok(());
+    }
+
+    /// Let an adaptive throttle know how BGG responded.  A 429, or a 202
+    /// for a request that has already been queued, slows it down, while
+    /// anything else that worked lets it recover.
+    fn adapt_throttle(&self, status: StatusCode, queued: u32) {
+        if let Some(throttle) = &self.inner.throttle {
+            if status == StatusCode::TOO_MANY_REQUESTS
+                || (status == StatusCode::ACCEPTED && queued > 0)
+            {
+                throttle.slow_down();
+            } else if (status.is_success() && status != StatusCode::ACCEPTED)
+                || status == StatusCode::NOT_MODIFIED
+            {
+                throttle.recover();
+            }
+        }
+    }
+
+    /// The wait after the given number of 202 responses, or a
+    /// `RbggError::QueueTimeout` if we've run out of attempts.  `waited` is
+    /// the running total.
+    fn queue_delay(&self, queued: u32, waited: &mut Duration) -> Result<Duration> {
+        let delay = match self.inner.config.queue.next_delay(queued) {
+            Some(delay) => delay,
+            None => {
+                return Err(RbggError::QueueTimeout {
+                    attempts: queued,
+                    waited: *waited,
+                })
+            }
+        };
+        *waited += delay;
+
+        return Ok(delay);
+    }
+
+    /// Record a request that failed to send with the circuit breaker, and
+    /// return the wait before retrying it, or `None` if it shouldn't be
+    fn failed_send_delay(&self, err: &TransportError, attempt: u32) -> Option<Duration> {
+        self.record_breaker(err.is_transient());
+
+        return self.error_retry_delay(err, attempt);
+    }
+
+    /// Return `RbggError::CircuitOpen` if the breaker is open, or the probe
+    /// if this call is the one testing the waters
+    fn check_breaker(&self) -> Result<Option<Probe<'_>>> {
+        return match &self.inner.breaker {
+            Some(breaker) => breaker.check(Instant::now()),
+            None => Ok(None),
+        };
+    }
+
+    /// Let the circuit breaker know how a request went
+    fn record_breaker(&self, failed: bool) {
+        if let Some(breaker) = &self.inner.breaker {
+            if !failed {
+                breaker.success();
+            } else if breaker.failure(Instant::now()) {
+                self.inner
+                    .counters
+                    .breaker_trips
+                    .fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// The wait before retrying a request that failed to send, or `None` if
+    /// it shouldn't be retried
+    fn error_retry_delay(&self, err: &TransportError, attempt: u32) -> Option<Duration> {
+        let policy = &self.inner.config.retry;
+        if !policy.retries_error(err) {
+            return None;
+        }
+
+        return self.retry_delay(policy.next_delay(attempt, None));
+    }
+
+    /// The wait before retrying a request that got the given response, or
+    /// `None` if it shouldn't be retried
+    fn status_retry_delay(
+        &self,
+        status: StatusCode,
+        headers: &HeaderMap,
+        attempt: u32,
+    ) -> Option<Duration> {
+        let policy = &self.inner.config.retry;
+        if !policy.retries_status(status.as_u16()) {
+            return None;
+        }
+
+        return self.retry_delay(policy.next_delay(attempt, retry_after(headers)));
+    }
+
+    /// Count the retry, if there is going to be one
+    fn retry_delay(&self, delay: Option<Duration>) -> Option<Duration> {
+        if delay.is_some() {
+            self.inner.counters.retries.fetch_add(1, Ordering::Relaxed);
+        }
+
+        return delay;
+    }
+}
+
+/// The query params (and JSON fields) whose values are never traced or
+/// recorded
+pub(crate) const REDACTED_PARAMS: &[&str] = &["password", "token", "access_token", "apikey", "key"];
+
+/// The tracing and metrics for a single request, including any retries
+/// and 202 waits.  With the `tracing` feature, everything is recorded on a
+/// `bgg_request` span: the endpoint, the query params (with anything
+/// sensitive redacted), the final HTTP status, how many times the request
+/// was retried or queued, and the total latency.  The same details are
+/// passed to the `MetricsSink`, if there is one, when the request is done.
+struct RequestTrace {
+    method: &'static str,
+    url: String,
+    start: Instant,
+    metrics: Option<Arc<dyn MetricsSink>>,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+impl RequestTrace {
+    fn new(method: &'static str, url: &str, metrics: Option<Arc<dyn MetricsSink>>) -> Self {
+        #[cfg(feature = "tracing")]
+        let span = {
+            let (endpoint, params) = redact_url(url);
+            tracing::info_span!(
+                "bgg_request",
+                method,
+                endpoint = %endpoint,
+                params = %params,
+                status = tracing::field::Empty,
+                retries = tracing::field::Empty,
+                queued = tracing::field::Empty,
+                latency_ms = tracing::field::Empty,
+            )
+        };
+
+        return Self {
+            method,
+            url: url.to_string(),
+            start: Instant::now(),
+            metrics,
+            #[cfg(feature = "tracing")]
+            span,
+        };
+    }
+
+    /// The request is going to be retried after `delay`
+    #[allow(unused_variables)]
+    fn retry(&self, attempt: u32, delay: Duration, reason: &dyn std::fmt::Display) {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(
+            parent: &self.span,
+            attempt,
+            delay_ms = delay.as_millis() as u64,
+            "retrying request: {}",
+            reason
+        );
+    }
+
+    /// BGG queued the request, so it's going to be made again after `delay`
+    #[allow(unused_variables)]
+    fn queued(&self, queued: u32, delay: Duration) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            parent: &self.span,
+            queued,
+            delay_ms = delay.as_millis() as u64,
+            "request queued by BGG"
+        );
+    }
+
+    /// The request got a response that is going back to the caller
+    fn finish(&self, status: StatusCode, attempts: u32, queued: u32) {
+        let error = status.is_client_error() || status.is_server_error();
+        self.record(Some(status), error, attempts, queued);
+
+        #[cfg(feature = "tracing")]
+        {
+            self.span.record("status", status.as_u16());
+            tracing::debug!(parent: &self.span, "request complete");
+        }
+    }
+
+    /// The request failed for good
+    #[allow(unused_variables)]
+    fn failed(&self, attempts: u32, queued: u32, err: &dyn std::fmt::Display) {
+        self.record(None, true, attempts, queued);
+
+        #[cfg(feature = "tracing")]
+        tracing::warn!(parent: &self.span, "request failed: {}", err);
+    }
+
+    fn record(&self, status: Option<StatusCode>, error: bool, attempts: u32, queued: u32) {
+        let retries = attempts.saturating_sub(1);
+        let latency = self.start.elapsed();
+
+        #[cfg(feature = "tracing")]
+        {
+            self.span.record("retries", retries);
+            self.span.record("queued", queued);
+            self.span.record("latency_ms", latency.as_millis() as u64);
+        }
+
+        if let Some(metrics) = &self.metrics {
+            let (path, _) = redact_url(&self.url);
+            metrics.record(&RequestEvent {
+                method: self.method,
+                endpoint: endpoint_name(&path),
+                status: status.map(|s| s.as_u16()),
+                error,
+                retries,
+                queued,
+                latency,
+            });
+        }
+    }
+}
+
+/// Split a url into the endpoint (its path) and its query params, with the
+/// values of any sensitive params replaced
+fn redact_url(url: &str) -> (String, String) {
+    let (base, query) = url.split_once('?').unwrap_or((url, ""));
+    let endpoint = match base.split_once("://") {
+        Some((_, rest)) => rest.find('/').map(|i| &rest[i..]).unwrap_or("/"),
+        None => base,
+    };
+
+    let params: Vec<String> = query
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|p| match p.split_once('=') {
+            Some((k, _)) if REDACTED_PARAMS.contains(&k.to_ascii_lowercase().as_str()) => {
+                format!("{}=REDACTED", k)
+            }
+            _ => p.to_string(),
+        })
+        .collect();
+
+    return (endpoint.to_string(), params.join("&"));
+}
+
+/// How much of a response to read before deciding whether it can be
+/// streamed out as is (see `streamable()`)
+const PEEK_LEN: usize = 256;
+
+/// Whether a response can be written out as it arrives, going by its status,
+/// headers and the start of its body.  Anything that looks like an error (an
+/// error status, an HTML page or one of BGG's error responses) is read in
+/// full and checked instead.
+fn streamable(status: StatusCode, headers: &HeaderMap, head: &[u8]) -> bool {
+    if !status.is_success() || is_html_type(headers) {
+        return false;
+    }
+    let head = String::from_utf8_lossy(head).to_ascii_lowercase();
+    let mut start = head.trim_start();
+    // Skip past the XML declaration to the root element
+    if start.starts_with("<?xml") {
+        start = match start.find("?>") {
+            Some(end) => start[end + 2..].trim_start(),
+            None => return false,
+        };
+    }
+
+    return !["<!doctype html", "<html", "<error", "<div"]
+        .iter()
+        .any(|p| start.starts_with(p));
+}
+
+/// Whether the content type says this is an HTML page
+fn is_html_type(headers: &HeaderMap) -> bool {
+    return headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_ascii_lowercase().contains("text/html"))
+        .unwrap_or(false);
+}
+
+/// How much of an HTML page to include in `RbggError::ServiceUnavailable`
+const HTML_SNIPPET_LEN: usize = 200;
+
+/// BGG sometimes serves an HTML page, like its "down for maintenance" page
+/// (with either a 200 or a 503), instead of an API response.  Spot that and
+/// return `RbggError::Maintenance` or, for any other page,
+/// `RbggError::ServiceUnavailable`, rather than letting it fail (or worse,
+/// succeed) in the conversion.
+fn check_html(status: StatusCode, headers: &HeaderMap, body: &str) -> Result<()> {
+    let is_html = is_html_type(headers) || {
+        let start: String = body.trim_start().chars().take(14).collect();
+        let start = start.to_ascii_lowercase();
+        start.starts_with("<!doctype html") || start.starts_with("<html")
+    };
+
+    if !is_html {
+        return Ok(());
+    }
+
+    if status == StatusCode::SERVICE_UNAVAILABLE
+        || body.to_ascii_lowercase().contains("maintenance")
+    {
+        return Err(RbggError::Maintenance {
+            status: status.as_u16(),
+            retry_after: retry_after(headers),
+        });
+    }
+
+    return Err(RbggError::ServiceUnavailable {
+        status: status.as_u16(),
+        snippet: snippet(body, HTML_SNIPPET_LEN),
+    });
+}
+
+/// Return an error for a client or server error status
+fn check_status(status: StatusCode, headers: &HeaderMap) -> Result<()> {
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        return Err(rate_limited(headers));
+    }
+    if status.is_client_error() || status.is_server_error() {
+        return Err(RbggError::Status {
+            status: status.as_u16(),
+        });
+    }
+
+    return Ok(());
+}
+
+/// The Retry-After header, if it's set, either in seconds or as an HTTP
+/// date
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    return retry_after_at(headers, SystemTime::now());
+}
+
+/// The Retry-After header, as of `now`.  A date in the past means there's
+/// no need to wait.
+fn retry_after_at(headers: &HeaderMap, now: SystemTime) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    let now = now.duration_since(UNIX_EPOCH).ok()?.as_secs() as i64;
+
+    return Some(Duration::from_secs((date.timestamp() - now).max(0) as u64));
+}
+
+/// The error for a 429, or an error response saying we've been rate
+/// limited
+fn rate_limited(headers: &HeaderMap) -> RbggError {
+    return RbggError::RateLimited {
+        retry_after: retry_after(headers),
+    };
+}
+
+/// Return an error for an error response, which is `RbggError::RateLimited`
+/// if BGG says we've made too many requests and `RbggError::Api` otherwise
+fn check_error_response(value: &Value, headers: &HeaderMap) -> Result<()> {
+    return match api_error(value) {
+        Some(message) if message.to_ascii_lowercase().contains("rate limit") => {
+            Err(rate_limited(headers))
+        }
+        Some(message) => Err(RbggError::Api { message }),
+        None => Ok(()),
+    };
+}
+
+/// Build the headers that get sent with every request
+fn default_headers(config: &HttpConfig) -> Result<HeaderMap> {
+    let mut headers = HeaderMap::new();
+
+    if let Some(lang) = &config.accept_language {
+        headers.insert(ACCEPT_LANGUAGE, HeaderValue::from_str(lang)?);
+    }
+    if let Some(agent) = &config.user_agent {
+        headers.insert(USER_AGENT, HeaderValue::from_str(agent)?);
+    }
+    for (name, value) in &config.headers {
+        headers.insert(
+            HeaderName::from_bytes(name.as_bytes())?,
+            HeaderValue::from_str(value)?,
+        );
+    }
+
+    return Ok(headers);
+}
+
+impl Default for HttpClient {
+    /// Create a client using the default configuration.  Like
+    /// `reqwest::Client::new()`, this will panic if the TLS backend cannot
+    /// be initialized.
+    fn default() -> Self {
+        return Self::new(HttpConfig::default()).expect("Failed to create the HTTP client");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::breaker::BreakerStatus;
+    use crate::metrics::RequestMetrics;
+    use crate::transport::{AsyncBody, TransportResult};
+    use futures_util::future::BoxFuture;
+    use http::header::ACCEPT_ENCODING;
+
+    #[test]
+    fn test_default_config() {
+        let cl = HttpClient::default();
+
+        assert!(cl.config().compression);
+        assert_eq!(cl.config().throttle, Some(ThrottleConfig::default()));
+        assert_eq!(cl.config().connect_timeout, Some(DEFAULT_CONNECT_TIMEOUT));
+        assert_eq!(cl.config().timeout, Some(DEFAULT_TIMEOUT));
+
+        let cl = HttpClient::new(HttpConfig {
+            compression: false,
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(!cl.config().compression);
+        assert_eq!(cl.priority(), Priority::Normal);
+
+        let low = cl.with_priority(Priority::Low);
+        assert_eq!(low.priority(), Priority::Low);
+        // The clone shares the same internals
+        assert!(Arc::ptr_eq(&cl.inner, &low.inner));
+    }
+
+    #[test]
+    fn test_call_config() {
+        let cl = HttpClient::default();
+        assert!(!cl.no_cache);
+
+        let fresh = cl.with_call_config(&CallConfig::no_cache());
+        assert!(fresh.no_cache);
+        assert_eq!(fresh.priority(), Priority::Normal);
+        assert!(Arc::ptr_eq(&cl.inner, &fresh.inner));
+
+        let high = fresh.with_call_config(&CallConfig::default().with_priority(Priority::High));
+        assert!(!high.no_cache);
+        assert_eq!(high.priority(), Priority::High);
+    }
+
+    #[test]
+    fn test_default_headers() {
+        let headers = default_headers(&HttpConfig::default()).unwrap();
+        assert_eq!(headers.len(), 1);
+        assert_eq!(headers[USER_AGENT], DEFAULT_USER_AGENT);
+
+        let config = HttpConfig {
+            accept_language: Some("de-DE,de;q=0.9".into()),
+            ..Default::default()
+        };
+        let headers = default_headers(&config).unwrap();
+        assert_eq!(headers[ACCEPT_LANGUAGE], "de-DE,de;q=0.9");
+
+        let config = HttpConfig {
+            user_agent: Some("mytool/1.0 (me@example.com)".into()),
+            ..Default::default()
+        };
+        let headers = default_headers(&config).unwrap();
+        assert_eq!(headers[USER_AGENT], "mytool/1.0 (me@example.com)");
+
+        let config = HttpConfig {
+            user_agent: None,
+            headers: vec![
+                ("X-Tool".into(), "mine".into()),
+                ("Accept-Encoding".into(), "gzip".into()),
+            ],
+            ..Default::default()
+        };
+        let headers = default_headers(&config).unwrap();
+        assert!(!headers.contains_key(USER_AGENT));
+        assert_eq!(headers["x-tool"], "mine");
+        assert_eq!(headers[ACCEPT_ENCODING], "gzip");
+
+        let config = HttpConfig {
+            headers: vec![("bad header".into(), "value".into())],
+            ..Default::default()
+        };
+        assert!(default_headers(&config).is_err());
+
+        // Invalid header values are caught up front
+        let config = HttpConfig {
+            accept_language: Some("bad\nvalue".into()),
+            ..Default::default()
+        };
+        assert!(HttpClient::new(config).is_err());
+    }
+
+    #[test]
+    fn test_stats() {
+        let counters = Counters::default();
+
+        assert_eq!(counters.snapshot(), Stats::default());
+
+        counters.record(StatusCode::ACCEPTED);
+        counters.record(StatusCode::ACCEPTED);
+        counters.record(StatusCode::TOO_MANY_REQUESTS);
+        counters.record(StatusCode::OK);
+
+        let stats = counters.snapshot();
+        assert_eq!(stats.requests, 4);
+        assert_eq!(stats.queue_waits, 2);
+        assert_eq!(stats.rate_limit_stalls, 1);
+        assert_eq!(stats.retries, 0);
+    }
+
+    #[test]
+    fn test_adapt_throttle() {
+        let cl = HttpClient::default();
+        let base = cl.throttle_state().unwrap().base_rate;
+
+        // The first 202 is expected, so it doesn't count
+        cl.adapt_throttle(StatusCode::ACCEPTED, 0);
+        assert_eq!(cl.throttle_state().unwrap().rate, base);
+        cl.adapt_throttle(StatusCode::ACCEPTED, 1);
+        assert!(cl.throttle_state().unwrap().is_backed_off());
+        cl.adapt_throttle(StatusCode::TOO_MANY_REQUESTS, 0);
+        let backed_off = cl.throttle_state().unwrap().rate;
+        cl.adapt_throttle(StatusCode::NOT_FOUND, 0);
+        assert_eq!(cl.throttle_state().unwrap().rate, backed_off);
+        cl.adapt_throttle(StatusCode::OK, 0);
+        assert!(cl.throttle_state().unwrap().rate > backed_off);
+
+        let cl = HttpClient::new(HttpConfig {
+            throttle: None,
+            ..Default::default()
+        })
+        .unwrap();
+        cl.adapt_throttle(StatusCode::TOO_MANY_REQUESTS, 0);
+        assert_eq!(cl.throttle_state(), None);
+    }
+
+    #[test]
+    fn test_request_metrics() {
+        let metrics = Arc::new(RequestMetrics::new());
+        let cl = HttpClient::new(HttpConfig {
+            metrics: Some(metrics.clone()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        cl.trace("GET", "https://boardgamegeek.com/xmlapi2/thing?id=1")
+            .finish(StatusCode::OK, 2, 1);
+        cl.trace("GET", "https://boardgamegeek.com/xmlapi/boardgame/12")
+            .finish(StatusCode::NOT_FOUND, 1, 0);
+        cl.trace("GET", "https://boardgamegeek.com/xmlapi2/thing?id=2")
+            .failed(4, 0, &"connection refused");
+
+        let snap = metrics.snapshot();
+        let thing = snap["/xmlapi2/thing"];
+        assert_eq!(thing.requests, 2);
+        assert_eq!(thing.errors, 1);
+        assert_eq!(thing.retries, 4);
+        assert_eq!(thing.queued, 1);
+        assert_eq!(snap["/xmlapi/boardgame/:id"].errors, 1);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_guarded() {
+        let cl = HttpClient::default();
+        assert_eq!(cl.guarded(async { Ok(1) }).await.unwrap(), 1);
+
+        let token = CancellationToken::new();
+        let handle = cl.with_cancel(&token);
+        token.cancel();
+        let err = handle
+            .guarded(future::pending::<Result<()>>())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RbggError::Cancelled));
+
+        let handle = cl.with_deadline(Instant::now() + Duration::from_millis(10));
+        let err = handle
+            .guarded(future::pending::<Result<()>>())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RbggError::DeadlineExceeded));
+    }
+
+    #[test]
+    fn test_cancel_b() {
+        let cl = HttpClient::default();
+        assert!(cl.check_cancel().is_ok());
+        assert!(cl.remaining().unwrap().is_none());
+        assert!(cl.sleep_b(Duration::from_millis(1)).is_ok());
+
+        let handle = cl.with_deadline(Instant::now() + Duration::from_millis(10));
+        assert!(handle.remaining().unwrap().is_some());
+        let err = handle.sleep_b(Duration::from_secs(10)).unwrap_err();
+        assert!(matches!(err, RbggError::DeadlineExceeded));
+        let err = handle.check_cancel().unwrap_err();
+        assert!(matches!(err, RbggError::DeadlineExceeded));
+
+        let token = CancellationToken::new();
+        let handle = cl.with_cancel(&token);
+        token.cancel();
+        let err = handle.sleep_b(Duration::from_secs(10)).unwrap_err();
+        assert!(matches!(err, RbggError::Cancelled));
+        // The original client isn't affected
+        assert!(cl.check_cancel().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_throttle() {
+        let res = HttpClient::new(HttpConfig {
+            throttle: Some(ThrottleConfig {
+                rate: 0.0,
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        assert!(matches!(res, Err(RbggError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_invalid_queue() {
+        let res = HttpClient::new(HttpConfig {
+            queue: QueuePolicy {
+                backoff: f64::INFINITY,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        assert!(matches!(res, Err(RbggError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_default_transports() {
+        let cl = HttpClient::new(HttpConfig::default()).unwrap();
+
+        // The blocking calls only go through ureq when reqwest isn't there
+        let blocking = format!("{:?}", cl.inner.transport_b);
+        if cfg!(feature = "async") {
+            assert!(blocking.starts_with("ReqwestTransport"));
+        } else {
+            assert!(blocking.starts_with("UreqTransport"));
+        }
+        #[cfg(feature = "async")]
+        assert!(format!("{:?}", cl.inner.transport).starts_with("ReqwestTransport"));
+    }
+
+    #[test]
+    fn test_breaker() {
+        let cl = HttpClient::new(HttpConfig {
+            breaker: Some(BreakerConfig {
+                failure_threshold: 2,
+                cooldown: Duration::from_secs(60),
+            }),
+            ..Default::default()
+        })
+        .unwrap();
+
+        cl.record_breaker(true);
+        cl.record_breaker(false);
+        cl.record_breaker(true);
+        assert!(cl.check_breaker().is_ok());
+        cl.record_breaker(true);
+        let err = cl.check_breaker().unwrap_err();
+        assert!(matches!(err, RbggError::CircuitOpen { .. }));
+        assert_eq!(cl.breaker_state().unwrap().status, BreakerStatus::Open);
+        assert_eq!(cl.stats().breaker_trips, 1);
+
+        assert_eq!(HttpClient::default().breaker_state(), None);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_breaker_dropped_probe() {
+        let cl = HttpClient::new(HttpConfig {
+            breaker: Some(BreakerConfig {
+                failure_threshold: 1,
+                cooldown: Duration::ZERO,
+            }),
+            throttle: None,
+            retry: RetryPolicy::none(),
+            ..Default::default()
+        })
+        .unwrap();
+        cl.record_breaker(true);
+
+        // Nothing ever answers, so the probe is stopped by its deadline
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/xmlapi2/hot", listener.local_addr().unwrap());
+        let handle = cl.with_deadline(Instant::now() + Duration::from_millis(50));
+        let err = handle.get_json(&url).await.unwrap_err();
+        assert!(matches!(err, RbggError::DeadlineExceeded));
+
+        // And the next call gets to test the waters
+        assert!(cl.check_breaker().unwrap().is_some());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_coalesce() {
+        let metrics = Arc::new(RequestMetrics::new());
+        let cl = HttpClient::new(HttpConfig {
+            coalesce: true,
+            metrics: Some(metrics.clone()),
+            throttle: None,
+            retry: RetryPolicy::none(),
+            ..Default::default()
+        })
+        .unwrap();
+        // Nothing is listening here, so both calls fail on the one request
+        let url = "http://127.0.0.1:1/xmlapi2/hot";
+        let (a, b) = tokio::join!(cl.get_json(url), cl.get_json(url));
+
+        assert!(a.is_err() && b.is_err());
+        assert_eq!(metrics.total().requests, 1);
+        assert!(cl.inner.inflight.is_empty());
+
+        // The order of the params doesn't matter
+        let (a, b) = tokio::join!(
+            cl.get_json("http://127.0.0.1:1/xmlapi2/thing?id=1&stats=1"),
+            cl.get_json("http://127.0.0.1:1/xmlapi2/thing?stats=1&id=1")
+        );
+        assert!(a.is_err() && b.is_err());
+        assert_eq!(metrics.total().requests, 2);
+
+        // But a call skipping the cache isn't merged with one that doesn't
+        let fresh = cl.with_call_config(&CallConfig::no_cache());
+        let (a, b) = tokio::join!(cl.get_json(url), fresh.get_json(url));
+        assert!(a.is_err() && b.is_err());
+        assert_eq!(metrics.total().requests, 4);
+        assert!(cl.inner.inflight.is_empty());
+    }
+
+    #[derive(Debug, Default)]
+    struct TestCache {
+        entries: Mutex<HashMap<String, (String, Duration)>>,
+    }
+
+    impl ResponseCache for TestCache {
+        fn get(&self, key: &str) -> Option<String> {
+            return self.entries.lock().unwrap().get(key).map(|e| e.0.clone());
+        }
+
+        fn put(&self, key: &str, body: &str, ttl: Duration) {
+            self.entries
+                .lock()
+                .unwrap()
+                .insert(key.into(), (body.into(), ttl));
+        }
+    }
+
+    #[test]
+    fn test_response_cache() {
+        let cache = Arc::new(TestCache::default());
+        let cl = HttpClient::new(HttpConfig {
+            cache: Some(cache.clone()),
+            cache_ttl: Duration::from_secs(5),
+            throttle: None,
+            retry: RetryPolicy::none(),
+            ..Default::default()
+        })
+        .unwrap();
+        let url = "http://127.0.0.1:1/xmlapi2/hot";
+
+        cl.store_cache(url, StatusCode::ACCEPTED, "<items/>");
+        assert!(cache.get(url).is_none());
+        cl.store_cache(url, StatusCode::OK, "<items/>");
+        assert_eq!(cache.get(url).unwrap(), "<items/>");
+        assert_eq!(cache.entries.lock().unwrap()[url].1, Duration::from_secs(5));
+
+        // Skipping the cache goes to the network again
+        let fresh = cl.with_call_config(&CallConfig::no_cache());
+        assert!(fresh.cache_hit(url, Instant::now()).is_none());
+        assert!(fresh.get_json_b(url).is_err());
+        assert_eq!(cl.stats().cache_hits, 0);
+    }
+
+    #[test]
+    fn test_cache_hit() {
+        let transport = Arc::new(MockTransport::new(&[(200, r#"<items total="1"/>"#)]));
+        let cl = HttpClient::new(HttpConfig {
+            cache: Some(Arc::new(TestCache::default())),
+            transport: Some(transport.clone()),
+            throttle: None,
+            ..Default::default()
+        })
+        .unwrap();
+
+        // The same params in a different order are the same request
+        let first = cl.get_json_b("https://bgg/thing?id=1&stats=1").unwrap();
+        let second = cl.get_json_b("https://bgg/thing?stats=1&id=1").unwrap();
+        assert_eq!(first, second);
+        assert_eq!(cl.stats().cache_hits, 1);
+        assert_eq!(transport.requests.lock().unwrap().len(), 1);
+    }
+
+    /// A transport that answers with the queued up statuses and bodies,
+    /// keeping the requests it was sent
+    #[derive(Debug, Default)]
+    struct MockTransport {
+        responses: Mutex<VecDeque<(u16, &'static str)>>,
+        requests: Mutex<Vec<TransportRequest>>,
+    }
+
+    impl MockTransport {
+        fn new(responses: &[(u16, &'static str)]) -> Self {
+            return Self {
+                responses: Mutex::new(responses.iter().copied().collect()),
+                ..Default::default()
+            };
+        }
+
+        fn next(&self, req: TransportRequest) -> (StatusCode, String, &'static str) {
+            let (status, body) = self.responses.lock().unwrap().pop_front().unwrap();
+            let url = req.url.clone();
+            self.requests.lock().unwrap().push(req);
+
+            return (StatusCode::from_u16(status).unwrap(), url, body);
+        }
+    }
+
+    impl HttpTransport for MockTransport {
+        fn send(&self, req: TransportRequest) -> BoxFuture<'_, TransportResult<AsyncBody>> {
+            return Box::pin(async move {
+                let (status, url, body) = self.next(req);
+                return Ok(TransportResponse::<AsyncBody>::new(status, &url, body));
+            });
+        }
+
+        fn send_b(&self, req: TransportRequest) -> TransportResult<BlockingBody> {
+            let (status, url, body) = self.next(req);
+            return Ok(TransportResponse::<BlockingBody>::new(status, &url, body));
+        }
+    }
+
+    fn mock_client(transport: &Arc<MockTransport>) -> HttpClient {
+        return HttpClient::new(HttpConfig {
+            transport: Some(transport.clone()),
+            throttle: None,
+            retry: RetryPolicy {
+                base_delay: Duration::from_millis(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_transport_b() {
+        let transport = Arc::new(MockTransport::new(&[(503, ""), (200, r#"{"a": 1}"#)]));
+        let cl = mock_client(&transport).with_call_config(&CallConfig::no_cache());
+        let url = "https://boardgamegeek.com/api/thing";
+
+        let value = cl.get_plain_json_b(url).unwrap();
+        assert_eq!(value["a"], 1);
+        assert_eq!(cl.stats().retries, 1);
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, Method::GET);
+        assert_eq!(requests[0].url, url);
+        assert_eq!(requests[0].headers[USER_AGENT], DEFAULT_USER_AGENT);
+        assert_eq!(requests[0].headers[CACHE_CONTROL], "no-cache");
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_transport() {
+        let transport = Arc::new(MockTransport::new(&[(200, "<items/>"), (404, "")]));
+        let cl = mock_client(&transport);
+
+        let mut out = vec![];
+        let written = cl.get_to_writer("https://bgg/hot", &mut out).await.unwrap();
+        assert_eq!(written, 8);
+        assert_eq!(out, b"<items/>");
+
+        let err = cl
+            .post_json("https://bgg/login", &Value::Null, Some("a=b"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("404"));
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests[1].method, Method::POST);
+        assert_eq!(requests[1].headers[COOKIE], "a=b");
+        assert_eq!(requests[1].body.as_deref(), Some("null"));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_to_writer_errors() {
+        let page = "<!DOCTYPE html><html><body>Down for maintenance</body></html>";
+        let errors = r#"<?xml version="1.0" encoding="utf-8"?>
+            <errors><error><message>Invalid username specified</message></error></errors>"#;
+        let transport = Arc::new(MockTransport::new(&[
+            (200, page),
+            (200, errors),
+            (404, "<items/>"),
+            (200, "<?xml version=\"1.0\"?><items/>"),
+        ]));
+        let cl = mock_client(&transport);
+
+        let mut out = vec![];
+        let err = cl
+            .get_to_writer("https://bgg/a", &mut out)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RbggError::Maintenance { status: 200, .. }));
+        let err = cl.get_to_writer_b("https://bgg/b", &mut out).unwrap_err();
+        assert!(matches!(err, RbggError::Api { .. }));
+        let err = cl.get_to_writer_b("https://bgg/c", &mut out).unwrap_err();
+        assert!(matches!(err, RbggError::Status { status: 404 }));
+        assert!(out.is_empty());
+
+        let written = cl.get_to_writer_b("https://bgg/d", &mut out).unwrap();
+        assert_eq!(written, 29);
+        assert_eq!(out, b"<?xml version=\"1.0\"?><items/>");
+    }
+
+    #[test]
+    fn test_streamable() {
+        let headers = HeaderMap::new();
+        assert!(streamable(StatusCode::OK, &headers, b"<items>"));
+        assert!(streamable(
+            StatusCode::OK,
+            &headers,
+            b"<?xml version=\"1.0\"?>\n<items>"
+        ));
+        assert!(!streamable(
+            StatusCode::OK,
+            &headers,
+            b"<?xml version=\"1.0\"?><error>"
+        ));
+        assert!(!streamable(StatusCode::OK, &headers, b"  <!DOCTYPE html>"));
+        assert!(!streamable(StatusCode::NOT_FOUND, &headers, b"<items>"));
+
+        let mut html = HeaderMap::new();
+        html.insert(CONTENT_TYPE, HeaderValue::from_static("text/html"));
+        assert!(!streamable(StatusCode::OK, &html, b"<items>"));
+    }
+
+    #[test]
+    fn test_api_response() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("30"));
+        headers.insert(CONTENT_LENGTH, HeaderValue::from_static("1234"));
+        let resp = ApiResponse {
+            value: Value::Null,
+            status: StatusCode::OK,
+            headers,
+            elapsed: Duration::from_millis(5),
+            final_url: "https://boardgamegeek.com/xmlapi2/hot".into(),
+            cached: false,
+        };
+
+        assert_eq!(resp.retry_after(), Some(Duration::from_secs(30)));
+        assert_eq!(resp.content_length(), Some(1234));
+
+        let resp = ApiResponse {
+            headers: HeaderMap::new(),
+            ..resp
+        };
+        assert_eq!(resp.retry_after(), None);
+        assert_eq!(resp.content_length(), None);
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let now = UNIX_EPOCH + Duration::from_secs(1445412400);
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after_at(&headers, now), None);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static(" 120 "));
+        assert_eq!(
+            retry_after_at(&headers, now),
+            Some(Duration::from_secs(120))
+        );
+
+        // 1445412480 is 80 seconds after now
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(retry_after_at(&headers, now), Some(Duration::from_secs(80)));
+        let later = now + Duration::from_secs(300);
+        assert_eq!(retry_after_at(&headers, later), Some(Duration::ZERO));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("soon"));
+        assert_eq!(retry_after_at(&headers, now), None);
+    }
+
+    #[test]
+    fn test_convert_error() {
+        let cl = HttpClient::default();
+        let body = format!("<items><item id=\"1\">{}", "x".repeat(1000));
+        let err = cl
+            .convert(
+                "https://bgg/xmlapi2/thing?id=1",
+                StatusCode::OK,
+                &HeaderMap::new(),
+                &body,
+            )
+            .unwrap_err();
+        match err {
+            RbggError::XmlParse {
+                status,
+                url,
+                snippet,
+                ..
+            } => {
+                assert_eq!(status, Some(200));
+                assert_eq!(url.as_deref(), Some("https://bgg/xmlapi2/thing?id=1"));
+                assert!(snippet.starts_with("<items><item id=\"1\">xxx"));
+                assert_eq!(snippet.len(), 503);
+            }
+            _ => panic!("Expected an XML parse error, got {:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_rate_limited() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("30"));
+
+        let err = check_status(StatusCode::TOO_MANY_REQUESTS, &headers).unwrap_err();
+        assert!(matches!(
+            err,
+            RbggError::RateLimited { retry_after: Some(d) } if d == Duration::from_secs(30)
+        ));
+        let cl = HttpClient::default();
+        let err = cl
+            .convert(
+                "u",
+                StatusCode::TOO_MANY_REQUESTS,
+                &HeaderMap::new(),
+                "<a/>",
+            )
+            .unwrap_err();
+        assert!(matches!(err, RbggError::RateLimited { retry_after: None }));
+
+        let value = serde_json::json!({"error": {"message": "Rate limit exceeded."}});
+        let err = check_error_response(&value, &headers).unwrap_err();
+        assert!(matches!(
+            err,
+            RbggError::RateLimited {
+                retry_after: Some(_)
+            }
+        ));
+        let value = serde_json::json!({"error": {"message": "Bad id"}});
+        let err = check_error_response(&value, &headers).unwrap_err();
+        assert!(matches!(err, RbggError::Api { message } if message == "Bad id"));
+        assert!(check_error_response(&serde_json::json!({"items": {}}), &headers).is_ok());
+    }
+
+    #[test]
+    fn test_validators() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(Validated::from_response(&headers, "<a/>"), None);
+
+        headers.insert(ETAG, HeaderValue::from_static("\"abc\""));
+        headers.insert(
+            LAST_MODIFIED,
+            HeaderValue::from_static("Mon, 01 Jan 2024 00:00:00 GMT"),
+        );
+        let validated = Validated::from_response(&headers, "<a/>").unwrap();
+        let cond = validated.headers();
+        assert_eq!(cond[IF_NONE_MATCH], "\"abc\"");
+        assert_eq!(cond[IF_MODIFIED_SINCE], "Mon, 01 Jan 2024 00:00:00 GMT");
+
+        let mut validators = Validators::new(2);
+        validators.insert("a", validated.clone());
+        validators.insert("b", validated.clone());
+        validators.insert("a", validated.clone());
+        assert_eq!(validators.get("a").unwrap().body, "<a/>");
+        // The oldest is forgotten once we're over capacity
+        validators.insert("c", validated);
+        assert!(validators.get("a").is_none());
+        assert!(validators.get("b").is_some());
+        assert!(validators.get("c").is_some());
+    }
+
+    #[test]
+    fn test_conditional_config() {
+        let headers = {
+            let mut h = HeaderMap::new();
+            h.insert(ETAG, HeaderValue::from_static("\"abc\""));
+            h
+        };
+        let url = "https://boardgamegeek.com/xmlapi2/hot";
+
+        // Nothing is remembered unless conditional requests are on
+        let cl = HttpClient::default();
+        cl.store_validated(url, StatusCode::OK, &headers, "<a/>");
+        assert!(cl.validated(url).is_none());
+
+        let cl = HttpClient::new(HttpConfig {
+            conditional: true,
+            ..Default::default()
+        })
+        .unwrap();
+        cl.store_validated(url, StatusCode::ACCEPTED, &headers, "");
+        assert!(cl.validated(url).is_none());
+        cl.store_validated(url, StatusCode::OK, &headers, "<a/>");
+        assert_eq!(cl.validated(url).unwrap().body, "<a/>");
+        // The order of the params doesn't matter
+        cl.store_validated(
+            "https://bgg/xmlapi2/thing?id=1&stats=1",
+            StatusCode::OK,
+            &headers,
+            "<b/>",
+        );
+        let validated = cl.validated("https://bgg/xmlapi2/thing?stats=1&id=1");
+        assert_eq!(validated.unwrap().body, "<b/>");
+        // A handle that skips the cache gets a full response
+        assert!(cl
+            .with_call_config(&CallConfig::no_cache())
+            .validated(url)
+            .is_none());
+    }
+
+    #[test]
+    fn test_redact_url() {
+        assert_eq!(
+            redact_url("https://boardgamegeek.com/xmlapi2/thing?id=1&stats=1"),
+            ("/xmlapi2/thing".to_string(), "id=1&stats=1".to_string())
+        );
+        assert_eq!(
+            redact_url("https://example.com/api?name=me&Token=abc"),
+            ("/api".to_string(), "name=me&Token=REDACTED".to_string())
+        );
+        assert_eq!(
+            redact_url("https://example.com"),
+            ("/".to_string(), "".to_string())
+        );
+    }
+
+    #[test]
+    fn test_queue_delay() {
+        let cl = HttpClient::new(HttpConfig {
+            queue: QueuePolicy {
+                max_attempts: 3,
+                delay: Duration::from_secs(1),
+                backoff: 2.0,
+                max_delay: Duration::from_secs(60),
+            },
+            ..Default::default()
+        })
+        .unwrap();
+        let mut waited = Duration::ZERO;
+
+        assert_eq!(
+            cl.queue_delay(1, &mut waited).unwrap(),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            cl.queue_delay(2, &mut waited).unwrap(),
+            Duration::from_secs(2)
+        );
+        let err = cl.queue_delay(3, &mut waited).unwrap_err();
+        match err {
+            RbggError::QueueTimeout { attempts, waited } => {
+                assert_eq!(attempts, 3);
+                assert_eq!(waited, Duration::from_secs(3));
+            }
+            _ => panic!("Expected a queue timeout, got {:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_check_html() {
+        let page = "<!DOCTYPE html><html><body>Down for maintenance</body></html>";
+        let html = HeaderMap::from_iter([(CONTENT_TYPE, HeaderValue::from_static("text/html"))]);
+
+        // Sniffed from the body alone, with a 200
+        let err = check_html(StatusCode::OK, &HeaderMap::new(), page).unwrap_err();
+        assert!(matches!(
+            err,
+            RbggError::Maintenance {
+                status: 200,
+                retry_after: None
+            }
+        ));
+
+        // Any HTML with a 503, passing along the Retry-After
+        let mut headers = html.clone();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("120"));
+        let err =
+            check_html(StatusCode::SERVICE_UNAVAILABLE, &headers, "<html></html>").unwrap_err();
+        assert!(matches!(
+            err,
+            RbggError::Maintenance {
+                status: 503,
+                retry_after: Some(d),
+            } if d == Duration::from_secs(120)
+        ));
+
+        // Normal responses pass through
+        let xml = "<?xml version=\"1.0\"?><items></items>";
+        assert!(check_html(StatusCode::OK, &HeaderMap::new(), xml).is_ok());
+
+        // Any other HTML is an error too
+        let err = check_html(StatusCode::OK, &html, "<html>\n  Other</html>").unwrap_err();
+        assert!(matches!(
+            err,
+            RbggError::ServiceUnavailable { status: 200, snippet } if snippet == "<html> Other</html>"
+        ));
+    }
+}
@@ -76,12 +76,57 @@ well.
 Beyond that, you are pretty much just following what the docs say on BGG's
 site as that's what the library implements.  Happy gaming!
 
+## Throttling
+BGG will throttle clients that hammer the API, so by default, clients are
+limited to about 2 requests per second (with small bursts).  You can relax
+or disable this via the `throttle` setting in `http::HttpConfig`.
+
+Requests that fail for a transient reason (a 429, a 5xx or a dropped
+connection) are retried a few times with an exponential backoff before the
+error is returned.  See `retry::RetryPolicy` to change this.
+
+## Errors
+Everything returns an `RbggError`, which says what kind of failure it was
+(e.g. `RbggError::RateLimited` or `RbggError::Maintenance`), so you can
+match on it rather than on the message.  See `errors`.
+
+## Caching
+Responses can be cached, so that repeated lookups don't go to BGG at all,
+by setting a `cache::ResponseCache` on the client.
+
+## Optional Features
+* `async` (default): The async calls, along with reqwest (and tokio) to send
+  them.  Without it, only the blocking (`_b`) calls are available.
+* `native-tls` (default): Use the platform's TLS stack for HTTPS.
+* `rustls`: Use rustls for HTTPS instead, which needs no system libraries.
+  Build with `default-features = false` to leave out native-tls.  With
+  reqwest, one of the two is needed to talk to BGG, which is only served
+  over HTTPS.
+* `fast-xml`: Convert the XML responses to JSON in a single streaming pass
+  (see `xml`), which is quicker for large collection and comments responses.
+* `report`: Render a collection as a self-contained HTML page (see `report`).
+* `unofficial`: A client for the site's undocumented (and unstable) JSON
+  endpoints (see `unofficial`).
+* `testing`: Sample responses and a mock server for testing code built on
+  rbgg (see `testing`).
+* `ureq`: A small, blocking HTTP transport (see `transport::UreqTransport`).
+  Build with `default-features = false, features = ["ureq"]` for a
+  blocking-only build without tokio or reqwest, where it's what the calls
+  use.  With `async` on as well, it's only used when you set it as the
+  `transport` in `http::HttpConfig`.
+* `redis`: A response cache stored in Redis (see `cache::RedisCache`).
+* `tracing`: Record a `bgg_request` span, via the `tracing` crate, for every
+  request, with the endpoint, params, status, retries and latency.
+
 ## Caveats to Be Aware Of
-* The library doesn't do things like automatic pagination
-  collection.  So, if there is more than 1 page of results, it is up to you
-  to handle this.  The upside is that you have easy access to this data.
-* If there is an error in the response itself, it is up to you to handle that
-  in the JSON response. It will look something like this:
+* The calls for paginated endpoints (like plays, comments and forums)
+  return the one page that was asked for, as BGG does.  To fetch every page,
+  use `Client2::paginator()`, the `*_stream()` calls (like `plays_stream()`)
+  or `Client2::collect_all_pages()`, which merges them into one response.
+* When BGG answers with an error response, rather than the data, it is
+  returned as an `RbggError::Api` with BGG's message.  If you'd rather handle
+  these yourself, turn off `api_errors` in `http::HttpConfig` and they come
+  back as JSON, looking something like this:
 
 ```json
 {
@@ -91,11 +136,42 @@ site as that's what the library implements.  Happy gaming!
 }
 ```
  */
-extern crate reqwest;
+#[cfg(not(any(feature = "async", feature = "ureq")))]
+compile_error!("rbgg needs a transport: turn on the `async` or `ureq` feature");
+
 extern crate serde_json;
 extern crate urlencoding;
 extern crate xmltojson;
 
+pub mod analysis;
+pub mod api;
 pub mod bgg1;
 pub mod bgg2;
+pub mod bgstats;
+pub mod breaker;
+pub mod cache;
+pub mod cancel;
+pub mod errors;
+pub mod http;
+pub mod metrics;
+pub mod models;
+pub mod query;
+#[cfg(feature = "async")]
+pub mod refresh;
+#[cfg(feature = "report")]
+pub mod report;
+pub mod retry;
+pub mod session;
+pub mod snapshot;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod throttle;
+pub mod transport;
+#[cfg(feature = "unofficial")]
+pub mod unofficial;
 pub mod utils;
+pub mod vcr;
+#[cfg(feature = "fast-xml")]
+pub mod xml;
+
+pub use errors::{RbggError, Result};
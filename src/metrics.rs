@@ -0,0 +1,226 @@
+/*!
+Hooks for recording metrics about the requests made to BGG.  Set a
+`MetricsSink` in the `http::HttpConfig` and it will be called once for
+every request, with the endpoint, the outcome, how many times it was
+retried or queued, and how long it took.  Forward these on to whatever
+metrics system you use, or use the `RequestMetrics` sink here, which keeps
+running totals per endpoint that can be exported on demand (e.g. from a
+Prometheus scrape handler).
+
+```ignore,rust
+use rbgg::{bgg2::{Client2, Hotness}, http::HttpConfig, metrics::RequestMetrics};
+use std::sync::Arc;
+
+let metrics = Arc::new(RequestMetrics::new());
+let config = HttpConfig {
+    metrics: Some(metrics.clone()),
+    ..Default::default()
+};
+let cl = Client2::new_with_config(None, None, config)?;
+cl.hot_b(Hotness::BoardGame, None)?;
+
+for (endpoint, m) in metrics.snapshot() {
+    println!("{}: {} requests, {} errors", endpoint, m.requests, m.errors);
+}
+```
+*/
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// The details of a single finished request
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequestEvent {
+    /// The HTTP method, e.g. "GET"
+    pub method: &'static str,
+    /// The path of the endpoint, with any IDs in it replaced by ":id" to
+    /// keep the number of distinct endpoints down (e.g. "/xmlapi2/thing" or
+    /// "/xmlapi/boardgame/:id")
+    pub endpoint: String,
+    /// The final HTTP status, if there was a response at all
+    pub status: Option<u16>,
+    /// Whether the request failed, either with an error status or without
+    /// getting a response
+    pub error: bool,
+    /// The number of times the request was retried
+    pub retries: u32,
+    /// The number of times BGG queued the request (with a 202)
+    pub queued: u32,
+    /// The total time taken, including any retries and 202 waits
+    pub latency: Duration,
+}
+
+/// Something that records metrics about requests.  This is called on the
+/// thread that made the request, so it should be quick.
+pub trait MetricsSink: fmt::Debug + Send + Sync {
+    /// Record a finished request
+    fn record(&self, event: &RequestEvent);
+}
+
+/// The running totals for an endpoint
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EndpointMetrics {
+    pub requests: u64,
+    pub errors: u64,
+    pub retries: u64,
+    pub queued: u64,
+    /// The sum of the request latencies
+    pub total_latency: Duration,
+    pub max_latency: Duration,
+}
+
+impl EndpointMetrics {
+    /// The fraction of requests that failed
+    pub fn error_rate(&self) -> f64 {
+        if self.requests == 0 {
+            return 0.0;
+        }
+
+        return self.errors as f64 / self.requests as f64;
+    }
+
+    /// The average time taken per request
+    pub fn mean_latency(&self) -> Duration {
+        if self.requests == 0 {
+            return Duration::ZERO;
+        }
+
+        // The count doesn't fit a u32, which is all a Duration divides by
+        let nanos = self.total_latency.as_nanos() / self.requests as u128;
+
+        return Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX));
+    }
+}
+
+/// A `MetricsSink` that keeps running totals per endpoint in memory
+#[derive(Debug, Default)]
+pub struct RequestMetrics {
+    endpoints: Mutex<BTreeMap<String, EndpointMetrics>>,
+}
+
+impl RequestMetrics {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    /// Return a copy of the totals so far, by endpoint
+    pub fn snapshot(&self) -> BTreeMap<String, EndpointMetrics> {
+        return self
+            .endpoints
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+    }
+
+    /// The totals across all endpoints
+    pub fn total(&self) -> EndpointMetrics {
+        let mut ret = EndpointMetrics::default();
+
+        for m in self.snapshot().values() {
+            ret.requests += m.requests;
+            ret.errors += m.errors;
+            ret.retries += m.retries;
+            ret.queued += m.queued;
+            ret.total_latency += m.total_latency;
+            ret.max_latency = ret.max_latency.max(m.max_latency);
+        }
+
+        return ret;
+    }
+}
+
+impl MetricsSink for RequestMetrics {
+    fn record(&self, event: &RequestEvent) {
+        let mut endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        let m = endpoints.entry(event.endpoint.clone()).or_default();
+
+        m.requests += 1;
+        if event.error {
+            m.errors += 1;
+        }
+        m.retries += event.retries as u64;
+        m.queued += event.queued as u64;
+        m.total_latency += event.latency;
+        m.max_latency = m.max_latency.max(event.latency);
+    }
+}
+
+/// Turn the path of a url into an endpoint name, replacing any segments
+/// that are IDs (or lists of them) with ":id"
+pub(crate) fn endpoint_name(path: &str) -> String {
+    let segments: Vec<&str> = path
+        .split('/')
+        .map(|seg| {
+            if seg.starts_with(|c: char| c.is_ascii_digit()) {
+                return ":id";
+            }
+            return seg;
+        })
+        .collect();
+
+    return segments.join("/");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(endpoint: &str, error: bool, latency_ms: u64) -> RequestEvent {
+        return RequestEvent {
+            method: "GET",
+            endpoint: endpoint.into(),
+            status: Some(if error { 500 } else { 200 }),
+            error,
+            retries: if error { 3 } else { 0 },
+            queued: 1,
+            latency: Duration::from_millis(latency_ms),
+        };
+    }
+
+    #[test]
+    fn test_request_metrics() {
+        let metrics = RequestMetrics::new();
+        metrics.record(&event("/xmlapi2/thing", false, 100));
+        metrics.record(&event("/xmlapi2/thing", true, 300));
+        metrics.record(&event("/xmlapi2/user", false, 50));
+
+        let snap = metrics.snapshot();
+        assert_eq!(snap.len(), 2);
+        let thing = snap["/xmlapi2/thing"];
+        assert_eq!(thing.requests, 2);
+        assert_eq!(thing.errors, 1);
+        assert_eq!(thing.retries, 3);
+        assert_eq!(thing.queued, 2);
+        assert_eq!(thing.error_rate(), 0.5);
+        assert_eq!(thing.mean_latency(), Duration::from_millis(200));
+
+        // A count past u32::MAX
+        let busy = EndpointMetrics {
+            requests: 1 << 32,
+            total_latency: Duration::from_secs(1 << 32),
+            ..Default::default()
+        };
+        assert_eq!(busy.mean_latency(), Duration::from_secs(1));
+        assert_eq!(EndpointMetrics::default().mean_latency(), Duration::ZERO);
+        assert_eq!(thing.max_latency, Duration::from_millis(300));
+
+        let total = metrics.total();
+        assert_eq!(total.requests, 3);
+        assert_eq!(total.max_latency, Duration::from_millis(300));
+        assert_eq!(EndpointMetrics::default().error_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_endpoint_name() {
+        assert_eq!(endpoint_name("/xmlapi2/thing"), "/xmlapi2/thing");
+        assert_eq!(
+            endpoint_name("/xmlapi/boardgame/1%2C2"),
+            "/xmlapi/boardgame/:id"
+        );
+        assert_eq!(
+            endpoint_name("/xmlapi/geeklist/123"),
+            "/xmlapi/geeklist/:id"
+        );
+    }
+}
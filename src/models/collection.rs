@@ -0,0 +1,226 @@
+/*!
+The typed model for a user's collection (from either version of the API)
+*/
+use crate::errors::Result;
+use crate::models::{de, warnings};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// A user's collection
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Collection {
+    #[serde(rename = "@totalitems", default, deserialize_with = "de::num")]
+    pub total_items: usize,
+    #[serde(rename = "item", default, deserialize_with = "de::one_or_many")]
+    pub items: Vec<CollectionItem>,
+}
+
+impl Collection {
+    /// Parse a collection from a `collection()` response
+    pub fn from_value(value: &Value) -> Result<Self> {
+        return super::from_node(value, "items");
+    }
+}
+
+/// A single item in a collection
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct CollectionItem {
+    /// The ID of the game (or other thing)
+    #[serde(rename = "@objectid", deserialize_with = "de::num")]
+    pub id: usize,
+    /// The ID of this entry in the collection.  The same game can be in a
+    /// collection multiple times (e.g. different versions) under different
+    /// collection IDs.
+    #[serde(rename = "@collid", default, deserialize_with = "de::opt_num")]
+    pub coll_id: Option<usize>,
+    #[serde(rename = "@subtype", default)]
+    pub subtype: String,
+    #[serde(default = "missing_name", deserialize_with = "de::text")]
+    pub name: String,
+    #[serde(rename = "yearpublished", default, deserialize_with = "de::opt_num")]
+    pub year_published: Option<i32>,
+    #[serde(default, deserialize_with = "de::opt_text")]
+    pub image: Option<String>,
+    #[serde(default, deserialize_with = "de::opt_text")]
+    pub thumbnail: Option<String>,
+    /// Only present when `stats=1` is requested
+    #[serde(default)]
+    pub stats: Option<CollectionStats>,
+    #[serde(default = "missing_status")]
+    pub status: CollectionStatus,
+    #[serde(rename = "numplays", default, deserialize_with = "de::num")]
+    pub num_plays: u32,
+}
+
+impl CollectionItem {
+    /// The user's own rating, if they have rated it (requires stats)
+    pub fn user_rating(&self) -> Option<f64> {
+        return self.stats.as_ref().and_then(|s| s.rating.value);
+    }
+
+    /// The average rating on BGG (requires stats)
+    pub fn average_rating(&self) -> Option<f64> {
+        return self.stats.as_ref().and_then(|s| s.rating.average);
+    }
+}
+
+/// The status flags for an item in a collection
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct CollectionStatus {
+    #[serde(rename = "@own", default, deserialize_with = "de::flag")]
+    pub own: bool,
+    #[serde(rename = "@prevowned", default, deserialize_with = "de::flag")]
+    pub prev_owned: bool,
+    #[serde(rename = "@fortrade", default, deserialize_with = "de::flag")]
+    pub for_trade: bool,
+    #[serde(rename = "@want", default, deserialize_with = "de::flag")]
+    pub want: bool,
+    #[serde(rename = "@wanttoplay", default, deserialize_with = "de::flag")]
+    pub want_to_play: bool,
+    #[serde(rename = "@wanttobuy", default, deserialize_with = "de::flag")]
+    pub want_to_buy: bool,
+    #[serde(rename = "@wishlist", default, deserialize_with = "de::flag")]
+    pub wishlist: bool,
+    /// From 1 (must have) to 5 (don't buy this), only set for wishlist items
+    #[serde(
+        rename = "@wishlistpriority",
+        default,
+        deserialize_with = "de::opt_num"
+    )]
+    pub wishlist_priority: Option<u8>,
+    #[serde(rename = "@preordered", default, deserialize_with = "de::flag")]
+    pub preordered: bool,
+    #[serde(rename = "@lastmodified", default)]
+    pub last_modified: Option<String>,
+}
+
+/// The stats for an item in a collection, when `stats=1` is requested
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct CollectionStats {
+    #[serde(rename = "@minplayers", default, deserialize_with = "de::opt_num")]
+    pub min_players: Option<u32>,
+    #[serde(rename = "@maxplayers", default, deserialize_with = "de::opt_num")]
+    pub max_players: Option<u32>,
+    #[serde(rename = "@minplaytime", default, deserialize_with = "de::opt_num")]
+    pub min_playtime: Option<u32>,
+    #[serde(rename = "@maxplaytime", default, deserialize_with = "de::opt_num")]
+    pub max_playtime: Option<u32>,
+    #[serde(rename = "@playingtime", default, deserialize_with = "de::opt_num")]
+    pub playing_time: Option<u32>,
+    #[serde(rename = "@numowned", default, deserialize_with = "de::opt_num")]
+    pub num_owned: Option<usize>,
+    #[serde(default)]
+    pub rating: CollectionRating,
+}
+
+/// The ratings for an item in a collection
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct CollectionRating {
+    /// The user's rating, which is "N/A" in the response if unrated
+    #[serde(rename = "@value", default, deserialize_with = "de::opt_num")]
+    pub value: Option<f64>,
+    #[serde(rename = "usersrated", default, deserialize_with = "de::opt_num")]
+    pub users_rated: Option<usize>,
+    #[serde(default, deserialize_with = "de::opt_num")]
+    pub average: Option<f64>,
+    #[serde(rename = "bayesaverage", default, deserialize_with = "de::opt_num")]
+    pub bayes_average: Option<f64>,
+}
+
+/// Every item should have a name and status, so warn when one is missing
+fn missing_name() -> String {
+    warnings::missing("name");
+
+    return String::new();
+}
+
+fn missing_status() -> CollectionStatus {
+    warnings::missing("status");
+
+    return CollectionStatus::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_collection() {
+        let data = json!({
+            "items": {
+                "@totalitems": "2",
+                "@termsofuse": "https://boardgamegeek.com/xmlapi/termsofuse",
+                "item": [
+                    {
+                        "@objecttype": "thing",
+                        "@objectid": "136888",
+                        "@subtype": "boardgame",
+                        "@collid": "1234",
+                        "name": {"@sortindex": "1", "#text": "Bruges"},
+                        "yearpublished": "2013",
+                        "thumbnail": "https://example.com/thumb.jpg",
+                        "status": {
+                            "@own": "1",
+                            "@prevowned": "0",
+                            "@fortrade": "0",
+                            "@want": "0",
+                            "@wanttoplay": "0",
+                            "@wanttobuy": "0",
+                            "@wishlist": "0",
+                            "@preordered": "0",
+                            "@lastmodified": "2023-01-01 10:00:00",
+                        },
+                        "numplays": "12",
+                        "stats": {
+                            "@minplayers": "2",
+                            "@maxplayers": "4",
+                            "@playingtime": "60",
+                            "rating": {
+                                "@value": "8",
+                                "usersrated": {"@value": "10000"},
+                                "average": {"@value": "7.3"},
+                                "bayesaverage": {"@value": "7.0"},
+                            },
+                        },
+                    },
+                    {
+                        "@objectid": "133473",
+                        "@subtype": "boardgame",
+                        "name": {"@sortindex": "1", "#text": "Sushi Go!"},
+                        "stats": {"rating": {"@value": "N/A", "average": {"@value": "7.0"}}},
+                    },
+                ],
+            },
+        });
+
+        let coll = Collection::from_value(&data).unwrap();
+        assert_eq!(coll.total_items, 2);
+        assert_eq!(coll.items.len(), 2);
+
+        let item = &coll.items[0];
+        assert_eq!(item.id, 136888);
+        assert_eq!(item.coll_id, Some(1234));
+        assert_eq!(item.name, "Bruges");
+        assert_eq!(item.year_published, Some(2013));
+        assert_eq!(item.image, None);
+        assert!(item.status.own);
+        assert!(!item.status.wishlist);
+        assert_eq!(item.num_plays, 12);
+        assert_eq!(item.user_rating(), Some(8.0));
+        assert_eq!(item.average_rating(), Some(7.3));
+        let stats = item.stats.as_ref().unwrap();
+        assert_eq!(stats.max_players, Some(4));
+        assert_eq!(stats.rating.users_rated, Some(10000));
+
+        assert_eq!(coll.items[1].user_rating(), None);
+        assert_eq!(coll.items[1].average_rating(), Some(7.0));
+
+        // A single item isn't wrapped in an array
+        let data = json!({"items": {"@totalitems": "1", "item": {"@objectid": "1"}}});
+        let coll = Collection::from_value(&data).unwrap();
+        assert_eq!(coll.items.len(), 1);
+        assert_eq!(coll.items[0].id, 1);
+        assert!(coll.items[0].stats.is_none());
+    }
+}
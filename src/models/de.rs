@@ -0,0 +1,231 @@
+/*!
+Deserialization helpers for dealing with the quirks of the JSON converted
+from BGG's XML
+*/
+use crate::models::warnings;
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{
+    DeserializeOwned, DeserializeSeed, Deserializer, Error, IntoDeserializer, MapAccess, SeqAccess,
+    Visitor,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+/// An element that can occur one or more times comes through as an object
+/// when there is only one of them, and an array otherwise.  This always
+/// gives back a `Vec`, and a missing (or empty) element is an empty `Vec`.
+///
+/// The elements are deserialized in place, rather than via a `Value`, so
+/// that errors and warnings can point at the exact element.
+pub fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    return deserializer.deserialize_any(OneOrMany(PhantomData));
+}
+
+struct OneOrMany<T>(PhantomData<T>);
+
+/// Deserializes a single element, reporting warnings against its model
+struct Element<T>(PhantomData<T>);
+
+impl<'de, T: DeserializeOwned> DeserializeSeed<'de> for Element<T> {
+    type Value = T;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<T, D::Error> {
+        return warnings::deserialize(deserializer);
+    }
+}
+
+impl<'de, T: DeserializeOwned> Visitor<'de> for OneOrMany<T> {
+    type Value = Vec<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return f.write_str("one or more elements");
+    }
+
+    fn visit_unit<E: Error>(self) -> Result<Vec<T>, E> {
+        return Ok(vec![]);
+    }
+
+    fn visit_none<E: Error>(self) -> Result<Vec<T>, E> {
+        return Ok(vec![]);
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<T>, A::Error> {
+        let mut ret = vec![];
+        while let Some(item) = seq.next_element_seed(Element(PhantomData))? {
+            ret.push(item);
+        }
+
+        return Ok(ret);
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Vec<T>, A::Error> {
+        return Ok(vec![warnings::deserialize(MapAccessDeserializer::new(
+            map,
+        ))?]);
+    }
+
+    fn visit_bool<E: Error>(self, v: bool) -> Result<Vec<T>, E> {
+        return Ok(vec![T::deserialize(v.into_deserializer())?]);
+    }
+
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Vec<T>, E> {
+        return Ok(vec![T::deserialize(v.into_deserializer())?]);
+    }
+
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Vec<T>, E> {
+        return Ok(vec![T::deserialize(v.into_deserializer())?]);
+    }
+
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Vec<T>, E> {
+        return Ok(vec![T::deserialize(v.into_deserializer())?]);
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Vec<T>, E> {
+        return Ok(vec![T::deserialize(v.into_deserializer())?]);
+    }
+}
+
+/// Parse a number, which BGG sends as a string (and sometimes as an
+/// element with a `value` attribute).  Blank values and placeholders like
+/// "N/A" become `None`.
+pub fn opt_num<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+{
+    return Ok(parse_num(&Value::deserialize(deserializer)?));
+}
+
+/// Like `opt_num()`, but missing values become the default (zero)
+pub fn num<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Default,
+{
+    return Ok(parse_num(&Value::deserialize(deserializer)?).unwrap_or_default());
+}
+
+/// Parse a "0"/"1" flag
+pub fn flag<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let ret: u8 = num(deserializer)?;
+
+    return Ok(ret != 0);
+}
+
+/// Get the text out of an element, which is either a plain string, or
+/// the `#text` key when the element also has attributes
+pub fn opt_text<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    return Ok(text_of(&Value::deserialize(deserializer)?));
+}
+
+/// Like `opt_text()`, but a missing element is an empty string
+pub fn text<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    return Ok(opt_text(deserializer)?.unwrap_or_default());
+}
+
+/// Get the text from a value, if there is any
+pub fn text_of(value: &Value) -> Option<String> {
+    return match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Object(map) => match map.get("#text").or_else(|| map.get("@value")) {
+            Some(v) => text_of(v),
+            None => None,
+        },
+        _ => None,
+    };
+}
+
+/// Parse a number from a value, if possible.  Anything other than a blank
+/// or a known placeholder that fails to parse is reported as a warning.
+pub fn parse_num<T: FromStr>(value: &Value) -> Option<T> {
+    let text = text_of(value)?;
+    let ret = text.trim().parse().ok();
+
+    if ret.is_none() && !PLACEHOLDERS.contains(&text.trim()) {
+        warnings::coercion(format!(
+            "\"{}\" is not a valid {}",
+            text,
+            std::any::type_name::<T>()
+        ));
+    }
+
+    return ret;
+}
+
+/// The values BGG uses in place of a number when there isn't one
+const PLACEHOLDERS: [&str; 3] = ["", "N/A", "Not Ranked"];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Deserialize)]
+    struct Test {
+        #[serde(default, deserialize_with = "one_or_many")]
+        items: Vec<usize>,
+        #[serde(default, deserialize_with = "opt_num")]
+        rating: Option<f64>,
+        #[serde(default, deserialize_with = "num")]
+        count: usize,
+        #[serde(default, deserialize_with = "flag")]
+        own: bool,
+        #[serde(default, deserialize_with = "text")]
+        name: String,
+    }
+
+    #[test]
+    fn test_helpers() {
+        let t: Test = serde_json::from_value(json!({
+            "items": [1, 2],
+            "rating": {"@value": "7.5"},
+            "count": "3",
+            "own": "1",
+            "name": {"@sortindex": "1", "#text": "Bruges"},
+        }))
+        .unwrap();
+
+        assert_eq!(t.items, vec![1, 2]);
+        assert_eq!(t.rating, Some(7.5));
+        assert_eq!(t.count, 3);
+        assert!(t.own);
+        assert_eq!(t.name, "Bruges");
+
+        let t: Test = serde_json::from_value(json!({
+            "items": 1,
+            "rating": "N/A",
+            "count": "",
+            "own": "0",
+            "name": "Bruges",
+        }))
+        .unwrap();
+
+        assert_eq!(t.items, vec![1]);
+        assert_eq!(t.rating, None);
+        assert_eq!(t.count, 0);
+        assert!(!t.own);
+        assert_eq!(t.name, "Bruges");
+
+        let t: Test = serde_json::from_value(json!({})).unwrap();
+        assert!(t.items.is_empty());
+        assert_eq!(t.rating, None);
+        assert_eq!(t.name, "");
+    }
+}
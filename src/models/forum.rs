@@ -0,0 +1,197 @@
+/*!
+The typed models for forums and their threads from the version 2 API
+*/
+use crate::errors::Result;
+use crate::models::de;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// A page of the threads in a forum
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Forum {
+    #[serde(rename = "@id", default, deserialize_with = "de::num")]
+    pub id: usize,
+    #[serde(rename = "@title", default)]
+    pub title: String,
+    /// The total number of threads across all pages
+    #[serde(rename = "@numthreads", default, deserialize_with = "de::num")]
+    pub num_threads: usize,
+    #[serde(rename = "@numposts", default, deserialize_with = "de::num")]
+    pub num_posts: usize,
+    #[serde(rename = "@lastpostdate", default)]
+    pub last_post_date: Option<String>,
+    #[serde(rename = "@noposting", default, deserialize_with = "de::flag")]
+    pub no_posting: bool,
+    #[serde(default, deserialize_with = "threads")]
+    pub threads: Vec<ThreadSummary>,
+}
+
+impl Forum {
+    /// Parse a page of a forum from a `forum()` response
+    pub fn from_value(value: &Value) -> Result<Self> {
+        return super::from_node(value, "forum");
+    }
+}
+
+/// A thread as listed in a forum
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ThreadSummary {
+    #[serde(rename = "@id", default, deserialize_with = "de::num")]
+    pub id: usize,
+    #[serde(rename = "@subject", default)]
+    pub subject: String,
+    /// The username of whoever started the thread
+    #[serde(rename = "@author", default)]
+    pub author: String,
+    #[serde(rename = "@numarticles", default, deserialize_with = "de::num")]
+    pub num_articles: usize,
+    #[serde(rename = "@postdate", default)]
+    pub post_date: Option<String>,
+    #[serde(rename = "@lastpostdate", default)]
+    pub last_post_date: Option<String>,
+}
+
+/// A thread, along with (some of) its articles
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Thread {
+    #[serde(rename = "@id", default, deserialize_with = "de::num")]
+    pub id: usize,
+    /// The total number of articles in the thread
+    #[serde(rename = "@numarticles", default, deserialize_with = "de::num")]
+    pub num_articles: usize,
+    #[serde(rename = "@link", default)]
+    pub link: String,
+    #[serde(default, deserialize_with = "de::text")]
+    pub subject: String,
+    #[serde(default, deserialize_with = "articles")]
+    pub articles: Vec<Article>,
+}
+
+impl Thread {
+    /// Parse a thread from a `thread()` response
+    pub fn from_value(value: &Value) -> Result<Self> {
+        return super::from_node(value, "thread");
+    }
+}
+
+/// A single post in a thread
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Article {
+    #[serde(rename = "@id", default, deserialize_with = "de::num")]
+    pub id: usize,
+    #[serde(rename = "@username", default)]
+    pub username: String,
+    #[serde(rename = "@link", default)]
+    pub link: String,
+    #[serde(rename = "@postdate", default)]
+    pub post_date: Option<String>,
+    #[serde(rename = "@editdate", default)]
+    pub edit_date: Option<String>,
+    #[serde(rename = "@numedits", default, deserialize_with = "de::num")]
+    pub num_edits: u32,
+    #[serde(default, deserialize_with = "de::text")]
+    pub subject: String,
+    /// The body of the post, which is HTML
+    #[serde(default, deserialize_with = "de::text")]
+    pub body: String,
+}
+
+/// The threads are nested inside a `threads` element
+fn threads<'de, D>(deserializer: D) -> Result<Vec<ThreadSummary>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Wrapper {
+        #[serde(default, deserialize_with = "de::one_or_many")]
+        thread: Vec<ThreadSummary>,
+    }
+
+    let ret: Option<Wrapper> = Option::deserialize(deserializer)?;
+
+    return Ok(ret.map(|w| w.thread).unwrap_or_default());
+}
+
+/// The articles are nested inside an `articles` element
+fn articles<'de, D>(deserializer: D) -> Result<Vec<Article>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Wrapper {
+        #[serde(default, deserialize_with = "de::one_or_many")]
+        article: Vec<Article>,
+    }
+
+    let ret: Option<Wrapper> = Option::deserialize(deserializer)?;
+
+    return Ok(ret.map(|w| w.article).unwrap_or_default());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_forum() {
+        let data = json!({
+            "forum": {
+                "@id": "19",
+                "@title": "General",
+                "@numthreads": "51",
+                "@numposts": "400",
+                "@lastpostdate": "Mon, 01 Jan 2024 00:00:00 +0000",
+                "@noposting": "0",
+                "@termsofuse": "https://boardgamegeek.com/xmlapi/termsofuse",
+                "threads": {
+                    "thread": [
+                        {
+                            "@id": "100",
+                            "@subject": "Hello",
+                            "@author": "someone",
+                            "@numarticles": "3",
+                            "@postdate": "Mon, 01 Jan 2024 00:00:00 +0000",
+                        },
+                        {"@id": "101", "@subject": "Again", "@numarticles": "1"},
+                    ],
+                },
+            },
+        });
+
+        let forum = Forum::from_value(&data).unwrap();
+        assert_eq!(forum.num_threads, 51);
+        assert!(!forum.no_posting);
+        assert_eq!(forum.threads.len(), 2);
+        assert_eq!(forum.threads[0].author, "someone");
+        assert_eq!(forum.threads[1].num_articles, 1);
+    }
+
+    #[test]
+    fn test_thread() {
+        let data = json!({
+            "thread": {
+                "@id": "100",
+                "@numarticles": "2",
+                "@link": "https://boardgamegeek.com/thread/100",
+                "subject": "Hello",
+                "articles": {
+                    "article": {
+                        "@id": "5",
+                        "@username": "someone",
+                        "@numedits": "1",
+                        "subject": "Hello",
+                        "body": "<b>Hi</b>",
+                    },
+                },
+            },
+        });
+
+        let thread = Thread::from_value(&data).unwrap();
+        assert_eq!(thread.subject, "Hello");
+        assert_eq!(thread.num_articles, 2);
+        assert_eq!(thread.articles.len(), 1);
+        assert_eq!(thread.articles[0].id, 5);
+        assert_eq!(thread.articles[0].body, "<b>Hi</b>");
+    }
+}
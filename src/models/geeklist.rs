@@ -0,0 +1,203 @@
+/*!
+The typed model for a geeklist (from the version 1 API)
+*/
+use crate::errors::Result;
+use crate::models::de;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// A geeklist
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct GeekList {
+    #[serde(rename = "@id", default, deserialize_with = "de::num")]
+    pub id: usize,
+    #[serde(default, deserialize_with = "de::text")]
+    pub title: String,
+    #[serde(default, deserialize_with = "de::text")]
+    pub username: String,
+    #[serde(default, deserialize_with = "de::text")]
+    pub description: String,
+    #[serde(rename = "postdate", default, deserialize_with = "de::opt_text")]
+    pub post_date: Option<String>,
+    #[serde(rename = "editdate", default, deserialize_with = "de::opt_text")]
+    pub edit_date: Option<String>,
+    #[serde(default, deserialize_with = "de::num")]
+    pub thumbs: u32,
+    /// The number of items in the whole list, which may be more than the
+    /// number of `items` fetched so far
+    #[serde(rename = "numitems", default, deserialize_with = "de::num")]
+    pub num_items: usize,
+    #[serde(rename = "item", default, deserialize_with = "de::one_or_many")]
+    pub items: Vec<GeekListItem>,
+    /// The comments on the list itself, only present when `comments=1` is
+    /// requested
+    #[serde(rename = "comment", default, deserialize_with = "de::one_or_many")]
+    pub comments: Vec<GeekListComment>,
+}
+
+impl GeekList {
+    /// Parse a geeklist from a `geeklist()` response
+    pub fn from_value(value: &Value) -> Result<Self> {
+        return super::from_node(value, "geeklist");
+    }
+
+    /// Whether all of the items in the list have been fetched
+    pub fn is_complete(&self) -> bool {
+        return self.items.len() >= self.num_items;
+    }
+
+    /// Merge the items from another fetch (page) of the same list into this
+    /// one, skipping any items we already have.  Returns the number of new
+    /// items added.
+    pub fn merge(&mut self, other: GeekList) -> usize {
+        let mut seen: HashSet<usize> = self.items.iter().map(|i| i.id).collect();
+        let before = self.items.len();
+
+        self.items
+            .extend(other.items.into_iter().filter(|i| seen.insert(i.id)));
+
+        return self.items.len() - before;
+    }
+}
+
+/// An item in a geeklist
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct GeekListItem {
+    /// The ID of the list item itself
+    #[serde(rename = "@id", default, deserialize_with = "de::num")]
+    pub id: usize,
+    #[serde(rename = "@objecttype", default)]
+    pub object_type: String,
+    #[serde(rename = "@subtype", default)]
+    pub subtype: String,
+    /// The ID of the thing (game, etc.) the item is about
+    #[serde(rename = "@objectid", default, deserialize_with = "de::num")]
+    pub object_id: usize,
+    #[serde(rename = "@objectname", default)]
+    pub object_name: String,
+    #[serde(rename = "@username", default)]
+    pub username: String,
+    #[serde(rename = "@postdate", default)]
+    pub post_date: Option<String>,
+    #[serde(rename = "@editdate", default)]
+    pub edit_date: Option<String>,
+    #[serde(rename = "@thumbs", default, deserialize_with = "de::num")]
+    pub thumbs: u32,
+    #[serde(rename = "@imageid", default, deserialize_with = "de::opt_num")]
+    pub image_id: Option<usize>,
+    #[serde(default, deserialize_with = "de::text")]
+    pub body: String,
+    /// The comments on this item, only present when `comments=1` is
+    /// requested
+    #[serde(rename = "comment", default, deserialize_with = "de::one_or_many")]
+    pub comments: Vec<GeekListComment>,
+}
+
+/// A comment on a geeklist or one of its items
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct GeekListComment {
+    #[serde(rename = "@username", default)]
+    pub username: String,
+    #[serde(rename = "@date", default)]
+    pub date: Option<String>,
+    #[serde(rename = "@postdate", default)]
+    pub post_date: Option<String>,
+    #[serde(rename = "@editdate", default)]
+    pub edit_date: Option<String>,
+    #[serde(rename = "@thumbs", default, deserialize_with = "de::num")]
+    pub thumbs: u32,
+    #[serde(rename = "#text", default)]
+    pub text: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_geeklist() {
+        let data = json!({
+            "geeklist": {
+                "@id": "12345",
+                "@termsofuse": "https://boardgamegeek.com/xmlapi/termsofuse",
+                "postdate": "Mon, 01 Jan 2023 10:00:00 +0000",
+                "thumbs": "12",
+                "numitems": "3",
+                "username": "someone",
+                "title": "Best Games",
+                "description": "My favorites",
+                "item": [
+                    {
+                        "@id": "1",
+                        "@objecttype": "thing",
+                        "@subtype": "boardgame",
+                        "@objectid": "136888",
+                        "@objectname": "Bruges",
+                        "@username": "someone",
+                        "@thumbs": "3",
+                        "@imageid": "0",
+                        "body": "So good",
+                        "comment": [
+                            {
+                                "@username": "other",
+                                "@date": "Tue, 02 Jan 2023 10:00:00 +0000",
+                                "@thumbs": "1",
+                                "#text": "Agreed!",
+                            },
+                            {"@username": "third", "@thumbs": "0", "#text": "Nope"},
+                        ],
+                    },
+                    {
+                        "@id": "2",
+                        "@objectid": "133473",
+                        "@objectname": "Sushi Go!",
+                        "@thumbs": "0",
+                        "body": null,
+                        "comment": {"@username": "other", "#text": "Yum"},
+                    },
+                ],
+                "comment": {"@username": "other", "@thumbs": "2", "#text": "Nice list"},
+            },
+        });
+
+        let mut list = GeekList::from_value(&data).unwrap();
+        assert_eq!(list.id, 12345);
+        assert_eq!(list.title, "Best Games");
+        assert_eq!(list.username, "someone");
+        assert_eq!(list.thumbs, 12);
+        assert_eq!(list.num_items, 3);
+        assert_eq!(list.items.len(), 2);
+        assert_eq!(list.items[0].object_id, 136888);
+        assert_eq!(list.items[0].thumbs, 3);
+        assert_eq!(list.items[0].body, "So good");
+        assert_eq!(list.items[1].body, "");
+        assert_eq!(list.comments.len(), 1);
+        assert_eq!(list.comments[0].thumbs, 2);
+
+        let comments = &list.items[0].comments;
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].username, "other");
+        assert_eq!(comments[0].thumbs, 1);
+        assert_eq!(comments[0].text, "Agreed!");
+        assert_eq!(list.items[1].comments[0].text, "Yum");
+        assert!(!list.is_complete());
+
+        // Merging skips the items we already have
+        let other = GeekList::from_value(&json!({
+            "geeklist": {
+                "numitems": "3",
+                "item": [
+                    {"@id": "2", "@objectid": "133473"},
+                    {"@id": "3", "@objectid": "1"},
+                ],
+            },
+        }))
+        .unwrap();
+
+        assert_eq!(list.merge(other), 1);
+        assert_eq!(list.items.len(), 3);
+        assert!(list.is_complete());
+    }
+}
@@ -0,0 +1,133 @@
+/*!
+The typed model for a guild from the version 2 API
+*/
+use crate::errors::Result;
+use crate::models::de;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// A guild, along with a page of its members when `members=1` is requested
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Guild {
+    #[serde(rename = "@id", default, deserialize_with = "de::num")]
+    pub id: usize,
+    #[serde(rename = "@name", default)]
+    pub name: String,
+    /// When the guild was created, as BGG formats it (e.g.
+    /// "Sat, 17 Oct 2009 15:25:51 +0000")
+    #[serde(rename = "@created", default)]
+    pub created: String,
+    #[serde(default, deserialize_with = "de::text")]
+    pub category: String,
+    #[serde(default, deserialize_with = "de::opt_text")]
+    pub website: Option<String>,
+    /// The username of the guild's manager
+    #[serde(default, deserialize_with = "de::text")]
+    pub manager: String,
+    #[serde(default, deserialize_with = "de::text")]
+    pub description: String,
+    #[serde(default)]
+    pub location: GuildLocation,
+    /// Only present when `members=1` is requested
+    #[serde(default)]
+    pub members: Option<GuildMembersPage>,
+}
+
+impl Guild {
+    /// Parse a guild from a `guild()` response
+    pub fn from_value(value: &Value) -> Result<Self> {
+        return super::from_node(value, "guild");
+    }
+}
+
+/// Where a guild is based.  Any of these may be blank.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct GuildLocation {
+    #[serde(default, deserialize_with = "de::text")]
+    pub addr1: String,
+    #[serde(default, deserialize_with = "de::text")]
+    pub addr2: String,
+    #[serde(default, deserialize_with = "de::text")]
+    pub city: String,
+    #[serde(rename = "stateorprovince", default, deserialize_with = "de::text")]
+    pub state_or_province: String,
+    #[serde(rename = "postalcode", default, deserialize_with = "de::text")]
+    pub postal_code: String,
+    #[serde(default, deserialize_with = "de::text")]
+    pub country: String,
+}
+
+/// A page of a guild's members
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct GuildMembersPage {
+    /// The total number of members across all pages
+    #[serde(rename = "@count", default, deserialize_with = "de::num")]
+    pub count: usize,
+    #[serde(rename = "@page", default, deserialize_with = "de::num")]
+    pub page: usize,
+    #[serde(rename = "member", default, deserialize_with = "de::one_or_many")]
+    pub members: Vec<GuildMember>,
+}
+
+/// A member of a guild
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct GuildMember {
+    /// The member's username
+    #[serde(rename = "@name", default)]
+    pub name: String,
+    /// When they joined the guild
+    #[serde(rename = "@date", default)]
+    pub date: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_guild() {
+        let data = json!({
+            "guild": {
+                "@id": "1303",
+                "@name": "Some Guild",
+                "@created": "Sat, 17 Oct 2009 15:25:51 +0000",
+                "@termsofuse": "https://boardgamegeek.com/xmlapi/termsofuse",
+                "category": "group",
+                "website": {},
+                "manager": "someuser",
+                "description": "We play games",
+                "location": {
+                    "addr1": {},
+                    "city": "Springfield",
+                    "stateorprovince": "IL",
+                    "country": "United States",
+                },
+                "members": {
+                    "@count": "31",
+                    "@page": "1",
+                    "member": [
+                        {"@name": "someuser", "@date": "Sat, 17 Oct 2009 15:25:51 +0000"},
+                        {"@name": "other", "@date": "Mon, 19 Oct 2009 01:00:00 +0000"},
+                    ],
+                },
+            },
+        });
+
+        let guild = Guild::from_value(&data).unwrap();
+        assert_eq!(guild.id, 1303);
+        assert_eq!(guild.name, "Some Guild");
+        assert_eq!(guild.manager, "someuser");
+        assert_eq!(guild.website, None);
+        assert_eq!(guild.location.city, "Springfield");
+        assert_eq!(guild.location.addr1, "");
+        let members = guild.members.as_ref().unwrap();
+        assert_eq!(members.count, 31);
+        assert_eq!(members.members.len(), 2);
+        assert_eq!(members.members[1].name, "other");
+
+        let data = json!({"guild": {"@id": "1", "@name": "Tiny"}});
+        let guild = Guild::from_value(&data).unwrap();
+        assert!(guild.members.is_none());
+    }
+}
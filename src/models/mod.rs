@@ -0,0 +1,130 @@
+/*!
+Typed models for BGG's responses.  The JSON converted from BGG's XML is
+full of attribute keys (like `"@value"`) and numbers that are really
+strings, and elements that appear once come through as an object rather
+than an array.  These models take care of all of that for you.
+
+Each model can be parsed from the `Value` returned by the corresponding
+client call.
+
+```ignore,rust
+use rbgg::{bgg2::Client2, models::Collection};
+
+let cl = Client2::new_from_defaults();
+let data = cl.collection_b("someuser", None)?;
+let coll = Collection::from_value(&data)?;
+```
+
+See `warnings` for finding out when a response doesn't match its model.
+*/
+use crate::errors::{RbggError, Result};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use serde_path_to_error::{Path, Segment, Track};
+use std::sync::Arc;
+
+pub mod collection;
+pub(crate) mod de;
+pub mod forum;
+pub mod geeklist;
+pub mod guild;
+pub mod plays;
+pub mod poll;
+pub mod search;
+pub mod thing;
+pub mod warnings;
+
+pub use collection::*;
+pub use forum::*;
+pub use geeklist::*;
+pub use guild::*;
+pub use plays::*;
+pub use poll::*;
+pub use search::*;
+pub use thing::*;
+
+/// The longest snippet of the offending JSON included in a parse error
+const SNIPPET_LEN: usize = 80;
+
+/// Deserialize a model from a (converted) BGG response.  On failure, this
+/// returns a `RbggError::Parse` saying where in the response it failed.
+pub fn from_value<T: DeserializeOwned>(value: &Value) -> Result<T> {
+    let mut track = Track::new();
+    let ret = warnings::deserialize(serde_path_to_error::Deserializer::new(value, &mut track));
+
+    return match ret {
+        Ok(ret) => Ok(ret),
+        Err(source) => {
+            let path = track.path();
+            Err(RbggError::Parse {
+                path: path.to_string(),
+                snippet: snippet(value, &path),
+                source: Arc::new(source),
+            })
+        }
+    };
+}
+
+/// Deserialize a model from the given top level element of a response,
+/// warning if the element isn't there
+fn from_node<T: DeserializeOwned>(value: &Value, node: &str) -> Result<T> {
+    if value.get(node).is_none() {
+        warnings::missing_model::<T>(node);
+    }
+
+    return from_value(&value[node]);
+}
+
+/// Find the JSON at the given path, and shorten it for an error message
+fn snippet(value: &Value, path: &Path) -> String {
+    let mut cur = value;
+    for seg in path.iter() {
+        let next = match seg {
+            Segment::Seq { index } => cur.get(index),
+            Segment::Map { key } => cur.get(key),
+            _ => None,
+        };
+        match next {
+            Some(v) => cur = v,
+            None => break,
+        }
+    }
+
+    let ret = cur.to_string();
+    if ret.chars().count() <= SNIPPET_LEN {
+        return ret;
+    }
+
+    return ret.chars().take(SNIPPET_LEN).collect::<String>() + "...";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_error() {
+        let data = json!({
+            "items": {
+                "item": [
+                    {"@id": "1", "@type": "boardgame"},
+                    {"@id": "2", "@type": "boardgame", "name": {"@type": ["bad"]}},
+                ],
+            },
+        });
+
+        let err = Things::from_value(&data).unwrap_err();
+        match &err {
+            RbggError::Parse { path, snippet, .. } => {
+                assert_eq!(path, "item[1].name.@type");
+                assert_eq!(snippet, r#"["bad"]"#);
+            }
+            _ => panic!("Unexpected error: {}", err),
+        }
+
+        let long = Value::String("x".repeat(100));
+        let snip = snippet(&long, &Track::new().path());
+        assert_eq!(snip.len(), SNIPPET_LEN + 3);
+    }
+}
@@ -0,0 +1,222 @@
+/*!
+The typed model for logged plays
+*/
+use crate::errors::Result;
+use crate::models::{de, warnings};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// A page of plays, either for a user or for an item
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Plays {
+    #[serde(rename = "@username", default)]
+    pub username: Option<String>,
+    #[serde(rename = "@userid", default, deserialize_with = "de::opt_num")]
+    pub user_id: Option<usize>,
+    /// The total number of plays across all pages
+    #[serde(rename = "@total", default, deserialize_with = "de::num")]
+    pub total: usize,
+    #[serde(rename = "@page", default, deserialize_with = "de::num")]
+    pub page: usize,
+    #[serde(rename = "play", default, deserialize_with = "de::one_or_many")]
+    pub plays: Vec<Play>,
+}
+
+impl Plays {
+    /// Parse the plays from a `plays()` response
+    pub fn from_value(value: &Value) -> Result<Self> {
+        return super::from_node(value, "plays");
+    }
+}
+
+/// A single logged play
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Play {
+    #[serde(rename = "@id", default, deserialize_with = "de::num")]
+    pub id: usize,
+    /// The date of the play as YYYY-MM-DD
+    #[serde(rename = "@date", default)]
+    pub date: String,
+    /// The number of times the game was played in this session
+    #[serde(rename = "@quantity", default, deserialize_with = "de::num")]
+    pub quantity: u32,
+    /// The length of the play in minutes
+    #[serde(rename = "@length", default, deserialize_with = "de::num")]
+    pub length: u32,
+    #[serde(rename = "@incomplete", default, deserialize_with = "de::flag")]
+    pub incomplete: bool,
+    #[serde(rename = "@nowinstats", default, deserialize_with = "de::flag")]
+    pub no_win_stats: bool,
+    #[serde(rename = "@location", default)]
+    pub location: String,
+    #[serde(default = "missing_item")]
+    pub item: PlayItem,
+    #[serde(default, deserialize_with = "de::opt_text")]
+    pub comments: Option<String>,
+    #[serde(default, deserialize_with = "players")]
+    pub players: Vec<Player>,
+}
+
+/// The game (or other item) that was played
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct PlayItem {
+    #[serde(rename = "@objectid", default, deserialize_with = "de::num")]
+    pub id: usize,
+    #[serde(rename = "@name", default)]
+    pub name: String,
+    #[serde(rename = "@objecttype", default)]
+    pub object_type: String,
+}
+
+/// A player in a logged play
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Player {
+    /// The BGG username, which is blank for players without an account
+    #[serde(rename = "@username", default)]
+    pub username: String,
+    #[serde(rename = "@userid", default, deserialize_with = "de::opt_num")]
+    pub user_id: Option<usize>,
+    #[serde(rename = "@name", default)]
+    pub name: String,
+    #[serde(rename = "@startposition", default)]
+    pub start_position: String,
+    #[serde(rename = "@color", default)]
+    pub color: String,
+    /// The raw score as entered, which isn't necessarily numeric
+    #[serde(rename = "@score", default)]
+    pub score: String,
+    #[serde(rename = "@new", default, deserialize_with = "de::flag")]
+    pub new: bool,
+    #[serde(rename = "@rating", default, deserialize_with = "de::opt_num")]
+    pub rating: Option<f64>,
+    #[serde(rename = "@win", default, deserialize_with = "de::flag")]
+    pub win: bool,
+}
+
+impl Player {
+    /// A name to identify the player by, preferring their BGG username
+    pub fn ident(&self) -> &str {
+        if self.username.is_empty() {
+            return &self.name;
+        }
+
+        return &self.username;
+    }
+
+    /// The score as a number, if one was entered and it is numeric
+    pub fn score_value(&self) -> Option<f64> {
+        return self.score.trim().parse().ok();
+    }
+
+    /// Whether this player is the given person, by BGG username or name
+    pub fn is(&self, who: &str) -> bool {
+        return (!self.username.is_empty() && self.username.eq_ignore_ascii_case(who))
+            || self.name.eq_ignore_ascii_case(who);
+    }
+}
+
+/// The players are nested inside a `players` element
+fn players<'de, D>(deserializer: D) -> Result<Vec<Player>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Players {
+        #[serde(default, deserialize_with = "de::one_or_many")]
+        player: Vec<Player>,
+    }
+
+    let ret: Option<Players> = Option::deserialize(deserializer)?;
+
+    return Ok(ret.map(|p| p.player).unwrap_or_default());
+}
+
+/// Every play should be of something, so warn when the item is missing
+fn missing_item() -> PlayItem {
+    warnings::missing("item");
+
+    return PlayItem::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_plays() {
+        let data = json!({
+            "plays": {
+                "@username": "someone",
+                "@userid": "42",
+                "@total": "2",
+                "@page": "1",
+                "play": [
+                    {
+                        "@id": "1001",
+                        "@date": "2023-01-05",
+                        "@quantity": "2",
+                        "@length": "60",
+                        "@incomplete": "0",
+                        "@nowinstats": "0",
+                        "@location": "Home",
+                        "item": {
+                            "@name": "Bruges",
+                            "@objecttype": "thing",
+                            "@objectid": "136888",
+                        },
+                        "comments": "Close game",
+                        "players": {
+                            "player": [
+                                {
+                                    "@username": "someone",
+                                    "@userid": "42",
+                                    "@name": "Some One",
+                                    "@score": "52",
+                                    "@new": "0",
+                                    "@rating": "0",
+                                    "@win": "1",
+                                },
+                                {
+                                    "@username": "",
+                                    "@userid": "0",
+                                    "@name": "Friend",
+                                    "@score": "48",
+                                    "@win": "0",
+                                },
+                            ],
+                        },
+                    },
+                    {
+                        "@id": "1002",
+                        "@date": "2023-01-06",
+                        "@quantity": "1",
+                        "item": {"@name": "Sushi Go!", "@objectid": "133473"},
+                    },
+                ],
+            },
+        });
+
+        let plays = Plays::from_value(&data).unwrap();
+        assert_eq!(plays.username, Some("someone".to_string()));
+        assert_eq!(plays.total, 2);
+        assert_eq!(plays.plays.len(), 2);
+
+        let play = &plays.plays[0];
+        assert_eq!(play.quantity, 2);
+        assert_eq!(play.location, "Home");
+        assert_eq!(play.item.id, 136888);
+        assert_eq!(play.comments, Some("Close game".to_string()));
+        assert_eq!(play.players.len(), 2);
+        assert!(play.players[0].win);
+        assert_eq!(play.players[0].ident(), "someone");
+        assert_eq!(play.players[1].ident(), "Friend");
+        assert_eq!(play.players[0].score_value(), Some(52.0));
+        assert!(play.players[0].is("SomeOne"));
+        assert!(!play.players[0].is("Friend"));
+        assert!(play.players[0].is("some one"));
+        assert!(play.players[1].is("friend"));
+
+        assert!(plays.plays[1].players.is_empty());
+    }
+}
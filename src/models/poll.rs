@@ -0,0 +1,374 @@
+/*!
+The typed models for the community polls on a thing, like the suggested
+number of players, the language dependence and the suggested player age.
+The polls all come through in the same generic shape, so `Poll` holds the
+raw votes, and the poll specific types (like `PlayerCountPoll`) turn those
+into something more useful.
+
+```ignore,rust
+use rbgg::bgg2::{Client2, Thing};
+
+let cl = Client2::new_from_defaults();
+let item = &cl.thing_typed_b(&vec![136888], &vec![Thing::BoardGame], None)?[0];
+if let Some(poll) = item.player_count_poll() {
+    println!("Best with {:?}", poll.best_player_counts());
+}
+```
+*/
+use crate::models::de;
+use serde::Deserialize;
+
+/// The name of the suggested number of players poll
+pub const PLAYER_COUNT_POLL: &str = "suggested_numplayers";
+/// The name of the language dependence poll
+pub const LANGUAGE_POLL: &str = "language_dependence";
+/// The name of the suggested player age poll
+pub const PLAYER_AGE_POLL: &str = "suggested_playerage";
+
+/// A community poll on a thing
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Poll {
+    /// The poll's name, e.g. "suggested_numplayers"
+    #[serde(rename = "@name", default)]
+    pub name: String,
+    #[serde(rename = "@title", default)]
+    pub title: String,
+    #[serde(rename = "@totalvotes", default, deserialize_with = "de::num")]
+    pub total_votes: usize,
+    #[serde(rename = "results", default, deserialize_with = "de::one_or_many")]
+    pub results: Vec<PollResults>,
+}
+
+/// A set of results in a poll.  Most polls have a single set, but the
+/// player count poll has one for each number of players.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct PollResults {
+    /// The number of players these results are for, like "3" or "4+".
+    /// Only set for the player count poll.
+    #[serde(rename = "@numplayers", default)]
+    pub num_players: Option<String>,
+    #[serde(rename = "result", default, deserialize_with = "de::one_or_many")]
+    pub results: Vec<PollResult>,
+}
+
+/// The votes for a single answer in a poll
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct PollResult {
+    /// The answer, e.g. "Best" or "Recommended"
+    #[serde(rename = "@value", default)]
+    pub value: String,
+    /// The level for ordered answers, like language dependence
+    #[serde(rename = "@level", default, deserialize_with = "de::opt_num")]
+    pub level: Option<u32>,
+    #[serde(rename = "@numvotes", default, deserialize_with = "de::num")]
+    pub num_votes: usize,
+}
+
+impl PollResults {
+    /// The number of votes for the given answer
+    pub fn votes_for(&self, value: &str) -> usize {
+        return self
+            .results
+            .iter()
+            .find(|r| r.value == value)
+            .map(|r| r.num_votes)
+            .unwrap_or(0);
+    }
+}
+
+/// The parsed suggested number of players poll
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlayerCountPoll {
+    pub total_votes: usize,
+    pub counts: Vec<PlayerCountVotes>,
+}
+
+/// The votes for a single number of players
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlayerCountVotes {
+    pub players: u32,
+    /// Set for the "N+" entry, which is for more than the listed maximum
+    /// number of players
+    pub more: bool,
+    pub best: usize,
+    pub recommended: usize,
+    pub not_recommended: usize,
+}
+
+impl PlayerCountVotes {
+    /// Whether "Best" got more votes than either of the other answers
+    pub fn is_best(&self) -> bool {
+        return self.best > 0 && self.best > self.recommended && self.best > self.not_recommended;
+    }
+
+    /// Whether "Best" and "Recommended" together outvote "Not Recommended"
+    pub fn is_recommended(&self) -> bool {
+        return self.best + self.recommended > self.not_recommended;
+    }
+}
+
+impl PlayerCountPoll {
+    /// Parse the votes from the generic poll.  Entries with a player count
+    /// that can't be parsed are skipped.
+    pub fn from_poll(poll: &Poll) -> Self {
+        let mut counts = vec![];
+
+        for res in &poll.results {
+            let num = res.num_players.as_deref().unwrap_or("").trim();
+            let (num, more) = match num.strip_suffix('+') {
+                Some(n) => (n, true),
+                None => (num, false),
+            };
+            let players = match num.parse() {
+                Ok(p) => p,
+                Err(_) => continue,
+            };
+
+            counts.push(PlayerCountVotes {
+                players,
+                more,
+                best: res.votes_for("Best"),
+                recommended: res.votes_for("Recommended"),
+                not_recommended: res.votes_for("Not Recommended"),
+            });
+        }
+
+        return Self {
+            total_votes: poll.total_votes,
+            counts,
+        };
+    }
+
+    /// The player counts where "Best" won the vote.  The "N+" entry is
+    /// left out, as it isn't a specific number of players.
+    pub fn best_player_counts(&self) -> Vec<u32> {
+        return self
+            .counts
+            .iter()
+            .filter(|c| !c.more && c.is_best())
+            .map(|c| c.players)
+            .collect();
+    }
+
+    /// The player counts that are recommended overall, including the ones
+    /// that are best
+    pub fn recommended_player_counts(&self) -> Vec<u32> {
+        return self
+            .counts
+            .iter()
+            .filter(|c| !c.more && c.is_recommended())
+            .map(|c| c.players)
+            .collect();
+    }
+}
+
+/// The parsed language dependence poll
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LanguagePoll {
+    pub total_votes: usize,
+    /// From the least (1) to the most (5) dependent
+    pub levels: Vec<LanguageLevel>,
+}
+
+/// The votes for a single level of language dependence
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LanguageLevel {
+    pub level: u32,
+    /// What the level means, e.g. "No necessary in-game text"
+    pub description: String,
+    pub votes: usize,
+}
+
+impl LanguagePoll {
+    /// Parse the votes from the generic poll.  Answers without a level are
+    /// skipped.
+    pub fn from_poll(poll: &Poll) -> Self {
+        let mut levels = vec![];
+
+        for res in poll.results.iter().flat_map(|r| &r.results) {
+            if let Some(level) = res.level {
+                levels.push(LanguageLevel {
+                    level,
+                    description: res.value.clone(),
+                    votes: res.num_votes,
+                });
+            }
+        }
+        levels.sort_by_key(|l| l.level);
+
+        return Self {
+            total_votes: poll.total_votes,
+            levels,
+        };
+    }
+
+    /// The level with the most votes, if anyone has voted.  Ties go to the
+    /// less dependent level.
+    pub fn modal_level(&self) -> Option<&LanguageLevel> {
+        return modal(&self.levels, |l| l.votes);
+    }
+}
+
+/// The parsed suggested player age poll
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlayerAgePoll {
+    pub total_votes: usize,
+    pub ages: Vec<AgeVotes>,
+}
+
+/// The votes for a single minimum age
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AgeVotes {
+    pub age: u32,
+    /// Set for the last answer, "21 and up"
+    pub and_up: bool,
+    pub votes: usize,
+}
+
+impl PlayerAgePoll {
+    /// Parse the votes from the generic poll.  Answers that don't start
+    /// with an age are skipped.
+    pub fn from_poll(poll: &Poll) -> Self {
+        let mut ages = vec![];
+
+        for res in poll.results.iter().flat_map(|r| &r.results) {
+            let digits: String = res
+                .value
+                .trim()
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            if let Ok(age) = digits.parse() {
+                ages.push(AgeVotes {
+                    age,
+                    and_up: res.value.contains("and up"),
+                    votes: res.num_votes,
+                });
+            }
+        }
+
+        return Self {
+            total_votes: poll.total_votes,
+            ages,
+        };
+    }
+
+    /// The age with the most votes, if anyone has voted.  Ties go to the
+    /// younger age.
+    pub fn modal_age(&self) -> Option<u32> {
+        return modal(&self.ages, |a| a.votes).map(|a| a.age);
+    }
+}
+
+/// The first entry with the most votes, or `None` if there are no votes
+fn modal<T, F>(entries: &[T], votes: F) -> Option<&T>
+where
+    F: Fn(&T) -> usize,
+{
+    let mut ret: Option<&T> = None;
+
+    for entry in entries {
+        if votes(entry) > ret.map(&votes).unwrap_or(0) {
+            ret = Some(entry);
+        }
+    }
+
+    return ret;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn results(num: &str, best: u32, rec: u32, not_rec: u32) -> serde_json::Value {
+        return json!({
+            "@numplayers": num,
+            "result": [
+                {"@value": "Best", "@numvotes": best.to_string()},
+                {"@value": "Recommended", "@numvotes": rec.to_string()},
+                {"@value": "Not Recommended", "@numvotes": not_rec.to_string()},
+            ],
+        });
+    }
+
+    #[test]
+    fn test_player_count_poll() {
+        let data = json!({
+            "@name": "suggested_numplayers",
+            "@title": "User Suggested Number of Players",
+            "@totalvotes": "120",
+            "results": [
+                results("1", 0, 2, 50),
+                results("2", 20, 60, 10),
+                results("3", 70, 30, 2),
+                results("4", 65, 40, 5),
+                results("4+", 0, 1, 80),
+                results("lots", 0, 0, 0),
+            ],
+        });
+
+        let poll: Poll = serde_json::from_value(data).unwrap();
+        assert_eq!(poll.name, PLAYER_COUNT_POLL);
+        assert_eq!(poll.total_votes, 120);
+        assert_eq!(poll.results[1].votes_for("Best"), 20);
+        assert_eq!(poll.results[1].votes_for("Meh"), 0);
+
+        let parsed = PlayerCountPoll::from_poll(&poll);
+        assert_eq!(parsed.counts.len(), 5);
+        assert!(parsed.counts[4].more);
+        assert_eq!(parsed.counts[4].players, 4);
+        assert_eq!(parsed.best_player_counts(), vec![3, 4]);
+        assert_eq!(parsed.recommended_player_counts(), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_language_poll() {
+        let data = json!({
+            "@name": "language_dependence",
+            "@totalvotes": "30",
+            "results": {
+                "result": [
+                    {"@level": "1", "@value": "No necessary in-game text", "@numvotes": "4"},
+                    {"@level": "2", "@value": "Some necessary text", "@numvotes": "20"},
+                    {"@level": "3", "@value": "Moderate in-game text", "@numvotes": "20"},
+                    {"@level": "4", "@value": "Extensive use of text", "@numvotes": "1"},
+                    {"@level": "5", "@value": "Unplayable in another language", "@numvotes": "0"},
+                ],
+            },
+        });
+
+        let poll: Poll = serde_json::from_value(data).unwrap();
+        let parsed = LanguagePoll::from_poll(&poll);
+        assert_eq!(parsed.total_votes, 30);
+        assert_eq!(parsed.levels.len(), 5);
+        let modal = parsed.modal_level().unwrap();
+        assert_eq!(modal.level, 2);
+        assert_eq!(modal.description, "Some necessary text");
+
+        assert_eq!(LanguagePoll::default().modal_level(), None);
+    }
+
+    #[test]
+    fn test_player_age_poll() {
+        let data = json!({
+            "@name": "suggested_playerage",
+            "@totalvotes": "12",
+            "results": {
+                "result": [
+                    {"@value": "8", "@numvotes": "2"},
+                    {"@value": "10", "@numvotes": "7"},
+                    {"@value": "12", "@numvotes": "3"},
+                    {"@value": "21 and up", "@numvotes": "0"},
+                ],
+            },
+        });
+
+        let poll: Poll = serde_json::from_value(data).unwrap();
+        let parsed = PlayerAgePoll::from_poll(&poll);
+        assert_eq!(parsed.ages.len(), 4);
+        assert!(parsed.ages[3].and_up);
+        assert_eq!(parsed.ages[3].age, 21);
+        assert_eq!(parsed.modal_age(), Some(10));
+    }
+}
@@ -0,0 +1,80 @@
+/*!
+The typed model for search results from the version 2 API
+*/
+use crate::errors::Result;
+use crate::models::de;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// The results from a `search()`
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct SearchResults {
+    #[serde(rename = "@total", default, deserialize_with = "de::num")]
+    pub total: usize,
+    #[serde(rename = "item", default, deserialize_with = "de::one_or_many")]
+    pub items: Vec<SearchItem>,
+}
+
+impl SearchResults {
+    /// Parse the results from a `search()` response
+    pub fn from_value(value: &Value) -> Result<Self> {
+        return super::from_node(value, "items");
+    }
+}
+
+/// A single search result
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct SearchItem {
+    #[serde(rename = "@id", deserialize_with = "de::num")]
+    pub id: usize,
+    /// The type of thing, e.g. "boardgame" or "boardgameexpansion"
+    #[serde(rename = "@type", default)]
+    pub item_type: String,
+    /// The name that matched, which may be an alternate name
+    #[serde(default, deserialize_with = "de::text")]
+    pub name: String,
+    #[serde(rename = "yearpublished", default, deserialize_with = "de::opt_num")]
+    pub year_published: Option<i32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_search_results() {
+        let data = json!({
+            "items": {
+                "@total": "2",
+                "@termsofuse": "https://boardgamegeek.com/xmlapi/termsofuse",
+                "item": [
+                    {
+                        "@type": "boardgame",
+                        "@id": "136888",
+                        "name": {"@type": "primary", "@value": "Bruges"},
+                        "yearpublished": {"@value": "2013"},
+                    },
+                    {
+                        "@type": "boardgameexpansion",
+                        "@id": "2",
+                        "name": {"@type": "alternate", "@value": "Brügge: Exp"},
+                    },
+                ],
+            },
+        });
+
+        let res = SearchResults::from_value(&data).unwrap();
+        assert_eq!(res.total, 2);
+        assert_eq!(res.items.len(), 2);
+        assert_eq!(res.items[0].id, 136888);
+        assert_eq!(res.items[0].item_type, "boardgame");
+        assert_eq!(res.items[0].name, "Bruges");
+        assert_eq!(res.items[0].year_published, Some(2013));
+        assert_eq!(res.items[1].year_published, None);
+
+        let res = SearchResults::from_value(&json!({"items": {"@total": "0"}})).unwrap();
+        assert_eq!(res.total, 0);
+        assert!(res.items.is_empty());
+    }
+}
@@ -0,0 +1,809 @@
+/*!
+The typed model for "things" (board games, expansions, etc.) from the
+version 2 API
+*/
+use crate::errors::Result;
+use crate::models::poll::{
+    LanguagePoll, PlayerAgePoll, PlayerCountPoll, Poll, LANGUAGE_POLL, PLAYER_AGE_POLL,
+    PLAYER_COUNT_POLL,
+};
+use crate::models::{de, warnings};
+use chrono::{DateTime, FixedOffset};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use std::fmt;
+
+/// The items from a `thing()` response
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Things {
+    #[serde(rename = "item", default, deserialize_with = "de::one_or_many")]
+    pub items: Vec<ThingItem>,
+}
+
+impl Things {
+    /// Parse the items from a `thing()` response
+    pub fn from_value(value: &Value) -> Result<Self> {
+        return super::from_node(value, "items");
+    }
+}
+
+/// A single "thing"
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ThingItem {
+    #[serde(rename = "@id", deserialize_with = "de::num")]
+    pub id: usize,
+    /// The type of thing, e.g. "boardgame"
+    #[serde(rename = "@type", default)]
+    pub item_type: String,
+    #[serde(rename = "name", default, deserialize_with = "de::one_or_many")]
+    pub names: Vec<Name>,
+    #[serde(default, deserialize_with = "de::opt_text")]
+    pub thumbnail: Option<String>,
+    #[serde(default, deserialize_with = "de::opt_text")]
+    pub image: Option<String>,
+    #[serde(default, deserialize_with = "de::text")]
+    pub description: String,
+    #[serde(rename = "yearpublished", default, deserialize_with = "de::opt_num")]
+    pub year_published: Option<i32>,
+    #[serde(rename = "minplayers", default, deserialize_with = "de::opt_num")]
+    pub min_players: Option<u32>,
+    #[serde(rename = "maxplayers", default, deserialize_with = "de::opt_num")]
+    pub max_players: Option<u32>,
+    #[serde(rename = "playingtime", default, deserialize_with = "de::opt_num")]
+    pub playing_time: Option<u32>,
+    #[serde(rename = "minplaytime", default, deserialize_with = "de::opt_num")]
+    pub min_playtime: Option<u32>,
+    #[serde(rename = "maxplaytime", default, deserialize_with = "de::opt_num")]
+    pub max_playtime: Option<u32>,
+    #[serde(rename = "minage", default, deserialize_with = "de::opt_num")]
+    pub min_age: Option<u32>,
+    /// Links to categories, mechanics, designers, publishers, etc.
+    #[serde(rename = "link", default, deserialize_with = "de::one_or_many")]
+    pub links: Vec<Link>,
+    /// The community polls, like the suggested number of players
+    #[serde(rename = "poll", default, deserialize_with = "de::one_or_many")]
+    pub polls: Vec<Poll>,
+    /// Only present when `stats=1` is requested
+    #[serde(default, deserialize_with = "statistics")]
+    pub statistics: Option<Statistics>,
+    /// Only present when `versions=1` is requested
+    #[serde(default, deserialize_with = "versions")]
+    pub versions: Vec<Version>,
+    /// Only present when `videos=1` is requested
+    #[serde(default, deserialize_with = "videos")]
+    pub videos: Vec<Video>,
+    /// Only present when `marketplace=1` is requested
+    #[serde(rename = "marketplacelistings", default, deserialize_with = "listings")]
+    pub listings: Vec<MarketListing>,
+    /// Only present when `comments=1` or `ratingcomments=1` is requested
+    #[serde(default)]
+    pub comments: Option<CommentsPage>,
+}
+
+impl ThingItem {
+    /// The primary name of the thing
+    pub fn name(&self) -> &str {
+        return self
+            .names
+            .iter()
+            .find(|n| n.name_type == "primary")
+            .or_else(|| self.names.first())
+            .map(|n| n.value.as_str())
+            .unwrap_or("");
+    }
+
+    /// The poll with the given name, e.g. "suggested_numplayers"
+    pub fn poll(&self, name: &str) -> Option<&Poll> {
+        return self.polls.iter().find(|p| p.name == name);
+    }
+
+    /// The parsed suggested number of players poll, if there is one
+    pub fn player_count_poll(&self) -> Option<PlayerCountPoll> {
+        return self.poll(PLAYER_COUNT_POLL).map(PlayerCountPoll::from_poll);
+    }
+
+    /// The parsed language dependence poll, if there is one
+    pub fn language_poll(&self) -> Option<LanguagePoll> {
+        return self.poll(LANGUAGE_POLL).map(LanguagePoll::from_poll);
+    }
+
+    /// The parsed suggested player age poll, if there is one
+    pub fn player_age_poll(&self) -> Option<PlayerAgePoll> {
+        return self.poll(PLAYER_AGE_POLL).map(PlayerAgePoll::from_poll);
+    }
+
+    /// The links of the given type, e.g. all of the mechanics
+    pub fn links_of(&self, ltype: LinkType) -> Vec<&Link> {
+        return self.links.iter().filter(|l| l.is(ltype)).collect();
+    }
+
+    /// The names of the links of the given type, e.g. the names of all of
+    /// the designers
+    pub fn link_values(&self, ltype: LinkType) -> Vec<&str> {
+        return self
+            .links_of(ltype)
+            .into_iter()
+            .map(|l| l.value.as_str())
+            .collect();
+    }
+}
+
+/// The kinds of links on a thing, for use with `ThingItem::links_of()`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LinkType {
+    BoardGameCategory,
+    BoardGameMechanic,
+    BoardGameFamily,
+    BoardGameDesigner,
+    BoardGameArtist,
+    BoardGamePublisher,
+    BoardGameExpansion,
+    BoardGameAccessory,
+    BoardGameImplementation,
+    BoardGameIntegration,
+    BoardGameCompilation,
+    BoardGameVersion,
+    Language,
+}
+
+impl LinkType {
+    pub fn as_str(&self) -> &'static str {
+        return match self {
+            LinkType::BoardGameCategory => "boardgamecategory",
+            LinkType::BoardGameMechanic => "boardgamemechanic",
+            LinkType::BoardGameFamily => "boardgamefamily",
+            LinkType::BoardGameDesigner => "boardgamedesigner",
+            LinkType::BoardGameArtist => "boardgameartist",
+            LinkType::BoardGamePublisher => "boardgamepublisher",
+            LinkType::BoardGameExpansion => "boardgameexpansion",
+            LinkType::BoardGameAccessory => "boardgameaccessory",
+            LinkType::BoardGameImplementation => "boardgameimplementation",
+            LinkType::BoardGameIntegration => "boardgameintegration",
+            LinkType::BoardGameCompilation => "boardgamecompilation",
+            LinkType::BoardGameVersion => "boardgameversion",
+            LinkType::Language => "language",
+        };
+    }
+}
+
+impl fmt::Display for LinkType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "{}", self.as_str());
+    }
+}
+
+/// A name for a thing
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Name {
+    /// Either "primary" or "alternate"
+    #[serde(rename = "@type", default)]
+    pub name_type: String,
+    #[serde(rename = "@sortindex", default, deserialize_with = "de::num")]
+    pub sort_index: usize,
+    #[serde(rename = "@value", default)]
+    pub value: String,
+}
+
+/// A link from a thing to something related, like a category or publisher
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Link {
+    /// The kind of link, e.g. "boardgamecategory" or "boardgamepublisher"
+    #[serde(rename = "@type", default)]
+    pub link_type: String,
+    #[serde(rename = "@id", default, deserialize_with = "de::num")]
+    pub id: usize,
+    #[serde(rename = "@value", default)]
+    pub value: String,
+    /// Set when the link points back from the related item, e.g. from an
+    /// expansion to its base game
+    #[serde(rename = "@inbound", default, deserialize_with = "inbound")]
+    pub inbound: bool,
+}
+
+impl Link {
+    /// Whether this is a link of the given type
+    pub fn is(&self, ltype: LinkType) -> bool {
+        return self.link_type == ltype.as_str();
+    }
+}
+
+/// A published version of a thing, when `versions=1` is requested
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Version {
+    #[serde(rename = "@id", default, deserialize_with = "de::num")]
+    pub id: usize,
+    #[serde(rename = "name", default, deserialize_with = "de::one_or_many")]
+    pub names: Vec<Name>,
+    #[serde(default, deserialize_with = "de::opt_text")]
+    pub thumbnail: Option<String>,
+    #[serde(default, deserialize_with = "de::opt_text")]
+    pub image: Option<String>,
+    #[serde(rename = "yearpublished", default, deserialize_with = "de::opt_num")]
+    pub year_published: Option<i32>,
+    #[serde(rename = "productcode", default, deserialize_with = "de::opt_text")]
+    pub product_code: Option<String>,
+    /// The publishers, languages, artists, etc. of the version
+    #[serde(rename = "link", default, deserialize_with = "de::one_or_many")]
+    pub links: Vec<Link>,
+    /// The box width in inches, which is 0 when it isn't known
+    #[serde(default, deserialize_with = "de::opt_num")]
+    pub width: Option<f64>,
+    /// The box length in inches, which is 0 when it isn't known
+    #[serde(default, deserialize_with = "de::opt_num")]
+    pub length: Option<f64>,
+    /// The box depth in inches, which is 0 when it isn't known
+    #[serde(default, deserialize_with = "de::opt_num")]
+    pub depth: Option<f64>,
+    /// The weight in pounds, which is 0 when it isn't known
+    #[serde(default, deserialize_with = "de::opt_num")]
+    pub weight: Option<f64>,
+}
+
+impl Version {
+    /// The primary name of the version
+    pub fn name(&self) -> &str {
+        return self
+            .names
+            .iter()
+            .find(|n| n.name_type == "primary")
+            .or_else(|| self.names.first())
+            .map(|n| n.value.as_str())
+            .unwrap_or("");
+    }
+
+    /// The names of the version's publishers
+    pub fn publishers(&self) -> Vec<&str> {
+        return self.link_values(LinkType::BoardGamePublisher);
+    }
+
+    /// The languages of the version
+    pub fn languages(&self) -> Vec<&str> {
+        return self.link_values(LinkType::Language);
+    }
+
+    /// The (width, length, depth) of the box in inches, if they're known
+    pub fn dimensions(&self) -> Option<(f64, f64, f64)> {
+        return match (self.width, self.length, self.depth) {
+            (Some(w), Some(l), Some(d)) if w > 0.0 && l > 0.0 && d > 0.0 => Some((w, l, d)),
+            _ => None,
+        };
+    }
+
+    fn link_values(&self, ltype: LinkType) -> Vec<&str> {
+        return self
+            .links
+            .iter()
+            .filter(|l| l.is(ltype))
+            .map(|l| l.value.as_str())
+            .collect();
+    }
+}
+
+/// A video about a thing, when `videos=1` is requested
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Video {
+    #[serde(rename = "@id", default, deserialize_with = "de::num")]
+    pub id: usize,
+    #[serde(rename = "@title", default)]
+    pub title: String,
+    /// e.g. "review", "instructional" or "session"
+    #[serde(rename = "@category", default)]
+    pub category: String,
+    #[serde(rename = "@language", default)]
+    pub language: String,
+    #[serde(rename = "@link", default)]
+    pub link: String,
+    #[serde(rename = "@username", default)]
+    pub username: String,
+    #[serde(rename = "@userid", default, deserialize_with = "de::opt_num")]
+    pub user_id: Option<usize>,
+    #[serde(rename = "@postdate", default)]
+    pub post_date: String,
+}
+
+/// A marketplace listing for a thing, when `marketplace=1` is requested
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct MarketListing {
+    /// When the item was listed
+    #[serde(rename = "listdate", default, deserialize_with = "list_date")]
+    pub list_date: Option<DateTime<FixedOffset>>,
+    #[serde(default)]
+    pub price: Price,
+    #[serde(default, deserialize_with = "de::text")]
+    pub condition: String,
+    #[serde(default, deserialize_with = "de::text")]
+    pub notes: String,
+    /// The url of the listing on the site
+    #[serde(default, deserialize_with = "href")]
+    pub link: String,
+}
+
+/// The asking price of a marketplace listing.  This is kept as a decimal so
+/// that prices compare and add up exactly.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Price {
+    /// The ISO 4217 currency code, e.g. "USD"
+    #[serde(rename = "@currency", default)]
+    pub currency: String,
+    #[serde(rename = "@value", default, deserialize_with = "de::opt_num")]
+    pub value: Option<Decimal>,
+}
+
+impl fmt::Display for Price {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self.value {
+            Some(v) => write!(f, "{} {}", v, self.currency),
+            None => write!(f, "{}", self.currency),
+        };
+    }
+}
+
+/// A page of comments on a thing
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct CommentsPage {
+    #[serde(rename = "@page", default, deserialize_with = "de::num")]
+    pub page: usize,
+    /// The total number of comments across all pages
+    #[serde(rename = "@totalitems", default, deserialize_with = "de::num")]
+    pub total: usize,
+    #[serde(rename = "comment", default, deserialize_with = "de::one_or_many")]
+    pub comments: Vec<Comment>,
+}
+
+impl CommentsPage {
+    /// The number of pages of comments, given the page size they were
+    /// requested with (BGG defaults to 100)
+    pub fn num_pages(&self, page_size: usize) -> usize {
+        return self.total.div_ceil(page_size.max(1));
+    }
+
+    /// Whether there are more pages after this one, given the page size they
+    /// were requested with
+    pub fn has_next(&self, page_size: usize) -> bool {
+        return !self.comments.is_empty() && self.page < self.num_pages(page_size);
+    }
+}
+
+/// A user's comment (and possibly rating) on a thing
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Comment {
+    #[serde(rename = "@username", default)]
+    pub username: String,
+    /// The rating, which is "N/A" in the response if they haven't rated it
+    #[serde(rename = "@rating", default, deserialize_with = "de::opt_num")]
+    pub rating: Option<f32>,
+    /// The comment itself, which may be blank for a rating without one
+    #[serde(rename = "@value", default)]
+    pub text: String,
+}
+
+/// The statistics for a thing, when `stats=1` is requested
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Statistics {
+    #[serde(rename = "usersrated", default, deserialize_with = "de::num")]
+    pub users_rated: usize,
+    #[serde(default, deserialize_with = "de::opt_num")]
+    pub average: Option<f64>,
+    #[serde(rename = "bayesaverage", default, deserialize_with = "de::opt_num")]
+    pub bayes_average: Option<f64>,
+    #[serde(rename = "stddev", default, deserialize_with = "de::opt_num")]
+    pub std_dev: Option<f64>,
+    #[serde(default, deserialize_with = "de::opt_num")]
+    pub median: Option<f64>,
+    #[serde(default, deserialize_with = "ranks")]
+    pub ranks: Vec<Rank>,
+    /// The number of users who own it
+    #[serde(default, deserialize_with = "de::num")]
+    pub owned: usize,
+    /// The number of users who have it for trade
+    #[serde(default, deserialize_with = "de::num")]
+    pub trading: usize,
+    /// The number of users who want it in trade
+    #[serde(default, deserialize_with = "de::num")]
+    pub wanting: usize,
+    /// The number of users who have it on their wishlist
+    #[serde(default, deserialize_with = "de::num")]
+    pub wishing: usize,
+    #[serde(rename = "numcomments", default, deserialize_with = "de::num")]
+    pub num_comments: usize,
+    #[serde(rename = "numweights", default, deserialize_with = "de::num")]
+    pub num_weights: usize,
+    /// The average weight (complexity), from 1 (light) to 5 (heavy)
+    #[serde(rename = "averageweight", default, deserialize_with = "de::opt_num")]
+    pub weight: Option<f64>,
+}
+
+impl Statistics {
+    /// The overall rank for the thing's subtype (e.g. "Board Game Rank"),
+    /// if it is ranked
+    pub fn rank(&self) -> Option<u32> {
+        return self
+            .ranks
+            .iter()
+            .find(|r| r.rank_type == "subtype")
+            .and_then(|r| r.value);
+    }
+
+    /// The rank within the given subdomain or family, like "strategygames",
+    /// if it is ranked there
+    pub fn rank_in(&self, name: &str) -> Option<u32> {
+        return self
+            .ranks
+            .iter()
+            .find(|r| r.name == name)
+            .and_then(|r| r.value);
+    }
+}
+
+/// A ranking for a thing, either overall for its subtype, or within a
+/// family (like "strategygames")
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Rank {
+    /// Either "subtype" or "family"
+    #[serde(rename = "@type", default)]
+    pub rank_type: String,
+    #[serde(rename = "@id", default, deserialize_with = "de::num")]
+    pub id: usize,
+    #[serde(rename = "@name", default)]
+    pub name: String,
+    #[serde(rename = "@friendlyname", default)]
+    pub friendly_name: String,
+    /// The rank, which is "Not Ranked" in the response if it isn't ranked
+    #[serde(rename = "@value", default, deserialize_with = "de::opt_num")]
+    pub value: Option<u32>,
+    #[serde(rename = "@bayesaverage", default, deserialize_with = "de::opt_num")]
+    pub bayes_average: Option<f64>,
+}
+
+/// The statistics are nested inside of a `ratings` element
+fn statistics<'de, D>(deserializer: D) -> Result<Option<Statistics>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Wrapper {
+        ratings: Option<Statistics>,
+    }
+
+    let ret: Option<Wrapper> = Option::deserialize(deserializer)?;
+
+    return Ok(ret.and_then(|w| w.ratings));
+}
+
+/// The ranks are nested inside of a `ranks` element
+fn ranks<'de, D>(deserializer: D) -> Result<Vec<Rank>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Wrapper {
+        #[serde(default, deserialize_with = "de::one_or_many")]
+        rank: Vec<Rank>,
+    }
+
+    let ret: Option<Wrapper> = Option::deserialize(deserializer)?;
+
+    return Ok(ret.map(|w| w.rank).unwrap_or_default());
+}
+
+/// The versions are nested inside a `versions` element
+fn versions<'de, D>(deserializer: D) -> Result<Vec<Version>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Wrapper {
+        #[serde(default, deserialize_with = "de::one_or_many")]
+        item: Vec<Version>,
+    }
+
+    let ret: Option<Wrapper> = Option::deserialize(deserializer)?;
+
+    return Ok(ret.map(|w| w.item).unwrap_or_default());
+}
+
+/// The videos are nested inside a `videos` element
+fn videos<'de, D>(deserializer: D) -> Result<Vec<Video>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Wrapper {
+        #[serde(default, deserialize_with = "de::one_or_many")]
+        video: Vec<Video>,
+    }
+
+    let ret: Option<Wrapper> = Option::deserialize(deserializer)?;
+
+    return Ok(ret.map(|w| w.video).unwrap_or_default());
+}
+
+/// The listings are nested inside a `marketplacelistings` element
+fn listings<'de, D>(deserializer: D) -> Result<Vec<MarketListing>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Wrapper {
+        #[serde(default, deserialize_with = "de::one_or_many")]
+        listing: Vec<MarketListing>,
+    }
+
+    let ret: Option<Wrapper> = Option::deserialize(deserializer)?;
+
+    return Ok(ret.map(|w| w.listing).unwrap_or_default());
+}
+
+/// The listing dates are RFC 2822, like "Mon, 01 Jan 2024 00:00:00 +0000"
+fn list_date<'de, D>(deserializer: D) -> Result<Option<DateTime<FixedOffset>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let text = match de::text_of(&Value::deserialize(deserializer)?) {
+        Some(t) if !t.trim().is_empty() => t,
+        _ => return Ok(None),
+    };
+    let ret = DateTime::parse_from_rfc2822(text.trim()).ok();
+
+    if ret.is_none() {
+        warnings::coercion(format!("\"{}\" is not a valid date", text));
+    }
+
+    return Ok(ret);
+}
+
+/// The url of a listing is the `href` attribute of its `link` element
+fn href<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let ret = Value::deserialize(deserializer)?;
+
+    return Ok(ret["@href"].as_str().unwrap_or("").to_string());
+}
+
+/// The inbound flag is "true" when set, rather than the usual "1"
+fn inbound<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let ret = Value::deserialize(deserializer)?;
+
+    return Ok(matches!(ret.as_str(), Some("true") | Some("1")));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_things() {
+        let data = json!({
+            "items": {
+                "@termsofuse": "https://boardgamegeek.com/xmlapi/termsofuse",
+                "item": [
+                    {
+                        "@type": "boardgame",
+                        "@id": "136888",
+                        "name": [
+                            {"@type": "alternate", "@sortindex": "1", "@value": "Brügge"},
+                            {"@type": "primary", "@sortindex": "1", "@value": "Bruges"},
+                        ],
+                        "statistics": {
+                            "@page": "1",
+                            "ratings": {
+                                "usersrated": {"@value": "12345"},
+                                "average": {"@value": "7.28"},
+                                "bayesaverage": {"@value": "7.01"},
+                                "stddev": {"@value": "1.25"},
+                                "median": {"@value": "0"},
+                                "owned": {"@value": "15000"},
+                                "trading": {"@value": "200"},
+                                "wanting": {"@value": "300"},
+                                "wishing": {"@value": "2500"},
+                                "numcomments": {"@value": "1800"},
+                                "numweights": {"@value": "600"},
+                                "averageweight": {"@value": "2.65"},
+                                "ranks": {
+                                    "rank": [
+                                        {
+                                            "@type": "subtype",
+                                            "@id": "1",
+                                            "@name": "boardgame",
+                                            "@friendlyname": "Board Game Rank",
+                                            "@value": "350",
+                                            "@bayesaverage": "7.01",
+                                        },
+                                        {
+                                            "@type": "family",
+                                            "@id": "5497",
+                                            "@name": "strategygames",
+                                            "@value": "300",
+                                        },
+                                    ],
+                                },
+                            },
+                        },
+                    },
+                    {
+                        "@type": "boardgame",
+                        "@id": "1",
+                        "name": {"@type": "primary", "@sortindex": "5", "@value": "The Game"},
+                        "statistics": {"ratings": {
+                            "ranks": {"rank": {"@type": "subtype", "@value": "Not Ranked"}},
+                        }},
+                    },
+                ],
+            },
+        });
+
+        let things = Things::from_value(&data).unwrap();
+        assert_eq!(things.items.len(), 2);
+
+        let item = &things.items[0];
+        assert_eq!(item.id, 136888);
+        assert_eq!(item.item_type, "boardgame");
+        assert_eq!(item.name(), "Bruges");
+        let stats = item.statistics.as_ref().unwrap();
+        assert_eq!(stats.average, Some(7.28));
+        assert_eq!(stats.ranks.len(), 2);
+        assert_eq!(stats.rank(), Some(350));
+        assert_eq!(stats.rank_in("strategygames"), Some(300));
+        assert_eq!(stats.rank_in("familygames"), None);
+        assert_eq!(stats.users_rated, 12345);
+        assert_eq!(stats.std_dev, Some(1.25));
+        assert_eq!(stats.owned, 15000);
+        assert_eq!(stats.wishing, 2500);
+        assert_eq!(stats.num_weights, 600);
+        assert_eq!(stats.weight, Some(2.65));
+
+        let item = &things.items[1];
+        assert_eq!(item.name(), "The Game");
+        assert_eq!(item.names[0].sort_index, 5);
+        assert_eq!(item.statistics.as_ref().unwrap().rank(), None);
+
+        let things = Things::from_value(&json!({"items": {"item": {"@id": "2"}}})).unwrap();
+        assert_eq!(things.items[0].name(), "");
+        assert!(things.items[0].statistics.is_none());
+    }
+
+    #[test]
+    fn test_thing_full() {
+        let data = json!({
+            "items": {
+                "item": {
+                    "@type": "boardgame",
+                    "@id": "136888",
+                    "thumbnail": "https://example.com/t.jpg",
+                    "name": {"@type": "primary", "@sortindex": "1", "@value": "Bruges"},
+                    "description": "Build up the city",
+                    "poll": {
+                        "@name": "suggested_numplayers",
+                        "@totalvotes": "10",
+                        "results": {
+                            "@numplayers": "2",
+                            "result": [
+                                {"@value": "Best", "@numvotes": "8"},
+                                {"@value": "Recommended", "@numvotes": "2"},
+                            ],
+                        },
+                    },
+                    "yearpublished": {"@value": "2013"},
+                    "minplayers": {"@value": "2"},
+                    "maxplayers": {"@value": "4"},
+                    "minage": {"@value": "10"},
+                    "link": [
+                        {"@type": "boardgamecategory", "@id": "1002", "@value": "Card Game"},
+                        {"@type": "boardgameexpansion", "@id": "2", "@value": "Bruges: Exp", "@inbound": "true"},
+                    ],
+                    "versions": {
+                        "item": {
+                            "@type": "boardgameversion",
+                            "@id": "178316",
+                            "name": {"@type": "primary", "@value": "English edition"},
+                            "yearpublished": {"@value": "2013"},
+                            "productcode": {"@value": "ZM7100"},
+                            "link": [
+                                {"@type": "boardgamepublisher", "@id": "538", "@value": "Z-Man Games"},
+                                {"@type": "language", "@id": "2184", "@value": "English"},
+                            ],
+                            "width": {"@value": "11.75"},
+                            "length": {"@value": "11.75"},
+                            "depth": {"@value": "2.75"},
+                            "weight": {"@value": "0"},
+                        },
+                    },
+                    "videos": {
+                        "@total": "1",
+                        "video": {
+                            "@id": "1",
+                            "@title": "Review",
+                            "@category": "review",
+                            "@link": "https://example.com/v",
+                            "@userid": "5",
+                        },
+                    },
+                    "marketplacelistings": {
+                        "listing": [
+                            {
+                                "listdate": {"@value": "Mon, 01 Jan 2024 00:00:00 +0000"},
+                                "price": {"@currency": "USD", "@value": "25.00"},
+                                "condition": {"@value": "new"},
+                                "notes": {"@value": "Shrink wrapped"},
+                                "link": {"@href": "https://example.com/l", "@title": "marketplace"},
+                            },
+                        ],
+                    },
+                    "comments": {
+                        "@page": "1",
+                        "@totalitems": "2",
+                        "comment": [
+                            {"@username": "a", "@rating": "8", "@value": "Great"},
+                            {"@username": "b", "@rating": "N/A", "@value": "Meh"},
+                        ],
+                    },
+                },
+            },
+        });
+
+        let item = &Things::from_value(&data).unwrap().items[0];
+        assert_eq!(item.description, "Build up the city");
+        assert_eq!(item.polls.len(), 1);
+        assert!(item.poll("language_dependence").is_none());
+        assert!(item.language_poll().is_none());
+        assert_eq!(
+            item.player_count_poll().unwrap().best_player_counts(),
+            vec![2]
+        );
+        assert_eq!(item.year_published, Some(2013));
+        assert_eq!(item.min_players, Some(2));
+        assert_eq!(item.min_age, Some(10));
+        assert_eq!(item.thumbnail, Some("https://example.com/t.jpg".into()));
+        assert_eq!(item.links.len(), 2);
+        assert!(!item.links[0].inbound);
+        assert!(item.links[1].inbound);
+        assert!(item.links[0].is(LinkType::BoardGameCategory));
+        assert_eq!(
+            item.link_values(LinkType::BoardGameCategory),
+            vec!["Card Game"]
+        );
+        let exps = item.links_of(LinkType::BoardGameExpansion);
+        assert_eq!(exps.len(), 1);
+        assert_eq!(exps[0].id, 2);
+        assert!(item.links_of(LinkType::BoardGameMechanic).is_empty());
+
+        assert_eq!(item.versions.len(), 1);
+        assert_eq!(item.versions[0].name(), "English edition");
+        assert_eq!(item.versions[0].product_code, Some("ZM7100".into()));
+        assert_eq!(item.versions[0].publishers(), vec!["Z-Man Games"]);
+        assert_eq!(item.versions[0].languages(), vec!["English"]);
+        assert_eq!(item.versions[0].dimensions(), Some((11.75, 11.75, 2.75)));
+        assert_eq!(item.versions[0].weight, Some(0.0));
+
+        assert_eq!(item.videos.len(), 1);
+        assert_eq!(item.videos[0].category, "review");
+        assert_eq!(item.videos[0].user_id, Some(5));
+
+        assert_eq!(item.listings.len(), 1);
+        assert_eq!(item.listings[0].price.currency, "USD");
+        assert_eq!(item.listings[0].price.value, Some(Decimal::new(2500, 2)));
+        assert_eq!(item.listings[0].price.to_string(), "25.00 USD");
+        assert_eq!(
+            item.listings[0].list_date.unwrap().to_rfc3339(),
+            "2024-01-01T00:00:00+00:00"
+        );
+        assert_eq!(item.listings[0].condition, "new");
+        assert_eq!(item.listings[0].link, "https://example.com/l");
+
+        let comments = item.comments.as_ref().unwrap();
+        assert_eq!(comments.total, 2);
+        assert_eq!(comments.comments[0].rating, Some(8.0));
+        assert_eq!(comments.comments[0].text, "Great");
+        assert_eq!(comments.comments[1].rating, None);
+        assert_eq!(comments.num_pages(100), 1);
+        assert!(!comments.has_next(100));
+        assert!(comments.has_next(1));
+    }
+}
@@ -0,0 +1,310 @@
+/*!
+Warnings about responses that don't look the way the models expect.  The
+models are tolerant: unknown fields are skipped, missing elements get
+defaults and values that won't parse are treated as blank.  That keeps
+things working when BGG changes a response, but it can also hide the
+change.  Register a handler to hear about each of these as it happens.
+
+```ignore,rust
+use rbgg::models::warnings;
+
+warnings::set_handler(|w| eprintln!("rbgg: {}", w));
+```
+
+Each warning is only reported once per handler, for the first time its
+kind shows up at a given place (model and path) in the responses, as the
+same quirk will usually show up on every item in a response.  Later ones
+that only differ in their message (like the value that couldn't be
+parsed) aren't reported, so what's remembered doesn't keep growing.
+*/
+use serde::{Deserialize, Deserializer};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+type Handler = Arc<dyn Fn(&Warning) + Send + Sync>;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static HANDLER: RwLock<Option<Handler>> = RwLock::new(None);
+static SEEN: Mutex<Option<HashSet<SeenKey>>> = Mutex::new(None);
+
+/// What warnings are deduped on: their kind, model and path
+type SeenKey = (WarningKind, String, String);
+
+thread_local! {
+    // The models currently being deserialized on this thread, innermost
+    // last, so that warnings can say where they came from
+    static MODELS: RefCell<Vec<&'static str>> = const { RefCell::new(vec![]) };
+}
+
+/// The kind of problem that was encountered
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WarningKind {
+    /// A field in the response that the model doesn't know about
+    UnexpectedField,
+    /// An element the model expects was not in the response
+    MissingNode,
+    /// A value couldn't be converted to the expected type and was treated
+    /// as blank instead
+    Coercion,
+}
+
+impl fmt::Display for WarningKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            WarningKind::UnexpectedField => "unexpected field",
+            WarningKind::MissingNode => "missing node",
+            WarningKind::Coercion => "coercion fallback",
+        };
+
+        return write!(f, "{}", s);
+    }
+}
+
+/// A single warning from parsing a response
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Warning {
+    pub kind: WarningKind,
+    /// The name of the model being parsed, e.g. "CollectionItem"
+    pub model: String,
+    /// The path to the field within the model, where known.  Array indexes
+    /// are written as `[]`.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(
+            f,
+            "{} in {} at \"{}\": {}",
+            self.kind, self.model, self.path, self.message
+        );
+    }
+}
+
+/// Register the handler to call with each warning, replacing any existing
+/// handler.  This also resets which warnings have already been reported.
+pub fn set_handler<F>(handler: F)
+where
+    F: Fn(&Warning) + Send + Sync + 'static,
+{
+    *HANDLER.write().unwrap() = Some(Arc::new(handler));
+    *SEEN.lock().unwrap() = Some(HashSet::new());
+    ENABLED.store(true, Ordering::Release);
+}
+
+/// Remove the handler, if any.  Warnings are no longer checked for.
+pub fn clear_handler() {
+    ENABLED.store(false, Ordering::Release);
+    *HANDLER.write().unwrap() = None;
+    *SEEN.lock().unwrap() = None;
+}
+
+/// Deserialize a model, reporting any fields in the input that it skipped
+pub(crate) fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    if !ENABLED.load(Ordering::Acquire) {
+        return T::deserialize(deserializer);
+    }
+
+    let model = model_name::<T>();
+    MODELS.with(|m| m.borrow_mut().push(model));
+    let ret = serde_ignored::deserialize(deserializer, |path| {
+        // Nested models report their own fields, so only report the ones
+        // that belong to the innermost model being deserialized
+        if current_model() != model {
+            return;
+        }
+        emit(
+            WarningKind::UnexpectedField,
+            model,
+            path_str(&path),
+            "field is not part of the model".to_string(),
+        );
+    });
+    MODELS.with(|m| m.borrow_mut().pop());
+
+    return ret;
+}
+
+/// Report an element that was expected but not found
+pub(crate) fn missing(path: &str) {
+    if ENABLED.load(Ordering::Acquire) {
+        emit(
+            WarningKind::MissingNode,
+            current_model(),
+            path.to_string(),
+            "expected element is missing".to_string(),
+        );
+    }
+}
+
+/// Like `missing()`, for an element that should hold a whole model
+pub(crate) fn missing_model<T>(path: &str) {
+    if ENABLED.load(Ordering::Acquire) {
+        emit(
+            WarningKind::MissingNode,
+            model_name::<T>(),
+            path.to_string(),
+            "expected element is missing".to_string(),
+        );
+    }
+}
+
+/// Report a value that had to be treated as blank
+pub(crate) fn coercion(message: String) {
+    if ENABLED.load(Ordering::Acquire) {
+        emit(
+            WarningKind::Coercion,
+            current_model(),
+            String::new(),
+            message,
+        );
+    }
+}
+
+/* Begin private functions */
+
+fn emit(kind: WarningKind, model: &str, path: String, message: String) {
+    let warning = Warning {
+        kind,
+        model: model.to_string(),
+        path,
+        message,
+    };
+
+    // Only report each warning once, leaving the message out so that a
+    // different bad value in the same place isn't a new warning
+    let key = (kind, warning.model.clone(), warning.path.clone());
+    let is_new = match SEEN.lock().unwrap().as_mut() {
+        Some(seen) => seen.insert(key),
+        None => false,
+    };
+    if !is_new {
+        return;
+    }
+
+    // Clone the handler out so that it isn't called with the lock held
+    let handler = HANDLER.read().unwrap().clone();
+    if let Some(handler) = handler {
+        handler(&warning);
+    }
+}
+
+fn current_model() -> &'static str {
+    return MODELS.with(|m| m.borrow().last().copied().unwrap_or(""));
+}
+
+/// The type name without the module path
+fn model_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    let base = name.split('<').next().unwrap_or(name);
+
+    return match base.rfind("::") {
+        Some(i) => &name[i + 2..],
+        None => name,
+    };
+}
+
+/// Format a path, leaving out the array indexes so that the same field in
+/// different items is reported only once
+fn path_str(path: &serde_ignored::Path) -> String {
+    use serde_ignored::Path;
+
+    return match path {
+        Path::Root => String::new(),
+        Path::Seq { parent, .. } => format!("{}[]", path_str(parent)),
+        Path::Map { parent, key } => {
+            let parent = path_str(parent);
+            match parent.is_empty() {
+                true => key.clone(),
+                false => format!("{}.{}", parent, key),
+            }
+        }
+        Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => path_str(parent),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::de;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize)]
+    struct DriftItem {
+        #[serde(default, deserialize_with = "de::num")]
+        count: usize,
+        #[serde(default = "missing_name")]
+        #[allow(dead_code)]
+        name: String,
+    }
+
+    fn missing_name() -> String {
+        missing("name");
+
+        return String::new();
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Drift {
+        #[serde(default, deserialize_with = "de::one_or_many")]
+        item: Vec<DriftItem>,
+    }
+
+    #[test]
+    fn test_warnings() {
+        let data = json!({
+            "new": "thing",
+            "item": [
+                {"count": "1", "name": "a", "extra": {"deep": "1"}},
+                {"count": "lots", "extra": {"deep": "2"}},
+                {"count": "heaps", "name": "c"},
+            ],
+        });
+
+        // Other tests parse models too, so only look at our own
+        let seen = Arc::new(Mutex::new(vec![]));
+        let s = seen.clone();
+        set_handler(move |w| {
+            if w.model.starts_with("Drift") {
+                s.lock().unwrap().push(w.clone());
+            }
+        });
+        let drift: Drift = deserialize(&data).unwrap();
+        clear_handler();
+
+        assert_eq!(drift.item[1].count, 0);
+        assert_eq!(drift.item[2].count, 0);
+
+        let mut seen = seen.lock().unwrap().clone();
+        seen.sort_by(|a, b| a.path.cmp(&b.path));
+        // The second bad count is in the same place as the first, so it
+        // isn't reported again
+        assert_eq!(seen.len(), 4, "{:?}", seen);
+        assert_eq!(seen[0].kind, WarningKind::Coercion);
+        assert_eq!(seen[0].model, "DriftItem");
+        assert!(seen[0].message.contains("lots"));
+        assert_eq!(seen[1].kind, WarningKind::UnexpectedField);
+        assert_eq!(seen[1].path, "extra");
+        assert_eq!(seen[1].model, "DriftItem");
+        assert_eq!(seen[2].kind, WarningKind::MissingNode);
+        assert_eq!(seen[2].path, "name");
+        assert_eq!(seen[3].model, "Drift");
+        assert_eq!(seen[3].path, "new");
+    }
+
+    #[test]
+    fn test_model_name() {
+        assert_eq!(model_name::<Drift>(), "Drift");
+        assert!(model_name::<Vec<Drift>>().starts_with("Vec<"));
+    }
+}
@@ -0,0 +1,291 @@
+/*!
+A small query layer for filtering a typed collection locally, so you can
+answer questions like "what should we play tonight?" without writing the
+filtering by hand.
+
+```ignore,rust
+use rbgg::{bgg2::Client2, models::Collection, utils::Params};
+
+let cl = Client2::new_from_defaults();
+let opts = Params::from([("stats".to_string(), "1".to_string())]);
+let coll = Collection::from_value(&cl.collection_b("someuser", Some(opts))?)?;
+
+let tonight = coll
+    .query()
+    .owned()
+    .min_rating(7.5)
+    .players(4)
+    .max_playtime_mins(90)
+    .items();
+```
+
+The player counts, play times and BGG ratings come from the collection's
+stats (when it was fetched with `stats=1`).  Games fetched with `thing()`
+can be added with `with_things()` to fill in anything the collection is
+missing.  An item without the data a filter needs never matches it.
+*/
+use crate::models::{Collection, CollectionItem, ThingItem};
+use std::collections::HashMap;
+
+type Filter<'a> = Box<dyn Fn(&Entry) -> bool + 'a>;
+
+/// A query over a collection, built up by chaining filters.  Every filter
+/// must match for an item to be included.
+pub struct CollectionQuery<'a> {
+    coll: &'a Collection,
+    things: HashMap<usize, &'a ThingItem>,
+    filters: Vec<Filter<'a>>,
+}
+
+impl Collection {
+    /// Start a query over the items in this collection
+    pub fn query(&self) -> CollectionQuery<'_> {
+        return CollectionQuery {
+            coll: self,
+            things: HashMap::new(),
+            filters: vec![],
+        };
+    }
+}
+
+impl<'a> CollectionQuery<'a> {
+    /// Use these things (fetched with stats) to fill in any data that is
+    /// missing from the collection
+    pub fn with_things(mut self, things: &'a [ThingItem]) -> Self {
+        self.things.extend(things.iter().map(|t| (t.id, t)));
+
+        return self;
+    }
+
+    /// Only the items that are currently owned
+    pub fn owned(self) -> Self {
+        return self.filter(|i| i.status.own);
+    }
+
+    /// Only the items on the wishlist
+    pub fn wishlist(self) -> Self {
+        return self.filter(|i| i.status.wishlist);
+    }
+
+    /// Only the items marked for trade
+    pub fn for_trade(self) -> Self {
+        return self.filter(|i| i.status.for_trade);
+    }
+
+    /// Only the items marked as want to play
+    pub fn want_to_play(self) -> Self {
+        return self.filter(|i| i.status.want_to_play);
+    }
+
+    /// Only the items that haven't been played
+    pub fn unplayed(self) -> Self {
+        return self.filter(|i| i.num_plays == 0);
+    }
+
+    /// Only the items the user rated at least this highly
+    pub fn min_rating(self, rating: f64) -> Self {
+        return self.filter(move |i| i.user_rating().is_some_and(|r| r >= rating));
+    }
+
+    /// Only the items with an average BGG rating of at least this
+    pub fn min_average(mut self, rating: f64) -> Self {
+        self.filters
+            .push(Box::new(move |e| e.average().is_some_and(|r| r >= rating)));
+
+        return self;
+    }
+
+    /// Only the items that play with the given number of players
+    pub fn players(mut self, count: u32) -> Self {
+        self.filters.push(Box::new(move |e| match e.players() {
+            Some((min, max)) => min <= count && count <= max,
+            None => false,
+        }));
+
+        return self;
+    }
+
+    /// Only the items that play in at most this many minutes
+    pub fn max_playtime_mins(mut self, mins: u32) -> Self {
+        self.filters
+            .push(Box::new(move |e| e.playtime().is_some_and(|t| t <= mins)));
+
+        return self;
+    }
+
+    /// Only the items whose name contains the given text (ignoring case)
+    pub fn name_contains(self, text: &str) -> Self {
+        let text = text.to_lowercase();
+
+        return self.filter(move |i| i.name.to_lowercase().contains(&text));
+    }
+
+    /// Only the items for which the given function returns true
+    pub fn filter<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&CollectionItem) -> bool + 'a,
+    {
+        self.filters.push(Box::new(move |e| f(e.item)));
+
+        return self;
+    }
+
+    /// Run the query, returning the matching items in collection order
+    pub fn items(&self) -> Vec<&'a CollectionItem> {
+        return self
+            .coll
+            .items
+            .iter()
+            .filter(|item| {
+                let entry = Entry {
+                    item,
+                    thing: self.things.get(&item.id).copied(),
+                };
+                self.filters.iter().all(|f| f(&entry))
+            })
+            .collect();
+    }
+
+    /// Run the query, returning the number of matching items
+    pub fn count(&self) -> usize {
+        return self.items().len();
+    }
+}
+
+/// An item along with its thing, if we have it
+struct Entry<'a> {
+    item: &'a CollectionItem,
+    thing: Option<&'a ThingItem>,
+}
+
+impl Entry<'_> {
+    fn players(&self) -> Option<(u32, u32)> {
+        let stats = self.item.stats.as_ref();
+        let min = stats
+            .and_then(|s| s.min_players)
+            .or_else(|| self.thing.and_then(|t| t.min_players))?;
+        let max = stats
+            .and_then(|s| s.max_players)
+            .or_else(|| self.thing.and_then(|t| t.max_players))
+            .unwrap_or(min);
+
+        return Some((min, max));
+    }
+
+    /// The longest the game takes, as that's what matters for fitting it in
+    fn playtime(&self) -> Option<u32> {
+        let stats = self.item.stats.as_ref();
+
+        return stats
+            .and_then(|s| s.max_playtime.or(s.playing_time))
+            .or_else(|| self.thing.and_then(|t| t.max_playtime.or(t.playing_time)));
+    }
+
+    fn average(&self) -> Option<f64> {
+        return self.item.average_rating().or_else(|| {
+            self.thing
+                .and_then(|t| t.statistics.as_ref())
+                .and_then(|s| s.average)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CollectionRating, CollectionStats, CollectionStatus, Statistics};
+
+    fn item(
+        id: usize,
+        own: bool,
+        rating: Option<f64>,
+        stats: Option<(u32, u32, u32)>,
+    ) -> CollectionItem {
+        return CollectionItem {
+            id,
+            name: format!("Game {}", id),
+            status: CollectionStatus {
+                own,
+                ..Default::default()
+            },
+            stats: stats.map(|(min, max, time)| CollectionStats {
+                min_players: Some(min),
+                max_players: Some(max),
+                max_playtime: Some(time),
+                rating: CollectionRating {
+                    value: rating,
+                    average: Some(7.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+    }
+
+    fn collection() -> Collection {
+        return Collection {
+            total_items: 4,
+            items: vec![
+                item(1, true, Some(8.0), Some((2, 4, 60))),
+                item(2, true, Some(9.0), Some((1, 2, 45))),
+                item(3, false, Some(8.0), Some((2, 5, 30))),
+                item(4, true, None, None),
+            ],
+        };
+    }
+
+    fn ids(items: Vec<&CollectionItem>) -> Vec<usize> {
+        return items.iter().map(|i| i.id).collect();
+    }
+
+    #[test]
+    fn test_query() {
+        let coll = collection();
+
+        assert_eq!(coll.query().count(), 4);
+        assert_eq!(ids(coll.query().owned().items()), vec![1, 2, 4]);
+        assert_eq!(
+            ids(coll
+                .query()
+                .owned()
+                .min_rating(7.5)
+                .players(4)
+                .max_playtime_mins(90)
+                .items()),
+            vec![1]
+        );
+        assert_eq!(ids(coll.query().max_playtime_mins(45).items()), vec![2, 3]);
+        assert_eq!(ids(coll.query().name_contains("GAME 3").items()), vec![3]);
+        assert_eq!(
+            ids(coll.query().filter(|i| i.id % 2 == 0).items()),
+            vec![2, 4]
+        );
+        assert_eq!(coll.query().min_average(7.0).count(), 3);
+    }
+
+    #[test]
+    fn test_with_things() {
+        let coll = collection();
+        let things = vec![ThingItem {
+            id: 4,
+            min_players: Some(3),
+            max_players: Some(6),
+            playing_time: Some(20),
+            statistics: Some(Statistics {
+                average: Some(8.1),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }];
+
+        // Without the thing, there is nothing to go on for item 4
+        assert_eq!(ids(coll.query().players(6).items()), Vec::<usize>::new());
+        let query = coll.query().with_things(&things);
+        assert_eq!(ids(query.players(6).max_playtime_mins(20).items()), vec![4]);
+        assert_eq!(
+            ids(coll.query().with_things(&things).min_average(8.0).items()),
+            vec![4]
+        );
+    }
+}
@@ -0,0 +1,275 @@
+/*!
+A small service that keeps the results for a set of registered queries
+fresh on a schedule.  This is the backbone for things like dashboards,
+where you want the latest data for a handful of queries available
+instantly, without hitting BGG every time a page is rendered.
+
+```ignore,rust
+use rbgg::{bgg2::*, refresh::{Query, RefreshService}};
+use std::{sync::Arc, time::Duration};
+
+let svc = Arc::new(RefreshService::new(Client2::new_from_defaults()));
+svc.register(
+    "hot",
+    Query::Hot(Hotness::BoardGame),
+    Duration::from_secs(60 * 60),
+);
+let handle = svc.clone().spawn();
+
+// Later on ...
+if let Some(latest) = svc.latest("hot") {
+    println!("{:?}", latest.value);
+}
+```
+*/
+use crate::bgg2::{Client2, Hotness, Thing};
+use crate::errors::Result;
+use crate::http::CallConfig;
+use crate::utils::Params;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::task::JoinHandle;
+
+/// How long to sleep when there is nothing registered
+const IDLE_SLEEP: Duration = Duration::from_secs(1);
+
+/// The queries that can be registered with the service
+#[derive(Clone, Debug)]
+pub enum Query {
+    /// A user's collection
+    Collection {
+        username: String,
+        options: Option<Params>,
+    },
+    /// A hot list
+    Hot(Hotness),
+    /// A set of "things"
+    Things {
+        ids: Vec<usize>,
+        ttypes: Vec<Thing>,
+        options: Option<Params>,
+    },
+}
+
+impl Query {
+    /// Run the query using the given client
+    pub async fn fetch(&self, client: &Client2) -> Result<Value> {
+        return match self {
+            Query::Collection { username, options } => {
+                client.collection(username, options.clone()).await
+            }
+            Query::Hot(htype) => client.hot(*htype, None).await,
+            Query::Things {
+                ids,
+                ttypes,
+                options,
+            } => client.thing(ids, ttypes, options.clone()).await,
+        };
+    }
+}
+
+/// The latest result for a registered query
+#[derive(Clone, Debug, Default)]
+pub struct Latest {
+    /// The most recent successful result
+    pub value: Option<Value>,
+    /// When `value` was fetched
+    pub refreshed_at: Option<SystemTime>,
+    /// The error from the most recent refresh, if it failed.  The previous
+    /// `value` is kept around when a refresh fails.
+    pub error: Option<String>,
+}
+
+struct Entry {
+    query: Query,
+    interval: Duration,
+    next_due: Instant,
+    latest: Latest,
+}
+
+/// Keeps the results for registered queries fresh on a schedule
+pub struct RefreshService {
+    client: Client2,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl RefreshService {
+    pub fn new(client: Client2) -> Self {
+        return Self {
+            client,
+            entries: Mutex::new(HashMap::new()),
+        };
+    }
+
+    /// Register a query to be refreshed every `interval`.  The first
+    /// refresh happens the next time the service runs.  Registering a name
+    /// that already exists replaces it.
+    pub fn register(&self, name: &str, query: Query, interval: Duration) {
+        self.lock().insert(
+            name.to_string(),
+            Entry {
+                query,
+                interval,
+                next_due: Instant::now(),
+                latest: Latest::default(),
+            },
+        );
+    }
+
+    /// Stop refreshing the given query, returning whether it existed
+    pub fn unregister(&self, name: &str) -> bool {
+        return self.lock().remove(name).is_some();
+    }
+
+    /// Return the latest result for a registered query
+    pub fn latest(&self, name: &str) -> Option<Latest> {
+        return self.lock().get(name).map(|e| e.latest.clone());
+    }
+
+    /// Return the names of all of the registered queries
+    pub fn names(&self) -> Vec<String> {
+        return self.lock().keys().cloned().collect();
+    }
+
+    /// Refresh every query that is due, returning the number of queries
+    /// that were refreshed (successfully or not).  Refreshes always go to
+    /// BGG, skipping any response cache the client has.
+    pub async fn refresh_due(&self) -> usize {
+        let due = self.due(Instant::now());
+        let client = self.client.with_call_config(&CallConfig::no_cache());
+
+        for (name, query) in &due {
+            let res = query.fetch(&client).await;
+
+            // The query may have been unregistered while we were fetching
+            if let Some(entry) = self.lock().get_mut(name) {
+                entry.next_due = Instant::now() + entry.interval;
+                match res {
+                    Ok(value) => {
+                        entry.latest = Latest {
+                            value: Some(value),
+                            refreshed_at: Some(SystemTime::now()),
+                            error: None,
+                        };
+                    }
+                    Err(e) => entry.latest.error = Some(e.to_string()),
+                }
+            }
+        }
+
+        return due.len();
+    }
+
+    /// Run the service forever, refreshing queries as they become due
+    pub async fn run(&self) {
+        loop {
+            self.refresh_due().await;
+
+            let wait = match self.lock().values().map(|e| e.next_due).min() {
+                Some(next) => next.saturating_duration_since(Instant::now()),
+                None => IDLE_SLEEP,
+            };
+            // Don't sleep too long so newly registered queries get picked
+            // up in a timely manner
+            tokio::time::sleep(wait.min(IDLE_SLEEP)).await;
+        }
+    }
+
+    /// Spawn the service onto the current tokio runtime.  Abort the returned
+    /// handle to stop the service.
+    pub fn spawn(self: Arc<Self>) -> JoinHandle<()> {
+        return tokio::spawn(async move { self.run().await });
+    }
+
+    /* Begin private functions */
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Entry>> {
+        return self.entries.lock().unwrap_or_else(|e| e.into_inner());
+    }
+
+    /// Return the queries that are due to be refreshed
+    fn due(&self, now: Instant) -> Vec<(String, Query)> {
+        return self
+            .lock()
+            .iter()
+            .filter(|(_, e)| e.next_due <= now)
+            .map(|(name, e)| (name.clone(), e.query.clone()))
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::{MemoryCache, ResponseCache};
+    use crate::retry::RetryPolicy;
+
+    #[test]
+    fn test_register() {
+        let svc = RefreshService::new(Client2::new_from_defaults());
+        svc.register(
+            "hot",
+            Query::Hot(Hotness::BoardGame),
+            Duration::from_secs(60),
+        );
+        svc.register(
+            "collection",
+            Query::Collection {
+                username: "someone".into(),
+                options: None,
+            },
+            Duration::from_secs(60),
+        );
+
+        assert_eq!(svc.names().len(), 2);
+        // Nothing has been fetched yet
+        let latest = svc.latest("hot").unwrap();
+        assert!(latest.value.is_none());
+        assert!(latest.refreshed_at.is_none());
+        assert!(svc.latest("nope").is_none());
+
+        // Newly registered queries are due straight away
+        assert_eq!(svc.due(Instant::now()).len(), 2);
+
+        svc.lock().get_mut("hot").unwrap().next_due = Instant::now() + Duration::from_secs(60);
+        let due = svc.due(Instant::now());
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].0, "collection");
+
+        assert!(svc.unregister("hot"));
+        assert!(!svc.unregister("hot"));
+        assert_eq!(svc.names(), vec!["collection".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_skips_cache() {
+        let cache = Arc::new(MemoryCache::new(10));
+        cache.put(
+            "http://127.0.0.1:1/xmlapi2/hot?type=boardgame",
+            "<items/>",
+            Duration::from_secs(60),
+        );
+        let client = Client2::builder()
+            .url_base("http://127.0.0.1:1")
+            .cache(cache)
+            .retry(RetryPolicy::none())
+            .throttle(None)
+            .build()
+            .unwrap();
+        let svc = RefreshService::new(client);
+        svc.register(
+            "hot",
+            Query::Hot(Hotness::BoardGame),
+            Duration::from_secs(60),
+        );
+
+        // Nothing is listening, so the refresh fails rather than serving
+        // the cached response
+        assert_eq!(svc.refresh_due().await, 1);
+        let latest = svc.latest("hot").unwrap();
+        assert!(latest.value.is_none());
+        assert!(latest.error.is_some());
+    }
+}
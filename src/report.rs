@@ -0,0 +1,305 @@
+/*!
+Render a user's collection as a single, self-contained HTML page, with a
+summary and a sortable table of the items.  This requires the `report`
+feature.
+
+```ignore,rust
+use rbgg::{bgg2::Client2, models::Collection, report, utils::Params};
+
+let cl = Client2::new_from_defaults();
+let opts = Params::from([("stats".to_string(), "1".to_string())]);
+let coll = Collection::from_value(&cl.collection_b("someuser", Some(opts))?)?;
+
+report::save(&coll, "someuser's collection", "collection.html")?;
+```
+*/
+use crate::errors::Result;
+use crate::models::{Collection, CollectionItem};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+const STYLE: &str = r#"
+body { font-family: sans-serif; margin: 2em; color: #222; }
+.summary { display: flex; gap: 2em; margin-bottom: 1.5em; }
+.summary div { background: #f3f3f3; padding: 0.75em 1.25em; border-radius: 4px; }
+.summary b { display: block; font-size: 1.5em; }
+table { border-collapse: collapse; width: 100%; }
+th, td { padding: 0.4em 0.6em; border-bottom: 1px solid #ddd; text-align: left; }
+th { cursor: pointer; background: #fafafa; user-select: none; }
+td img { max-width: 64px; max-height: 64px; }
+"#;
+
+// Sort the table by the clicked column, toggling the direction on
+// repeated clicks.  Cells carry a data-sort value when it differs from the
+// displayed text.
+const SCRIPT: &str = r#"
+document.querySelectorAll("th").forEach((th, col) => {
+  th.addEventListener("click", () => {
+    const body = th.closest("table").tBodies[0];
+    const asc = th.dataset.dir !== "asc";
+    th.dataset.dir = asc ? "asc" : "desc";
+    const key = (row) => {
+      const cell = row.cells[col];
+      return cell.dataset.sort !== undefined ? cell.dataset.sort : cell.textContent;
+    };
+    const rows = Array.from(body.rows);
+    rows.sort((a, b) => {
+      const x = key(a), y = key(b);
+      const nx = parseFloat(x), ny = parseFloat(y);
+      const cmp = (isNaN(nx) || isNaN(ny)) ? x.localeCompare(y) : nx - ny;
+      return asc ? cmp : -cmp;
+    });
+    rows.forEach((row) => body.appendChild(row));
+  });
+});
+"#;
+
+/// Summary figures for a collection report
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Summary {
+    pub items: usize,
+    pub owned: usize,
+    pub wishlist: usize,
+    pub plays: u64,
+    /// The mean of the user's ratings, over the rated items
+    pub avg_rating: Option<f64>,
+    /// The mean BGG rating, over the items that have one
+    pub avg_bgg_rating: Option<f64>,
+}
+
+impl Summary {
+    /// Calculate the summary for the collection
+    pub fn new(coll: &Collection) -> Self {
+        let items = &coll.items;
+
+        return Self {
+            items: items.len(),
+            owned: items.iter().filter(|i| i.status.own).count(),
+            wishlist: items.iter().filter(|i| i.status.wishlist).count(),
+            plays: items.iter().map(|i| i.num_plays as u64).sum(),
+            avg_rating: mean(items.iter().filter_map(|i| i.user_rating())),
+            avg_bgg_rating: mean(items.iter().filter_map(|i| i.average_rating())),
+        };
+    }
+}
+
+/// Render the collection as an HTML page with the given title
+pub fn render(coll: &Collection, title: &str) -> String {
+    let summary = Summary::new(coll);
+    let mut ret = String::new();
+
+    // Writing to a String can't fail, so the results are ignored throughout
+    let _ = write!(
+        ret,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+        <title>{0}</title>\n<style>{1}</style>\n</head>\n<body>\n<h1>{0}</h1>\n",
+        escape(title),
+        STYLE,
+    );
+
+    let _ = write!(
+        ret,
+        "<div class=\"summary\">\n\
+        <div><b>{}</b>items</div>\n\
+        <div><b>{}</b>owned</div>\n\
+        <div><b>{}</b>on the wishlist</div>\n\
+        <div><b>{}</b>plays</div>\n\
+        <div><b>{}</b>average rating</div>\n\
+        <div><b>{}</b>average BGG rating</div>\n\
+        </div>\n",
+        summary.items,
+        summary.owned,
+        summary.wishlist,
+        summary.plays,
+        fmt_rating(summary.avg_rating),
+        fmt_rating(summary.avg_bgg_rating),
+    );
+
+    ret.push_str(
+        "<table>\n<thead>\n<tr><th></th><th>Name</th><th>Year</th><th>Players</th>\
+        <th>Plays</th><th>Rating</th><th>BGG Rating</th><th>Status</th></tr>\n\
+        </thead>\n<tbody>\n",
+    );
+    for item in &coll.items {
+        ret.push_str(&row(item));
+    }
+    let _ = write!(ret, "</tbody>\n</table>\n<script>{}</script>\n", SCRIPT);
+    ret.push_str("</body>\n</html>\n");
+
+    return ret;
+}
+
+/// Render the collection and write the page to the given path
+pub fn save<P: AsRef<Path>>(coll: &Collection, title: &str, path: P) -> Result<()> {
+    fs::write(path, render(coll, title))?;
+
+    return Ok(());
+}
+
+/* Begin private functions */
+
+/// Render a single table row for the item
+fn row(item: &CollectionItem) -> String {
+    let thumb = match &item.thumbnail {
+        Some(url) => format!("<img src=\"{}\" alt=\"\" loading=\"lazy\">", escape(url)),
+        None => String::new(),
+    };
+    let players = match item.stats.as_ref().map(|s| (s.min_players, s.max_players)) {
+        Some((Some(min), Some(max))) if min == max => min.to_string(),
+        Some((Some(min), Some(max))) => format!("{}–{}", min, max),
+        _ => String::new(),
+    };
+    let min_players = item.stats.as_ref().and_then(|s| s.min_players).unwrap_or(0);
+
+    return format!(
+        "<tr><td>{}</td>\
+        <td><a href=\"https://boardgamegeek.com/boardgame/{}\">{}</a></td>\
+        <td>{}</td><td data-sort=\"{}\">{}</td><td>{}</td>\
+        <td>{}</td><td>{}</td><td>{}</td></tr>\n",
+        thumb,
+        item.id,
+        escape(&item.name),
+        item.year_published
+            .map(|y| y.to_string())
+            .unwrap_or_default(),
+        min_players,
+        players,
+        item.num_plays,
+        fmt_rating(item.user_rating()),
+        fmt_rating(item.average_rating()),
+        escape(&status(item)),
+    );
+}
+
+/// A short, human readable description of the item's status flags
+fn status(item: &CollectionItem) -> String {
+    let st = &item.status;
+    let mut ret = vec![];
+
+    for (set, label) in [
+        (st.own, "Owned"),
+        (st.prev_owned, "Previously owned"),
+        (st.for_trade, "For trade"),
+        (st.want, "Want"),
+        (st.want_to_play, "Want to play"),
+        (st.want_to_buy, "Want to buy"),
+        (st.preordered, "Preordered"),
+    ] {
+        if set {
+            ret.push(label.to_string());
+        }
+    }
+    if st.wishlist {
+        match st.wishlist_priority {
+            Some(p) => ret.push(format!("Wishlist ({})", p)),
+            None => ret.push("Wishlist".to_string()),
+        }
+    }
+
+    return ret.join(", ");
+}
+
+fn fmt_rating(rating: Option<f64>) -> String {
+    return rating.map(|r| format!("{:.2}", r)).unwrap_or_default();
+}
+
+fn mean<I: Iterator<Item = f64>>(vals: I) -> Option<f64> {
+    let (sum, count) = vals.fold((0.0, 0), |(s, c), v| (s + v, c + 1));
+    if count == 0 {
+        return None;
+    }
+
+    return Some(sum / count as f64);
+}
+
+/// Escape text for use in HTML content and attribute values
+fn escape(s: &str) -> String {
+    let mut ret = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => ret.push_str("&amp;"),
+            '<' => ret.push_str("&lt;"),
+            '>' => ret.push_str("&gt;"),
+            '"' => ret.push_str("&quot;"),
+            '\'' => ret.push_str("&#39;"),
+            _ => ret.push(c),
+        }
+    }
+
+    return ret;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CollectionRating, CollectionStats, CollectionStatus};
+
+    fn collection() -> Collection {
+        return Collection {
+            total_items: 2,
+            items: vec![
+                CollectionItem {
+                    id: 136888,
+                    name: "Bruges".into(),
+                    year_published: Some(2013),
+                    thumbnail: Some("https://example.com/bruges.jpg".into()),
+                    stats: Some(CollectionStats {
+                        min_players: Some(2),
+                        max_players: Some(4),
+                        rating: CollectionRating {
+                            value: Some(8.0),
+                            average: Some(7.3),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    }),
+                    status: CollectionStatus {
+                        own: true,
+                        ..Default::default()
+                    },
+                    num_plays: 3,
+                    ..Default::default()
+                },
+                CollectionItem {
+                    id: 1,
+                    name: "Cats & <Dogs>".into(),
+                    status: CollectionStatus {
+                        wishlist: true,
+                        wishlist_priority: Some(2),
+                        ..Default::default()
+                    },
+                    num_plays: 1,
+                    ..Default::default()
+                },
+            ],
+        };
+    }
+
+    #[test]
+    fn test_summary() {
+        let summary = Summary::new(&collection());
+
+        assert_eq!(summary.items, 2);
+        assert_eq!(summary.owned, 1);
+        assert_eq!(summary.wishlist, 1);
+        assert_eq!(summary.plays, 4);
+        assert_eq!(summary.avg_rating, Some(8.0));
+        assert_eq!(summary.avg_bgg_rating, Some(7.3));
+        assert_eq!(Summary::new(&Collection::default()).avg_rating, None);
+    }
+
+    #[test]
+    fn test_render() {
+        let html = render(&collection(), "Me & my games");
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Me &amp; my games</title>"));
+        assert!(html.contains("<img src=\"https://example.com/bruges.jpg\""));
+        assert!(html.contains("https://boardgamegeek.com/boardgame/136888"));
+        assert!(html.contains("<td data-sort=\"2\">2–4</td>"));
+        assert!(html.contains("Cats &amp; &lt;Dogs&gt;"));
+        assert!(html.contains("Wishlist (2)"));
+        assert!(html.contains("<script>"));
+    }
+}
@@ -0,0 +1,320 @@
+/*!
+Retrying of requests that fail for transient reasons, like BGG's rate
+limiting (HTTP 429), a server error (5xx) or a dropped connection.  Clients
+retry these by default (see `RetryPolicy::default()`), waiting
+exponentially longer between each attempt, with some random jitter so that
+a group of clients don't all retry in lockstep.
+
+Requests that BGG has queued (HTTP 202) are handled separately, by the
+`QueuePolicy`, as waiting on those is expected rather than a failure.
+
+```ignore,rust
+use rbgg::{bgg2::Client2, http::HttpConfig, retry::RetryPolicy};
+use std::time::Duration;
+
+let config = HttpConfig {
+    retry: RetryPolicy {
+        max_attempts: 6,
+        base_delay: Duration::from_secs(2),
+        ..Default::default()
+    },
+    ..Default::default()
+};
+let cl = Client2::new_with_config(None, None, config)?;
+
+// Or surface every failure straight away
+let config = HttpConfig {
+    retry: RetryPolicy::none(),
+    ..Default::default()
+};
+```
+*/
+use crate::errors::{RbggError, Result};
+use crate::transport::TransportError;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// How (and whether) to retry requests that fail for a transient reason
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// The total number of attempts, including the first one.  1 means
+    /// that nothing is retried.
+    pub max_attempts: u32,
+    /// The wait before the first retry, which doubles on each one after
+    pub base_delay: Duration,
+    /// The most we'll wait between attempts, including any Retry-After
+    /// that BGG sends
+    pub max_delay: Duration,
+    /// The fraction (0.0 - 1.0) of each wait that is randomized.  With 0.5,
+    /// a 4 second wait becomes anything from 2 to 4 seconds.
+    pub jitter: f64,
+    /// The HTTP status codes that are retried
+    pub statuses: Vec<u16>,
+    /// Whether to retry when the request couldn't be sent, or timed out
+    pub network_errors: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        return Self {
+            max_attempts: 4,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+            jitter: 0.5,
+            statuses: vec![429, 500, 502, 503, 504],
+            network_errors: true,
+        };
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries
+    pub fn none() -> Self {
+        return Self {
+            max_attempts: 1,
+            ..Default::default()
+        };
+    }
+
+    /// Whether a response with the given status should be retried
+    pub fn retries_status(&self, status: u16) -> bool {
+        return self.statuses.contains(&status);
+    }
+
+    /// Whether a request that failed with the given error should be retried
+    pub fn retries_error(&self, err: &TransportError) -> bool {
+        return self.network_errors && err.is_transient();
+    }
+
+    /// The wait (before jitter) after the given attempt, where the first
+    /// attempt is 1
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+
+        return self
+            .base_delay
+            .checked_mul(factor)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay);
+    }
+
+    /// The wait before retrying after the given attempt, or `None` when
+    /// there are no attempts left.  A Retry-After from the server is
+    /// honoured (up to `max_delay`) when it is longer than our own backoff.
+    pub(crate) fn next_delay(
+        &self,
+        attempt: u32,
+        retry_after: Option<Duration>,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+
+        let delay = with_jitter(self.backoff(attempt), self.jitter);
+        let ret = match retry_after {
+            Some(after) => delay.max(after.min(self.max_delay)),
+            None => delay,
+        };
+
+        return Some(ret);
+    }
+}
+
+/// How long to keep waiting when BGG queues a request.  For large requests,
+/// most often a user's collection, BGG first answers with a 202 and we have
+/// to ask again once it has built the response.  If a request is never
+/// going to be ready, this stops us from asking forever, and
+/// `RbggError::QueueTimeout` is returned instead.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueuePolicy {
+    /// The total number of requests to make before giving up
+    pub max_attempts: u32,
+    /// The wait after the first 202
+    pub delay: Duration,
+    /// What to multiply the wait by after each 202 after that.  1.0 waits
+    /// the same amount each time.
+    pub backoff: f64,
+    /// The most we'll wait between any two requests
+    pub max_delay: Duration,
+}
+
+impl Default for QueuePolicy {
+    fn default() -> Self {
+        return Self {
+            max_attempts: 10,
+            delay: Duration::from_secs(2),
+            backoff: 1.5,
+            max_delay: Duration::from_secs(60),
+        };
+    }
+}
+
+impl QueuePolicy {
+    /// The wait after the given attempt got a 202, or `None` when there are
+    /// no attempts left
+    pub fn next_delay(&self, attempt: u32) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+
+        let exp = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let factor = self.backoff.max(1.0).powi(exp);
+        // A large backoff, or enough attempts, overflows a Duration, so
+        // anything past the cap is just the cap
+        let ret = Duration::try_from_secs_f64(self.delay.as_secs_f64() * factor)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay);
+
+        return Some(ret);
+    }
+
+    /// Check that the settings are usable, returning
+    /// `RbggError::InvalidInput` if not
+    pub fn validate(&self) -> Result<()> {
+        if !(self.backoff.is_finite() && self.backoff > 0.0) {
+            return Err(RbggError::InvalidInput(format!(
+                "The queue backoff must be a positive number, not {}",
+                self.backoff
+            )));
+        }
+
+        return Ok(());
+    }
+}
+
+/// Take a random fraction, up to `jitter`, off of the delay
+fn with_jitter(delay: Duration, jitter: f64) -> Duration {
+    let jitter = jitter.clamp(0.0, 1.0);
+    if jitter == 0.0 {
+        return delay;
+    }
+
+    return delay.mul_f64(1.0 - jitter * random());
+}
+
+/// A random number in [0, 1).  This doesn't need to be good, just different
+/// across calls and processes, so we lean on the randomly keyed hasher
+/// rather than pulling in a dependency.
+fn random() -> f64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let bits = RandomState::new().hash_one(n);
+
+    return (bits >> 11) as f64 / (1u64 << 53) as f64;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy {
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(5),
+            jitter: 0.0,
+            ..Default::default()
+        };
+
+        assert_eq!(policy.backoff(1), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff(3), Duration::from_secs(4));
+        assert_eq!(policy.backoff(4), Duration::from_secs(5));
+        assert_eq!(policy.backoff(100), Duration::from_secs(5));
+
+        assert_eq!(policy.next_delay(1, None), Some(Duration::from_secs(1)));
+        // A longer Retry-After wins, but is capped
+        assert_eq!(
+            policy.next_delay(1, Some(Duration::from_secs(3))),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(
+            policy.next_delay(1, Some(Duration::from_secs(60))),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(policy.next_delay(4, None), None);
+        assert_eq!(RetryPolicy::none().next_delay(1, None), None);
+    }
+
+    #[test]
+    fn test_jitter() {
+        let delay = Duration::from_secs(4);
+
+        for _ in 0..100 {
+            let d = with_jitter(delay, 0.5);
+            assert!(d >= Duration::from_secs(2) && d <= delay);
+        }
+        assert_eq!(with_jitter(delay, 0.0), delay);
+    }
+
+    #[test]
+    fn test_queue_policy() {
+        let policy = QueuePolicy {
+            max_attempts: 4,
+            delay: Duration::from_secs(2),
+            backoff: 1.5,
+            max_delay: Duration::from_secs(60),
+        };
+
+        assert_eq!(policy.next_delay(1), Some(Duration::from_secs(2)));
+        assert_eq!(policy.next_delay(2), Some(Duration::from_secs(3)));
+        assert_eq!(policy.next_delay(3), Some(Duration::from_millis(4500)));
+        assert_eq!(policy.next_delay(4), None);
+
+        let policy = QueuePolicy {
+            backoff: 0.5,
+            ..policy
+        };
+        assert_eq!(policy.next_delay(3), Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_queue_policy_cap() {
+        let policy = QueuePolicy {
+            max_attempts: u32::MAX,
+            delay: Duration::from_secs(2),
+            backoff: 2.0,
+            max_delay: Duration::from_secs(10),
+        };
+
+        assert_eq!(policy.next_delay(3), Some(Duration::from_secs(8)));
+        assert_eq!(policy.next_delay(4), Some(Duration::from_secs(10)));
+        // Far past what a Duration can hold
+        assert_eq!(policy.next_delay(5000), Some(Duration::from_secs(10)));
+        assert_eq!(
+            policy.next_delay(u32::MAX - 1),
+            Some(Duration::from_secs(10))
+        );
+
+        let policy = QueuePolicy {
+            backoff: f64::INFINITY,
+            ..policy
+        };
+        assert_eq!(policy.next_delay(2), Some(Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_queue_policy_validate() {
+        assert!(QueuePolicy::default().validate().is_ok());
+
+        for backoff in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let policy = QueuePolicy {
+                backoff,
+                ..Default::default()
+            };
+            assert!(matches!(policy.validate(), Err(RbggError::InvalidInput(_))));
+        }
+    }
+
+    #[test]
+    fn test_statuses() {
+        let policy = RetryPolicy::default();
+
+        assert!(policy.retries_status(429));
+        assert!(policy.retries_status(503));
+        assert!(!policy.retries_status(404));
+        assert!(!policy.retries_status(200));
+    }
+}
@@ -0,0 +1,304 @@
+/*!
+An authenticated session on boardgamegeek.com.  The XML APIs are read only,
+so anything that changes data on the site, like logging a play, has to go
+through the site itself as a logged in user.
+
+```ignore,rust
+use rbgg::{bgstats::BgStatsExport, session::BggSession};
+
+let export = BgStatsExport::load("BGStatsExport.json")?;
+let session = BggSession::login_b("someuser", "secret")?;
+let ids = session.log_plays_b(&export.to_plays())?;
+```
+*/
+use crate::errors::{RbggError, Result};
+use crate::http::HttpClient;
+use crate::models::Play;
+use http::header::{HeaderMap, SET_COOKIE};
+use serde_json::{json, Value};
+
+const URL_BASE: &str = "https://boardgamegeek.com";
+
+/// A logged in session on the site
+#[derive(Clone)]
+pub struct BggSession {
+    http: HttpClient,
+    url_base: String,
+    username: String,
+    cookies: String,
+}
+
+impl BggSession {
+    /// Log in (async) to the site with the given credentials
+    #[cfg(feature = "async")]
+    pub async fn login(username: &str, password: &str) -> Result<Self> {
+        return Self::login_with_http(HttpClient::default(), None, username, password).await;
+    }
+
+    /// Log in (blocking) to the site with the given credentials
+    pub fn login_b(username: &str, password: &str) -> Result<Self> {
+        return Self::login_with_http_b(HttpClient::default(), None, username, password);
+    }
+
+    /// Like `login()`, but with a custom HTTP client and, optionally, a
+    /// different url base than the default of https://boardgamegeek.com
+    #[cfg(feature = "async")]
+    pub async fn login_with_http(
+        http: HttpClient,
+        url_base: Option<String>,
+        username: &str,
+        password: &str,
+    ) -> Result<Self> {
+        let url_base = url_base.unwrap_or(URL_BASE.to_string());
+        let url = format!("{}/login/api/v1", url_base);
+        let (headers, _) = http
+            .post_json(&url, &login_body(username, password), None)
+            .await?;
+
+        return Self::from_login(http, url_base, username, &headers);
+    }
+
+    /// Like `login_b()`, but with a custom HTTP client and, optionally, a
+    /// different url base than the default of https://boardgamegeek.com
+    pub fn login_with_http_b(
+        http: HttpClient,
+        url_base: Option<String>,
+        username: &str,
+        password: &str,
+    ) -> Result<Self> {
+        let url_base = url_base.unwrap_or(URL_BASE.to_string());
+        let url = format!("{}/login/api/v1", url_base);
+        let (headers, _) = http.post_json_b(&url, &login_body(username, password), None)?;
+
+        return Self::from_login(http, url_base, username, &headers);
+    }
+
+    /// The user this session is logged in as
+    pub fn username(&self) -> &str {
+        return &self.username;
+    }
+
+    /// Log (async) a play for the logged in user, returning the ID BGG
+    /// assigned to it
+    #[cfg(feature = "async")]
+    pub async fn log_play(&self, play: &Play) -> Result<usize> {
+        let (_, body) = self
+            .http
+            .post_json(&self.play_url(), &play_body(play), Some(&self.cookies))
+            .await?;
+
+        return play_id(&body);
+    }
+
+    /// Log (blocking) a play for the logged in user, returning the ID BGG
+    /// assigned to it
+    pub fn log_play_b(&self, play: &Play) -> Result<usize> {
+        let (_, body) =
+            self.http
+                .post_json_b(&self.play_url(), &play_body(play), Some(&self.cookies))?;
+
+        return play_id(&body);
+    }
+
+    /// Log (async) each of the plays in turn, returning the new IDs.  This
+    /// stops at the first failure.
+    #[cfg(feature = "async")]
+    pub async fn log_plays(&self, plays: &[Play]) -> Result<Vec<usize>> {
+        let mut ret = vec![];
+        for play in plays {
+            ret.push(self.log_play(play).await?);
+        }
+
+        return Ok(ret);
+    }
+
+    /// Log (blocking) each of the plays in turn, returning the new IDs.  This
+    /// stops at the first failure.
+    pub fn log_plays_b(&self, plays: &[Play]) -> Result<Vec<usize>> {
+        let mut ret = vec![];
+        for play in plays {
+            ret.push(self.log_play_b(play)?);
+        }
+
+        return Ok(ret);
+    }
+
+    /* Begin private functions */
+
+    fn from_login(
+        http: HttpClient,
+        url_base: String,
+        username: &str,
+        headers: &HeaderMap,
+    ) -> Result<Self> {
+        let cookies = session_cookies(headers);
+        if cookies.is_empty() {
+            return Err(RbggError::Api {
+                message: format!("Login for {} did not return a session", username),
+            });
+        }
+
+        return Ok(Self {
+            http,
+            url_base,
+            username: username.to_string(),
+            cookies,
+        });
+    }
+
+    fn play_url(&self) -> String {
+        return format!("{}/geekplay.php", self.url_base);
+    }
+}
+
+/// The body for the login request
+fn login_body(username: &str, password: &str) -> Value {
+    return json!({
+        "credentials": {
+            "username": username,
+            "password": password,
+        },
+    });
+}
+
+/// Collect the cookies set by the login response into a `Cookie` header
+/// value, skipping any that are being cleared
+fn session_cookies(headers: &HeaderMap) -> String {
+    let mut ret = vec![];
+    for val in headers.get_all(SET_COOKIE) {
+        let pair = match val.to_str() {
+            Ok(v) => v.split(';').next().unwrap_or("").trim(),
+            Err(_) => continue,
+        };
+        match pair.split_once('=') {
+            Some((_, value)) if !value.is_empty() && value != "deleted" => {
+                ret.push(pair.to_string())
+            }
+            _ => (),
+        }
+    }
+
+    return ret.join("; ");
+}
+
+/// The body used by the site's play logging form
+fn play_body(play: &Play) -> Value {
+    let players: Vec<Value> = play
+        .players
+        .iter()
+        .map(|p| {
+            json!({
+                "username": p.username,
+                "userid": p.user_id.unwrap_or(0),
+                "name": p.name,
+                "position": p.start_position,
+                "color": p.color,
+                "score": p.score,
+                "rating": p.rating.unwrap_or(0.0),
+                "new": p.new,
+                "win": p.win,
+            })
+        })
+        .collect();
+
+    return json!({
+        "action": "save",
+        "ajax": 1,
+        "objecttype": match play.item.object_type.is_empty() {
+            true => "thing",
+            false => &play.item.object_type,
+        },
+        "objectid": play.item.id.to_string(),
+        "playdate": play.date,
+        "quantity": play.quantity.max(1).to_string(),
+        "length": play.length,
+        "incomplete": play.incomplete,
+        "nowinstats": play.no_win_stats,
+        "location": play.location,
+        "comments": play.comments.clone().unwrap_or_default(),
+        "players": players,
+    });
+}
+
+/// Pull the new play ID out of a play logging response
+fn play_id(body: &str) -> Result<usize> {
+    let resp: Value = serde_json::from_str(body)?;
+    if let Some(err) = resp["error"].as_str() {
+        return Err(RbggError::Api {
+            message: format!("Failed to log play: {}", err),
+        });
+    }
+
+    return match &resp["playid"] {
+        Value::Number(n) => n.as_u64().map(|n| n as usize),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| RbggError::Api {
+        message: format!("No play ID in the response: {}", body),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PlayItem, Player};
+    use http::header::HeaderValue;
+
+    #[test]
+    fn test_session_cookies() {
+        let mut headers = HeaderMap::new();
+        headers.append(
+            SET_COOKIE,
+            HeaderValue::from_static("bggusername=me; path=/; secure"),
+        );
+        headers.append(
+            SET_COOKIE,
+            HeaderValue::from_static("bggpassword=abc123; path=/"),
+        );
+        headers.append(SET_COOKIE, HeaderValue::from_static("old=deleted; path=/"));
+        headers.append(SET_COOKIE, HeaderValue::from_static("SessionID=xyz"));
+
+        assert_eq!(
+            session_cookies(&headers),
+            "bggusername=me; bggpassword=abc123; SessionID=xyz",
+        );
+        assert_eq!(session_cookies(&HeaderMap::new()), "");
+    }
+
+    #[test]
+    fn test_play_body() {
+        let play = Play {
+            date: "2023-01-05".into(),
+            length: 60,
+            item: PlayItem {
+                id: 136888,
+                name: "Bruges".into(),
+                ..Default::default()
+            },
+            players: vec![Player {
+                name: "Me".into(),
+                score: "52".into(),
+                win: true,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let body = play_body(&play);
+
+        assert_eq!(body["objecttype"], "thing");
+        assert_eq!(body["objectid"], "136888");
+        assert_eq!(body["quantity"], "1");
+        assert_eq!(body["playdate"], "2023-01-05");
+        assert_eq!(body["players"][0]["name"], "Me");
+        assert_eq!(body["players"][0]["win"], true);
+    }
+
+    #[test]
+    fn test_play_id() {
+        assert_eq!(play_id(r#"{"playid":"1234","numplays":3}"#).unwrap(), 1234);
+        assert_eq!(play_id(r#"{"playid":1234}"#).unwrap(), 1234);
+        assert!(play_id(r#"{"error":"You must login"}"#).is_err());
+        assert!(play_id(r#"{}"#).is_err());
+    }
+}
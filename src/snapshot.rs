@@ -0,0 +1,327 @@
+/*!
+Point in time snapshots of a collection, which can be saved to disk and
+diffed against each other later.  This is what you want for periodic jobs
+that report on what changed in a collection between runs.
+
+```ignore,rust
+use rbgg::{bgg2::Client2, models::Collection, snapshot::CollectionSnapshot, utils::Params};
+
+let cl = Client2::new_from_defaults();
+let params = Params::from([("stats".into(), "1".into())]);
+let coll = Collection::from_value(&cl.collection_b("someuser", Some(params))?)?;
+let new = CollectionSnapshot::new("someuser", &coll);
+
+let old = CollectionSnapshot::load("someuser.json")?;
+let diff = CollectionSnapshot::diff(&old, &new);
+println!("{} added, {} removed", diff.added.len(), diff.removed.len());
+
+new.save("someuser.json")?;
+```
+*/
+use crate::errors::Result;
+use crate::models::{Collection, CollectionItem};
+use crate::utils;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The status flags for an item in a snapshot
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotStatus {
+    pub own: bool,
+    pub prev_owned: bool,
+    pub for_trade: bool,
+    pub want: bool,
+    pub want_to_play: bool,
+    pub want_to_buy: bool,
+    pub wishlist: bool,
+    pub wishlist_priority: Option<u8>,
+    pub preordered: bool,
+}
+
+/// What items in a snapshot are keyed on: their collection ID (if they have
+/// one) and game ID.  Using both keeps a collection ID from ever being
+/// mistaken for the game ID of an item without one.
+pub type SnapshotKey = (Option<usize>, usize);
+
+/// A single item in a snapshot
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotItem {
+    pub id: usize,
+    pub coll_id: Option<usize>,
+    pub name: String,
+    pub status: SnapshotStatus,
+    /// The user's rating, which requires `stats=1` on the collection call
+    pub rating: Option<f64>,
+}
+
+impl From<&CollectionItem> for SnapshotItem {
+    fn from(item: &CollectionItem) -> Self {
+        let s = &item.status;
+
+        return Self {
+            id: item.id,
+            coll_id: item.coll_id,
+            name: item.name.clone(),
+            status: SnapshotStatus {
+                own: s.own,
+                prev_owned: s.prev_owned,
+                for_trade: s.for_trade,
+                want: s.want,
+                want_to_play: s.want_to_play,
+                want_to_buy: s.want_to_buy,
+                wishlist: s.wishlist,
+                wishlist_priority: s.wishlist_priority,
+                preordered: s.preordered,
+            },
+            rating: item.user_rating(),
+        };
+    }
+}
+
+impl SnapshotItem {
+    /// The key for this item in a snapshot
+    pub fn key(&self) -> SnapshotKey {
+        return (self.coll_id, self.id);
+    }
+}
+
+/// A snapshot of a user's collection at a point in time
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CollectionSnapshot {
+    pub username: String,
+    /// When the snapshot was taken, in seconds since the epoch
+    pub taken_at: u64,
+    /// The items, keyed by their collection and game IDs.  These are saved
+    /// as a list, as the keys can be worked out from the items.
+    #[serde(serialize_with = "save_items", deserialize_with = "load_items")]
+    pub items: BTreeMap<SnapshotKey, SnapshotItem>,
+}
+
+/// The differences between two snapshots
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CollectionDiff {
+    pub added: Vec<SnapshotItem>,
+    pub removed: Vec<SnapshotItem>,
+    /// Items whose status flags changed as (old, new)
+    pub status_changed: Vec<(SnapshotItem, SnapshotItem)>,
+    /// Items whose rating changed as (old, new)
+    pub rating_changed: Vec<(SnapshotItem, SnapshotItem)>,
+}
+
+impl CollectionDiff {
+    /// Whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        return self.added.is_empty()
+            && self.removed.is_empty()
+            && self.status_changed.is_empty()
+            && self.rating_changed.is_empty();
+    }
+}
+
+impl CollectionSnapshot {
+    /// Take a snapshot of the given collection
+    pub fn new(username: &str, collection: &Collection) -> Self {
+        let taken_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        return Self {
+            username: username.to_string(),
+            taken_at,
+            items: collection
+                .items
+                .iter()
+                .map(SnapshotItem::from)
+                .map(|i| (i.key(), i))
+                .collect(),
+        };
+    }
+
+    /// Save the snapshot (as JSON) to the given path
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer.flush()?;
+
+        return Ok(());
+    }
+
+    /// Load a snapshot previously written with `save()`
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let data = fs::read_to_string(path)?;
+        let ret = serde_json::from_value(utils::parse_json(&data)?)?;
+
+        return Ok(ret);
+    }
+
+    /// Work out what changed between the `old` and `new` snapshots
+    pub fn diff(old: &Self, new: &Self) -> CollectionDiff {
+        let mut ret = CollectionDiff::default();
+
+        for (key, item) in &new.items {
+            match old.items.get(key) {
+                None => ret.added.push(item.clone()),
+                Some(prev) => {
+                    if prev.status != item.status {
+                        ret.status_changed.push((prev.clone(), item.clone()));
+                    }
+                    if prev.rating != item.rating {
+                        ret.rating_changed.push((prev.clone(), item.clone()));
+                    }
+                }
+            }
+        }
+
+        for (key, item) in &old.items {
+            if !new.items.contains_key(key) {
+                ret.removed.push(item.clone());
+            }
+        }
+
+        return ret;
+    }
+}
+
+/// Save the items as a list
+fn save_items<S>(items: &BTreeMap<SnapshotKey, SnapshotItem>, ser: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    return ser.collect_seq(items.values());
+}
+
+/// The items as they've been saved: a list, or a map keyed by the
+/// collection (or game) ID in older snapshots
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SavedItems {
+    List(Vec<SnapshotItem>),
+    Map(BTreeMap<String, SnapshotItem>),
+}
+
+/// Load the items, in either format, working out their keys
+fn load_items<'de, D>(de: D) -> Result<BTreeMap<SnapshotKey, SnapshotItem>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let items = match SavedItems::deserialize(de)? {
+        SavedItems::List(items) => items,
+        SavedItems::Map(items) => items.into_values().collect(),
+    };
+
+    return Ok(items.into_iter().map(|i| (i.key(), i)).collect());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: usize, coll_id: usize, own: bool, rating: Option<f64>) -> SnapshotItem {
+        return SnapshotItem {
+            id,
+            coll_id: Some(coll_id),
+            name: format!("Game {}", id),
+            status: SnapshotStatus {
+                own,
+                ..Default::default()
+            },
+            rating,
+        };
+    }
+
+    fn snapshot(items: Vec<SnapshotItem>) -> CollectionSnapshot {
+        return CollectionSnapshot {
+            username: "someone".into(),
+            taken_at: 0,
+            items: items.into_iter().map(|i| (i.key(), i)).collect(),
+        };
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = snapshot(vec![
+            item(1, 10, true, None),
+            item(2, 11, true, Some(7.0)),
+            item(3, 12, false, None),
+        ]);
+        let new = snapshot(vec![
+            item(1, 10, true, None),
+            item(2, 11, false, Some(8.0)),
+            item(4, 13, true, None),
+        ]);
+
+        let diff = CollectionSnapshot::diff(&old, &new);
+        assert!(!diff.is_empty());
+        assert_eq!(diff.added, vec![item(4, 13, true, None)]);
+        assert_eq!(diff.removed, vec![item(3, 12, false, None)]);
+        assert_eq!(diff.status_changed.len(), 1);
+        assert_eq!(diff.status_changed[0].0.id, 2);
+        assert!(!diff.status_changed[0].1.status.own);
+        assert_eq!(diff.rating_changed.len(), 1);
+        assert_eq!(diff.rating_changed[0].1.rating, Some(8.0));
+
+        assert!(CollectionSnapshot::diff(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn test_save_load() {
+        let mut coll_item = CollectionItem {
+            id: 136888,
+            coll_id: Some(1),
+            name: "Bruges".into(),
+            ..Default::default()
+        };
+        coll_item.status.own = true;
+        let coll = Collection {
+            total_items: 1,
+            items: vec![coll_item],
+        };
+        let snap = CollectionSnapshot::new("someone", &coll);
+        assert!(snap.taken_at > 0);
+        assert!(snap.items[&(Some(1), 136888)].status.own);
+
+        let path = std::env::temp_dir().join(format!("rbgg-snapshot-{}.json", std::process::id()));
+        snap.save(&path).unwrap();
+        let loaded = CollectionSnapshot::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, snap);
+    }
+
+    #[test]
+    fn test_key_collision() {
+        // An item without a collection ID, whose game ID is the same as
+        // another item's collection ID
+        let mut loose = item(10, 0, true, None);
+        loose.coll_id = None;
+        let old = snapshot(vec![item(1, 10, true, None), loose.clone()]);
+        assert_eq!(old.items.len(), 2);
+
+        let new = snapshot(vec![item(1, 10, true, None)]);
+        let diff = CollectionSnapshot::diff(&old, &new);
+        assert!(diff.added.is_empty());
+        assert_eq!(diff.removed, vec![loose]);
+        assert!(diff.status_changed.is_empty());
+    }
+
+    #[test]
+    fn test_load_old_format() {
+        let data = r#"{
+            "username": "someone",
+            "taken_at": 1,
+            "items": {"10": {"id": 1, "coll_id": 10, "name": "Game 1",
+                "status": {"own": true, "prev_owned": false, "for_trade": false,
+                    "want": false, "want_to_play": false, "want_to_buy": false,
+                    "wishlist": false, "wishlist_priority": null, "preordered": false},
+                "rating": null}}
+        }"#;
+        let snap: CollectionSnapshot = serde_json::from_str(data).unwrap();
+
+        assert_eq!(snap.items[&(Some(10), 1)], item(1, 10, true, None));
+    }
+}
@@ -0,0 +1,385 @@
+/*!
+Utilities for testing code built on rbgg (with the `testing` feature), so
+that your tests don't need to hit boardgamegeek.com.  There's a realistic
+sample response for each endpoint, and a mock server (using `wiremock`)
+that serves them, along with clients pointed at it.
+
+```ignore,rust
+use rbgg::{bgg2::Hotness, testing};
+
+#[tokio::test]
+async fn test_my_hotness_widget() {
+    let server = testing::mock_server().await;
+    let cl = testing::client2(&server).unwrap();
+
+    let hot = cl.hot(Hotness::BoardGame, None).await.unwrap();
+    // ...
+}
+```
+
+The server is a plain `wiremock::MockServer`, so you can mount your own
+mocks on it too.  To replace one of the sample responses, mount yours with
+a higher priority (e.g. `.with_priority(1)`).
+*/
+use crate::bgg1::Client1;
+use crate::bgg2::Client2;
+use crate::errors::Result;
+use crate::http::HttpConfig;
+use crate::retry::RetryPolicy;
+use wiremock::matchers::{method, path_regex};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// A response from the v2 `thing` endpoint, with stats
+pub const THING: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<items termsofuse="https://boardgamegeek.com/xmlapi/termsofuse">
+    <item type="boardgame" id="13">
+        <thumbnail>https://cf.geekdo-images.com/catan_t.jpg</thumbnail>
+        <image>https://cf.geekdo-images.com/catan.jpg</image>
+        <name type="primary" sortindex="1" value="CATAN" />
+        <name type="alternate" sortindex="1" value="Die Siedler von Catan" />
+        <description>In CATAN, players try to be the dominant force on the island of Catan.</description>
+        <yearpublished value="1995" />
+        <minplayers value="3" />
+        <maxplayers value="4" />
+        <playingtime value="120" />
+        <minplaytime value="60" />
+        <maxplaytime value="120" />
+        <minage value="10" />
+        <link type="boardgamecategory" id="1021" value="Economic" />
+        <link type="boardgamemechanic" id="2072" value="Dice Rolling" />
+        <link type="boardgamedesigner" id="11" value="Klaus Teuber" />
+        <link type="boardgamepublisher" id="37" value="KOSMOS" />
+        <statistics page="1">
+            <ratings>
+                <usersrated value="120000" />
+                <average value="7.1" />
+                <bayesaverage value="6.9" />
+                <ranks>
+                    <rank type="subtype" id="1" name="boardgame" friendlyname="Board Game Rank" value="500" bayesaverage="6.9" />
+                </ranks>
+                <stddev value="1.5" />
+                <owned value="200000" />
+                <trading value="2000" />
+                <wanting value="500" />
+                <wishing value="5000" />
+                <numcomments value="20000" />
+                <numweights value="8000" />
+                <averageweight value="2.3" />
+            </ratings>
+        </statistics>
+    </item>
+</items>"#;
+
+/// A response from the v2 `hot` endpoint
+pub const HOT: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<items termsofuse="https://boardgamegeek.com/xmlapi/termsofuse">
+    <item id="13" rank="1">
+        <thumbnail value="https://cf.geekdo-images.com/catan_t.jpg" />
+        <name value="CATAN" />
+        <yearpublished value="1995" />
+    </item>
+    <item id="822" rank="2">
+        <thumbnail value="https://cf.geekdo-images.com/carcassonne_t.jpg" />
+        <name value="Carcassonne" />
+        <yearpublished value="2000" />
+    </item>
+</items>"#;
+
+/// A response from the v2 `user` endpoint
+pub const USER: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<user id="1" name="someuser" termsofuse="https://boardgamegeek.com/xmlapi/termsofuse">
+    <firstname value="Some" />
+    <lastname value="User" />
+    <avatarlink value="N/A" />
+    <yearregistered value="2010" />
+    <lastlogin value="2024-01-01" />
+    <stateorprovince value="" />
+    <country value="United States" />
+    <webaddress value="" />
+    <traderating value="5" />
+</user>"#;
+
+/// A response from the v2 `collection` endpoint, with stats
+pub const COLLECTION: &str = r#"<?xml version="1.0" encoding="utf-8" standalone="yes"?>
+<items totalitems="2" termsofuse="https://boardgamegeek.com/xmlapi/termsofuse" pubdate="Mon, 01 Jan 2024 00:00:00 +0000">
+    <item objecttype="thing" objectid="13" subtype="boardgame" collid="1001">
+        <name sortindex="1">CATAN</name>
+        <yearpublished>1995</yearpublished>
+        <stats minplayers="3" maxplayers="4" minplaytime="60" maxplaytime="120" playingtime="120" numowned="200000">
+            <rating value="8">
+                <usersrated value="120000" />
+                <average value="7.1" />
+                <bayesaverage value="6.9" />
+            </rating>
+        </stats>
+        <status own="1" prevowned="0" fortrade="0" want="0" wanttoplay="0" wanttobuy="0" wishlist="0" preordered="0" lastmodified="2024-01-01 00:00:00" />
+        <numplays>12</numplays>
+    </item>
+    <item objecttype="thing" objectid="822" subtype="boardgame" collid="1002">
+        <name sortindex="1">Carcassonne</name>
+        <yearpublished>2000</yearpublished>
+        <stats minplayers="2" maxplayers="5" minplaytime="30" maxplaytime="45" playingtime="45" numowned="150000">
+            <rating value="N/A">
+                <usersrated value="100000" />
+                <average value="7.4" />
+                <bayesaverage value="7.2" />
+            </rating>
+        </stats>
+        <status own="0" prevowned="0" fortrade="0" want="0" wanttoplay="0" wanttobuy="0" wishlist="1" wishlistpriority="2" preordered="0" lastmodified="2024-01-02 00:00:00" />
+        <numplays>0</numplays>
+    </item>
+</items>"#;
+
+/// A response from the v2 `plays` endpoint
+pub const PLAYS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<plays username="someuser" userid="1" total="1" page="1" termsofuse="https://boardgamegeek.com/xmlapi/termsofuse">
+    <play id="5000" date="2024-01-01" quantity="1" length="90" incomplete="0" nowinstats="0" location="Home">
+        <item name="CATAN" objecttype="thing" objectid="13">
+            <subtypes>
+                <subtype value="boardgame" />
+            </subtypes>
+        </item>
+        <players>
+            <player username="someuser" userid="1" name="Some User" startposition="1" color="red" score="10" new="0" rating="0" win="1" />
+            <player username="" userid="0" name="Friend" startposition="2" color="blue" score="8" new="1" rating="0" win="0" />
+        </players>
+    </play>
+</plays>"#;
+
+/// A response from the v2 `search` endpoint
+pub const SEARCH: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<items total="2" termsofuse="https://boardgamegeek.com/xmlapi/termsofuse">
+    <item type="boardgame" id="13">
+        <name type="primary" value="CATAN" />
+        <yearpublished value="1995" />
+    </item>
+    <item type="boardgameexpansion" id="926">
+        <name type="primary" value="CATAN: Seafarers" />
+        <yearpublished value="1997" />
+    </item>
+</items>"#;
+
+/// A response from the v2 `family` endpoint
+pub const FAMILY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<items termsofuse="https://boardgamegeek.com/xmlapi/termsofuse">
+    <item type="boardgamefamily" id="3">
+        <thumbnail>https://cf.geekdo-images.com/family_t.jpg</thumbnail>
+        <image>https://cf.geekdo-images.com/family.jpg</image>
+        <name type="primary" sortindex="1" value="Catan" />
+        <description>Games in the Catan family.</description>
+        <link type="boardgamefamily" id="13" value="CATAN" inbound="true" />
+        <link type="boardgamefamily" id="926" value="CATAN: Seafarers" inbound="true" />
+    </item>
+</items>"#;
+
+/// A response from the v2 `guild` endpoint, with members
+pub const GUILD: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<guild id="1000" name="Some Guild" created="Mon, 01 Jan 2024 00:00:00 +0000" termsofuse="https://boardgamegeek.com/xmlapi/termsofuse">
+    <category>group</category>
+    <website>https://example.com</website>
+    <manager>someuser</manager>
+    <description>A guild for testing.</description>
+    <location>
+        <addr1></addr1>
+        <addr2></addr2>
+        <city>Springfield</city>
+        <stateorprovince></stateorprovince>
+        <postalcode></postalcode>
+        <country>United States</country>
+    </location>
+    <members count="2" page="1">
+        <member name="someuser" date="Mon, 01 Jan 2024 00:00:00 +0000" />
+        <member name="friend" date="Tue, 02 Jan 2024 00:00:00 +0000" />
+    </members>
+</guild>"#;
+
+/// A response from the v2 `forumlist` endpoint
+pub const FORUM_LIST: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<forums type="thing" id="13" termsofuse="https://boardgamegeek.com/xmlapi/termsofuse">
+    <forum id="19" groupid="0" title="General" noposting="0" description="Post here" numthreads="51" numposts="400" lastpostdate="Mon, 01 Jan 2024 00:00:00 +0000" />
+    <forum id="20" groupid="0" title="Rules" noposting="0" description="Rules questions" numthreads="10" numposts="40" lastpostdate="Mon, 01 Jan 2024 00:00:00 +0000" />
+</forums>"#;
+
+/// A response from the v2 `forum` endpoint
+pub const FORUM: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<forum id="19" title="General" numthreads="2" numposts="4" lastpostdate="Mon, 01 Jan 2024 00:00:00 +0000" noposting="0" termsofuse="https://boardgamegeek.com/xmlapi/termsofuse">
+    <threads>
+        <thread id="100" subject="Hello" author="someuser" numarticles="3" postdate="Mon, 01 Jan 2024 00:00:00 +0000" lastpostdate="Mon, 01 Jan 2024 00:00:00 +0000" />
+        <thread id="101" subject="Again" author="friend" numarticles="1" postdate="Mon, 01 Jan 2024 00:00:00 +0000" lastpostdate="Mon, 01 Jan 2024 00:00:00 +0000" />
+    </threads>
+</forum>"#;
+
+/// A response from the `thread` endpoint (the same in both versions)
+pub const THREAD: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<thread id="100" numarticles="2" link="https://boardgamegeek.com/thread/100" termsofuse="https://boardgamegeek.com/xmlapi/termsofuse">
+    <subject>Hello</subject>
+    <articles>
+        <article id="5" username="someuser" link="https://boardgamegeek.com/thread/100/article/5#5" postdate="2024-01-01T00:00:00-00:00" editdate="2024-01-01T00:00:00-00:00" numedits="0">
+            <subject>Hello</subject>
+            <body>Is anyone out there?</body>
+        </article>
+        <article id="6" username="friend" link="https://boardgamegeek.com/thread/100/article/6#6" postdate="2024-01-02T00:00:00-00:00" editdate="2024-01-02T00:00:00-00:00" numedits="1">
+            <subject>Re: Hello</subject>
+            <body>Yes!</body>
+        </article>
+    </articles>
+</thread>"#;
+
+/// A response from the v1 `boardgame` endpoint
+pub const BOARDGAME_V1: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<boardgames termsofuse="https://boardgamegeek.com/xmlapi/termsofuse">
+    <boardgame objectid="13">
+        <yearpublished>1995</yearpublished>
+        <minplayers>3</minplayers>
+        <maxplayers>4</maxplayers>
+        <playingtime>120</playingtime>
+        <age>10</age>
+        <name primary="true" sortindex="1">CATAN</name>
+        <description>In CATAN, players try to be the dominant force on the island of Catan.</description>
+        <thumbnail>https://cf.geekdo-images.com/catan_t.jpg</thumbnail>
+        <image>https://cf.geekdo-images.com/catan.jpg</image>
+        <boardgamepublisher objectid="37">KOSMOS</boardgamepublisher>
+        <boardgamedesigner objectid="11">Klaus Teuber</boardgamedesigner>
+    </boardgame>
+</boardgames>"#;
+
+/// A response from the v1 `search` endpoint
+pub const SEARCH_V1: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<boardgames termsofuse="https://boardgamegeek.com/xmlapi/termsofuse">
+    <boardgame objectid="13">
+        <name primary="true">CATAN</name>
+        <yearpublished>1995</yearpublished>
+    </boardgame>
+</boardgames>"#;
+
+/// A response from the v1 `geeklist` endpoint
+pub const GEEKLIST_V1: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<geeklist id="200" termsofuse="https://boardgamegeek.com/xmlapi/termsofuse">
+    <postdate>Mon, 01 Jan 2024 00:00:00 +0000</postdate>
+    <postdate_timestamp>1704067200</postdate_timestamp>
+    <editdate>Mon, 01 Jan 2024 00:00:00 +0000</editdate>
+    <editdate_timestamp>1704067200</editdate_timestamp>
+    <thumbs>5</thumbs>
+    <numitems>1</numitems>
+    <username>someuser</username>
+    <title>My favourites</title>
+    <description>The games I like best.</description>
+    <item id="300" objecttype="thing" subtype="boardgame" objectid="13" objectname="CATAN" username="someuser" postdate="Mon, 01 Jan 2024 00:00:00 +0000" editdate="Mon, 01 Jan 2024 00:00:00 +0000" thumbs="2" imageid="0">
+        <body>A classic.</body>
+    </item>
+</geeklist>"#;
+
+/// The sample responses, by the (regex) path they're served at
+pub const FIXTURES: &[(&str, &str)] = &[
+    ("^/xmlapi2/thing$", THING),
+    ("^/xmlapi2/hot$", HOT),
+    ("^/xmlapi2/user$", USER),
+    ("^/xmlapi2/collection$", COLLECTION),
+    ("^/xmlapi2/plays$", PLAYS),
+    ("^/xmlapi2/search$", SEARCH),
+    ("^/xmlapi2/family$", FAMILY),
+    ("^/xmlapi2/guild$", GUILD),
+    ("^/xmlapi2/forumlist$", FORUM_LIST),
+    ("^/xmlapi2/forum$", FORUM),
+    ("^/xmlapi2/thread$", THREAD),
+    ("^/xmlapi/boardgame/.+$", BOARDGAME_V1),
+    ("^/xmlapi/search$", SEARCH_V1),
+    ("^/xmlapi/geeklist/.+$", GEEKLIST_V1),
+    ("^/xmlapi/thread/.+$", THREAD),
+];
+
+/// Return the sample response for an endpoint's path (e.g.
+/// "/xmlapi2/thing" or "/xmlapi/boardgame/13")
+pub fn fixture(path: &str) -> Option<&'static str> {
+    return FIXTURES
+        .iter()
+        .find(|(pattern, _)| {
+            let pattern = pattern.trim_start_matches('^').trim_end_matches('$');
+            match pattern.strip_suffix("/.+") {
+                Some(prefix) => path
+                    .strip_prefix(prefix)
+                    .is_some_and(|rest| rest.len() > 1 && rest.starts_with('/')),
+                None => path == pattern,
+            }
+        })
+        .map(|(_, body)| *body);
+}
+
+/// Start a mock server that serves the sample responses
+pub async fn mock_server() -> MockServer {
+    let server = MockServer::start().await;
+    mount_fixtures(&server).await;
+
+    return server;
+}
+
+/// Mount the sample responses on an existing mock server
+pub async fn mount_fixtures(server: &MockServer) {
+    for (pattern, body) in FIXTURES {
+        Mock::given(method("GET"))
+            .and(path_regex(*pattern))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/xml; charset=utf-8")
+                    .set_body_string(*body),
+            )
+            .mount(server)
+            .await;
+    }
+}
+
+/// The configuration for clients talking to a mock server: no throttling,
+/// and no retries, so that tests run quickly
+pub fn test_config() -> HttpConfig {
+    return HttpConfig {
+        throttle: None,
+        retry: RetryPolicy::none(),
+        ..Default::default()
+    };
+}
+
+/// A v2 client that talks to the mock server
+pub fn client2(server: &MockServer) -> Result<Client2> {
+    return Client2::builder()
+        .url_base(server.uri())
+        .config(test_config())
+        .build();
+}
+
+/// A v1 client that talks to the mock server
+pub fn client1(server: &MockServer) -> Result<Client1> {
+    return Client1::new_with_config(Some(server.uri()), None, test_config());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::HttpClient;
+
+    #[test]
+    fn test_fixture() {
+        assert_eq!(fixture("/xmlapi2/thing"), Some(THING));
+        assert_eq!(fixture("/xmlapi/boardgame/13"), Some(BOARDGAME_V1));
+        assert_eq!(fixture("/xmlapi/boardgame/"), None);
+        assert_eq!(fixture("/xmlapi2/nothing"), None);
+    }
+
+    #[tokio::test]
+    async fn test_mock_server() {
+        let server = mock_server().await;
+        assert!(client1(&server).is_ok());
+        assert!(client2(&server).is_ok());
+        let http = HttpClient::new(test_config()).unwrap();
+
+        for (path, body) in [
+            ("/xmlapi2/hot?type=boardgame", HOT),
+            ("/xmlapi/geeklist/200", GEEKLIST_V1),
+        ] {
+            let mut out = vec![];
+            http.get_to_writer(&format!("{}{}", server.uri(), path), &mut out)
+                .await
+                .unwrap();
+            assert_eq!(out, body.as_bytes());
+        }
+    }
+}
@@ -0,0 +1,590 @@
+/*!
+A simple rate limiter (token bucket) with a priority aware queue in front
+of it.  Clients are throttled by default (see `ThrottleConfig::default()`),
+which means every request has to acquire a token before it is sent.
+Requests that are waiting on a token are serviced highest priority first,
+then in the order they arrived, so interactive lookups aren't starved by a
+long running background job.
+
+The throttle is also adaptive by default (see `AdaptiveConfig`): when BGG
+rate limits us, or keeps queueing requests, the rate is cut and then slowly
+recovers, so that long running crawlers regulate themselves.  Check on it
+with `throttle_state()` on a client.
+
+```ignore,rust
+use rbgg::{bgg2::Client2, http::HttpConfig, throttle::{Priority, ThrottleConfig}};
+
+// If you know what you're doing, you can relax the throttle, or disable it
+// entirely with `throttle: None`
+let config = HttpConfig {
+    throttle: Some(ThrottleConfig { rate: 5.0, burst: 10, ..Default::default() }),
+    ..Default::default()
+};
+let cl = Client2::new_with_config(None, None, config)?;
+
+// A bulk export can run at a low priority ...
+let bulk = cl.with_priority(Priority::Low);
+// ... while the original client keeps servicing lookups first
+let res = cl.boardgame(&vec![136888], None).await?;
+```
+*/
+use crate::errors::{RbggError, Result};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+#[cfg(feature = "async")]
+use tokio::sync::Notify;
+
+/// The priority of a request when a throttle is in use
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+/// The settings for the rate limiter.  The default is a polite 2 requests
+/// per second with small bursts, which keeps you in line with BGG's
+/// guidance for API consumers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThrottleConfig {
+    /// The sustained number of requests per second
+    pub rate: f64,
+    /// The number of requests that can be made back to back before the
+    /// rate kicks in
+    pub burst: u32,
+    /// Slow down automatically when BGG pushes back, or `None` to always
+    /// use `rate`
+    pub adaptive: Option<AdaptiveConfig>,
+}
+
+impl Default for ThrottleConfig {
+    fn default() -> Self {
+        return Self {
+            rate: 2.0,
+            burst: 3,
+            adaptive: Some(AdaptiveConfig::default()),
+        };
+    }
+}
+
+impl ThrottleConfig {
+    /// Check that the settings are usable, returning
+    /// `RbggError::InvalidInput` if not
+    pub fn validate(&self) -> Result<()> {
+        if !(self.rate.is_finite() && self.rate > 0.0) {
+            return Err(RbggError::InvalidInput(format!(
+                "The throttle rate must be a positive number, not {}",
+                self.rate
+            )));
+        }
+        if let Some(adaptive) = &self.adaptive {
+            let min_rate = adaptive.min_rate;
+            if !(min_rate.is_finite() && min_rate > 0.0 && min_rate <= self.rate) {
+                return Err(RbggError::InvalidInput(format!(
+                    "The adaptive throttle's min_rate must be above 0 and at most \
+                    the rate ({}), not {}",
+                    self.rate, min_rate
+                )));
+            }
+            let backoff = adaptive.backoff;
+            if !(backoff > 0.0 && backoff <= 1.0) {
+                return Err(RbggError::InvalidInput(format!(
+                    "The adaptive throttle's backoff must be above 0 and at most 1, \
+                    not {}",
+                    backoff
+                )));
+            }
+            let recovery = adaptive.recovery;
+            if !(recovery.is_finite() && recovery >= 0.0) {
+                return Err(RbggError::InvalidInput(format!(
+                    "The adaptive throttle's recovery must be 0 or more, not {}",
+                    recovery
+                )));
+            }
+        }
+
+        return Ok(());
+    }
+}
+
+/// How the throttle adapts when BGG pushes back, either by rate limiting us
+/// (HTTP 429) or by repeatedly queueing a request (HTTP 202).  Each time,
+/// the rate is cut by `backoff`, and every successful response after that
+/// wins back `recovery` requests per second, until we're back at the
+/// configured rate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdaptiveConfig {
+    /// What to multiply the rate by on each push back.  This must be above
+    /// 0, and no more than 1.
+    pub backoff: f64,
+    /// The requests per second to add back on each successful response.
+    /// This can't be negative.
+    pub recovery: f64,
+    /// The lowest the rate will go, in requests per second.  This must be
+    /// above 0, and no more than the configured rate.
+    pub min_rate: f64,
+}
+
+impl Default for AdaptiveConfig {
+    fn default() -> Self {
+        return Self {
+            backoff: 0.5,
+            recovery: 0.05,
+            min_rate: 0.1,
+        };
+    }
+}
+
+/// A snapshot of where a throttle is at
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ThrottleState {
+    /// The current requests per second, which is below `base_rate` while
+    /// the throttle is backed off
+    pub rate: f64,
+    /// The configured requests per second
+    pub base_rate: f64,
+    /// The requests that can be sent right now without waiting
+    pub tokens: f64,
+    /// The number of requests waiting on the throttle
+    pub queued: usize,
+}
+
+impl ThrottleState {
+    /// Whether the throttle has slowed down from its configured rate
+    pub fn is_backed_off(&self) -> bool {
+        return self.rate < self.base_rate;
+    }
+}
+
+/// A request waiting in the queue.  The ordering is such that the highest
+/// priority, then the oldest request, is at the top of the heap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Ticket {
+    priority: Priority,
+    seq: u64,
+}
+
+impl Ord for Ticket {
+    fn cmp(&self, other: &Self) -> Ordering {
+        return self
+            .priority
+            .cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq));
+    }
+}
+
+impl PartialOrd for Ticket {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+struct State {
+    rate: f64,
+    base_rate: f64,
+    burst: f64,
+    tokens: f64,
+    last: Instant,
+    queue: BinaryHeap<Ticket>,
+    next_seq: u64,
+}
+
+impl State {
+    /// Add any tokens that have accumulated since we last looked
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+        self.last = now;
+    }
+
+    /// Try to take a token for the given ticket.  If the ticket is at the
+    /// head of the queue, but there are no tokens left, this returns how
+    /// long to wait for the next token.  If the ticket isn't at the head of
+    /// the queue, there's nothing to do but wait to be notified.
+    fn try_take(&mut self, ticket: Ticket, now: Instant) -> Take {
+        if self.queue.peek() != Some(&ticket) {
+            return Take::Queued;
+        }
+
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            self.queue.pop();
+            return Take::Acquired;
+        }
+
+        return Take::Wait(Duration::from_secs_f64((1.0 - self.tokens) / self.rate));
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Take {
+    Acquired,
+    Queued,
+    Wait(Duration),
+}
+
+/// The rate limiter itself.  This is shared by the async and blocking
+/// sides of a client, so it will wake up both kinds of waiters.
+pub(crate) struct Throttle {
+    state: Mutex<State>,
+    adaptive: Option<AdaptiveConfig>,
+    #[cfg(feature = "async")]
+    notify: Notify,
+    cond: Condvar,
+}
+
+impl Throttle {
+    pub fn new(config: &ThrottleConfig) -> Self {
+        let burst = config.burst.max(1) as f64;
+
+        return Self {
+            state: Mutex::new(State {
+                rate: config.rate,
+                base_rate: config.rate,
+                burst,
+                tokens: burst,
+                last: Instant::now(),
+                queue: BinaryHeap::new(),
+                next_seq: 0,
+            }),
+            adaptive: config.adaptive.clone(),
+            #[cfg(feature = "async")]
+            notify: Notify::new(),
+            cond: Condvar::new(),
+        };
+    }
+
+    /// Return a snapshot of the throttle's current state
+    pub fn state(&self) -> ThrottleState {
+        let mut state = self.lock();
+        state.refill(Instant::now());
+
+        return ThrottleState {
+            rate: state.rate,
+            base_rate: state.base_rate,
+            tokens: state.tokens,
+            queued: state.queue.len(),
+        };
+    }
+
+    /// BGG has pushed back, so cut the rate (if the throttle is adaptive)
+    pub fn slow_down(&self) {
+        if let Some(adaptive) = &self.adaptive {
+            let mut state = self.lock();
+            // Anything accumulated so far was earned at the old rate
+            state.refill(Instant::now());
+            let floor = adaptive.min_rate.min(state.base_rate);
+            state.rate = (state.rate * adaptive.backoff).max(floor);
+        }
+    }
+
+    /// A request went through, so win back some of the rate (if the
+    /// throttle is adaptive and backed off)
+    pub fn recover(&self) {
+        if let Some(adaptive) = &self.adaptive {
+            let mut state = self.lock();
+            if state.rate < state.base_rate {
+                state.refill(Instant::now());
+                state.rate = (state.rate + adaptive.recovery).min(state.base_rate);
+                // Waiters may be able to go sooner now
+                drop(state);
+                self.wake_all();
+            }
+        }
+    }
+
+    /// Wait until a request at the given priority is allowed to be sent
+    #[cfg(feature = "async")]
+    pub async fn acquire(&self, priority: Priority) {
+        let guard = self.enqueue(priority);
+
+        loop {
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            // Make sure we don't miss a wakeup between checking the state
+            // and waiting
+            notified.as_mut().enable();
+
+            let take = self.lock().try_take(guard.ticket, Instant::now());
+            match take {
+                Take::Acquired => break,
+                Take::Queued => notified.await,
+                Take::Wait(dur) => {
+                    tokio::select! {
+                        _ = tokio::time::sleep(dur) => {},
+                        _ = notified => {},
+                    }
+                }
+            }
+        }
+
+        guard.acquired();
+    }
+
+    /// Wait (blocking) until a request at the given priority is allowed to
+    /// be sent
+    pub fn acquire_b(&self, priority: Priority) {
+        let guard = self.enqueue(priority);
+        let mut state = self.lock();
+
+        loop {
+            match state.try_take(guard.ticket, Instant::now()) {
+                Take::Acquired => break,
+                Take::Queued => {
+                    state = self.cond.wait(state).unwrap_or_else(|e| e.into_inner());
+                }
+                Take::Wait(dur) => {
+                    state = self
+                        .cond
+                        .wait_timeout(state, dur)
+                        .unwrap_or_else(|e| e.into_inner())
+                        .0;
+                }
+            }
+        }
+
+        drop(state);
+        guard.acquired();
+    }
+
+    /* Begin private functions */
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        return self.state.lock().unwrap_or_else(|e| e.into_inner());
+    }
+
+    /// Add a ticket to the queue, returning a guard that will remove it
+    /// again if the waiter goes away (e.g. a dropped future)
+    fn enqueue(&self, priority: Priority) -> QueueGuard<'_> {
+        let mut state = self.lock();
+        let ticket = Ticket {
+            priority,
+            seq: state.next_seq,
+        };
+        state.next_seq += 1;
+        state.queue.push(ticket);
+
+        return QueueGuard {
+            throttle: self,
+            ticket,
+            done: false,
+        };
+    }
+
+    /// Wake up everything waiting as the head of the queue has changed
+    fn wake_all(&self) {
+        #[cfg(feature = "async")]
+        self.notify.notify_waiters();
+        self.cond.notify_all();
+    }
+}
+
+/// Keeps the queue in order when a waiter is done, one way or another
+struct QueueGuard<'a> {
+    throttle: &'a Throttle,
+    ticket: Ticket,
+    done: bool,
+}
+
+impl QueueGuard<'_> {
+    fn acquired(mut self) {
+        // The ticket was already popped off the queue when taking the token
+        self.done = true;
+        self.throttle.wake_all();
+    }
+}
+
+impl Drop for QueueGuard<'_> {
+    fn drop(&mut self) {
+        if !self.done {
+            let ticket = self.ticket;
+            self.throttle.lock().queue.retain(|t| *t != ticket);
+            self.throttle.wake_all();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert!(ThrottleConfig::default().validate().is_ok());
+        for rate in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let config = ThrottleConfig {
+                rate,
+                ..Default::default()
+            };
+            let err = config.validate().unwrap_err();
+            assert!(matches!(err, RbggError::InvalidInput(_)));
+        }
+
+        // The adaptive throttle can't slow down to nothing, or "slow down"
+        // to more than the rate
+        for min_rate in [0.0, -0.1, f64::NAN, 3.0] {
+            let config = ThrottleConfig {
+                rate: 2.0,
+                adaptive: Some(AdaptiveConfig {
+                    min_rate,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+            let err = config.validate().unwrap_err();
+            assert!(matches!(err, RbggError::InvalidInput(_)));
+        }
+        let config = ThrottleConfig {
+            rate: 2.0,
+            adaptive: Some(AdaptiveConfig {
+                min_rate: 2.0,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        // Backing off can't speed up, or stop, the throttle
+        for backoff in [0.0, -0.5, 1.5, f64::NAN, f64::INFINITY] {
+            let config = ThrottleConfig {
+                adaptive: Some(AdaptiveConfig {
+                    backoff,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+            let err = config.validate().unwrap_err();
+            assert!(matches!(err, RbggError::InvalidInput(_)));
+        }
+        for recovery in [-0.1, f64::NAN, f64::INFINITY] {
+            let config = ThrottleConfig {
+                adaptive: Some(AdaptiveConfig {
+                    recovery,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+            let err = config.validate().unwrap_err();
+            assert!(matches!(err, RbggError::InvalidInput(_)));
+        }
+        let config = ThrottleConfig {
+            adaptive: Some(AdaptiveConfig {
+                backoff: 1.0,
+                recovery: 0.0,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_ticket_order() {
+        let mut heap = BinaryHeap::new();
+        heap.push(Ticket {
+            priority: Priority::Low,
+            seq: 0,
+        });
+        heap.push(Ticket {
+            priority: Priority::Normal,
+            seq: 1,
+        });
+        heap.push(Ticket {
+            priority: Priority::High,
+            seq: 3,
+        });
+        heap.push(Ticket {
+            priority: Priority::High,
+            seq: 2,
+        });
+
+        let order: Vec<u64> = std::iter::from_fn(|| heap.pop()).map(|t| t.seq).collect();
+        assert_eq!(order, vec![2, 3, 1, 0]);
+    }
+
+    #[test]
+    fn test_try_take() {
+        let throttle = Throttle::new(&ThrottleConfig {
+            rate: 1.0,
+            burst: 1,
+            adaptive: None,
+        });
+        let low = throttle.enqueue(Priority::Low);
+        let high = throttle.enqueue(Priority::High);
+        let now = Instant::now();
+
+        {
+            let mut state = throttle.lock();
+            // The high priority request jumps the queue
+            assert_eq!(state.try_take(low.ticket, now), Take::Queued);
+            assert_eq!(state.try_take(high.ticket, now), Take::Acquired);
+            // And the bucket is now empty
+            match state.try_take(low.ticket, now) {
+                Take::Wait(d) => assert!(d <= Duration::from_secs(1)),
+                other => panic!("Unexpected: {:?}", other),
+            }
+        }
+        high.acquired();
+
+        // Dropping a waiter removes it from the queue
+        drop(low);
+        assert!(throttle.lock().queue.is_empty());
+    }
+
+    #[test]
+    fn test_adaptive() {
+        let throttle = Throttle::new(&ThrottleConfig {
+            rate: 2.0,
+            burst: 1,
+            adaptive: Some(AdaptiveConfig {
+                backoff: 0.5,
+                recovery: 0.25,
+                min_rate: 0.4,
+            }),
+        });
+        assert!(!throttle.state().is_backed_off());
+
+        throttle.slow_down();
+        assert_eq!(throttle.state().rate, 1.0);
+        assert!(throttle.state().is_backed_off());
+        throttle.slow_down();
+        throttle.slow_down();
+        assert_eq!(throttle.state().rate, 0.4);
+
+        throttle.recover();
+        assert_eq!(throttle.state().rate, 0.65);
+        for _ in 0..10 {
+            throttle.recover();
+        }
+        assert_eq!(throttle.state().rate, 2.0);
+        assert_eq!(throttle.state().base_rate, 2.0);
+
+        // A fixed throttle ignores the push back
+        let throttle = Throttle::new(&ThrottleConfig {
+            adaptive: None,
+            ..Default::default()
+        });
+        throttle.slow_down();
+        assert!(!throttle.state().is_backed_off());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_acquire() {
+        let throttle = Throttle::new(&ThrottleConfig {
+            rate: 2.0,
+            burst: 2,
+            ..Default::default()
+        });
+        let start = Instant::now();
+
+        // The burst goes straight through
+        throttle.acquire(Priority::Normal).await;
+        throttle.acquire(Priority::Normal).await;
+        assert!(start.elapsed() < Duration::from_millis(10));
+    }
+}
@@ -0,0 +1,617 @@
+/*!
+The transport is what actually sends requests over the wire.  By default
+this is `ReqwestTransport`, or `UreqTransport` for the blocking calls with
+the `ureq` feature, but anything implementing `HttpTransport` can be
+plugged in via the `transport` setting in `http::HttpConfig`, whether that's
+a different HTTP library or a test double that never touches the network.
+Everything else (throttling, retries, 202 waits, caching, etc.) is still
+handled by the `HttpClient`.
+
+```ignore,rust
+use rbgg::{bgg2::Client2, transport::*};
+use futures_util::future::BoxFuture;
+use http::StatusCode;
+use std::sync::Arc;
+
+#[derive(Debug)]
+struct Canned;
+
+impl HttpTransport for Canned {
+    fn send(&self, req: TransportRequest) -> BoxFuture<'_, TransportResult<AsyncBody>> {
+        return Box::pin(async move {
+            return Ok(TransportResponse::<AsyncBody>::new(StatusCode::OK, &req.url, "<items/>"));
+        });
+    }
+
+    fn send_b(&self, req: TransportRequest) -> TransportResult<BlockingBody> {
+        return Ok(TransportResponse::<BlockingBody>::new(StatusCode::OK, &req.url, "<items/>"));
+    }
+}
+
+let cl = Client2::builder().transport(Arc::new(Canned)).build()?;
+```
+*/
+use crate::errors::Result;
+use crate::http::HttpConfig;
+#[cfg(all(feature = "ureq", not(feature = "async")))]
+use futures_util::future;
+use futures_util::future::BoxFuture;
+use futures_util::stream::{self, BoxStream, StreamExt};
+use http::header::HeaderMap;
+#[cfg(feature = "ureq")]
+use http::header::{HeaderName, HeaderValue, ACCEPT_ENCODING};
+use http::{Method, StatusCode};
+use std::fmt;
+use std::io::{Cursor, Read};
+use std::sync::Arc;
+#[cfg(feature = "async")]
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// The body of an async response, as a stream of chunks
+pub type AsyncBody = BoxStream<'static, Result<Vec<u8>>>;
+/// The body of a blocking response
+pub type BlockingBody = Box<dyn Read + Send>;
+/// What a transport returns for a request
+pub type TransportResult<B> = std::result::Result<TransportResponse<B>, TransportError>;
+
+/// A request to be sent by a transport
+#[derive(Clone, Debug)]
+pub struct TransportRequest {
+    pub method: Method,
+    pub url: String,
+    /// All of the headers to send, including the client's defaults (like
+    /// the User-Agent)
+    pub headers: HeaderMap,
+    pub body: Option<String>,
+    /// The longest this request may take, if it has to finish sooner than
+    /// the transport's own timeout allows
+    pub timeout: Option<Duration>,
+}
+
+impl TransportRequest {
+    /// A GET request for the url, with no headers
+    pub fn get(url: &str) -> Self {
+        return Self {
+            method: Method::GET,
+            url: url.to_string(),
+            headers: HeaderMap::new(),
+            body: None,
+            timeout: None,
+        };
+    }
+}
+
+/// A response from a transport, whose body is either an `AsyncBody` or a
+/// `BlockingBody`
+pub struct TransportResponse<B> {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    /// The url of the response, after any redirects
+    pub url: String,
+    pub body: B,
+}
+
+impl<B> fmt::Debug for TransportResponse<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f
+            .debug_struct("TransportResponse")
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .field("url", &self.url)
+            .finish_non_exhaustive();
+    }
+}
+
+impl TransportResponse<AsyncBody> {
+    /// A response with the whole body already in hand, and no headers
+    pub fn new(status: StatusCode, url: &str, body: impl Into<Vec<u8>>) -> Self {
+        let body = body.into();
+
+        return Self {
+            status,
+            headers: HeaderMap::new(),
+            url: url.to_string(),
+            body: stream::once(async move { Ok(body) }).boxed(),
+        };
+    }
+
+    /// Read the rest of the body
+    pub async fn bytes(mut self) -> Result<Vec<u8>> {
+        let mut ret = vec![];
+        while let Some(chunk) = self.body.next().await {
+            ret.extend(chunk?);
+        }
+
+        return Ok(ret);
+    }
+
+    /// Read the rest of the body as text
+    pub async fn text(self) -> Result<String> {
+        return Ok(to_text(self.bytes().await?));
+    }
+}
+
+impl TransportResponse<BlockingBody> {
+    /// A response with the whole body already in hand, and no headers
+    pub fn new(status: StatusCode, url: &str, body: impl Into<Vec<u8>>) -> Self {
+        return Self {
+            status,
+            headers: HeaderMap::new(),
+            url: url.to_string(),
+            body: Box::new(Cursor::new(body.into())),
+        };
+    }
+
+    /// Read the rest of the body
+    pub fn bytes(mut self) -> Result<Vec<u8>> {
+        let mut ret = vec![];
+        self.body.read_to_end(&mut ret)?;
+
+        return Ok(ret);
+    }
+
+    /// Read the rest of the body as text
+    pub fn text(self) -> Result<String> {
+        return Ok(to_text(self.bytes()?));
+    }
+}
+
+/// Why a request couldn't be sent, which decides whether it's retried
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransportErrorKind {
+    /// We couldn't connect to the server
+    Connect,
+    /// The request took too long
+    Timeout,
+    /// The request failed part way through
+    Request,
+    /// Anything else, which is never retried
+    Other,
+}
+
+/// A request that couldn't be sent (as opposed to one that got an error
+/// status back)
+#[derive(Clone, Debug)]
+pub struct TransportError {
+    kind: TransportErrorKind,
+    source: Arc<dyn std::error::Error + Send + Sync>,
+}
+
+impl TransportError {
+    pub fn new(
+        kind: TransportErrorKind,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        return Self {
+            kind,
+            source: Arc::from(source.into()),
+        };
+    }
+
+    pub fn kind(&self) -> TransportErrorKind {
+        return self.kind;
+    }
+
+    pub fn is_connect(&self) -> bool {
+        return self.kind == TransportErrorKind::Connect;
+    }
+
+    pub fn is_timeout(&self) -> bool {
+        return self.kind == TransportErrorKind::Timeout;
+    }
+
+    pub fn is_request(&self) -> bool {
+        return self.kind == TransportErrorKind::Request;
+    }
+
+    /// Whether this is a transient failure, which may work if retried
+    pub fn is_transient(&self) -> bool {
+        return self.kind != TransportErrorKind::Other;
+    }
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}", self.source);
+    }
+}
+
+impl std::error::Error for TransportError {
+    /// The underlying error (e.g. the `reqwest::Error`)
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return Some(&*self.source);
+    }
+}
+
+#[cfg(feature = "async")]
+impl From<reqwest::Error> for TransportError {
+    fn from(err: reqwest::Error) -> Self {
+        let kind = if err.is_connect() {
+            TransportErrorKind::Connect
+        } else if err.is_timeout() {
+            TransportErrorKind::Timeout
+        } else if err.is_request() {
+            TransportErrorKind::Request
+        } else {
+            TransportErrorKind::Other
+        };
+
+        return Self::new(kind, err);
+    }
+}
+
+/// Something that can send HTTP requests, both async and blocking.  This
+/// is shared between threads, so it should be cheap to call concurrently.
+pub trait HttpTransport: fmt::Debug + Send + Sync {
+    /// Send the request, returning the response once its headers are in
+    fn send(&self, req: TransportRequest) -> BoxFuture<'_, TransportResult<AsyncBody>>;
+
+    /// Send (blocking) the request, returning the response once its headers
+    /// are in
+    fn send_b(&self, req: TransportRequest) -> TransportResult<BlockingBody>;
+}
+
+/// The default transport (with the `async` feature), using reqwest.  This
+/// takes its compression and timeout settings from the `HttpConfig`.
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+    // The blocking client is created lazily as creating one inside of an
+    // async runtime will panic.
+    blocking: OnceLock<reqwest::blocking::Client>,
+    compression: bool,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+}
+
+#[cfg(feature = "async")]
+impl ReqwestTransport {
+    pub fn new(config: &HttpConfig) -> Result<Self> {
+        let mut builder = reqwest::Client::builder()
+            .gzip(config.compression)
+            .deflate(config.compression);
+        if let Some(timeout) = config.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = config.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        return Ok(Self {
+            client: builder.build()?,
+            blocking: OnceLock::new(),
+            compression: config.compression,
+            connect_timeout: config.connect_timeout,
+            timeout: config.timeout,
+        });
+    }
+
+    /// Return the blocking client, creating it on first use
+    fn blocking(&self) -> std::result::Result<&reqwest::blocking::Client, reqwest::Error> {
+        if let Some(client) = self.blocking.get() {
+            return Ok(client);
+        }
+
+        // The blocking client has its own 30 second default, so always set
+        // it to match the async client
+        let client = reqwest::blocking::Client::builder()
+            .gzip(self.compression)
+            .deflate(self.compression)
+            .connect_timeout(self.connect_timeout)
+            .timeout(self.timeout)
+            .build()?;
+
+        // If another thread beat us to it, theirs wins and ours is dropped
+        return Ok(self.blocking.get_or_init(|| client));
+    }
+}
+
+#[cfg(feature = "async")]
+impl HttpTransport for ReqwestTransport {
+    fn send(&self, req: TransportRequest) -> BoxFuture<'_, TransportResult<AsyncBody>> {
+        return Box::pin(async move {
+            let mut builder = self
+                .client
+                .request(req.method, &req.url)
+                .headers(req.headers);
+            if let Some(body) = req.body {
+                builder = builder.body(body);
+            }
+            if let Some(timeout) = req.timeout {
+                builder = builder.timeout(timeout);
+            }
+            let resp = builder.send().await?;
+
+            return Ok(TransportResponse {
+                status: resp.status(),
+                headers: resp.headers().clone(),
+                url: resp.url().to_string(),
+                body: chunks(resp),
+            });
+        });
+    }
+
+    fn send_b(&self, req: TransportRequest) -> TransportResult<BlockingBody> {
+        let mut builder = self
+            .blocking()?
+            .request(req.method, &req.url)
+            .headers(req.headers);
+        if let Some(body) = req.body {
+            builder = builder.body(body);
+        }
+        if let Some(timeout) = req.timeout {
+            builder = builder.timeout(timeout);
+        }
+        let resp = builder.send()?;
+
+        return Ok(TransportResponse {
+            status: resp.status(),
+            headers: resp.headers().clone(),
+            url: resp.url().to_string(),
+            body: Box::new(resp),
+        });
+    }
+}
+
+/// A transport using ureq (with the `ureq` feature), which is a small,
+/// blocking HTTP client.  Along with turning off the default features, this
+/// gives a build for CLI tools with neither tokio nor reqwest in it, where
+/// it's what every call uses:
+///
+/// ```toml
+/// rbgg = { version = "0.1", default-features = false, features = ["ureq"] }
+/// ```
+///
+/// When the `async` feature is on too, requests still go through reqwest
+/// unless this is set as the `transport` in the `HttpConfig`.  Async calls
+/// made through it each tie up a thread from tokio's blocking pool while
+/// they run.  Like `ReqwestTransport`, this takes its compression and
+/// timeout settings from the `HttpConfig`.
+#[cfg(feature = "ureq")]
+#[derive(Clone, Debug)]
+pub struct UreqTransport {
+    agent: ureq::Agent,
+    compression: bool,
+}
+
+#[cfg(feature = "ureq")]
+impl UreqTransport {
+    /// Create a transport using the compression and timeouts from the
+    /// config
+    pub fn new(config: &HttpConfig) -> Self {
+        let mut builder = ureq::AgentBuilder::new();
+        if let Some(timeout) = config.connect_timeout {
+            builder = builder.timeout_connect(timeout);
+        }
+        if let Some(timeout) = config.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        return Self {
+            agent: builder.build(),
+            compression: config.compression,
+        };
+    }
+
+    /// Send (blocking) the request, reading the whole body in, for an async
+    /// call
+    fn send_buffered(&self, req: TransportRequest) -> TransportResult<AsyncBody> {
+        let resp = self.send_b(req)?;
+        let (status, headers, url) = (resp.status, resp.headers.clone(), resp.url.clone());
+        let body = resp
+            .bytes()
+            .map_err(|e| TransportError::new(TransportErrorKind::Request, e))?;
+
+        return Ok(TransportResponse::<AsyncBody> {
+            headers,
+            ..TransportResponse::<AsyncBody>::new(status, &url, body)
+        });
+    }
+}
+
+#[cfg(feature = "ureq")]
+impl HttpTransport for UreqTransport {
+    #[cfg(feature = "async")]
+    fn send(&self, req: TransportRequest) -> BoxFuture<'_, TransportResult<AsyncBody>> {
+        let this = self.clone();
+
+        return Box::pin(async move {
+            return tokio::task::spawn_blocking(move || this.send_buffered(req))
+                .await
+                .map_err(|e| TransportError::new(TransportErrorKind::Other, e))?;
+        });
+    }
+
+    /// Without tokio, there's nowhere else to run the request, so it blocks
+    /// whatever polls it
+    #[cfg(not(feature = "async"))]
+    fn send(&self, req: TransportRequest) -> BoxFuture<'_, TransportResult<AsyncBody>> {
+        return Box::pin(future::ready(self.send_buffered(req)));
+    }
+
+    fn send_b(&self, req: TransportRequest) -> TransportResult<BlockingBody> {
+        let mut ureq_req = self.agent.request(req.method.as_str(), &req.url);
+        for (name, value) in &req.headers {
+            let value = value
+                .to_str()
+                .map_err(|e| TransportError::new(TransportErrorKind::Other, e))?;
+            ureq_req = ureq_req.set(name.as_str(), value);
+        }
+        // ureq asks for gzip unless the request says otherwise
+        if !self.compression && !req.headers.contains_key(ACCEPT_ENCODING) {
+            ureq_req = ureq_req.set(ACCEPT_ENCODING.as_str(), "identity");
+        }
+        if let Some(timeout) = req.timeout {
+            ureq_req = ureq_req.timeout(timeout);
+        }
+
+        let res = match req.body {
+            Some(body) => ureq_req.send_string(&body),
+            None => ureq_req.call(),
+        };
+        let resp = match res {
+            Ok(resp) => resp,
+            // ureq treats error statuses as errors, but we want to see them
+            Err(ureq::Error::Status(_, resp)) => resp,
+            Err(ureq::Error::Transport(e)) => return Err(ureq_error(e)),
+        };
+
+        let status = StatusCode::from_u16(resp.status())
+            .map_err(|e| TransportError::new(TransportErrorKind::Other, e))?;
+        let mut headers = HeaderMap::new();
+        for name in resp.headers_names() {
+            let name = match HeaderName::from_bytes(name.as_bytes()) {
+                Ok(name) => name,
+                Err(_) => continue,
+            };
+            for value in resp.all(name.as_str()) {
+                if let Ok(value) = HeaderValue::from_str(value) {
+                    headers.append(name.clone(), value);
+                }
+            }
+        }
+
+        return Ok(TransportResponse {
+            status,
+            headers,
+            url: resp.get_url().to_string(),
+            body: Box::new(resp.into_reader()),
+        });
+    }
+}
+
+/// Work out what kind of failure a ureq error is
+#[cfg(feature = "ureq")]
+fn ureq_error(err: ureq::Transport) -> TransportError {
+    let timed_out = std::error::Error::source(&err)
+        .and_then(|e| e.downcast_ref::<std::io::Error>())
+        .is_some_and(|e| {
+            matches!(
+                e.kind(),
+                std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock
+            )
+        });
+    let kind = match err.kind() {
+        _ if timed_out => TransportErrorKind::Timeout,
+        ureq::ErrorKind::Dns | ureq::ErrorKind::ConnectionFailed => TransportErrorKind::Connect,
+        ureq::ErrorKind::Io => TransportErrorKind::Request,
+        _ => TransportErrorKind::Other,
+    };
+
+    return TransportError::new(kind, err);
+}
+
+/// Stream the body of a reqwest response, stopping after the first error
+#[cfg(feature = "async")]
+fn chunks(resp: reqwest::Response) -> AsyncBody {
+    return stream::unfold(Some(resp), |resp| async move {
+        let mut resp = resp?;
+        return match resp.chunk().await {
+            Ok(Some(chunk)) => Some((Ok(chunk.to_vec()), Some(resp))),
+            Ok(None) => None,
+            Err(e) => Some((Err(e.into()), None)),
+        };
+    })
+    .boxed();
+}
+
+/// BGG always sends UTF-8, but don't fail on the odd bad byte
+pub(crate) fn to_text(bytes: Vec<u8>) -> String {
+    return match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) => String::from_utf8_lossy(e.as_bytes()).into_owned(),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_response() {
+        let resp = TransportResponse::<AsyncBody>::new(StatusCode::OK, "http://x/", "<a/>");
+        assert_eq!(resp.status, StatusCode::OK);
+        assert_eq!(resp.text().await.unwrap(), "<a/>");
+
+        let resp = TransportResponse::<BlockingBody>::new(StatusCode::OK, "http://x/", "<a/>");
+        assert_eq!(resp.url, "http://x/");
+        assert_eq!(resp.text().unwrap(), "<a/>");
+        assert_eq!(to_text(vec![b'a', 0xff]), "a\u{fffd}");
+    }
+
+    #[cfg(feature = "ureq")]
+    #[test]
+    fn test_ureq() {
+        let transport = UreqTransport::new(&HttpConfig::default());
+        // Nothing is listening here
+        let err = transport
+            .send_b(TransportRequest::get("http://127.0.0.1:1/xmlapi2/hot"))
+            .unwrap_err();
+        assert!(err.is_connect());
+    }
+
+    /// Answer one request with an empty 200, returning what was sent
+    #[cfg(feature = "ureq")]
+    fn serve_once() -> (String, std::thread::JoinHandle<String>) {
+        use std::io::{BufRead, BufReader, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/xmlapi2/hot", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut ret = String::new();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                ret.push_str(&line.to_lowercase());
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\n<a/>")
+                .unwrap();
+
+            return ret;
+        });
+
+        return (url, handle);
+    }
+
+    #[cfg(feature = "ureq")]
+    #[test]
+    fn test_ureq_compression() {
+        let (url, handle) = serve_once();
+        let transport = UreqTransport::new(&HttpConfig::default());
+        let resp = transport.send_b(TransportRequest::get(&url)).unwrap();
+        assert_eq!(resp.text().unwrap(), "<a/>");
+        assert!(handle.join().unwrap().contains("accept-encoding: gzip"));
+
+        let (url, handle) = serve_once();
+        let transport = UreqTransport::new(&HttpConfig {
+            compression: false,
+            ..Default::default()
+        });
+        transport.send_b(TransportRequest::get(&url)).unwrap();
+        let sent = handle.join().unwrap();
+        assert!(sent.contains("accept-encoding: identity"));
+        assert!(!sent.contains("gzip"));
+    }
+
+    #[test]
+    fn test_error() {
+        let err = TransportError::new(TransportErrorKind::Timeout, "slow");
+        assert!(err.is_timeout() && err.is_transient());
+        assert!(!err.is_connect());
+        assert_eq!(err.to_string(), "slow");
+
+        let err = TransportError::new(TransportErrorKind::Other, "bad");
+        assert!(!err.is_transient());
+        let source = std::error::Error::source(&err).unwrap();
+        assert_eq!(source.to_string(), "bad");
+    }
+}
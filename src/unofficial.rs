@@ -0,0 +1,187 @@
+/*!
+A client for the undocumented JSON endpoints that the boardgamegeek.com
+site itself uses (under `/api/...`).  These return some data that the XML
+APIs don't, like the full details of an item as shown on its page, or the
+reviews in users' collections.  This requires the `unofficial` feature.
+
+**These endpoints are unstable.**  BGG doesn't document or support them,
+and they can change or disappear at any time without notice, so only use
+them for things the XML APIs can't give you.
+
+Unlike the XML API clients, the responses are BGG's JSON as is.
+
+```ignore,rust
+use rbgg::unofficial::ClientJson;
+
+let cl = ClientJson::new_from_defaults();
+let item = cl.geekitem_b(136888, "thing", None)?;
+println!("{}", item["item"]["name"]);
+```
+*/
+use crate::errors::Result;
+use crate::http::{HttpClient, Stats};
+use crate::utils::{self, Params};
+use serde_json::Value;
+
+/// A client for the unofficial JSON API
+#[derive(Clone)]
+pub struct ClientJson {
+    pub url_base: String,
+    http: HttpClient,
+}
+
+impl ClientJson {
+    /// If the url_base is not supplied, the default of
+    /// "https://boardgamegeek.com" will be used
+    pub fn new(url_base: Option<String>) -> Self {
+        return Self::new_with_http(url_base, HttpClient::default());
+    }
+
+    /// Like `new()`, but using an existing `HttpClient`.  This allows
+    /// sharing the connection pools and throttle with the other clients.
+    pub fn new_with_http(url_base: Option<String>, http: HttpClient) -> Self {
+        let ub = match url_base {
+            Some(u) => u.trim_end_matches('/').to_string(),
+            None => "https://boardgamegeek.com".to_string(),
+        };
+
+        return Self { url_base: ub, http };
+    }
+
+    /// Create a new instance using the default url_base
+    pub fn new_from_defaults() -> Self {
+        return Self::new(None);
+    }
+
+    /// Get (async) the full details for an item, as shown on its page.
+    /// `objecttype` is the kind of item, e.g. "thing" or "family".
+    #[cfg(feature = "async")]
+    pub async fn geekitem(
+        &self,
+        id: usize,
+        objecttype: &str,
+        options: Option<Params>,
+    ) -> Result<Value> {
+        let url = self.get_full_url("geekitems", options, item_params(id, objecttype));
+
+        return self.http.get_plain_json(&url).await;
+    }
+
+    /// Get (sync) the full details for an item, as shown on its page.
+    /// `objecttype` is the kind of item, e.g. "thing" or "family".
+    pub fn geekitem_b(
+        &self,
+        id: usize,
+        objecttype: &str,
+        options: Option<Params>,
+    ) -> Result<Value> {
+        let url = self.get_full_url("geekitems", options, item_params(id, objecttype));
+
+        return self.http.get_plain_json_b(&url);
+    }
+
+    /// Get (async) the collection entries (ratings, reviews, etc.) users
+    /// have for an item.  Use options like `showcount`, `pageid` and `sort`
+    /// to page through them.
+    #[cfg(feature = "async")]
+    pub async fn collections(
+        &self,
+        id: usize,
+        objecttype: &str,
+        options: Option<Params>,
+    ) -> Result<Value> {
+        let url = self.get_full_url("collections", options, item_params(id, objecttype));
+
+        return self.http.get_plain_json(&url).await;
+    }
+
+    /// Get (sync) the collection entries (ratings, reviews, etc.) users
+    /// have for an item.  Use options like `showcount`, `pageid` and `sort`
+    /// to page through them.
+    pub fn collections_b(
+        &self,
+        id: usize,
+        objecttype: &str,
+        options: Option<Params>,
+    ) -> Result<Value> {
+        let url = self.get_full_url("collections", options, item_params(id, objecttype));
+
+        return self.http.get_plain_json_b(&url);
+    }
+
+    /// Get (async) the hotness list for a site, e.g. "boardgame" or "rpg".
+    /// This includes more detail than the XML API's hot list.
+    #[cfg(feature = "async")]
+    pub async fn hotness(&self, geeksite: &str, options: Option<Params>) -> Result<Value> {
+        let url = self.get_full_url("hotness", options, hotness_params(geeksite));
+
+        return self.http.get_plain_json(&url).await;
+    }
+
+    /// Get (sync) the hotness list for a site, e.g. "boardgame" or "rpg".
+    /// This includes more detail than the XML API's hot list.
+    pub fn hotness_b(&self, geeksite: &str, options: Option<Params>) -> Result<Value> {
+        let url = self.get_full_url("hotness", options, hotness_params(geeksite));
+
+        return self.http.get_plain_json_b(&url);
+    }
+
+    /// Return a snapshot of the request counters for this client
+    pub fn stats(&self) -> Stats {
+        return self.http.stats();
+    }
+
+    /* Begin private functions */
+
+    /// Build the url for the given endpoint, with the required params
+    /// overriding any of the same name in the options
+    fn get_full_url(&self, path: &str, options: Option<Params>, params: Params) -> String {
+        let mut opts = utils::get_opts(options);
+        opts.extend(params);
+
+        return format!("{}/api/{}?{}", self.url_base, path, utils::params2qs(&opts));
+    }
+}
+
+fn item_params(id: usize, objecttype: &str) -> Params {
+    return Params::from([
+        ("objectid".into(), id.to_string()),
+        ("objecttype".into(), objecttype.into()),
+    ]);
+}
+
+fn hotness_params(geeksite: &str) -> Params {
+    return Params::from([
+        ("geeksite".into(), geeksite.into()),
+        ("objecttype".into(), "thing".into()),
+    ]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client() {
+        let cl = ClientJson::new_from_defaults();
+        assert_eq!(cl.url_base, "https://boardgamegeek.com");
+
+        let cl = ClientJson::new(Some("https://example.com/".into()));
+        assert_eq!(cl.url_base, "https://example.com");
+    }
+
+    #[test]
+    fn test_get_full_url() {
+        let cl = ClientJson::new_from_defaults();
+        let url = cl.get_full_url(
+            "hotness",
+            Some(Params::from([("geeksite".into(), "rpg".into())])),
+            Params::from([("geeksite".into(), "boardgame".into())]),
+        );
+
+        assert_eq!(
+            url,
+            "https://boardgamegeek.com/api/hotness?geeksite=boardgame"
+        );
+    }
+}
@@ -1,81 +1,713 @@
 /*!
 This has some low level conveience functions, but what you will want to use
 here is the `Params` type, which is just a shorthand for
-HashMap<String, String>.
+HashMap<String, String>.  There's also a `Paginator` for fetching every page
+of a paginated endpoint, and a `SingleFlight` for deduping concurrent
+calls.
 */
-use anyhow::{anyhow, Result};
-use reqwest;
+use crate::errors::{RbggError, Result};
+use crate::http::{HttpClient, HttpConfig, DEFAULT_CONNECT_TIMEOUT, DEFAULT_TIMEOUT};
+use futures_util::future::{BoxFuture, FutureExt, Shared};
 use serde_json::Value;
 use std::collections::HashMap;
-use std::thread;
-use tokio::time::{self, Duration};
+use std::future::Future;
+use std::io::Write;
+use std::ops::{Bound, RangeBounds};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+#[cfg(feature = "async")]
+use tokio::io::AsyncWrite;
 use urlencoding::encode;
+#[cfg(not(feature = "fast-xml"))]
 use xmltojson::to_json;
 
 /// Convenience type that is just a shorthand for a HashMap
 pub type Params = HashMap<String, String>;
 
-pub async fn get_json_resp(url: &str) -> Result<Value> {
-    let mut resp;
+/// Anything that can be used as the options for an API call: a `Params`,
+/// an `Option<Params>` (so `None` still works) or one of the typed options
+/// builders, like `bgg2::SearchOptions`.  The builders check their values
+/// here, so this can fail.
+pub trait IntoParams {
+    fn into_params(self) -> Result<Option<Params>>;
+}
+
+impl IntoParams for Option<Params> {
+    fn into_params(self) -> Result<Option<Params>> {
+        return Ok(self);
+    }
+}
+
+impl IntoParams for Params {
+    fn into_params(self) -> Result<Option<Params>> {
+        return Ok(Some(self));
+    }
+}
+
+/// Format a bool the way BGG expects it in a query string
+pub(crate) fn flag(val: bool) -> String {
+    return if val { "1".into() } else { "0".into() };
+}
+
+/// A value that can be moved on (or back) by a step, without overflowing,
+/// for turning an excluded range bound into an inclusive one
+pub(crate) trait Step: Copy + std::fmt::Display {
+    fn checked_add_step(self, step: Self) -> Option<Self>;
+    fn checked_sub_step(self, step: Self) -> Option<Self>;
+}
+
+impl Step for u32 {
+    fn checked_add_step(self, step: Self) -> Option<Self> {
+        return self.checked_add(step);
+    }
+
+    fn checked_sub_step(self, step: Self) -> Option<Self> {
+        return self.checked_sub(step);
+    }
+}
+
+impl Step for f64 {
+    fn checked_add_step(self, step: Self) -> Option<Self> {
+        return Some(self + step);
+    }
+
+    fn checked_sub_step(self, step: Self) -> Option<Self> {
+        return Some(self - step);
+    }
+}
+
+/// Set the min/max params for a range of values.  BGG's bounds are all
+/// inclusive, so an excluded bound (like the end of `1..5`) is only allowed
+/// for whole numbers, where `step` is used to convert it.  A range that
+/// can't be converted, like `..0`, gives `RbggError::InvalidInput`.
+pub(crate) fn range_params<T, R>(
+    params: &mut Params,
+    range: &R,
+    min_key: &str,
+    max_key: &str,
+    step: Option<T>,
+) -> Result<()>
+where
+    T: Step,
+    R: RangeBounds<T>,
+{
+    match (range.start_bound(), step) {
+        (Bound::Included(v), _) => {
+            params.insert(min_key.into(), v.to_string());
+        }
+        (Bound::Excluded(v), Some(step)) => {
+            let min = match v.checked_add_step(step) {
+                Some(min) => min,
+                None => {
+                    return Err(RbggError::InvalidInput(format!(
+                        "The lower bound for {} is out of range: {}",
+                        min_key, v
+                    )))
+                }
+            };
+            params.insert(min_key.into(), min.to_string());
+        }
+        (Bound::Excluded(_), None) => {
+            return Err(RbggError::InvalidInput(format!(
+                "The lower bound for {} must be inclusive",
+                min_key
+            )))
+        }
+        (Bound::Unbounded, _) => (),
+    }
+
+    match (range.end_bound(), step) {
+        (Bound::Included(v), _) => {
+            params.insert(max_key.into(), v.to_string());
+        }
+        (Bound::Excluded(v), Some(step)) => {
+            let max = match v.checked_sub_step(step) {
+                Some(max) => max,
+                None => {
+                    return Err(RbggError::InvalidInput(format!(
+                        "The upper bound for {} is out of range: {}",
+                        max_key, v
+                    )))
+                }
+            };
+            params.insert(max_key.into(), max.to_string());
+        }
+        (Bound::Excluded(_), None) => {
+            return Err(RbggError::InvalidInput(format!(
+                "The upper bound for {} must be inclusive",
+                max_key
+            )))
+        }
+        (Bound::Unbounded, _) => (),
+    }
+
+    return Ok(());
+}
+
+/// Pages through a paginated endpoint, one (converted) response at a time.
+/// It sets the `page` param for each request, and works out when it's done
+/// from the total in the response (e.g. the `total` attribute on `<plays>`),
+/// or from a short or empty page.
+///
+/// The `root` is a JSON pointer to the element holding the items, and `item`
+/// is the name of the items in it.  For a user's plays:
+///
+/// ```ignore,rust
+/// let mut pager = cl.paginator("plays", Some(params), "/plays", "play")?;
+/// while let Some(page) = pager.next_page_b()? {
+///     // ...
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Paginator {
+    http: HttpClient,
+    url: String,
+    params: Params,
+    root: String,
+    item: String,
+    total_key: String,
+    page_size: Option<usize>,
+    page: usize,
+    total: Option<usize>,
+    done: bool,
+}
+
+impl Paginator {
+    /// Create a paginator for the given endpoint url (without a query
+    /// string) and params
+    pub fn new(http: HttpClient, url: &str, params: Params, root: &str, item: &str) -> Self {
+        return Self {
+            http,
+            url: url.trim_end_matches('?').to_string(),
+            params,
+            root: root.to_string(),
+            item: item.to_string(),
+            total_key: "@total".into(),
+            page_size: None,
+            page: 1,
+            total: None,
+            done: false,
+        };
+    }
+
+    /// The attribute on the root element with the total number of items,
+    /// which defaults to `@total`
+    pub fn total_key(mut self, key: &str) -> Self {
+        self.total_key = key.to_string();
+
+        return self;
+    }
+
+    /// The number of items in a full page.  If this isn't set, it's taken
+    /// from the size of the first page.
+    pub fn page_size(mut self, size: usize) -> Self {
+        self.page_size = Some(size);
+
+        return self;
+    }
+
+    /// The page to start from, which defaults to 1.  Pages are numbered
+    /// from 1, so 0 is taken to mean the first page.
+    pub fn start_page(mut self, page: usize) -> Self {
+        self.page = page.max(1);
+
+        return self;
+    }
 
-    // Sometimes, when a large request, often for a user's collection,
-    // is made, we'll get a 202 response and we have to request this again
-    // after the server has cached it on their side
-    loop {
-        resp = reqwest::get(url).await?;
-        if resp.status() == 202 {
-            // We're going to sleep here and try again
-            time::sleep(Duration::from_secs(1)).await;
-        } else {
-            // We should be good to process the response now
-            break;
+    /// The page that will be fetched next
+    pub fn page(&self) -> usize {
+        return self.page;
+    }
+
+    /// The total number of items, once a page has been fetched, if the
+    /// endpoint reports it
+    pub fn total(&self) -> Option<usize> {
+        return self.total;
+    }
+
+    /// Whether all of the pages have been fetched
+    pub fn is_done(&self) -> bool {
+        return self.done;
+    }
+
+    /// Fetch (async) the next page, or `None` when there are no more
+    #[cfg(feature = "async")]
+    pub async fn next_page(&mut self) -> Result<Option<Value>> {
+        if self.done {
+            return Ok(None);
         }
+
+        let data = self.http.get_json(&self.page_url()).await?;
+
+        return Ok(self.advance(data));
+    }
+
+    /// Fetch (sync) the next page, or `None` when there are no more
+    pub fn next_page_b(&mut self) -> Result<Option<Value>> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let data = self.http.get_json_b(&self.page_url())?;
+
+        return Ok(self.advance(data));
+    }
+
+    /// Iterate (sync) over the remaining pages.  An error ends the
+    /// iteration.
+    pub fn pages_b(mut self) -> impl Iterator<Item = Result<Value>> {
+        return std::iter::from_fn(move || match self.next_page_b() {
+            Ok(page) => page.map(Ok),
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        });
     }
 
-    let data = resp.text().await?;
+    /// Fetch (async) all of the remaining pages and merge them into a single
+    /// response, see `merge_pages()`
+    #[cfg(feature = "async")]
+    pub async fn collect_all(mut self) -> Result<Value> {
+        let mut pages = vec![];
+        while let Some(page) = self.next_page().await? {
+            pages.push(page);
+        }
+
+        return Ok(merge_pages(pages, &self.root, &self.item));
+    }
+
+    /// Fetch (sync) all of the remaining pages and merge them into a single
+    /// response, see `merge_pages()`
+    pub fn collect_all_b(mut self) -> Result<Value> {
+        let mut pages = vec![];
+        while let Some(page) = self.next_page_b()? {
+            pages.push(page);
+        }
+
+        return Ok(merge_pages(pages, &self.root, &self.item));
+    }
+
+    /// The items in a page, which are not wrapped in an array when there's
+    /// only one of them
+    pub fn items<'a>(&self, data: &'a Value) -> Vec<&'a Value> {
+        return match data.pointer(&self.root).and_then(|r| r.get(&self.item)) {
+            Some(Value::Array(list)) => list.iter().collect(),
+            Some(Value::Null) | None => vec![],
+            Some(item) => vec![item],
+        };
+    }
+
+    fn page_url(&self) -> String {
+        let mut params = self.params.clone();
+        params.insert("page".into(), self.page.to_string());
+
+        return format!("{}?{}", self.url, params2qs(&params));
+    }
+
+    /// Work out from a page whether there are any more, and move on to the
+    /// next one.  An empty page is the end, and isn't returned.
+    fn advance(&mut self, data: Value) -> Option<Value> {
+        let num = self.items(&data).len();
+        if num == 0 {
+            self.done = true;
+            return None;
+        }
+
+        let total = data
+            .pointer(&self.root)
+            .and_then(|r| r.get(&self.total_key))
+            .and_then(|t| t.as_str())
+            .and_then(|t| t.parse().ok());
+        if total.is_some() {
+            self.total = total;
+        }
+
+        let size = *self.page_size.get_or_insert(num);
+        let fetched = self
+            .page
+            .saturating_sub(1)
+            .saturating_mul(size)
+            .saturating_add(num);
+        self.done = num < size || self.total.map(|t| fetched >= t).unwrap_or(false);
+        self.page += 1;
+
+        return Some(data);
+    }
+}
+
+/// Merge pages of a response into one.  Everything outside of the items
+/// (like the `total` and `termsofuse` attributes) comes from the first page,
+/// and the items from every page are put together in a single array, even
+/// if there's only one.  `root` and `item` are as for `Paginator`.
+pub fn merge_pages(pages: Vec<Value>, root: &str, item: &str) -> Value {
+    let mut items = vec![];
+    let mut ret = None;
+
+    for mut page in pages {
+        match page
+            .pointer_mut(root)
+            .and_then(|r| r.get_mut(item))
+            .map(Value::take)
+        {
+            Some(Value::Array(list)) => items.extend(list),
+            Some(Value::Null) | None => (),
+            Some(single) => items.push(single),
+        }
+        if ret.is_none() {
+            ret = Some(page);
+        }
+    }
+
+    let mut ret = ret.unwrap_or(Value::Null);
+    if let Some(Value::Object(map)) = ret.pointer_mut(root) {
+        map.insert(item.to_string(), Value::Array(items));
+    }
+
+    return ret;
+}
+
+type Flight<T> = Shared<BoxFuture<'static, Result<T>>>;
+
+/// Dedupes concurrent calls with the same key, so that only the first
+/// (the leader) does the work, and everyone else waiting on that key gets a
+/// copy of its result.  Once the call is done, the next one with that key
+/// starts afresh, so nothing is cached.  This is used by `HttpClient` to
+/// coalesce concurrent requests for the same url (see
+/// `HttpConfig::coalesce`).
+///
+/// If the call fails, everyone gets a copy of the error.
+pub struct SingleFlight<T> {
+    inflight: Mutex<HashMap<String, Flight<T>>>,
+}
+
+impl<T> Default for SingleFlight<T> {
+    fn default() -> Self {
+        return Self {
+            inflight: Mutex::new(HashMap::new()),
+        };
+    }
+}
+
+impl<T> SingleFlight<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    /// Run `call` for the key, unless a call for it is already in flight,
+    /// in which case wait for that one instead
+    pub async fn run<F, Fut>(&self, key: &str, call: F) -> Result<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>> + Send + 'static,
+    {
+        let (flight, leader) = {
+            let mut inflight = self.lock();
+            match inflight.get(key) {
+                Some(flight) => (flight.clone(), None),
+                None => {
+                    let flight = call().boxed().shared();
+                    inflight.insert(key.to_string(), flight.clone());
+                    let guard = FlightGuard {
+                        owner: self,
+                        key,
+                        flight: flight.clone(),
+                    };
+                    (flight, Some(guard))
+                }
+            }
+        };
+
+        let res = flight.await;
+        // Drop the guard, so the key is free
+        drop(leader);
+
+        return res;
+    }
+
+    /// The number of calls in flight
+    pub fn len(&self) -> usize {
+        return self.lock().len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.len() == 0;
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Flight<T>>> {
+        return self.inflight.lock().unwrap_or_else(|e| e.into_inner());
+    }
+}
+
+/// Frees up the key when the leader is done, even if it gave up (e.g. it
+/// was cancelled) before the call finished
+struct FlightGuard<'a, T> {
+    owner: &'a SingleFlight<T>,
+    key: &'a str,
+    flight: Flight<T>,
+}
+
+impl<T> Drop for FlightGuard<'_, T> {
+    fn drop(&mut self) {
+        let mut inflight = self
+            .owner
+            .inflight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        // Only remove our own flight, not one that has since replaced it
+        if inflight
+            .get(self.key)
+            .is_some_and(|f| f.ptr_eq(&self.flight))
+        {
+            inflight.remove(self.key);
+        }
+    }
+}
+
+/// Fetch the url and convert the XML response to JSON using a default
+/// `HttpClient`, which gives up after the default timeouts (see
+/// `http::DEFAULT_TIMEOUT`)
+#[cfg(feature = "async")]
+pub async fn get_json_resp(url: &str) -> Result<Value> {
+    return get_json_resp_timeout(url, Some(DEFAULT_CONNECT_TIMEOUT), Some(DEFAULT_TIMEOUT)).await;
+}
+
+/// Fetch (blocking) the url and convert the XML response to JSON using a
+/// default `HttpClient`, which gives up after the default timeouts (see
+/// `http::DEFAULT_TIMEOUT`)
+pub fn get_json_resp_b(url: &str) -> Result<Value> {
+    return get_json_resp_timeout_b(url, Some(DEFAULT_CONNECT_TIMEOUT), Some(DEFAULT_TIMEOUT));
+}
+
+/// Like `get_json_resp()`, but with the given connect and overall request
+/// timeouts.  `None` means no limit.
+#[cfg(feature = "async")]
+pub async fn get_json_resp_timeout(
+    url: &str,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+) -> Result<Value> {
+    let http = HttpClient::new(timeout_config(connect_timeout, timeout))?;
 
-    let ret = match to_json(&data) {
+    return http.get_json(url).await;
+}
+
+/// Like `get_json_resp_b()`, but with the given connect and overall request
+/// timeouts.  `None` means no limit.
+pub fn get_json_resp_timeout_b(
+    url: &str,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+) -> Result<Value> {
+    let http = HttpClient::new(timeout_config(connect_timeout, timeout))?;
+
+    return http.get_json_b(url);
+}
+
+fn timeout_config(connect_timeout: Option<Duration>, timeout: Option<Duration>) -> HttpConfig {
+    return HttpConfig {
+        connect_timeout,
+        timeout,
+        ..Default::default()
+    };
+}
+
+/// Fetch the given url and write the raw (XML) response body to `writer`
+/// as it arrives, without ever holding the full body in memory.  Returns
+/// the number of bytes written.
+#[cfg(feature = "async")]
+pub async fn get_to_writer<W>(url: &str, writer: &mut W) -> Result<u64>
+where
+    W: AsyncWrite + Unpin,
+{
+    return HttpClient::default().get_to_writer(url, writer).await;
+}
+
+/// Fetch (blocking) the given url and write the raw (XML) response body to
+/// `writer` as it arrives, without ever holding the full body in memory.
+/// Returns the number of bytes written.
+pub fn get_to_writer_b<W>(url: &str, writer: &mut W) -> Result<u64>
+where
+    W: Write,
+{
+    return HttpClient::default().get_to_writer_b(url, writer);
+}
+
+/// How much of the body to include in `RbggError::XmlParse`
+const XML_SNIPPET_LEN: usize = 500;
+
+#[cfg(feature = "fast-xml")]
+fn convert_xml(data: &str) -> std::result::Result<Value, String> {
+    return crate::xml::to_json(data);
+}
+
+#[cfg(not(feature = "fast-xml"))]
+fn convert_xml(data: &str) -> std::result::Result<Value, String> {
+    return to_json(data).map_err(|e| e.to_string());
+}
+
+/// Convert a raw XML response body into its JSON representation.  With the
+/// `fast-xml` feature, this uses the streaming conversion in `xml`.
+pub fn xml_to_json(data: &str) -> Result<Value> {
+    let ret = match convert_xml(data) {
         Ok(res) => res,
-        Err(_) => return Err(anyhow!("Failed to convert to JSON")),
+        Err(message) => {
+            return Err(RbggError::XmlParse {
+                message,
+                status: None,
+                url: None,
+                snippet: snippet(data, XML_SNIPPET_LEN),
+            })
+        }
     };
 
     return Ok(ret);
 }
 
-pub fn get_json_resp_b(url: &str) -> Result<Value> {
-    let mut resp;
+/// The start of a response body, with the whitespace collapsed, for showing
+/// in an error.  It's cut off after `max` characters.
+pub(crate) fn snippet(body: &str, max: usize) -> String {
+    let collapsed = body.split_whitespace().collect::<Vec<&str>>().join(" ");
+    if collapsed.chars().count() <= max {
+        return collapsed;
+    }
+    let mut ret: String = collapsed.chars().take(max).collect();
+    ret.push_str("...");
 
-    // Sometimes, when a large request, often for a user's collection,
-    // is made, we'll get a 202 response and we have to request this again
-    // after the server has cached it on their side
-    loop {
-        resp = reqwest::blocking::get(url)?;
-        if resp.status() == 202 {
-            // We're going to sleep here and try again
-            thread::sleep(Duration::from_secs(1));
-        } else {
-            // We should be good to process the response now
-            break;
+    return ret;
+}
+
+/// Return the message from an error response, if that's what this is.  BGG
+/// reports errors in a few shapes, all answered with a 200:
+///
+/// ```xml
+/// <error><message>Rate limit exceeded.</message></error>
+/// <errors><error><message>Invalid username specified</message></error></errors>
+/// <error message="Item not found"/>
+/// <div class="messagebox error">Invalid object or user</div>
+/// ```
+pub fn api_error(value: &Value) -> Option<String> {
+    if let Some(div) = value.get("div") {
+        let class = div.get("@class").and_then(Value::as_str)?;
+        if !class.split_whitespace().any(|c| c == "error") {
+            return None;
         }
+        return Some(div.get("#text")?.as_str()?.trim().to_string());
     }
+    let error = match (value.get("error"), value.get("errors")) {
+        (Some(error), _) => error,
+        (None, Some(errors)) => errors.get("error")?,
+        (None, None) => return None,
+    };
+    // There may be more than one, but the first is enough to go on
+    let error = match error {
+        Value::Array(errors) => errors.first()?,
+        _ => error,
+    };
+    let message = match error {
+        Value::Object(map) => map.get("message").or_else(|| map.get("@message"))?,
+        _ => error,
+    };
+    let message = match message {
+        Value::Object(map) => map.get("#text")?,
+        _ => message,
+    };
 
-    let data = resp.text()?;
+    return Some(message.as_str()?.trim().to_string());
+}
 
-    let ret = match to_json(&data) {
-        Ok(res) => res,
-        Err(_) => {
-            return Err(anyhow!("Failed to convert to JSON"));
+/// Return `RbggError::Api` if the response is one of BGG's error responses
+/// (see `api_error()`)
+pub fn check_api_error(value: &Value) -> Result<()> {
+    return match api_error(value) {
+        Some(message) => Err(RbggError::Api { message }),
+        None => Ok(()),
+    };
+}
+
+/// Whether a lookup found nothing.  BGG answers one with an empty `<items>`
+/// (for things and families) or, in the v1 API, an "Item not found" error in
+/// place of each `<boardgame>`.
+pub fn is_not_found(data: &Value) -> bool {
+    if let Some(items) = data.get("items") {
+        return items.get("item").is_none();
+    }
+    if let Some(games) = data.get("boardgames") {
+        return match games.get("boardgame") {
+            Some(Value::Array(games)) => games.iter().all(|g| g.get("error").is_some()),
+            Some(game) => game.get("error").is_some(),
+            None => true,
+        };
+    }
+
+    return false;
+}
+
+/// Turn the result of a lookup that found nothing (see `is_not_found()`),
+/// or that BGG answered with "Item not found", into `RbggError::NotFound`
+pub fn check_found(res: Result<Value>, kind: &str, id: &str) -> Result<Value> {
+    let not_found = match &res {
+        Ok(data) => is_not_found(data),
+        Err(RbggError::Api { message }) => message.to_ascii_lowercase().contains("not found"),
+        Err(_) => false,
+    };
+    if not_found {
+        return Err(RbggError::NotFound {
+            kind: kind.into(),
+            id: id.into(),
+        });
+    }
+
+    return res;
+}
+
+/// Turn the result of a call for a user that doesn't exist into
+/// `RbggError::UnknownUser`.  BGG answers a user lookup for them with a
+/// `<user>` without an ID, and collection and plays calls with an "Invalid
+/// username specified" (or "Invalid object or user") error.
+pub fn check_user(res: Result<Value>, username: &str) -> Result<Value> {
+    let unknown = match &res {
+        Ok(data) => data.pointer("/user/@id").and_then(Value::as_str) == Some(""),
+        Err(RbggError::Api { message }) => {
+            let message = message.to_ascii_lowercase();
+            message.contains("invalid username") || message.contains("invalid object or user")
         }
+        Err(_) => false,
     };
+    if unknown {
+        return Err(RbggError::UnknownUser(username.into()));
+    }
+
+    return res;
+}
+
+/// Join numeric IDs into a comma separated list, as BGG expects them
+pub fn join_ids(ids: &[usize]) -> String {
+    return ids
+        .iter()
+        .map(|i| i.to_string())
+        .collect::<Vec<String>>()
+        .join(",");
+}
+
+/// Parse a JSON document into a `Value`, with any error as an `RbggError`
+pub fn parse_json(data: &str) -> Result<Value> {
+    let ret: Value = serde_json::from_str(data)?;
 
     return Ok(ret);
 }
 
-/// Convert a set of Params into a query string
+/// Convert a set of Params into a query string.  The params are sorted by
+/// key, so the same params always give the same query string (and url).
 pub fn params2qs(params: &Params) -> String {
     let mut parts = vec![];
+    let mut sorted: Vec<(&String, &String)> = params.iter().collect();
+    sorted.sort();
 
-    for (k, v) in params {
+    for (k, v) in sorted {
         let mut tmp = String::new();
 
         tmp += &encode(k).to_owned();
@@ -88,6 +720,19 @@ pub fn params2qs(params: &Params) -> String {
     return parts.join("&");
 }
 
+/// Return the url with its query params sorted, so that urls asking for the
+/// same thing compare equal however their params were ordered
+pub(crate) fn normalize_url(url: &str) -> String {
+    let (base, query) = match url.split_once('?') {
+        Some(parts) => parts,
+        None => return url.to_string(),
+    };
+    let mut params: Vec<&str> = query.split('&').filter(|p| !p.is_empty()).collect();
+    params.sort();
+
+    return format!("{}?{}", base, params.join("&"));
+}
+
 /// A convenience function to return params, empty or not from an option
 pub fn get_opts(options: Option<Params>) -> Params {
     let mut opts = Params::new();
@@ -102,6 +747,177 @@ pub fn get_opts(options: Option<Params>) -> Params {
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "async")]
+    use std::sync::Arc;
+
+    #[test]
+    fn test_into_params() {
+        assert_eq!(None.into_params().unwrap(), None);
+
+        let p = Params::from([("key".into(), "value".into())]);
+        assert_eq!(Some(p.clone()).into_params().unwrap(), Some(p.clone()));
+        assert_eq!(p.clone().into_params().unwrap(), Some(p));
+    }
+
+    #[test]
+    fn test_range_params() {
+        let mut p = Params::new();
+        range_params(&mut p, &(2u32..5), "min", "max", Some(1)).unwrap();
+        assert_eq!(p["min"], "2");
+        assert_eq!(p["max"], "4");
+
+        let mut p = Params::new();
+        range_params(&mut p, &(7.5..), "min", "max", None).unwrap();
+        assert_eq!(p["min"], "7.5");
+        assert!(!p.contains_key("max"));
+
+        let mut p = Params::new();
+        assert!(range_params(&mut p, &(1.0..5.0), "min", "max", None).is_err());
+    }
+
+    #[test]
+    fn test_range_params_overflow() {
+        let mut p = Params::new();
+        let res = range_params(&mut p, &(..0u32), "min", "max", Some(1));
+        assert!(matches!(res, Err(RbggError::InvalidInput(_))));
+
+        let mut p = Params::new();
+        let range = (Bound::Excluded(u32::MAX), Bound::Unbounded);
+        let res = range_params(&mut p, &range, "min", "max", Some(1));
+        assert!(matches!(res, Err(RbggError::InvalidInput(_))));
+
+        // The bounds right next to the limits are fine
+        let mut p = Params::new();
+        let range = (Bound::Excluded(u32::MAX - 1), Bound::Excluded(1u32));
+        range_params(&mut p, &range, "min", "max", Some(1)).unwrap();
+        assert_eq!(p["min"], u32::MAX.to_string());
+        assert_eq!(p["max"], "0");
+    }
+
+    fn plays_pager() -> Paginator {
+        let params = Params::from([("username".into(), "someone".into())]);
+
+        return Paginator::new(
+            HttpClient::default(),
+            "https://example.com/xmlapi2/plays",
+            params,
+            "/plays",
+            "play",
+        );
+    }
+
+    #[test]
+    fn test_paginator() {
+        let mut pager = plays_pager();
+        let url = pager.page_url();
+        assert!(url.starts_with("https://example.com/xmlapi2/plays?"));
+        assert!(url.contains("page=1"));
+        assert!(url.contains("username=someone"));
+
+        let page = serde_json::json!({"plays": {"@total": "5", "play": [1, 2]}});
+        assert!(pager.advance(page).is_some());
+        assert_eq!(pager.total(), Some(5));
+        assert_eq!(pager.page(), 2);
+        assert!(!pager.is_done());
+        assert!(pager
+            .advance(serde_json::json!({"plays": {"play": [3, 4]}}))
+            .is_some());
+        // A single item isn't in an array
+        let last = serde_json::json!({"plays": {"play": 5}});
+        assert_eq!(pager.items(&last).len(), 1);
+        assert!(pager.advance(last).is_some());
+        assert!(pager.is_done());
+
+        // A short page is the last one, even without a total
+        let mut pager = plays_pager().page_size(100);
+        assert!(pager
+            .advance(serde_json::json!({"plays": {"play": [1]}}))
+            .is_some());
+        assert!(pager.is_done());
+
+        // An empty page isn't returned
+        let mut pager = plays_pager();
+        assert!(pager
+            .advance(serde_json::json!({"plays": {"@total": "0"}}))
+            .is_none());
+        assert!(pager.is_done());
+
+        // There's no page 0, so that's the first page
+        let mut pager = plays_pager().start_page(0);
+        assert_eq!(pager.page(), 1);
+        assert!(pager.page_url().contains("page=1"));
+        assert!(pager
+            .advance(serde_json::json!({"plays": {"@total": "3", "play": [1, 2]}}))
+            .is_some());
+        assert_eq!(pager.page(), 2);
+        assert!(!pager.is_done());
+    }
+
+    #[test]
+    fn test_timeout_config() {
+        let config = timeout_config(None, Some(Duration::from_secs(5)));
+        assert_eq!(config.connect_timeout, None);
+        assert_eq!(config.timeout, Some(Duration::from_secs(5)));
+        assert!(config.compression);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_single_flight() {
+        let flights: Arc<SingleFlight<usize>> = Arc::new(SingleFlight::new());
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let rx = rx.shared();
+
+        let mut handles = vec![];
+        for _ in 0..3 {
+            let (flights, calls, rx) = (flights.clone(), calls.clone(), rx.clone());
+            handles.push(tokio::spawn(async move {
+                return flights
+                    .run("key", || async move {
+                        calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                        rx.await.ok();
+                        return Ok(42);
+                    })
+                    .await;
+            }));
+        }
+        // Let them all pile up on the one call
+        while flights.is_empty() {
+            tokio::task::yield_now().await;
+        }
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        tx.send(()).unwrap();
+
+        for handle in handles {
+            assert_eq!(handle.await.unwrap().unwrap(), 42);
+        }
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert!(flights.is_empty());
+
+        // Once done, the next call starts afresh, and errors come through
+        let res = flights
+            .run("key", || async { Err(RbggError::Status { status: 500 }) })
+            .await;
+        assert!(matches!(res, Err(RbggError::Status { status: 500 })));
+    }
+
+    #[test]
+    fn test_merge_pages() {
+        let pages = vec![
+            serde_json::json!({"plays": {"@total": "3", "@page": "1", "play": [1, 2]}}),
+            serde_json::json!({"plays": {"@total": "3", "@page": "2", "play": 3}}),
+        ];
+        let res = merge_pages(pages, "/plays", "play");
+        assert_eq!(
+            res,
+            serde_json::json!({"plays": {"@total": "3", "@page": "1", "play": [1, 2, 3]}})
+        );
+
+        assert_eq!(merge_pages(vec![], "/plays", "play"), Value::Null);
+    }
 
     #[test]
     fn test_get_opts() {
@@ -113,8 +929,122 @@ mod tests {
         let res = get_opts(Some(p));
 
         assert_eq!(res.len(), 1);
-        assert!(res.contains_key("key".into()));
+        assert!(res.contains_key("key"));
     }
+    #[test]
+    fn test_api_error() {
+        let value = serde_json::json!({"error": {"message": "Rate limit exceeded."}});
+        assert_eq!(api_error(&value).unwrap(), "Rate limit exceeded.");
+
+        let value = serde_json::json!({"errors": {"error": [
+            {"message": " Invalid username specified "},
+            {"message": "Another"},
+        ]}});
+        assert_eq!(api_error(&value).unwrap(), "Invalid username specified");
+        assert!(matches!(
+            check_api_error(&value),
+            Err(RbggError::Api { message }) if message == "Invalid username specified"
+        ));
+
+        let value = serde_json::json!({"error": {"@message": "Item not found"}});
+        assert_eq!(api_error(&value).unwrap(), "Item not found");
+        let value = serde_json::json!({"error": {"message": {"#text": "Bad"}}});
+        assert_eq!(api_error(&value).unwrap(), "Bad");
+
+        let value = serde_json::json!({"items": {"@total": "0"}});
+        assert_eq!(api_error(&value), None);
+        assert!(check_api_error(&value).is_ok());
+    }
+
+    #[test]
+    fn test_check_found() {
+        let empty = serde_json::json!({"items": {"@termsofuse": "https://x"}});
+        assert!(is_not_found(&empty));
+        assert!(matches!(
+            check_found(Ok(empty), "thing", "1,2"),
+            Err(RbggError::NotFound { kind, id }) if kind == "thing" && id == "1,2"
+        ));
+        let found = serde_json::json!({"items": {"item": {"@id": "1"}}});
+        assert!(check_found(Ok(found), "thing", "1").is_ok());
+
+        let games = serde_json::json!({"boardgames": {"boardgame": [
+            {"@objectid": "1", "error": {"@message": "Item not found"}},
+            {"@objectid": "2", "name": "Die Macher"},
+        ]}});
+        assert!(!is_not_found(&games));
+        let games = serde_json::json!({"boardgames": {"boardgame":
+            {"@objectid": "1", "error": {"@message": "Item not found"}},
+        }});
+        assert!(is_not_found(&games));
+
+        let err = RbggError::Api {
+            message: "Item not found".into(),
+        };
+        assert!(matches!(
+            check_found(Err(err), "boardgame", "1"),
+            Err(RbggError::NotFound { .. })
+        ));
+        let err = RbggError::Status { status: 500 };
+        assert!(matches!(
+            check_found(Err(err), "thing", "1"),
+            Err(RbggError::Status { .. })
+        ));
+        assert_eq!(join_ids(&[1, 22, 333]), "1,22,333");
+    }
+
+    #[test]
+    fn test_check_user() {
+        let user = serde_json::json!({"user": {"@id": "", "@name": "nobody"}});
+        assert!(matches!(
+            check_user(Ok(user), "nobody"),
+            Err(RbggError::UnknownUser(name)) if name == "nobody"
+        ));
+        let user = serde_json::json!({"user": {"@id": "123", "@name": "somebody"}});
+        assert!(check_user(Ok(user), "somebody").is_ok());
+
+        // An empty collection is still a collection
+        let coll = serde_json::json!({"items": {"@totalitems": "0"}});
+        assert!(check_user(Ok(coll), "somebody").is_ok());
+
+        let err = RbggError::Api {
+            message: "Invalid username specified".into(),
+        };
+        assert!(matches!(
+            check_user(Err(err), "nobody"),
+            Err(RbggError::UnknownUser(_))
+        ));
+        let value = serde_json::json!({"div": {"@class": "messagebox error", "#text": "Invalid object or user"}});
+        let err = check_api_error(&value).unwrap_err();
+        assert!(matches!(
+            check_user(Err(err), "nobody"),
+            Err(RbggError::UnknownUser(_))
+        ));
+        let err = RbggError::Api {
+            message: "Rate limit exceeded.".into(),
+        };
+        assert!(matches!(
+            check_user(Err(err), "somebody"),
+            Err(RbggError::Api { .. })
+        ));
+    }
+
+    #[test]
+    fn test_snippet() {
+        assert_eq!(snippet("  a\n\n b  ", 10), "a b");
+        assert_eq!(snippet("abcdefghijkl", 5), "abcde...");
+        assert_eq!(snippet("ééééé", 3), "ééé...");
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let res = parse_json(r#"{"items": {"@total": "2", "item": [1, 2]}}"#).unwrap();
+
+        assert_eq!(res["items"]["@total"], "2");
+        assert_eq!(res["items"]["item"].as_array().unwrap().len(), 2);
+
+        assert!(parse_json("{not json").is_err());
+    }
+
     #[test]
     fn test_params_encoding() {
         // Basic test
@@ -125,16 +1055,23 @@ mod tests {
         let p = Params::from([("key=".into(), "value".into())]);
         assert_eq!(params2qs(&p), "key%3D=value");
 
-        // Due to the lack of stable ordering in a hash map, I have to do some
-        // different style of checks here.
+        // The params are always in the same (sorted) order
         let p = Params::from([
-            ("key1".into(), "value1".into()),
             ("key2".into(), "value2".into()),
+            ("key1".into(), "value1".into()),
+            ("key3".into(), "value3".into()),
         ]);
-        let res = params2qs(&p);
-        let amp_count = res.chars().filter(|c| *c == '&').count();
-        assert_eq!(amp_count, 1);
-        assert!(res.contains("key1=value1"));
-        assert!(res.contains("key2=value2"));
+        for _ in 0..10 {
+            assert_eq!(params2qs(&p), "key1=value1&key2=value2&key3=value3");
+        }
+    }
+
+    #[test]
+    fn test_normalize_url() {
+        assert_eq!(
+            normalize_url("https://bgg/thing?type=boardgame&id=1&stats=1"),
+            "https://bgg/thing?id=1&stats=1&type=boardgame"
+        );
+        assert_eq!(normalize_url("https://bgg/hot"), "https://bgg/hot");
     }
 }
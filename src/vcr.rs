@@ -0,0 +1,562 @@
+/*!
+Record and replay of requests, for tests that don't touch the network.  In
+record mode, a `VcrTransport` sends each request through another transport
+as normal and writes the request and its response to a "cassette" file.
+In replay mode, the responses are served back from the cassette, so tests
+built on it are fast, deterministic and work offline.
+
+```ignore,rust
+use rbgg::{bgg2::{Client2, Hotness}, http::HttpConfig, transport::ReqwestTransport, vcr::VcrTransport};
+use std::sync::Arc;
+
+// Once, against the real site
+let inner = Arc::new(ReqwestTransport::new(&HttpConfig::default())?);
+let vcr = VcrTransport::record("tests/cassettes/hot.json", inner);
+let cl = Client2::builder().transport(Arc::new(vcr)).build()?;
+cl.hot_b(Hotness::BoardGame, None)?;
+
+// Then in the tests
+let vcr = VcrTransport::replay("tests/cassettes/hot.json")?;
+let cl = Client2::builder().transport(Arc::new(vcr)).build()?;
+let hot = cl.hot_b(Hotness::BoardGame, None)?;
+```
+
+Requests are matched on their method, url and body.  If the same request
+was recorded more than once (e.g. a 202 followed by a 200), the responses
+are replayed in order, with the last one repeated after that.
+
+Cassettes are plain JSON, so check what's in them before committing them.
+Credentials are redacted as they're recorded: the values of params like
+`password` and `token`, in both the url and a JSON or form body (such as
+the one `session::BggSession` logs in with), and the values of any cookies
+set by the responses.  Everything else in the urls, bodies and response
+headers is stored as is.
+*/
+use crate::errors::Result;
+use crate::http::REDACTED_PARAMS;
+use crate::transport::{
+    AsyncBody, BlockingBody, HttpTransport, TransportError, TransportErrorKind, TransportRequest,
+    TransportResponse, TransportResult,
+};
+use crate::utils::normalize_url;
+use futures_util::future::BoxFuture;
+use http::header::{HeaderMap, HeaderName, HeaderValue, SET_COOKIE};
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A recorded request and its response
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub method: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<String>,
+    pub status: u16,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Interaction {
+    /// Whether this is a recording of the request.  The urls are compared
+    /// with their params sorted, so the order they were sent in doesn't
+    /// matter, and with any credentials redacted, as they were when the
+    /// request was recorded.
+    fn matches(&self, req: &TransportRequest) -> bool {
+        return self.method == req.method.as_str()
+            && normalize_url(&self.url) == normalize_url(&redact_url(&req.url))
+            && self.request_body == req.body.as_deref().map(redact_body);
+    }
+
+    fn header_map(&self) -> HeaderMap {
+        let mut ret = HeaderMap::new();
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                ret.append(name, value);
+            }
+        }
+
+        return ret;
+    }
+}
+
+/// The recorded interactions, in the order they happened
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    /// Load a cassette from a file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let data = fs::read_to_string(path)?;
+
+        return Ok(serde_json::from_str(&data)?);
+    }
+
+    /// Save the cassette to a file, creating any missing directories
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+
+        return Ok(());
+    }
+}
+
+/// Whether a `VcrTransport` is recording or replaying
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VcrMode {
+    Record,
+    Replay,
+}
+
+/// A transport that records requests to, or replays them from, a cassette
+#[derive(Debug)]
+pub struct VcrTransport {
+    mode: VcrMode,
+    path: PathBuf,
+    inner: Option<Arc<dyn HttpTransport>>,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    cassette: Cassette,
+    // How many times each recorded interaction has been replayed, by its
+    // index in the cassette
+    replayed: HashMap<usize, usize>,
+}
+
+impl VcrTransport {
+    /// Send requests through `inner`, recording them to a new cassette at
+    /// `path`.  The cassette is saved after every request.
+    pub fn record(path: impl AsRef<Path>, inner: Arc<dyn HttpTransport>) -> Self {
+        return Self {
+            mode: VcrMode::Record,
+            path: path.as_ref().to_path_buf(),
+            inner: Some(inner),
+            state: Mutex::new(State::default()),
+        };
+    }
+
+    /// Serve the responses recorded in the cassette at `path`.  A request
+    /// that wasn't recorded fails.
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        return Ok(Self {
+            mode: VcrMode::Replay,
+            state: Mutex::new(State {
+                cassette: Cassette::load(&path)?,
+                ..Default::default()
+            }),
+            path,
+            inner: None,
+        });
+    }
+
+    pub fn mode(&self) -> VcrMode {
+        return self.mode;
+    }
+
+    /// Return a copy of the cassette so far
+    pub fn cassette(&self) -> Cassette {
+        return self.lock().cassette.clone();
+    }
+
+    /// Find the recorded response for the request
+    fn find(&self, req: &TransportRequest) -> Result<Interaction, TransportError> {
+        let mut state = self.lock();
+        let matching: Vec<usize> = state
+            .cassette
+            .interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| i.matches(req))
+            .map(|(idx, _)| idx)
+            .collect();
+        let first = match matching.first() {
+            Some(first) => *first,
+            None => {
+                return Err(other(format!(
+                    "No recorded response for {} {}",
+                    req.method, req.url
+                )))
+            }
+        };
+
+        // Keep count against the first match, and step through the rest
+        let count = state.replayed.entry(first).or_default();
+        let idx = matching[(*count).min(matching.len() - 1)];
+        *count += 1;
+
+        return Ok(state.cassette.interactions[idx].clone());
+    }
+
+    /// Add an interaction to the cassette and save it
+    fn add(&self, interaction: Interaction) -> Result<()> {
+        let mut state = self.lock();
+        state.cassette.interactions.push(interaction);
+
+        return state.cassette.save(&self.path);
+    }
+
+    fn inner(&self) -> &Arc<dyn HttpTransport> {
+        // Only replaying transports, which never send, have no inner one
+        return self
+            .inner
+            .as_ref()
+            .expect("A recording VcrTransport has a transport");
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        return self.state.lock().unwrap_or_else(|e| e.into_inner());
+    }
+}
+
+impl HttpTransport for VcrTransport {
+    fn send(&self, req: TransportRequest) -> BoxFuture<'_, TransportResult<AsyncBody>> {
+        return Box::pin(async move {
+            let interaction = match self.mode {
+                VcrMode::Replay => self.find(&req)?,
+                VcrMode::Record => {
+                    let mut interaction = new_interaction(&req);
+                    let resp = self.inner().send(req).await?;
+                    interaction.status = resp.status.as_u16();
+                    interaction.headers = header_pairs(&resp.headers);
+                    interaction.body = resp.text().await.map_err(other)?;
+                    self.add(interaction.clone()).map_err(other)?;
+                    interaction
+                }
+            };
+
+            return Ok(TransportResponse {
+                headers: interaction.header_map(),
+                ..TransportResponse::<AsyncBody>::new(
+                    status(interaction.status)?,
+                    &interaction.url,
+                    interaction.body,
+                )
+            });
+        });
+    }
+
+    fn send_b(&self, req: TransportRequest) -> TransportResult<BlockingBody> {
+        let interaction = match self.mode {
+            VcrMode::Replay => self.find(&req)?,
+            VcrMode::Record => {
+                let mut interaction = new_interaction(&req);
+                let resp = self.inner().send_b(req)?;
+                interaction.status = resp.status.as_u16();
+                interaction.headers = header_pairs(&resp.headers);
+                interaction.body = resp.text().map_err(other)?;
+                self.add(interaction.clone()).map_err(other)?;
+                interaction
+            }
+        };
+
+        return Ok(TransportResponse {
+            headers: interaction.header_map(),
+            ..TransportResponse::<BlockingBody>::new(
+                status(interaction.status)?,
+                &interaction.url,
+                interaction.body,
+            )
+        });
+    }
+}
+
+/// Start an interaction for the request, with any credentials redacted
+fn new_interaction(req: &TransportRequest) -> Interaction {
+    return Interaction {
+        method: req.method.to_string(),
+        url: redact_url(&req.url),
+        request_body: req.body.as_deref().map(redact_body),
+        ..Default::default()
+    };
+}
+
+/// The response headers to record, with the values of any cookies redacted
+fn header_pairs(headers: &HeaderMap) -> Vec<(String, String)> {
+    return headers
+        .iter()
+        .filter_map(|(name, value)| {
+            let value = value.to_str().ok()?;
+            if name == SET_COOKIE {
+                return Some((name.to_string(), redact_cookie(value)));
+            }
+            return Some((name.to_string(), value.to_string()));
+        })
+        .collect();
+}
+
+/// What credentials are replaced with in a cassette
+const REDACTED: &str = "REDACTED";
+
+/// Whether the param (or JSON field) holds a credential
+fn is_secret(key: &str) -> bool {
+    return REDACTED_PARAMS.contains(&key.to_ascii_lowercase().as_str());
+}
+
+/// Redact the values of any credentials in the url's query string
+fn redact_url(url: &str) -> String {
+    return match url.split_once('?') {
+        Some((base, query)) => format!("{}?{}", base, redact_form(query)),
+        None => url.to_string(),
+    };
+}
+
+/// Redact the values of any credentials in a form encoded string (which
+/// is also what a query string is)
+fn redact_form(form: &str) -> String {
+    return form
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((k, _)) if is_secret(k) => format!("{}={}", k, REDACTED),
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join("&");
+}
+
+/// Redact the values of any credentials in a request body, which is JSON
+/// (like the login request) or form encoded
+fn redact_body(body: &str) -> String {
+    if let Ok(mut value) = serde_json::from_str::<Value>(body) {
+        if redact_json(&mut value) {
+            return value.to_string();
+        }
+        return body.to_string();
+    }
+
+    return redact_form(body);
+}
+
+/// Redact the values of any credentials in the JSON, at any depth,
+/// returning whether there were any
+fn redact_json(value: &mut Value) -> bool {
+    let mut ret = false;
+    match value {
+        Value::Object(map) => {
+            for (key, val) in map.iter_mut() {
+                if is_secret(key) {
+                    *val = Value::String(REDACTED.into());
+                    ret = true;
+                } else {
+                    ret |= redact_json(val);
+                }
+            }
+        }
+        Value::Array(arr) => {
+            for val in arr.iter_mut() {
+                ret |= redact_json(val);
+            }
+        }
+        _ => (),
+    }
+
+    return ret;
+}
+
+/// Redact the value of a `Set-Cookie` header, keeping its name and
+/// attributes.  Cookies that are being cleared are left alone.
+fn redact_cookie(header: &str) -> String {
+    let (pair, attrs) = match header.split_once(';') {
+        Some((pair, attrs)) => (pair, Some(attrs)),
+        None => (header, None),
+    };
+    let pair = match pair.split_once('=') {
+        Some((name, value)) if !value.trim().is_empty() && value.trim() != "deleted" => {
+            format!("{}={}", name, REDACTED)
+        }
+        _ => pair.to_string(),
+    };
+
+    return match attrs {
+        Some(attrs) => format!("{};{}", pair, attrs),
+        None => pair,
+    };
+}
+
+fn status(code: u16) -> std::result::Result<StatusCode, TransportError> {
+    return StatusCode::from_u16(code).map_err(other);
+}
+
+fn other(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> TransportError {
+    return TransportError::new(TransportErrorKind::Other, err);
+}
+
+#[cfg(all(test, feature = "async"))]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU16, Ordering};
+
+    /// Answers with a 202 the first time, then a 200
+    #[derive(Debug, Default)]
+    struct Queued {
+        calls: AtomicU16,
+    }
+
+    impl Queued {
+        fn next(&self) -> u16 {
+            return match self.calls.fetch_add(1, Ordering::Relaxed) {
+                0 => 202,
+                _ => 200,
+            };
+        }
+    }
+
+    impl HttpTransport for Queued {
+        fn send(&self, req: TransportRequest) -> BoxFuture<'_, TransportResult<AsyncBody>> {
+            return Box::pin(async move {
+                let status = StatusCode::from_u16(self.next()).unwrap();
+                return Ok(TransportResponse::<AsyncBody>::new(
+                    status, &req.url, "<a/>",
+                ));
+            });
+        }
+
+        fn send_b(&self, req: TransportRequest) -> TransportResult<BlockingBody> {
+            let status = StatusCode::from_u16(self.next()).unwrap();
+            let mut resp = TransportResponse::<BlockingBody>::new(status, &req.url, "<a/>");
+            resp.headers
+                .insert("x-test", HeaderValue::from_static("yes"));
+            return Ok(resp);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_replay() {
+        let path = std::env::temp_dir().join(format!("rbgg-vcr-{}/hot.json", std::process::id()));
+        let url = "https://bgg/xmlapi2/hot";
+
+        let vcr = VcrTransport::record(&path, Arc::new(Queued::default()));
+        assert_eq!(vcr.mode(), VcrMode::Record);
+        assert_eq!(vcr.send_b(TransportRequest::get(url)).unwrap().status, 202);
+        assert_eq!(
+            vcr.send(TransportRequest::get(url)).await.unwrap().status,
+            200
+        );
+        assert_eq!(vcr.cassette().interactions.len(), 2);
+        assert_eq!(Cassette::load(&path).unwrap(), vcr.cassette());
+
+        // The responses come back in order, and the last one sticks
+        let vcr = VcrTransport::replay(&path).unwrap();
+        let resp = vcr.send_b(TransportRequest::get(url)).unwrap();
+        assert_eq!(resp.status, 202);
+        assert_eq!(resp.headers["x-test"], "yes");
+        assert_eq!(resp.text().unwrap(), "<a/>");
+        assert_eq!(
+            vcr.send(TransportRequest::get(url)).await.unwrap().status,
+            200
+        );
+        assert_eq!(vcr.send_b(TransportRequest::get(url)).unwrap().status, 200);
+
+        // Params are matched in any order
+        let path = path.with_file_name("thing.json");
+        let vcr = VcrTransport::record(&path, Arc::new(Queued::default()));
+        vcr.send_b(TransportRequest::get("https://bgg/thing?id=1&stats=1"))
+            .unwrap();
+        let vcr = VcrTransport::replay(&path).unwrap();
+        let resp = vcr
+            .send_b(TransportRequest::get("https://bgg/thing?stats=1&id=1"))
+            .unwrap();
+        assert_eq!(resp.status, 202);
+
+        let err = vcr
+            .send_b(TransportRequest::get("https://bgg/xmlapi2/thing"))
+            .unwrap_err();
+        assert!(err.to_string().contains("No recorded response"));
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    /// Answers every request with a 200 that sets a session cookie
+    #[derive(Debug)]
+    struct Login;
+
+    impl HttpTransport for Login {
+        fn send(&self, req: TransportRequest) -> BoxFuture<'_, TransportResult<AsyncBody>> {
+            return Box::pin(async move {
+                return Ok(TransportResponse::<AsyncBody>::new(
+                    StatusCode::OK,
+                    &req.url,
+                    "",
+                ));
+            });
+        }
+
+        fn send_b(&self, req: TransportRequest) -> TransportResult<BlockingBody> {
+            let mut resp = TransportResponse::<BlockingBody>::new(StatusCode::OK, &req.url, "");
+            resp.headers.append(
+                SET_COOKIE,
+                HeaderValue::from_static("SessionID=abc123; Path=/; HttpOnly"),
+            );
+            resp.headers
+                .append(SET_COOKIE, HeaderValue::from_static("bggpassword=deleted"));
+            return Ok(resp);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_redacts() {
+        let path =
+            std::env::temp_dir().join(format!("rbgg-vcr-redact-{}/login.json", std::process::id()));
+        let login = || TransportRequest {
+            method: http::Method::POST,
+            body: Some(r#"{"credentials":{"username":"me","password":"hunter2"}}"#.to_string()),
+            ..TransportRequest::get("https://bgg/login/api/v1?token=abc&id=1")
+        };
+
+        let vcr = VcrTransport::record(&path, Arc::new(Login));
+        vcr.send_b(login()).unwrap();
+        let saved = fs::read_to_string(&path).unwrap();
+        assert!(!saved.contains("hunter2"));
+        assert!(!saved.contains("abc"));
+
+        let interaction = &vcr.cassette().interactions[0];
+        assert_eq!(
+            interaction.url,
+            "https://bgg/login/api/v1?token=REDACTED&id=1"
+        );
+        let body: Value = serde_json::from_str(interaction.request_body.as_ref().unwrap()).unwrap();
+        assert_eq!(body["credentials"]["password"], "REDACTED");
+        assert_eq!(body["credentials"]["username"], "me");
+        assert!(interaction.headers.contains(&(
+            "set-cookie".to_string(),
+            "SessionID=REDACTED; Path=/; HttpOnly".to_string()
+        )));
+        assert!(interaction
+            .headers
+            .contains(&("set-cookie".to_string(), "bggpassword=deleted".to_string())));
+
+        // The real request still matches the redacted recording
+        let vcr = VcrTransport::replay(&path).unwrap();
+        assert_eq!(vcr.send(login()).await.unwrap().status, 200);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_redact_body() {
+        assert_eq!(
+            redact_body("user=me&Password=x"),
+            "user=me&Password=REDACTED"
+        );
+        // Bodies without credentials are left exactly as they were
+        assert_eq!(redact_body(r#"{"b": 1,  "a": 2}"#), r#"{"b": 1,  "a": 2}"#);
+        assert_eq!(redact_body("<a/>"), "<a/>");
+        assert_eq!(redact_cookie("a="), "a=");
+    }
+}